@@ -1,13 +1,61 @@
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+
+use crate::validate::FieldErrors;
 use std::future::Future;
 use std::pin::Pin;
+use std::time::Duration;
 
 pub type ActionResult<T> = Result<T, ActionError>;
 
+/// The arguments did not deserialize into the action's input type.
+pub const INVALID_INPUT: &str = "INVALID_INPUT";
+/// A typed action returned its own error, serialized in `details`.
+pub const ACTION_FAILED: &str = "ACTION_FAILED";
+/// Something went wrong outside the action's control, e.g. its result did not serialize.
+pub const INTERNAL_ERROR: &str = "INTERNAL_ERROR";
+/// Returned by [`redirect`]; the response navigates instead of failing.
+pub const REDIRECT: &str = "REDIRECT";
+/// Returned by [`not_found`]; the response is the 404 page.
+pub const NOT_FOUND: &str = "NOT_FOUND";
+/// Returned by [`too_many_requests`] when a rate limit or concurrency cap rejected the call.
+pub const TOO_MANY_REQUESTS: &str = "TOO_MANY_REQUESTS";
+
+/// Ends the action with a navigation to `url`:
+///
+/// ```rust,ignore
+/// let post = db::create_post(&title).await?;
+/// return Err(redirect(format!("/posts/{}", post.id)));
+/// ```
+pub fn redirect(url: impl Into<String>) -> ActionError {
+    let url = url.into();
+    ActionError::with_code(format!("Redirect to {}", url), REDIRECT)
+        .with_details(serde_json::json!({ "url": url }))
+}
+
+/// Ends the action with the 404 page, e.g. when the record it mutates does not exist.
+pub fn not_found() -> ActionError {
+    ActionError::with_code("Not found", NOT_FOUND)
+}
+
+/// Rejects the call, telling the caller when it may try again if that is known. The
+/// registry returns it for actions over their `ActionLimits`.
+pub fn too_many_requests(retry_after: Option<Duration>) -> ActionError {
+    let error = ActionError::with_code("Too many requests", TOO_MANY_REQUESTS);
+    match retry_after {
+        Some(wait) => {
+            error.with_details(serde_json::json!({ "retry_after_ms": wait.as_millis() as u64 }))
+        }
+        None => error,
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActionError {
     pub message: String,
     pub code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
 }
 
 impl ActionError {
@@ -15,6 +63,7 @@ impl ActionError {
         Self {
             message: message.into(),
             code: None,
+            details: None,
         }
     }
 
@@ -22,6 +71,81 @@ impl ActionError {
         Self {
             message: message.into(),
             code: Some(code.into()),
+            details: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    /// Arguments that failed validation, with the message for each invalid field.
+    pub fn validation(field_errors: FieldErrors) -> Self {
+        let details = serde_json::to_value(&field_errors).unwrap_or_default();
+        Self::with_code("Validation failed", INVALID_INPUT).with_details(details)
+    }
+
+    /// The per-field messages of an error created with [`ActionError::validation`].
+    pub fn field_errors(&self) -> Option<FieldErrors> {
+        if self.code.as_deref() != Some(INVALID_INPUT) {
+            return None;
+        }
+        serde_json::from_value(self.details.clone()?).ok()
+    }
+
+    /// The target of an error created with [`redirect`].
+    pub fn redirect_url(&self) -> Option<&str> {
+        if self.code.as_deref() != Some(REDIRECT) {
+            return None;
+        }
+        self.details.as_ref()?.get("url")?.as_str()
+    }
+
+    pub fn is_too_many_requests(&self) -> bool {
+        self.code.as_deref() == Some(TOO_MANY_REQUESTS)
+    }
+
+    /// How long to wait before calling again, for errors from [`too_many_requests`].
+    pub fn retry_after(&self) -> Option<Duration> {
+        if !self.is_too_many_requests() {
+            return None;
+        }
+        let millis = self.details.as_ref()?.get("retry_after_ms")?.as_u64()?;
+        Some(Duration::from_millis(millis))
+    }
+}
+
+/// How a typed action failed, as seen by its caller.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypedActionError<E> {
+    /// The caller sent arguments the action cannot accept.
+    Validation(String),
+    /// The action ran and returned `E`.
+    Action(E),
+    /// The server failed, or the error did not match `E`.
+    Internal(String),
+}
+
+impl<E: DeserializeOwned> From<ActionError> for TypedActionError<E> {
+    fn from(error: ActionError) -> Self {
+        match error.code.as_deref() {
+            Some(INVALID_INPUT) => Self::Validation(error.message),
+            Some(ACTION_FAILED) => error
+                .details
+                .and_then(|details| serde_json::from_value(details).ok())
+                .map_or(Self::Internal(error.message), Self::Action),
+            _ => Self::Internal(error.message),
+        }
+    }
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for TypedActionError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Validation(message) => write!(f, "invalid input: {}", message),
+            Self::Action(error) => write!(f, "{}", error),
+            Self::Internal(message) => write!(f, "internal error: {}", message),
         }
     }
 }
@@ -91,6 +215,12 @@ pub struct ActionResponse {
     pub success: bool,
     pub data: Option<serde_json::Value>,
     pub error: Option<ActionError>,
+    /// Where the client navigates after an action that called [`redirect`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redirect: Option<String>,
+    /// Messages for the invalid fields of a form, when validation failed.
+    #[serde(default, skip_serializing_if = "FieldErrors::is_empty")]
+    pub field_errors: FieldErrors,
 }
 
 impl ActionResponse {
@@ -99,15 +229,61 @@ impl ActionResponse {
             success: true,
             data: serde_json::to_value(data).ok(),
             error: None,
+            redirect: None,
+            field_errors: FieldErrors::new(),
         }
     }
 
+    /// A failed response, or a redirect if `error` came from [`redirect`].
     pub fn error(error: ActionError) -> Self {
+        if let Some(url) = error.redirect_url() {
+            return Self::redirect(url);
+        }
         Self {
             success: false,
             data: None,
+            field_errors: error.field_errors().unwrap_or_default(),
             error: Some(error),
+            redirect: None,
+        }
+    }
+
+    pub fn redirect(url: impl Into<String>) -> Self {
+        Self {
+            success: true,
+            data: None,
+            error: None,
+            redirect: Some(url.into()),
+            field_errors: FieldErrors::new(),
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.error
+            .as_ref()
+            .is_some_and(|e| e.code.as_deref() == Some(NOT_FOUND))
+    }
+
+    pub fn is_too_many_requests(&self) -> bool {
+        self.error
+            .as_ref()
+            .is_some_and(ActionError::is_too_many_requests)
+    }
+
+    /// Decodes the response of an action registered with `ActionRegistry::register_typed`.
+    pub fn into_typed<T, E>(self) -> Result<T, TypedActionError<E>>
+    where
+        T: DeserializeOwned,
+        E: DeserializeOwned,
+    {
+        if !self.success {
+            let error = self
+                .error
+                .unwrap_or_else(|| ActionError::new("action failed"));
+            return Err(error.into());
         }
+        serde_json::from_value(self.data.unwrap_or(serde_json::Value::Null))
+            .map_err(|e| TypedActionError::Internal(format!("invalid action output: {}", e)))
     }
 }
 
@@ -141,6 +317,20 @@ mod tests {
         assert!(response.error.is_some());
     }
 
+    #[test]
+    fn test_redirect_and_not_found_responses() {
+        let response = ActionResponse::error(redirect("/posts/7"));
+        assert!(response.success);
+        assert_eq!(response.redirect.as_deref(), Some("/posts/7"));
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["redirect"], "/posts/7");
+
+        let response = ActionResponse::error(not_found());
+        assert!(!response.success);
+        assert!(response.is_not_found());
+        assert!(response.redirect.is_none());
+    }
+
     #[tokio::test]
     async fn test_action_call() {
         let action = Action::new("test-action", |input: String| {
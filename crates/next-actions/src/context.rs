@@ -0,0 +1,154 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use crate::csrf::cookie_value;
+use crate::multipart::UploadedFile;
+use crate::stream::ProgressSender;
+
+/// The HTTP request an action was called from, plus whatever middleware attached to it
+/// (the signed-in user, the tenant, ...).
+#[derive(Clone, Default)]
+pub struct ActionContext {
+    /// Keyed by lowercase name.
+    headers: HashMap<String, String>,
+    extensions: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+    progress: ProgressSender,
+    /// The files uploaded with this request; the only ones the action's input can refer to.
+    uploads: Vec<UploadedFile>,
+    /// Shared by every clone, so the server sees what the action asked for.
+    revalidation: Arc<Mutex<Revalidation>>,
+}
+
+/// Cached pages an action invalidated, applied by the server before it responds.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Revalidation {
+    pub paths: Vec<String>,
+    pub tags: Vec<String>,
+}
+
+impl Revalidation {
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty() && self.tags.is_empty()
+    }
+}
+
+impl ActionContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a request header. Repeated headers are joined as HTTP allows.
+    pub fn with_header(mut self, name: &str, value: impl Into<String>) -> Self {
+        let name = name.to_ascii_lowercase();
+        let separator = if name == "cookie" { "; " } else { ", " };
+        let value = value.into();
+        self.headers
+            .entry(name)
+            .and_modify(|existing| {
+                existing.push_str(separator);
+                existing.push_str(&value);
+            })
+            .or_insert(value);
+        self
+    }
+
+    /// Adds the files uploaded with the request, e.g. those of its `FormData`.
+    pub fn with_uploads<'a>(mut self, uploads: impl IntoIterator<Item = &'a UploadedFile>) -> Self {
+        self.uploads.extend(uploads.into_iter().cloned());
+        self
+    }
+
+    pub fn uploads(&self) -> &[UploadedFile] {
+        &self.uploads
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
+    pub fn cookie(&self, name: &str) -> Option<&str> {
+        cookie_value(self.header("cookie")?, name)
+    }
+
+    /// Attaches `value` for middleware further down and the action, replacing any earlier
+    /// value of the same type.
+    pub fn insert<T: Send + Sync + 'static>(&mut self, value: T) {
+        self.extensions.insert(TypeId::of::<T>(), Arc::new(value));
+    }
+
+    pub fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.extensions.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    /// Reports progress when the action is called with streaming; see
+    /// `ActionRegistry::register_streaming`.
+    pub fn progress(&self) -> ProgressSender {
+        self.progress.clone()
+    }
+
+    /// Drops the cached render of `path`, e.g. `/posts` after creating a post.
+    pub fn revalidate_path(&self, path: impl Into<String>) {
+        let path = path.into();
+        let mut revalidation = self.revalidation.lock().unwrap();
+        if !revalidation.paths.contains(&path) {
+            revalidation.paths.push(path);
+        }
+    }
+
+    /// Drops every cached render of routes tagged `tag`.
+    pub fn revalidate_tag(&self, tag: impl Into<String>) {
+        let tag = tag.into();
+        let mut revalidation = self.revalidation.lock().unwrap();
+        if !revalidation.tags.contains(&tag) {
+            revalidation.tags.push(tag);
+        }
+    }
+
+    /// What the action revalidated so far.
+    pub fn revalidation(&self) -> Revalidation {
+        self.revalidation.lock().unwrap().clone()
+    }
+
+    pub(crate) fn with_progress(mut self, progress: ProgressSender) -> Self {
+        self.progress = progress;
+        self
+    }
+}
+
+impl fmt::Debug for ActionContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ActionContext")
+            .field("headers", &self.headers)
+            .field("extensions", &self.extensions.len())
+            .field("uploads", &self.uploads)
+            .field("revalidation", &self.revalidation())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_revalidation_is_shared_by_clones() {
+        let context = ActionContext::new();
+        let handed_to_action = context.clone();
+        handed_to_action.revalidate_path("/posts");
+        handed_to_action.revalidate_tag("posts");
+        handed_to_action.revalidate_tag("posts");
+
+        assert_eq!(
+            context.revalidation(),
+            Revalidation {
+                paths: vec!["/posts".to_string()],
+                tags: vec!["posts".to_string()],
+            }
+        );
+        assert!(ActionContext::new().revalidation().is_empty());
+    }
+}
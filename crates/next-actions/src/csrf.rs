@@ -0,0 +1,96 @@
+/// Cookie holding the CSRF token. Scripts on the page read it to send [`CSRF_HEADER`];
+/// other sites can neither read it nor guess it.
+pub const CSRF_COOKIE: &str = "__next_csrf";
+pub const CSRF_HEADER: &str = "X-CSRF-Token";
+/// Hidden form field carrying the token in forms posted without JS.
+pub const CSRF_FIELD: &str = "$csrf";
+
+/// What the action endpoint checks before running an action.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CsrfProtection {
+    /// The request comes from this site's origin and echoes the token of its cookie.
+    #[default]
+    Full,
+    /// Only the origin is checked, e.g. for actions posted from cached pages.
+    OriginOnly,
+    /// Nothing is checked; for actions meant to be called cross-site.
+    Disabled,
+}
+
+/// 16 bytes from the OS random source, hex-encoded.
+pub(crate) fn random_token() -> String {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes).expect("the OS random source is unavailable");
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn generate_csrf_token() -> String {
+    random_token()
+}
+
+/// The `Set-Cookie` value storing `token`.
+pub fn csrf_cookie(token: &str) -> String {
+    format!("{}={}; Path=/; SameSite=Lax", CSRF_COOKIE, token)
+}
+
+/// The value of cookie `name` in a `Cookie` header.
+pub fn cookie_value<'a>(cookie_header: &'a str, name: &str) -> Option<&'a str> {
+    cookie_header.split(';').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        (key.trim() == name).then(|| value.trim())
+    })
+}
+
+/// Whether `origin` (an `Origin` or `Referer` header) names the same host as `host`.
+pub fn same_origin(origin: &str, host: &str) -> bool {
+    let authority = origin
+        .split_once("://")
+        .map_or(origin, |(_, rest)| rest)
+        .split('/')
+        .next()
+        .unwrap_or("");
+    !authority.is_empty() && authority.eq_ignore_ascii_case(host)
+}
+
+/// Compares tokens in constant time.
+pub fn tokens_match(expected: &str, submitted: &str) -> bool {
+    if expected.is_empty() || expected.len() != submitted.len() {
+        return false;
+    }
+    expected
+        .bytes()
+        .zip(submitted.bytes())
+        .fold(0, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_origin() {
+        assert!(same_origin("https://example.com", "example.com"));
+        assert!(same_origin(
+            "http://localhost:3000/posts/1",
+            "localhost:3000"
+        ));
+        assert!(!same_origin("https://evil.test", "example.com"));
+        assert!(!same_origin("https://example.com.evil.test", "example.com"));
+        assert!(!same_origin("null", "example.com"));
+    }
+
+    #[test]
+    fn test_tokens_and_cookies() {
+        let token = generate_csrf_token();
+        assert_eq!(token.len(), 32);
+        assert_ne!(token, generate_csrf_token());
+        assert!(tokens_match(&token, &token));
+        assert!(!tokens_match(&token, "guess"));
+        assert!(!tokens_match("", ""));
+
+        let header = format!("theme=dark; {}", csrf_cookie(&token));
+        assert_eq!(cookie_value(&header, CSRF_COOKIE), Some(token.as_str()));
+        assert_eq!(cookie_value("theme=dark", CSRF_COOKIE), None);
+    }
+}
@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 
+use crate::multipart::{self, MultipartError, MultipartLimits, Part, UploadedFile};
+
+/// Hidden form field carrying an action's bound arguments as JSON.
+pub const BOUND_ARGS_FIELD: &str = "$bound";
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FormData {
     fields: HashMap<String, FormValue>,
@@ -12,6 +18,7 @@ pub enum FormValue {
     Text(String),
     File(FileData),
     Multiple(Vec<String>),
+    Upload(UploadedFile),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +43,72 @@ impl FormData {
         self.fields.insert(key.into(), FormValue::Multiple(values));
     }
 
+    /// Adds a value, keeping earlier values for the same key (e.g. checkbox groups).
+    pub fn append(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        let value = value.into();
+        match self.fields.entry(key.into()) {
+            Entry::Vacant(entry) => {
+                entry.insert(FormValue::Text(value));
+            }
+            Entry::Occupied(mut entry) => match entry.get_mut() {
+                FormValue::Multiple(values) => values.push(value),
+                FormValue::Text(first) => {
+                    let first = std::mem::take(first);
+                    entry.insert(FormValue::Multiple(vec![first, value]));
+                }
+                FormValue::File(_) | FormValue::Upload(_) => {
+                    entry.insert(FormValue::Text(value));
+                }
+            },
+        }
+    }
+
+    /// Parses an `application/x-www-form-urlencoded` body, as posted by forms without JS.
+    pub fn from_urlencoded(body: &str) -> Self {
+        let mut form = Self::new();
+        for pair in body.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            form.append(percent_decode(key), percent_decode(value));
+        }
+        form
+    }
+
+    /// Parses a `multipart/form-data` body, storing file parts as uploads. A field holds
+    /// one file; a later file with the same name replaces it.
+    pub fn from_multipart(
+        body: &[u8],
+        boundary: &str,
+        limits: &MultipartLimits,
+    ) -> Result<Self, MultipartError> {
+        let mut form = Self::new();
+        for part in multipart::parse(body, boundary, limits)? {
+            match part {
+                Part::Text { name, value } => form.append(name, value),
+                Part::File { name, file } => form.set_upload(name, file),
+            }
+        }
+        Ok(form)
+    }
+
+    pub fn set_upload(&mut self, key: impl Into<String>, file: UploadedFile) {
+        self.fields.insert(key.into(), FormValue::Upload(file));
+    }
+
+    pub fn get_upload(&self, key: &str) -> Option<&UploadedFile> {
+        match self.fields.get(key) {
+            Some(FormValue::Upload(f)) => Some(f),
+            _ => None,
+        }
+    }
+
+    /// Every upload in the form, for [`ActionContext::with_uploads`](crate::ActionContext::with_uploads).
+    pub fn uploads(&self) -> impl Iterator<Item = &UploadedFile> {
+        self.fields.values().filter_map(|value| match value {
+            FormValue::Upload(f) => Some(f),
+            _ => None,
+        })
+    }
+
     pub fn set_file(&mut self, key: impl Into<String>, file: FileData) {
         self.fields.insert(key.into(), FormValue::File(file));
     }
@@ -81,11 +154,63 @@ impl FormData {
                     "size": f.size,
                     "contentType": f.content_type,
                 }),
+                FormValue::Upload(f) => f.handle(),
             };
             map.insert(key.clone(), json_value);
         }
         serde_json::Value::Object(map)
     }
+
+    /// The JSON payload passed to a form's action. Bound arguments from [`BOUND_ARGS_FIELD`]
+    /// are merged in when they are an object, otherwise kept under that key; the CSRF
+    /// token field is left out.
+    pub fn to_action_payload(&self) -> serde_json::Value {
+        let mut payload = self.to_json();
+        let map = match payload.as_object_mut() {
+            Some(map) => map,
+            None => return payload,
+        };
+        map.remove(crate::csrf::CSRF_FIELD);
+        let bound = match map.remove(BOUND_ARGS_FIELD) {
+            Some(serde_json::Value::String(json)) => serde_json::from_str(&json).ok(),
+            _ => None,
+        };
+        match bound {
+            Some(serde_json::Value::Object(args)) => map.extend(args),
+            Some(other) => {
+                map.insert(BOUND_ARGS_FIELD.to_string(), other);
+            }
+            None => {}
+        }
+        payload
+    }
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                match (hex_value(bytes[i + 1]), hex_value(bytes[i + 2])) {
+                    (Some(high), Some(low)) => {
+                        decoded.push(high << 4 | low);
+                        i += 2;
+                    }
+                    _ => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|d| d as u8)
 }
 
 #[derive(Debug, Clone)]
@@ -186,4 +311,106 @@ mod tests {
         let json = form.to_json();
         assert_eq!(json["name"], "Test");
     }
+
+    #[test]
+    fn test_form_data_from_urlencoded() {
+        let form = FormData::from_urlencoded("title=Hello+world%21&tags=a&tags=b&note=");
+
+        assert_eq!(form.get("title"), Some("Hello world!"));
+        assert_eq!(form.get_all("tags"), vec!["a", "b"]);
+        assert_eq!(form.get("note"), Some(""));
+    }
+
+    #[test]
+    fn test_form_data_from_multipart() {
+        let body = "--XyZ\r\n\
+            Content-Disposition: form-data; name=\"title\"\r\n\r\n\
+            Holiday\r\n\
+            --XyZ\r\n\
+            Content-Disposition: form-data; name=\"photo\"; filename=\"beach.png\"\r\n\
+            Content-Type: image/png\r\n\r\n\
+            \x7fPNG\x00\r\n\
+            --XyZ\r\n\
+            Content-Disposition: form-data; name=\"extra\"; filename=\"\"\r\n\r\n\
+            \r\n\
+            --XyZ--\r\n";
+        let form =
+            FormData::from_multipart(body.as_bytes(), "XyZ", &MultipartLimits::default()).unwrap();
+
+        assert_eq!(form.get("title"), Some("Holiday"));
+        assert!(form.get_upload("extra").is_none());
+        let photo = form.get_upload("photo").unwrap();
+        assert_eq!(photo.file_name(), "beach.png");
+        assert_eq!(photo.content_type(), "image/png");
+        assert_eq!(photo.read().unwrap(), b"\x7fPNG\0");
+
+        // The action receives a handle it can resolve back to the upload, but only while
+        // the uploads of this request are the ones in scope.
+        let payload = form.to_action_payload();
+        assert!(serde_json::from_value::<UploadedFile>(payload["photo"].clone()).is_err());
+        let uploads: Vec<_> = form.uploads().cloned().collect();
+        let handle: UploadedFile = multipart::with_request_uploads(&uploads, || {
+            serde_json::from_value(payload["photo"].clone())
+        })
+        .unwrap();
+        assert!(serde_json::to_value(&handle)
+            .unwrap()
+            .get("upload")
+            .is_none());
+        let temp = handle.path().unwrap();
+        let dest = std::env::temp_dir().join(format!("persisted-{}", photo.size()));
+        handle.persist(&dest).unwrap();
+        assert!(photo.path().is_none());
+        assert!(!temp.exists());
+        assert!(dest.exists());
+        std::fs::remove_file(dest).unwrap();
+
+        let forged = serde_json::json!({"upload": "0000", "name": "passwd"});
+        assert!(multipart::with_request_uploads(&uploads, || {
+            serde_json::from_value::<UploadedFile>(forged)
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_multipart_limits_and_cleanup() {
+        let body = "--b\r\n\
+            Content-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\n\r\n\
+            0123456789\r\n\
+            --b--\r\n";
+        let limits = MultipartLimits::default().max_file_size(4);
+        let err = FormData::from_multipart(body.as_bytes(), "b", &limits).unwrap_err();
+        assert!(err.is_limit());
+
+        let form =
+            FormData::from_multipart(body.as_bytes(), "b", &MultipartLimits::default()).unwrap();
+        let temp = form.get_upload("doc").unwrap().path().unwrap();
+        assert!(temp.exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&temp).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        drop(form);
+        assert!(!temp.exists());
+
+        assert_eq!(
+            multipart::multipart_boundary("multipart/form-data; boundary=\"b\""),
+            Some("b")
+        );
+        assert_eq!(multipart::multipart_boundary("text/plain"), None);
+    }
+
+    #[test]
+    fn test_action_payload_merges_bound_args() {
+        let mut form = FormData::new();
+        form.set("title", "Post");
+        form.set(BOUND_ARGS_FIELD, r#"{"post_id":7}"#);
+
+        let payload = form.to_action_payload();
+        assert_eq!(payload["title"], "Post");
+        assert_eq!(payload["post_id"], 7);
+        assert!(payload.get(BOUND_ARGS_FIELD).is_none());
+    }
 }
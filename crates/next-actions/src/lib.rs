@@ -1,7 +1,34 @@
 mod action;
+mod context;
+mod csrf;
 mod form;
+mod limits;
+mod middleware;
+mod multipart;
 mod registry;
+mod stream;
+mod validate;
 
-pub use action::{Action, ActionError, ActionRequest, ActionResponse, ActionResult, ServerAction};
-pub use form::{FormAction, FormData};
-pub use registry::ActionRegistry;
+pub use action::{
+    not_found, redirect, too_many_requests, Action, ActionError, ActionRequest, ActionResponse,
+    ActionResult, ServerAction, TypedActionError, ACTION_FAILED, INTERNAL_ERROR, INVALID_INPUT,
+    NOT_FOUND, REDIRECT, TOO_MANY_REQUESTS,
+};
+pub use context::{ActionContext, Revalidation};
+pub use csrf::{
+    cookie_value, csrf_cookie, generate_csrf_token, same_origin, tokens_match, CsrfProtection,
+    CSRF_COOKIE, CSRF_FIELD, CSRF_HEADER,
+};
+pub use form::{FormAction, FormData, BOUND_ARGS_FIELD};
+pub use limits::ActionLimits;
+pub use middleware::Next;
+pub use multipart::{multipart_boundary, MultipartError, MultipartLimits, UploadedFile};
+pub use registry::{ActionRegistry, ServerActionEntry};
+pub use stream::{
+    ActionStream, ActionStreamDecoder, ActionStreamEvent, ProgressSender,
+    ACTION_STREAM_CONTENT_TYPE,
+};
+pub use validate::{FieldErrors, FieldRules, Validate, Validation};
+
+#[doc(hidden)]
+pub use inventory;
@@ -0,0 +1,151 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::action::{too_many_requests, ActionError};
+
+/// Protects an expensive action; calls over a limit fail with [`too_many_requests`]. Built
+/// with [`ActionLimits::rate`] and [`ActionLimits::max_concurrent`], which check their values.
+///
+/// ```rust,ignore
+/// registry.set_limits(
+///     "send_invite",
+///     ActionLimits::new().rate(10, Duration::from_secs(60)).max_concurrent(2),
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ActionLimits {
+    /// At most `.0` calls per `.1`, with bursts up to `.0`.
+    rate: Option<(u32, Duration)>,
+    max_concurrent: Option<usize>,
+}
+
+impl ActionLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows `calls` calls per `per`.
+    ///
+    /// # Panics
+    ///
+    /// If `calls` or `per` is zero; such a limit would reject every call.
+    pub fn rate(mut self, calls: u32, per: Duration) -> Self {
+        assert!(
+            calls > 0 && !per.is_zero(),
+            "ActionLimits::rate needs at least one call per non-zero duration, got {} per {:?}",
+            calls,
+            per
+        );
+        self.rate = Some((calls, per));
+        self
+    }
+
+    pub fn max_concurrent(mut self, calls: usize) -> Self {
+        self.max_concurrent = Some(calls);
+        self
+    }
+}
+
+/// The limits of one action and the calls counted against them.
+pub(crate) struct Limiter {
+    limits: ActionLimits,
+    /// Calls that may start right now; refills continuously up to the rate's burst.
+    tokens: Mutex<(f64, Instant)>,
+    running: Arc<AtomicUsize>,
+}
+
+/// Counts a running call until dropped.
+pub(crate) struct Permit {
+    running: Arc<AtomicUsize>,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.running.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl Limiter {
+    pub(crate) fn new(limits: ActionLimits) -> Self {
+        let burst = limits.rate.map_or(0.0, |(calls, _)| f64::from(calls));
+        Self {
+            limits,
+            tokens: Mutex::new((burst, Instant::now())),
+            running: Arc::default(),
+        }
+    }
+
+    pub(crate) fn acquire(&self) -> Result<Permit, ActionError> {
+        let max = self.limits.max_concurrent.unwrap_or(usize::MAX);
+        self.running
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |running| {
+                (running < max).then_some(running + 1)
+            })
+            .map_err(|_| too_many_requests(None))?;
+        // Held from here on, so a call rejected by the rate limit stops counting as running.
+        let permit = Permit {
+            running: self.running.clone(),
+        };
+
+        if let Some((calls, per)) = self.limits.rate {
+            let calls = f64::from(calls);
+            let mut tokens = self.tokens.lock().unwrap();
+            let (available, refilled_at) = *tokens;
+            let now = Instant::now();
+            let refill = now.duration_since(refilled_at).as_secs_f64() / per.as_secs_f64();
+            let available = (available + refill * calls).min(calls);
+            if available < 1.0 {
+                *tokens = (available, now);
+                let wait = per.mul_f64((1.0 - available) / calls);
+                return Err(too_many_requests(Some(wait)));
+            }
+            *tokens = (available - 1.0, now);
+        }
+        Ok(permit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit_refills_over_time() {
+        let limiter = Limiter::new(ActionLimits::new().rate(2, Duration::from_millis(40)));
+        assert!(limiter.acquire().is_ok());
+        assert!(limiter.acquire().is_ok());
+
+        let error = limiter.acquire().err().unwrap();
+        assert!(error.is_too_many_requests());
+        let wait = error.retry_after().unwrap();
+        assert!(wait > Duration::ZERO && wait <= Duration::from_millis(20));
+
+        std::thread::sleep(Duration::from_millis(25));
+        assert!(limiter.acquire().is_ok());
+    }
+
+    #[test]
+    fn test_concurrency_cap_counts_running_calls() {
+        let limiter = Limiter::new(ActionLimits::new().max_concurrent(1));
+        let first = limiter.acquire().unwrap();
+        let error = limiter.acquire().err().unwrap();
+        assert!(error.is_too_many_requests());
+        assert_eq!(error.retry_after(), None);
+
+        drop(first);
+        assert!(limiter.acquire().is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one call")]
+    fn test_rate_rejects_zero_calls() {
+        let _ = ActionLimits::new().rate(0, Duration::from_secs(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "non-zero duration")]
+    fn test_rate_rejects_zero_duration() {
+        let _ = ActionLimits::new().rate(5, Duration::ZERO);
+    }
+}
@@ -0,0 +1,70 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::action::{ActionRequest, ActionResult};
+use crate::context::ActionContext;
+use crate::limits::Limiter;
+use crate::stream::ActionFuture;
+
+pub(crate) type BoxedHandler = Box<dyn Fn(Value, ActionContext) -> ActionFuture + Send + Sync>;
+pub(crate) type BoxedMiddleware =
+    Arc<dyn Fn(ActionRequest, ActionContext, Next) -> ActionFuture + Send + Sync>;
+
+/// The rest of an action call: the remaining middleware, then the action itself.
+pub struct Next {
+    middleware: Arc<Vec<BoxedMiddleware>>,
+    index: usize,
+    handler: Arc<BoxedHandler>,
+    limiter: Option<Arc<Limiter>>,
+}
+
+impl Next {
+    pub(crate) fn new(
+        middleware: Arc<Vec<BoxedMiddleware>>,
+        handler: Arc<BoxedHandler>,
+        limiter: Option<Arc<Limiter>>,
+    ) -> Self {
+        Self {
+            middleware,
+            index: 0,
+            handler,
+            limiter,
+        }
+    }
+
+    /// Continues the call with `request` and `context`, both possibly changed.
+    pub fn run(
+        self,
+        request: ActionRequest,
+        context: ActionContext,
+    ) -> impl Future<Output = ActionResult<Value>> + Send {
+        self.call(request, context)
+    }
+
+    pub(crate) fn call(self, request: ActionRequest, context: ActionContext) -> ActionFuture {
+        match self.middleware.get(self.index).cloned() {
+            Some(middleware) => {
+                let next = Self {
+                    index: self.index + 1,
+                    ..self
+                };
+                middleware(request, context, next)
+            }
+            // Limits apply after middleware, so calls it rejects do not count against them.
+            None => match self.limiter.as_ref().map(|limiter| limiter.acquire()) {
+                Some(Ok(permit)) => {
+                    let call = (self.handler)(request.payload, context);
+                    Box::pin(async move {
+                        let result = call.await;
+                        drop(permit);
+                        result
+                    })
+                }
+                Some(Err(error)) => Box::pin(async move { Err(error) }),
+                None => (self.handler)(request.payload, context),
+            },
+        }
+    }
+}
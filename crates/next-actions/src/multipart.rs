@@ -0,0 +1,370 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cell::RefCell;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Size limits applied while parsing a multipart form submission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MultipartLimits {
+    pub max_file_size: u64,
+    pub max_total_size: u64,
+    pub max_files: usize,
+}
+
+impl Default for MultipartLimits {
+    fn default() -> Self {
+        Self {
+            max_file_size: 10 * 1024 * 1024,
+            max_total_size: 50 * 1024 * 1024,
+            max_files: 16,
+        }
+    }
+}
+
+impl MultipartLimits {
+    pub fn max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = bytes;
+        self
+    }
+
+    pub fn max_total_size(mut self, bytes: u64) -> Self {
+        self.max_total_size = bytes;
+        self
+    }
+
+    pub fn max_files(mut self, count: usize) -> Self {
+        self.max_files = count;
+        self
+    }
+}
+
+#[derive(Debug)]
+pub enum MultipartError {
+    /// The body exceeds `max_total_size`.
+    BodyTooLarge {
+        limit: u64,
+    },
+    FileTooLarge {
+        field: String,
+        limit: u64,
+    },
+    TooManyFiles {
+        limit: usize,
+    },
+    Malformed(String),
+    Io(io::Error),
+}
+
+impl MultipartError {
+    /// Whether the submission was rejected by a limit rather than being invalid.
+    pub fn is_limit(&self) -> bool {
+        matches!(
+            self,
+            Self::BodyTooLarge { .. } | Self::FileTooLarge { .. } | Self::TooManyFiles { .. }
+        )
+    }
+}
+
+impl fmt::Display for MultipartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BodyTooLarge { limit } => write!(f, "form body exceeds {} bytes", limit),
+            Self::FileTooLarge { field, limit } => {
+                write!(f, "file in field '{}' exceeds {} bytes", field, limit)
+            }
+            Self::TooManyFiles { limit } => write!(f, "more than {} files uploaded", limit),
+            Self::Malformed(message) => write!(f, "malformed multipart body: {}", message),
+            Self::Io(e) => write!(f, "failed to store upload: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for MultipartError {}
+
+impl From<io::Error> for MultipartError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// The boundary of a `multipart/form-data` content type, if it is one.
+pub fn multipart_boundary(content_type: &str) -> Option<&str> {
+    let (mime, params) = content_type.split_once(';')?;
+    if !mime.trim().eq_ignore_ascii_case("multipart/form-data") {
+        return None;
+    }
+    params.split(';').find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case("boundary")
+            .then(|| value.trim().trim_matches('"'))
+    })
+}
+
+pub(crate) enum Part {
+    Text { name: String, value: String },
+    File { name: String, file: UploadedFile },
+}
+
+pub(crate) fn parse(
+    body: &[u8],
+    boundary: &str,
+    limits: &MultipartLimits,
+) -> Result<Vec<Part>, MultipartError> {
+    if body.len() as u64 > limits.max_total_size {
+        return Err(MultipartError::BodyTooLarge {
+            limit: limits.max_total_size,
+        });
+    }
+    let delimiter = format!("--{}", boundary);
+    let delimiter = delimiter.as_bytes();
+
+    let mut rest = match find(body, delimiter) {
+        Some(start) => &body[start + delimiter.len()..],
+        None => return Err(MultipartError::Malformed("missing boundary".into())),
+    };
+    let mut parts = Vec::new();
+    let mut files = 0;
+    while !rest.starts_with(b"--") {
+        rest = rest
+            .strip_prefix(b"\r\n")
+            .ok_or_else(|| MultipartError::Malformed("expected CRLF after boundary".into()))?;
+        let end = find(rest, delimiter)
+            .ok_or_else(|| MultipartError::Malformed("unterminated part".into()))?;
+        let part = rest[..end]
+            .strip_suffix(b"\r\n")
+            .ok_or_else(|| MultipartError::Malformed("expected CRLF before boundary".into()))?;
+        rest = &rest[end + delimiter.len()..];
+
+        let header_end = find(part, b"\r\n\r\n")
+            .ok_or_else(|| MultipartError::Malformed("part without headers".into()))?;
+        let headers = String::from_utf8_lossy(&part[..header_end]);
+        let content = &part[header_end + 4..];
+
+        let mut name = None;
+        let mut filename = None;
+        let mut content_type = None;
+        for line in headers.split("\r\n") {
+            let Some((header, value)) = line.split_once(':') else {
+                continue;
+            };
+            if header.trim().eq_ignore_ascii_case("content-disposition") {
+                name = disposition_param(value, "name");
+                filename = disposition_param(value, "filename");
+            } else if header.trim().eq_ignore_ascii_case("content-type") {
+                content_type = Some(value.trim().to_string());
+            }
+        }
+        let name = name.ok_or_else(|| MultipartError::Malformed("part without a name".into()))?;
+
+        match filename {
+            // A file input left empty still submits an empty part without a filename.
+            Some(filename) if filename.is_empty() && content.is_empty() => {}
+            Some(filename) => {
+                files += 1;
+                if files > limits.max_files {
+                    return Err(MultipartError::TooManyFiles {
+                        limit: limits.max_files,
+                    });
+                }
+                if content.len() as u64 > limits.max_file_size {
+                    return Err(MultipartError::FileTooLarge {
+                        field: name,
+                        limit: limits.max_file_size,
+                    });
+                }
+                let content_type =
+                    content_type.unwrap_or_else(|| "application/octet-stream".to_string());
+                let file = UploadedFile::store(filename, content_type, content)?;
+                parts.push(Part::File { name, file });
+            }
+            None => parts.push(Part::Text {
+                name,
+                value: String::from_utf8_lossy(content).into_owned(),
+            }),
+        }
+    }
+    Ok(parts)
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn disposition_param(value: &str, key: &str) -> Option<String> {
+    value.split(';').skip(1).find_map(|param| {
+        let (k, v) = param.split_once('=')?;
+        (k.trim() == key).then(|| v.trim().trim_matches('"').to_string())
+    })
+}
+
+/// An uploaded file, stored in a temp file until the action persists it. Files still in
+/// the temp directory are removed once the last handle is dropped, after the action ran.
+///
+/// In an action's payload a file is `{"upload": <id>, "name", "size", "contentType"}`;
+/// deserializing it yields the handle for an upload of the current request, and fails for
+/// any other id. Serializing it, e.g. in an action's result, leaves the id out.
+#[derive(Clone)]
+pub struct UploadedFile {
+    inner: Arc<Upload>,
+}
+
+struct Upload {
+    id: String,
+    file_name: String,
+    content_type: String,
+    size: u64,
+    /// `None` once persisted or discarded.
+    path: Mutex<Option<PathBuf>>,
+}
+
+thread_local! {
+    /// The uploads of the request whose action input is being deserialized.
+    static REQUEST_UPLOADS: RefCell<Vec<UploadedFile>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Runs `f` with `uploads` as the only ones upload handles resolve to.
+pub(crate) fn with_request_uploads<R>(uploads: &[UploadedFile], f: impl FnOnce() -> R) -> R {
+    let outer = REQUEST_UPLOADS.with(|current| current.replace(uploads.to_vec()));
+    let result = f();
+    REQUEST_UPLOADS.with(|current| *current.borrow_mut() = outer);
+    result
+}
+
+impl UploadedFile {
+    fn store(file_name: String, content_type: String, data: &[u8]) -> io::Result<Self> {
+        let id = crate::csrf::random_token();
+        let path = std::env::temp_dir().join(format!("next-rs-upload-{}", id));
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        io::Write::write_all(&mut options.open(&path)?, data)?;
+        Ok(Self {
+            inner: Arc::new(Upload {
+                id,
+                file_name,
+                content_type,
+                size: data.len() as u64,
+                path: Mutex::new(Some(path)),
+            }),
+        })
+    }
+
+    /// The payload value an action deserializes back into this upload.
+    pub(crate) fn handle(&self) -> serde_json::Value {
+        serde_json::json!({
+            "upload": self.inner.id,
+            "name": self.inner.file_name,
+            "size": self.inner.size,
+            "contentType": self.inner.content_type,
+        })
+    }
+
+    /// The file name sent by the browser; never use it as a path unchecked.
+    pub fn file_name(&self) -> &str {
+        &self.inner.file_name
+    }
+
+    pub fn content_type(&self) -> &str {
+        &self.inner.content_type
+    }
+
+    pub fn size(&self) -> u64 {
+        self.inner.size
+    }
+
+    /// The temp file, until the upload is persisted or discarded.
+    pub fn path(&self) -> Option<PathBuf> {
+        self.inner.path.lock().unwrap().clone()
+    }
+
+    pub fn read(&self) -> io::Result<Vec<u8>> {
+        match self.path() {
+            Some(path) => std::fs::read(path),
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "upload was already persisted or discarded",
+            )),
+        }
+    }
+
+    /// Moves the upload to `dest`, keeping it past the request.
+    pub fn persist(&self, dest: impl AsRef<Path>) -> io::Result<()> {
+        let mut path = self.inner.path.lock().unwrap();
+        let Some(temp) = path.as_ref() else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "upload was already persisted or discarded",
+            ));
+        };
+        if std::fs::rename(temp, dest.as_ref()).is_err() {
+            // The temp dir may be on another filesystem.
+            std::fs::copy(temp, dest.as_ref())?;
+            let _ = std::fs::remove_file(temp);
+        }
+        *path = None;
+        Ok(())
+    }
+
+    /// Removes the upload now instead of at the end of the request.
+    pub fn discard(&self) -> io::Result<()> {
+        match self.inner.path.lock().unwrap().take() {
+            Some(temp) => std::fs::remove_file(temp),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for Upload {
+    fn drop(&mut self) {
+        if let Some(temp) = self.path.get_mut().unwrap().take() {
+            let _ = std::fs::remove_file(temp);
+        }
+    }
+}
+
+impl fmt::Debug for UploadedFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UploadedFile")
+            .field("file_name", &self.inner.file_name)
+            .field("content_type", &self.inner.content_type)
+            .field("size", &self.inner.size)
+            .finish()
+    }
+}
+
+impl Serialize for UploadedFile {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde_json::json!({
+            "name": self.inner.file_name,
+            "size": self.inner.size,
+            "contentType": self.inner.content_type,
+        })
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for UploadedFile {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Handle {
+            upload: String,
+        }
+
+        let handle = Handle::deserialize(deserializer)?;
+        REQUEST_UPLOADS
+            .with(|current| {
+                current
+                    .borrow()
+                    .iter()
+                    .find(|file| file.inner.id == handle.upload)
+                    .cloned()
+            })
+            .ok_or_else(|| serde::de::Error::custom("unknown upload"))
+    }
+}
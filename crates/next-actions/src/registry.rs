@@ -3,50 +3,118 @@ use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 
-use crate::action::{ActionError, ActionRequest, ActionResponse, ActionResult};
+use crate::action::{
+    ActionError, ActionRequest, ActionResponse, ActionResult, ACTION_FAILED, INTERNAL_ERROR,
+    INVALID_INPUT,
+};
+use crate::context::ActionContext;
+use crate::csrf::CsrfProtection;
+use crate::limits::{ActionLimits, Limiter};
+use crate::middleware::{BoxedHandler, BoxedMiddleware, Next};
+use crate::multipart;
+use crate::stream::{ActionStream, ProgressSender};
+use crate::validate::Validate;
 
-type BoxedHandler = Box<
-    dyn Fn(
-            serde_json::Value,
-        ) -> Pin<Box<dyn Future<Output = ActionResult<serde_json::Value>> + Send>>
-        + Send
-        + Sync,
->;
+/// A server action defined with `#[server_action]`, collected at link time.
+pub struct ServerActionEntry {
+    id: &'static str,
+    register: fn(&mut ActionRegistry),
+}
+
+impl ServerActionEntry {
+    pub const fn new(id: &'static str, register: fn(&mut ActionRegistry)) -> Self {
+        Self { id, register }
+    }
+
+    pub fn id(&self) -> &'static str {
+        self.id
+    }
+}
+
+inventory::collect!(ServerActionEntry);
 
 pub struct ActionRegistry {
     handlers: HashMap<String, Arc<BoxedHandler>>,
+    middleware: Arc<Vec<BoxedMiddleware>>,
+    csrf: HashMap<String, CsrfProtection>,
+    limits: HashMap<String, Arc<Limiter>>,
 }
 
 impl ActionRegistry {
     pub fn new() -> Self {
         Self {
             handlers: HashMap::new(),
+            middleware: Arc::default(),
+            csrf: HashMap::new(),
+            limits: HashMap::new(),
         }
     }
 
+    /// A registry holding every `#[server_action]` linked into the binary.
+    pub fn with_server_actions() -> Self {
+        let mut registry = Self::new();
+        for entry in inventory::iter::<ServerActionEntry> {
+            (entry.register)(&mut registry);
+        }
+        registry
+    }
+
     pub fn register<F, Fut, I, O>(&mut self, action_id: impl Into<String>, handler: F)
     where
         F: Fn(I) -> Fut + Send + Sync + 'static,
         Fut: Future<Output = ActionResult<O>> + Send + 'static,
         I: for<'de> serde::Deserialize<'de> + Send + 'static,
         O: serde::Serialize + Send + 'static,
+    {
+        self.register_with_context(action_id, move |input: I, _: ActionContext| handler(input));
+    }
+
+    /// Registers a long-running action that reports progress while it runs. Called through
+    /// [`ActionRegistry::execute_streaming`] its progress events reach the client before
+    /// the response; [`ActionRegistry::execute`] drops them.
+    pub fn register_streaming<F, Fut, I, O>(&mut self, action_id: impl Into<String>, handler: F)
+    where
+        F: Fn(I, ProgressSender) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ActionResult<O>> + Send + 'static,
+        I: for<'de> serde::Deserialize<'de> + Send + 'static,
+        O: serde::Serialize + Send + 'static,
+    {
+        self.register_with_context(action_id, move |input: I, context: ActionContext| {
+            handler(input, context.progress())
+        });
+    }
+
+    /// Registers an action that reads the request it was called from, or values attached
+    /// by middleware.
+    pub fn register_with_context<F, Fut, I, O>(&mut self, action_id: impl Into<String>, handler: F)
+    where
+        F: Fn(I, ActionContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ActionResult<O>> + Send + 'static,
+        I: for<'de> serde::Deserialize<'de> + Send + 'static,
+        O: serde::Serialize + Send + 'static,
     {
         let id = action_id.into();
-        let wrapped: BoxedHandler = Box::new(move |value: serde_json::Value| {
-            let input: Result<I, _> = serde_json::from_value(value);
+        let wrapped: BoxedHandler = Box::new(move |value: serde_json::Value, context| {
+            let input: Result<I, _> = multipart::with_request_uploads(context.uploads(), || {
+                serde_json::from_value(value)
+            });
             match input {
                 Ok(input) => {
-                    let future = handler(input);
+                    let future = handler(input, context);
                     Box::pin(async move {
                         let result = future.await?;
-                        serde_json::to_value(result)
-                            .map_err(|e| ActionError::new(format!("Serialization error: {}", e)))
+                        serde_json::to_value(result).map_err(|e| {
+                            ActionError::with_code(
+                                format!("Serialization error: {}", e),
+                                INTERNAL_ERROR,
+                            )
+                        })
                     })
                 }
                 Err(e) => Box::pin(async move {
                     Err(ActionError::with_code(
                         format!("Invalid input: {}", e),
-                        "INVALID_INPUT",
+                        INVALID_INPUT,
                     ))
                 }),
             }
@@ -55,28 +123,165 @@ impl ActionRegistry {
         self.handlers.insert(id, Arc::new(wrapped));
     }
 
+    /// Registers an action whose input is validated first. Invalid input never reaches
+    /// `handler`; the response carries the failures in `field_errors`.
+    pub fn register_validated<F, Fut, I, O>(&mut self, action_id: impl Into<String>, handler: F)
+    where
+        F: Fn(I) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ActionResult<O>> + Send + 'static,
+        I: for<'de> serde::Deserialize<'de> + Validate + Send + 'static,
+        O: serde::Serialize + Send + 'static,
+    {
+        self.register(
+            action_id,
+            move |input: I| -> Pin<Box<dyn Future<Output = ActionResult<O>> + Send>> {
+                match input.validate() {
+                    Ok(()) => Box::pin(handler(input)),
+                    Err(errors) => Box::pin(async move { Err(ActionError::validation(errors)) }),
+                }
+            },
+        );
+    }
+
+    /// Registers an action whose handler returns its own serializable error type. Callers
+    /// decode the response with `ActionResponse::into_typed`, which tells invalid
+    /// arguments and server failures apart from the action's error.
+    pub fn register_typed<Args, Ok, Err, F, Fut>(
+        &mut self,
+        action_id: impl Into<String>,
+        handler: F,
+    ) where
+        F: Fn(Args) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Ok, Err>> + Send + 'static,
+        Args: for<'de> serde::Deserialize<'de> + Send + 'static,
+        Ok: serde::Serialize + Send + 'static,
+        Err: serde::Serialize + std::fmt::Display + Send + 'static,
+    {
+        self.register(action_id, move |args: Args| {
+            let future = handler(args);
+            async move {
+                future.await.map_err(|error| {
+                    let failed = ActionError::with_code(error.to_string(), ACTION_FAILED);
+                    match serde_json::to_value(&error) {
+                        Ok(details) => failed.with_details(details),
+                        Err(e) => ActionError::with_code(
+                            format!("Serialization error: {}", e),
+                            INTERNAL_ERROR,
+                        ),
+                    }
+                })
+            }
+        });
+    }
+
+    /// Wraps every action call in `middleware`, in registration order. It may reject the
+    /// call by returning an error, or change the request and attach values to the context
+    /// before continuing with `next.run`.
+    ///
+    /// ```rust,ignore
+    /// registry.register_middleware(|request, mut context, next| async move {
+    ///     let user = match context.cookie("session").and_then(find_session) {
+    ///         Some(user) => user,
+    ///         None if request.action_id.starts_with("public::") => {
+    ///             return next.run(request, context).await
+    ///         }
+    ///         None => return Err(ActionError::with_code("Sign in first", "UNAUTHORIZED")),
+    ///     };
+    ///     context.insert(user);
+    ///     next.run(request, context).await
+    /// });
+    /// ```
+    pub fn register_middleware<F, Fut>(&mut self, middleware: F)
+    where
+        F: Fn(ActionRequest, ActionContext, Next) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ActionResult<serde_json::Value>> + Send + 'static,
+    {
+        let middleware: BoxedMiddleware =
+            Arc::new(move |request, context, next| Box::pin(middleware(request, context, next)));
+        Arc::make_mut(&mut self.middleware).push(middleware);
+    }
+
+    /// Overrides the CSRF checks for `action_id`, which default to [`CsrfProtection::Full`].
+    pub fn set_csrf_protection(&mut self, action_id: impl Into<String>, csrf: CsrfProtection) {
+        self.csrf.insert(action_id.into(), csrf);
+    }
+
+    pub fn csrf_protection(&self, action_id: &str) -> CsrfProtection {
+        self.csrf.get(action_id).copied().unwrap_or_default()
+    }
+
+    /// Caps how often and how many at once `action_id` runs. Calls over a limit fail with a
+    /// `TOO_MANY_REQUESTS` error, served as `429 Too Many Requests`.
+    pub fn set_limits(&mut self, action_id: impl Into<String>, limits: ActionLimits) {
+        self.limits
+            .insert(action_id.into(), Arc::new(Limiter::new(limits)));
+    }
+
     pub fn has(&self, action_id: &str) -> bool {
         self.handlers.contains_key(action_id)
     }
 
     pub async fn execute(&self, request: ActionRequest) -> ActionResponse {
+        self.execute_with_context(request, ActionContext::new())
+            .await
+    }
+
+    /// Runs the action through the registered middleware, for a call made with `context`.
+    pub async fn execute_with_context(
+        &self,
+        request: ActionRequest,
+        context: ActionContext,
+    ) -> ActionResponse {
         match self.handlers.get(&request.action_id) {
-            Some(handler) => match handler(request.payload).await {
+            Some(handler) => match self
+                .chain(&request.action_id, handler)
+                .call(request, context)
+                .await
+            {
                 Ok(data) => ActionResponse::success(data),
                 Err(error) => ActionResponse::error(error),
             },
-            None => ActionResponse::error(ActionError::with_code(
-                format!("Action '{}' not found", request.action_id),
-                "ACTION_NOT_FOUND",
-            )),
+            None => ActionResponse::error(action_not_found(&request.action_id)),
         }
     }
 
+    /// Runs the action like [`ActionRegistry::execute_with_context`], yielding its progress
+    /// events as they are sent and then its response. Actions registered without progress
+    /// only yield the response.
+    pub fn execute_streaming(
+        &self,
+        request: ActionRequest,
+        context: ActionContext,
+    ) -> ActionStream {
+        match self.handlers.get(&request.action_id) {
+            Some(handler) => {
+                let next = self.chain(&request.action_id, handler);
+                ActionStream::new(|progress| next.call(request, context.with_progress(progress)))
+            }
+            None => ActionStream::done(ActionResponse::error(action_not_found(&request.action_id))),
+        }
+    }
+
+    fn chain(&self, action_id: &str, handler: &Arc<BoxedHandler>) -> Next {
+        Next::new(
+            self.middleware.clone(),
+            handler.clone(),
+            self.limits.get(action_id).cloned(),
+        )
+    }
+
     pub fn action_ids(&self) -> impl Iterator<Item = &String> {
         self.handlers.keys()
     }
 }
 
+fn action_not_found(action_id: &str) -> ActionError {
+    ActionError::with_code(
+        format!("Action '{}' not found", action_id),
+        "ACTION_NOT_FOUND",
+    )
+}
+
 impl Default for ActionRegistry {
     fn default() -> Self {
         Self::new()
@@ -151,6 +356,215 @@ mod tests {
         assert!(response.error.is_some());
     }
 
+    #[tokio::test]
+    async fn test_registry_register_typed() {
+        use crate::action::TypedActionError;
+
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        enum TransferError {
+            InsufficientFunds { balance: u64 },
+        }
+
+        impl std::fmt::Display for TransferError {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "insufficient funds")
+            }
+        }
+
+        let mut registry = ActionRegistry::new();
+        registry.register_typed("transfer", |amount: u64| async move {
+            if amount > 100 {
+                Err(TransferError::InsufficientFunds { balance: 100 })
+            } else {
+                Ok(100 - amount)
+            }
+        });
+
+        let call = |payload| {
+            registry.execute(ActionRequest {
+                action_id: "transfer".to_string(),
+                payload,
+            })
+        };
+
+        let ok = call(serde_json::json!(40))
+            .await
+            .into_typed::<u64, TransferError>();
+        assert_eq!(ok, Ok(60));
+
+        let failed = call(serde_json::json!(500))
+            .await
+            .into_typed::<u64, TransferError>();
+        assert_eq!(
+            failed,
+            Err(TypedActionError::Action(TransferError::InsufficientFunds {
+                balance: 100
+            }))
+        );
+
+        let invalid = call(serde_json::json!("lots"))
+            .await
+            .into_typed::<u64, TransferError>();
+        assert!(matches!(invalid, Err(TypedActionError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_registry_register_validated() {
+        use crate::validate::{FieldErrors, Validation};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(serde::Deserialize)]
+        struct Subscribe {
+            email: String,
+        }
+
+        impl Validate for Subscribe {
+            fn validate(&self) -> Result<(), FieldErrors> {
+                let mut v = Validation::new();
+                v.field("email", &self.email).email("Enter a valid email");
+                v.finish()
+            }
+        }
+
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        let mut registry = ActionRegistry::new();
+        registry.register_validated("subscribe", |input: Subscribe| async move {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+            Ok(input.email)
+        });
+
+        let call = |email: &str| {
+            registry.execute(ActionRequest {
+                action_id: "subscribe".to_string(),
+                payload: serde_json::json!({ "email": email }),
+            })
+        };
+
+        let response = call("not-an-email").await;
+        assert!(!response.success);
+        assert_eq!(response.field_errors["email"], "Enter a valid email");
+        assert_eq!(CALLS.load(Ordering::SeqCst), 0);
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["field_errors"]["email"], "Enter a valid email");
+
+        let response = call("ann@example.com").await;
+        assert!(response.success);
+        assert!(response.field_errors.is_empty());
+        assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_registry_middleware_guards_and_enriches() {
+        use std::sync::Mutex;
+
+        struct User(String);
+
+        static LOG: Mutex<Vec<String>> = Mutex::new(Vec::new());
+        let mut registry = ActionRegistry::new();
+        registry.register_middleware(|request: ActionRequest, context, next: Next| async move {
+            LOG.lock()
+                .unwrap()
+                .push(format!("start {}", request.action_id));
+            let result = next.run(request, context).await;
+            LOG.lock()
+                .unwrap()
+                .push(format!("end ok={}", result.is_ok()));
+            result
+        });
+        registry.register_middleware(
+            |request, mut context: ActionContext, next: Next| async move {
+                let Some(session) = context.cookie("session") else {
+                    return Err(ActionError::with_code("Sign in first", "UNAUTHORIZED"));
+                };
+                let user = User(session.to_string());
+                context.insert(user);
+                next.run(request, context).await
+            },
+        );
+        registry.register_with_context("whoami", |_: (), context: ActionContext| async move {
+            Ok(context.get::<User>().map(|user| user.0.clone()))
+        });
+
+        let request = || ActionRequest {
+            action_id: "whoami".to_string(),
+            payload: serde_json::Value::Null,
+        };
+        let signed_in = ActionContext::new()
+            .with_header("Cookie", "theme=dark")
+            .with_header("cookie", "session=ann");
+        assert_eq!(signed_in.header("COOKIE"), Some("theme=dark; session=ann"));
+
+        let response = registry.execute_with_context(request(), signed_in).await;
+        assert_eq!(response.data.unwrap(), "ann");
+
+        let response = registry.execute(request()).await;
+        assert_eq!(
+            response.error.unwrap().code.as_deref(),
+            Some("UNAUTHORIZED")
+        );
+
+        assert_eq!(
+            *LOG.lock().unwrap(),
+            [
+                "start whoami",
+                "end ok=true",
+                "start whoami",
+                "end ok=false"
+            ]
+        );
+    }
+
+    fn register_ping(registry: &mut ActionRegistry) {
+        registry.register("ping", |_: ()| async { Ok("pong") });
+    }
+
+    inventory::submit! {
+        ServerActionEntry::new("ping", register_ping)
+    }
+
+    #[tokio::test]
+    async fn test_registry_with_server_actions() {
+        let registry = ActionRegistry::with_server_actions();
+        assert!(registry.has("ping"));
+
+        let request = ActionRequest {
+            action_id: "ping".to_string(),
+            payload: serde_json::Value::Null,
+        };
+        assert_eq!(registry.execute(request).await.data.unwrap(), "pong");
+    }
+
+    #[tokio::test]
+    async fn test_registry_resolves_only_the_requests_uploads() {
+        let mut registry = ActionRegistry::new();
+        registry.register("size", |file: crate::UploadedFile| async move {
+            Ok(file.size())
+        });
+
+        let body = "--b\r\n\
+            Content-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\n\r\n\
+            hello\r\n\
+            --b--\r\n";
+        let form = crate::FormData::from_multipart(
+            body.as_bytes(),
+            "b",
+            &crate::MultipartLimits::default(),
+        )
+        .unwrap();
+        let request = || ActionRequest {
+            action_id: "size".to_string(),
+            payload: form.to_action_payload()["doc"].clone(),
+        };
+
+        let own = ActionContext::new().with_uploads(form.uploads());
+        let response = registry.execute_with_context(request(), own).await;
+        assert_eq!(response.data.unwrap(), 5);
+
+        // Another request naming the same upload id cannot claim it.
+        let response = registry.execute(request()).await;
+        assert_eq!(response.error.unwrap().code.as_deref(), Some(INVALID_INPUT));
+    }
+
     #[test]
     fn test_registry_action_ids() {
         let mut registry = ActionRegistry::new();
@@ -0,0 +1,242 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use futures_core::Stream;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::action::{ActionResponse, ActionResult};
+
+/// Content type of a streamed action response: one [`ActionStreamEvent`] per line.
+pub const ACTION_STREAM_CONTENT_TYPE: &str = "application/x-ndjson";
+
+pub(crate) type ActionFuture = Pin<Box<dyn Future<Output = ActionResult<Value>> + Send>>;
+
+/// An event of a streamed action call. Any number of `Progress` events are followed by
+/// exactly one `Done`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActionStreamEvent {
+    Progress(Value),
+    Done(ActionResponse),
+}
+
+#[derive(Default)]
+struct Channel {
+    events: VecDeque<Value>,
+    waker: Option<Waker>,
+}
+
+/// Reports progress from an action registered with `ActionRegistry::register_streaming`,
+/// or from `ActionContext::progress`.
+/// Events sent after the action returned, or while it is called without streaming, are
+/// dropped.
+#[derive(Clone, Default)]
+pub struct ProgressSender {
+    channel: Option<Arc<Mutex<Channel>>>,
+}
+
+impl ProgressSender {
+    /// A sender whose events go nowhere.
+    pub fn discard() -> Self {
+        Self::default()
+    }
+
+    pub fn send(&self, event: impl Serialize) {
+        let Some(channel) = &self.channel else {
+            return;
+        };
+        let Ok(event) = serde_json::to_value(event) else {
+            return;
+        };
+        let mut channel = channel.lock().unwrap();
+        channel.events.push_back(event);
+        if let Some(waker) = channel.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// The events of one streamed action call, from `ActionRegistry::execute_streaming`.
+pub struct ActionStream {
+    channel: Arc<Mutex<Channel>>,
+    action: Option<ActionFuture>,
+    response: Option<ActionResponse>,
+}
+
+impl ActionStream {
+    pub(crate) fn new(start: impl FnOnce(ProgressSender) -> ActionFuture) -> Self {
+        let channel = Arc::new(Mutex::new(Channel::default()));
+        let action = start(ProgressSender {
+            channel: Some(channel.clone()),
+        });
+        Self {
+            channel,
+            action: Some(action),
+            response: None,
+        }
+    }
+
+    /// A stream with no progress, only `response`.
+    pub(crate) fn done(response: ActionResponse) -> Self {
+        Self {
+            channel: Arc::default(),
+            action: None,
+            response: Some(response),
+        }
+    }
+
+    fn next_progress(&self) -> Option<ActionStreamEvent> {
+        let event = self.channel.lock().unwrap().events.pop_front()?;
+        Some(ActionStreamEvent::Progress(event))
+    }
+}
+
+impl Stream for ActionStream {
+    type Item = ActionStreamEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(event) = self.next_progress() {
+            return Poll::Ready(Some(event));
+        }
+        let this = &mut *self;
+        if let Some(action) = this.action.as_mut() {
+            this.channel.lock().unwrap().waker = Some(cx.waker().clone());
+            let result = match action.as_mut().poll(cx) {
+                Poll::Ready(result) => result,
+                Poll::Pending => return Poll::Pending,
+            };
+            self.action = None;
+            self.response = Some(match result {
+                Ok(data) => ActionResponse::success(data),
+                Err(error) => ActionResponse::error(error),
+            });
+            // Progress sent right before returning still goes out ahead of the response.
+            if let Some(event) = self.next_progress() {
+                return Poll::Ready(Some(event));
+            }
+        }
+        Poll::Ready(self.response.take().map(ActionStreamEvent::Done))
+    }
+}
+
+/// Splits a streamed action response into events as its chunks arrive.
+#[derive(Debug, Default)]
+pub struct ActionStreamDecoder {
+    buffer: String,
+}
+
+impl ActionStreamDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The events completed by `chunk`. A line that is not an event is an error.
+    pub fn push(&mut self, chunk: &str) -> Result<Vec<ActionStreamEvent>, serde_json::Error> {
+        self.buffer.push_str(chunk);
+        let Some(end) = self.buffer.rfind('\n') else {
+            return Ok(Vec::new());
+        };
+        let complete: String = self.buffer.drain(..=end).collect();
+        complete
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ActionContext, ActionRegistry, ActionRequest};
+
+    async fn collect(mut stream: ActionStream) -> Vec<ActionStreamEvent> {
+        let mut events = Vec::new();
+        while let Some(event) = std::future::poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await
+        {
+            events.push(event);
+        }
+        events
+    }
+
+    fn import_registry() -> ActionRegistry {
+        let mut registry = ActionRegistry::new();
+        registry.register_streaming("import", |rows: u32, progress: ProgressSender| async move {
+            for row in 1..=rows {
+                tokio::task::yield_now().await;
+                progress.send(serde_json::json!({ "done": row, "total": rows }));
+            }
+            Ok(format!("imported {}", rows))
+        });
+        registry
+    }
+
+    #[tokio::test]
+    async fn test_execute_streaming_yields_progress_then_response() {
+        let registry = import_registry();
+        let events = collect(registry.execute_streaming(
+            ActionRequest {
+                action_id: "import".to_string(),
+                payload: serde_json::json!(3),
+            },
+            ActionContext::new(),
+        ))
+        .await;
+
+        assert_eq!(events.len(), 4);
+        for (i, event) in events[..3].iter().enumerate() {
+            match event {
+                ActionStreamEvent::Progress(p) => assert_eq!(p["done"], i as u64 + 1),
+                other => panic!("expected progress, got {:?}", other),
+            }
+        }
+        match &events[3] {
+            ActionStreamEvent::Done(response) => {
+                assert_eq!(response.data.as_ref().unwrap(), "imported 3")
+            }
+            other => panic!("expected response, got {:?}", other),
+        }
+
+        let response = registry
+            .execute(ActionRequest {
+                action_id: "import".to_string(),
+                payload: serde_json::json!(2),
+            })
+            .await;
+        assert_eq!(response.data.unwrap(), "imported 2");
+
+        let missing = collect(registry.execute_streaming(
+            ActionRequest {
+                action_id: "missing".to_string(),
+                payload: serde_json::Value::Null,
+            },
+            ActionContext::new(),
+        ))
+        .await;
+        assert!(matches!(&missing[..], [ActionStreamEvent::Done(r)] if !r.success));
+    }
+
+    #[test]
+    fn test_decoder_splits_lines_across_chunks() {
+        let progress =
+            serde_json::to_string(&ActionStreamEvent::Progress(serde_json::json!(50))).unwrap();
+        let done = serde_json::to_string(&ActionStreamEvent::Done(ActionResponse::success(
+            serde_json::json!("ok"),
+        )))
+        .unwrap();
+        assert_eq!(progress, r#"{"progress":50}"#);
+        let body = format!("{}\n{}\n", progress, done);
+        let (first, second) = body.split_at(progress.len() + 5);
+
+        let mut decoder = ActionStreamDecoder::new();
+        let events = decoder.push(first).unwrap();
+        assert!(matches!(&events[..], [ActionStreamEvent::Progress(p)] if p == 50));
+        let events = decoder.push(second).unwrap();
+        assert!(matches!(&events[..], [ActionStreamEvent::Done(r)] if r.success));
+        assert!(decoder.push("not json\n").is_err());
+    }
+}
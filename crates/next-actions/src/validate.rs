@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+
+/// The first failure of each invalid field, keyed by field name. Client forms show them next
+/// to their inputs with `Form::set_field_errors`.
+pub type FieldErrors = BTreeMap<String, String>;
+
+/// Checks an action's arguments before it runs; see `ActionRegistry::register_validated`.
+///
+/// ```rust,ignore
+/// impl Validate for CreatePost {
+///     fn validate(&self) -> Result<(), FieldErrors> {
+///         let mut v = Validation::new();
+///         v.field("title", &self.title)
+///             .required("Title is required")
+///             .max_length(120, "Keep it under 120 characters");
+///         v.check("tags", self.tags.len() <= 5, "At most 5 tags");
+///         v.finish()
+///     }
+/// }
+/// ```
+pub trait Validate {
+    fn validate(&self) -> Result<(), FieldErrors>;
+}
+
+/// Collects per-field failures, keeping the first for each field.
+#[derive(Debug, Default)]
+pub struct Validation {
+    errors: FieldErrors,
+}
+
+impl Validation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn field<'a>(&'a mut self, name: &'a str, value: &'a str) -> FieldRules<'a> {
+        FieldRules {
+            validation: self,
+            name,
+            value,
+        }
+    }
+
+    /// Records `message` for `name` unless `valid`.
+    pub fn check(&mut self, name: &str, valid: bool, message: &str) -> &mut Self {
+        if !valid {
+            self.errors
+                .entry(name.to_string())
+                .or_insert_with(|| message.to_string());
+        }
+        self
+    }
+
+    pub fn finish(self) -> Result<(), FieldErrors> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.errors)
+        }
+    }
+}
+
+/// Rules for one string field, created with [`Validation::field`].
+pub struct FieldRules<'a> {
+    validation: &'a mut Validation,
+    name: &'a str,
+    value: &'a str,
+}
+
+impl FieldRules<'_> {
+    pub fn required(self, message: &str) -> Self {
+        let valid = !self.value.trim().is_empty();
+        self.rule(valid, message)
+    }
+
+    pub fn min_length(self, min: usize, message: &str) -> Self {
+        let valid = self.value.chars().count() >= min;
+        self.rule(valid, message)
+    }
+
+    pub fn max_length(self, max: usize, message: &str) -> Self {
+        let valid = self.value.chars().count() <= max;
+        self.rule(valid, message)
+    }
+
+    /// A rough shape check: something before and after a single `@`, with a dot in the domain.
+    pub fn email(self, message: &str) -> Self {
+        let valid = match self.value.split_once('@') {
+            Some((local, domain)) => {
+                !local.is_empty()
+                    && !domain.contains('@')
+                    && domain.split('.').count() > 1
+                    && domain.split('.').all(|part| !part.is_empty())
+            }
+            None => false,
+        };
+        self.rule(valid, message)
+    }
+
+    pub fn matches(self, predicate: impl FnOnce(&str) -> bool, message: &str) -> Self {
+        let valid = predicate(self.value);
+        self.rule(valid, message)
+    }
+
+    fn rule(self, valid: bool, message: &str) -> Self {
+        self.validation.check(self.name, valid, message);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Signup {
+        name: String,
+        email: String,
+        age: u32,
+    }
+
+    impl Validate for Signup {
+        fn validate(&self) -> Result<(), FieldErrors> {
+            let mut v = Validation::new();
+            v.field("name", &self.name)
+                .required("Name is required")
+                .min_length(3, "Name is too short");
+            v.field("email", &self.email).email("Enter a valid email");
+            v.check("age", self.age >= 13, "You must be 13 or older");
+            v.finish()
+        }
+    }
+
+    #[test]
+    fn test_validate_collects_first_error_per_field() {
+        let errors = Signup {
+            name: " ".to_string(),
+            email: "ann@example".to_string(),
+            age: 9,
+        }
+        .validate()
+        .unwrap_err();
+
+        assert_eq!(errors.len(), 3);
+        assert_eq!(errors["name"], "Name is required");
+        assert_eq!(errors["email"], "Enter a valid email");
+        assert_eq!(errors["age"], "You must be 13 or older");
+
+        let valid = Signup {
+            name: "Ann".to_string(),
+            email: "ann@example.com".to_string(),
+            age: 30,
+        };
+        assert!(valid.validate().is_ok());
+    }
+}
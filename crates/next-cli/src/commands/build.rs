@@ -55,6 +55,9 @@ pub async fn run_build() -> Result<()> {
 
     for file in &result.files {
         println!("  ✓ {} ({} bytes)", file.route, file.size_bytes);
+        for error in &file.errors {
+            println!("    ⚠ {} (digest {})", error.message, error.digest);
+        }
     }
 
     let manifest = serde_json::json!({
@@ -1,62 +1,79 @@
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, ItemFn};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, FnArg, ItemFn};
 
 /// Marks a function as a server component.
 ///
 /// Server components run only on the server and can access databases,
-/// file systems, and other server-only resources directly.
+/// file systems, and other server-only resources directly. A component takes
+/// no arguments or a single props struct implementing `next_rs_rsc::Props`.
 ///
 /// ```rust,ignore
 /// #[server_component]
-/// fn article_list() -> Element {
-///     div().child(h1().text("Articles"))
+/// fn article_list(props: ArticleListProps) -> Element {
+///     div().child(h1().text(props.title))
 /// }
 /// ```
+///
+/// Server components can be `async fn`s; wrap them in an `AsyncServerComponent` to stream
+/// a fallback first and their content once it resolves.
+///
+/// ```rust,ignore
+/// #[server_component]
+/// async fn comments() -> Element {
+///     let comments = db::comments().await;
+///     ul().children(comments.iter().map(|c| li().text(&c.body)))
+/// }
+///
+/// let component = async_server_component("comments", comments);
+/// ```
 #[proc_macro_attribute]
 pub fn server_component(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(item as ItemFn);
-    let fn_name = &input.sig.ident;
-    let fn_name_str = fn_name.to_string();
-    let vis = &input.vis;
-    let block = &input.block;
-    let output = &input.sig.output;
-
-    let expanded = quote! {
-        #vis fn #fn_name() #output {
-            next_rs_rsc::global_registry().register_server(module_path!(), #fn_name_str);
-            (|| #block)()
-        }
-    };
-
-    expanded.into()
+    component(item, quote!(register_server))
 }
 
 /// Marks a function as a client component.
 ///
 /// Client components are shipped to the browser as WASM and can use
-/// interactive features like event handlers and reactive state.
+/// interactive features like event handlers and reactive state. Props come
+/// from the server serialized in the RSC payload, so a component takes no
+/// arguments or a single props struct implementing `next_rs_rsc::Props`;
+/// register it with `ClientComponentRegistry::register_typed`.
 ///
 /// ```rust,ignore
 /// #[client_component]
-/// fn counter() -> Element {
-///     let (count, set_count) = create_signal(0);
+/// fn counter(props: CounterProps) -> Element {
+///     let (count, set_count) = create_signal(props.start);
 ///     div().child(button().text("+").on_click(move |_| set_count.update(|n| *n += 1)))
 /// }
 /// ```
 #[proc_macro_attribute]
 pub fn client_component(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    component(item, quote!(register_client))
+}
+
+fn component(item: TokenStream, register: proc_macro2::TokenStream) -> TokenStream {
     let input = parse_macro_input!(item as ItemFn);
-    let fn_name = &input.sig.ident;
-    let fn_name_str = fn_name.to_string();
+    if input.sig.inputs.len() > 1 {
+        return syn::Error::new_spanned(&input.sig.inputs, "components take a single props struct")
+            .to_compile_error()
+            .into();
+    }
+    let fn_name_str = input.sig.ident.to_string();
     let vis = &input.vis;
+    let sig = &input.sig;
     let block = &input.block;
-    let output = &input.sig.output;
+
+    let body = if sig.asyncness.is_some() {
+        quote!((async move #block).await)
+    } else {
+        quote!((|| #block)())
+    };
 
     let expanded = quote! {
-        #vis fn #fn_name() #output {
-            next_rs_rsc::global_registry().register_client(module_path!(), #fn_name_str);
-            (|| #block)()
+        #vis #sig {
+            next_rs_rsc::directive::global_registry().#register(module_path!(), #fn_name_str);
+            #body
         }
     };
 
@@ -65,8 +82,14 @@ pub fn client_component(_attr: TokenStream, item: TokenStream) -> TokenStream {
 
 /// Marks an async function as a server action.
 ///
-/// Server actions can be called from client components and are
-/// automatically serialized/deserialized across the network boundary.
+/// On the server the function is registered under `<module path>::<name>` and
+/// `ActionRegistry::with_server_actions` picks it up; its arguments arrive as the JSON
+/// payload (a tuple when there are several). Compiled for wasm, the body is replaced by a
+/// stub with the same signature that POSTs the arguments to `/_action/<id>`. The error
+/// type must convert to and from `ActionError`.
+///
+/// With `#[server_action(validate)]` the single argument is checked with its
+/// `next_rs_actions::Validate` impl before the function runs.
 ///
 /// ```rust,ignore
 /// #[server_action]
@@ -75,19 +98,111 @@ pub fn client_component(_attr: TokenStream, item: TokenStream) -> TokenStream {
 /// }
 /// ```
 #[proc_macro_attribute]
-pub fn server_action(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn server_action(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let validate = match syn::parse::<Option<syn::Ident>>(attr) {
+        Ok(None) => false,
+        Ok(Some(flag)) if flag == "validate" => true,
+        Ok(Some(flag)) => {
+            return syn::Error::new_spanned(flag, "expected `validate`")
+                .to_compile_error()
+                .into()
+        }
+        Err(e) => return e.to_compile_error().into(),
+    };
     let input = parse_macro_input!(item as ItemFn);
+    if input.sig.asyncness.is_none() {
+        return syn::Error::new_spanned(input.sig.fn_token, "server actions must be async")
+            .to_compile_error()
+            .into();
+    }
+    let mut arg_names = Vec::new();
+    let mut arg_types = Vec::new();
+    for (i, arg) in input.sig.inputs.iter().enumerate() {
+        match arg {
+            FnArg::Typed(arg) => {
+                arg_names.push(format_ident!("__arg{}", i));
+                arg_types.push(arg.ty.clone());
+            }
+            FnArg::Receiver(receiver) => {
+                return syn::Error::new_spanned(receiver, "server actions cannot take self")
+                    .to_compile_error()
+                    .into();
+            }
+        }
+    }
+
     let fn_name = &input.sig.ident;
     let fn_name_str = fn_name.to_string();
     let vis = &input.vis;
     let sig = &input.sig;
     let block = &input.block;
+    let attrs = &input.attrs;
+    if validate && arg_names.len() != 1 {
+        return syn::Error::new_spanned(
+            &input.sig.inputs,
+            "validated server actions take a single argument implementing `Validate`",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let register = if validate {
+        quote!(register_validated)
+    } else {
+        quote!(register)
+    };
+
+    let mut stub_sig = sig.clone();
+    for (arg, name) in stub_sig.inputs.iter_mut().zip(&arg_names) {
+        if let FnArg::Typed(arg) = arg {
+            *arg.pat = syn::parse_quote!(#name);
+        }
+    }
+    let (args_type, args_pattern, args_value) = match arg_names.len() {
+        1 => (
+            quote!(#(#arg_types)*),
+            quote!(#(#arg_names)*),
+            quote!(&#(#arg_names)*),
+        ),
+        _ => (
+            quote!((#(#arg_types,)*)),
+            quote!((#(#arg_names,)*)),
+            quote!((#(&#arg_names,)*)),
+        ),
+    };
 
     let expanded = quote! {
+        #(#attrs)*
+        #[cfg(not(target_arch = "wasm32"))]
         #vis #sig {
-            next_rs_rsc::global_registry().register_server(module_path!(), #fn_name_str);
+            next_rs_rsc::directive::global_registry().register_server(module_path!(), #fn_name_str);
             #block
         }
+
+        #(#attrs)*
+        #[cfg(target_arch = "wasm32")]
+        #vis #stub_sig {
+            react_rs_wasm::call_action(concat!(module_path!(), "::", #fn_name_str), #args_value)
+                .await
+                .map_err(|e| next_rs_actions::ActionError::from(e).into())
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        const _: () = {
+            fn register(registry: &mut next_rs_actions::ActionRegistry) {
+                registry.#register(
+                    concat!(module_path!(), "::", #fn_name_str),
+                    |#args_pattern: #args_type| async move {
+                        #fn_name(#(#arg_names),*).await.map_err(Into::into)
+                    },
+                );
+            }
+            next_rs_actions::inventory::submit! {
+                next_rs_actions::ServerActionEntry::new(
+                    concat!(module_path!(), "::", #fn_name_str),
+                    register,
+                )
+            }
+        };
     };
 
     expanded.into()
@@ -2,6 +2,6 @@ mod matcher;
 mod request;
 mod response;
 
-pub use matcher::{MiddlewareMatcher, PathMatcher};
+pub use matcher::{MatcherError, MiddlewareMatcher, PathMatcher};
 pub use request::NextRequest;
 pub use response::{MiddlewareResult, NextResponse};
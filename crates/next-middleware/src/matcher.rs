@@ -1,26 +1,75 @@
 use regex::Regex;
+use std::fmt;
 
 #[derive(Debug, Clone)]
 pub enum PathMatcher {
     Exact(String),
     Prefix(String),
-    Regex(String),
+    /// Compiled once; build it with [`PathMatcher::regex`].
+    Regex(Regex),
     All,
 }
 
 impl PathMatcher {
+    /// A regex matcher.
+    ///
+    /// # Panics
+    ///
+    /// If `pattern` is not a valid regex, naming the pattern; use [`PathMatcher::try_regex`]
+    /// for patterns that are not known when the app is built.
+    pub fn regex(pattern: &str) -> Self {
+        Self::try_regex(pattern)
+            .unwrap_or_else(|e| panic!("invalid middleware matcher pattern {:?}: {}", pattern, e))
+    }
+
+    pub fn try_regex(pattern: &str) -> Result<Self, regex::Error> {
+        Regex::new(pattern).map(Self::Regex)
+    }
+
     pub fn matches(&self, path: &str) -> bool {
         match self {
             PathMatcher::Exact(p) => path == p,
             PathMatcher::Prefix(p) => path.starts_with(p),
-            PathMatcher::Regex(pattern) => Regex::new(pattern)
-                .map(|re| re.is_match(path))
-                .unwrap_or(false),
+            PathMatcher::Regex(re) => re.is_match(path),
             PathMatcher::All => true,
         }
     }
 }
 
+/// A `matcher` pattern [`MiddlewareMatcher::from_config`] cannot use.
+#[derive(Debug)]
+pub enum MatcherError {
+    /// The pattern uses look-ahead or look-behind, e.g. `/((?!_next/static).*)`, which the
+    /// regex engine does not support.
+    LookAround { pattern: String },
+    Invalid {
+        pattern: String,
+        error: regex::Error,
+    },
+}
+
+impl fmt::Display for MatcherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LookAround { pattern } => write!(
+                f,
+                "middleware matcher pattern {:?} uses look-around, which is not supported; \
+                 exclude paths with `MiddlewareMatcher::exclude` or `exclude_internal()` instead",
+                pattern
+            ),
+            Self::Invalid { pattern, error } => write!(
+                f,
+                "invalid middleware matcher pattern {:?}: {}",
+                pattern, error
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MatcherError {}
+
+/// Decides which paths a middleware runs for. Exclusions win over inclusions; without
+/// inclusions every path not excluded matches.
 #[derive(Debug, Clone)]
 pub struct MiddlewareMatcher {
     include: Vec<PathMatcher>,
@@ -45,6 +94,15 @@ impl MiddlewareMatcher {
         self
     }
 
+    /// Skips framework assets under `/_next/`, static files (paths whose last segment has
+    /// an extension, like `/favicon.ico`) and API routes.
+    pub fn exclude_internal(self) -> Self {
+        self.exclude(PathMatcher::Prefix("/_next/".to_string()))
+            .exclude(PathMatcher::regex(r"\.[^/]+$"))
+            .exclude(PathMatcher::Exact("/api".to_string()))
+            .exclude(PathMatcher::Prefix("/api/".to_string()))
+    }
+
     pub fn matches(&self, path: &str) -> bool {
         for excluded in &self.exclude {
             if excluded.matches(path) {
@@ -65,27 +123,73 @@ impl MiddlewareMatcher {
         false
     }
 
-    pub fn from_config(patterns: Vec<&str>) -> Self {
+    /// Builds a matcher from Next.js-style `matcher` patterns:
+    ///
+    /// - `/about` matches exactly;
+    /// - `/blog/:slug` matches one segment; `:slug?`, `:path+` and `:path*` match zero or
+    ///   one, one or more, and any number of segments;
+    /// - `/api/*` matches the prefix;
+    /// - patterns starting with `^` or containing `(` are regexes, matched against the
+    ///   whole path, so `/api/(.*)` does not match `/x/api/users`.
+    ///
+    /// Look-around such as Next.js' `/((?!_next/static|favicon.ico).*)` is rejected; use
+    /// [`MiddlewareMatcher::exclude_internal`] or [`MiddlewareMatcher::exclude`] instead.
+    pub fn from_config(patterns: Vec<&str>) -> Result<Self, MatcherError> {
         let mut matcher = Self::new();
         for pattern in patterns {
-            if pattern.ends_with("*") {
-                matcher.include.push(PathMatcher::Prefix(
-                    pattern.trim_end_matches('*').to_string(),
-                ));
-            } else if pattern.starts_with("^") || pattern.contains("(") {
-                matcher
-                    .include
-                    .push(PathMatcher::Regex(pattern.to_string()));
-            } else {
-                matcher
-                    .include
-                    .push(PathMatcher::Exact(pattern.to_string()));
-            }
+            matcher = matcher.include(parse_pattern(pattern)?);
+        }
+        Ok(matcher)
+    }
+}
+
+fn parse_pattern(pattern: &str) -> Result<PathMatcher, MatcherError> {
+    if pattern.starts_with('^') || pattern.contains('(') {
+        if ["(?=", "(?!", "(?<=", "(?<!"]
+            .iter()
+            .any(|look_around| pattern.contains(look_around))
+        {
+            return Err(MatcherError::LookAround {
+                pattern: pattern.to_string(),
+            });
         }
-        matcher
+        let body = pattern.strip_prefix('^').unwrap_or(pattern);
+        let body = body.strip_suffix('$').unwrap_or(body);
+        PathMatcher::try_regex(&format!("^(?:{})$", body)).map_err(|error| MatcherError::Invalid {
+            pattern: pattern.to_string(),
+            error,
+        })
+    } else if pattern.contains("/:") {
+        Ok(PathMatcher::regex(&segment_pattern_regex(pattern)))
+    } else if let Some(prefix) = pattern.strip_suffix('*') {
+        Ok(PathMatcher::Prefix(prefix.to_string()))
+    } else {
+        Ok(PathMatcher::Exact(pattern.to_string()))
     }
 }
 
+fn segment_pattern_regex(pattern: &str) -> String {
+    let mut regex = String::from("^");
+    for segment in pattern.split('/').filter(|s| !s.is_empty()) {
+        let Some(param) = segment.strip_prefix(':') else {
+            regex.push('/');
+            regex.push_str(&regex::escape(segment));
+            continue;
+        };
+        regex.push_str(match param.chars().last() {
+            Some('?') => "(?:/[^/]+)?",
+            Some('+') => "(?:/[^/]+)+",
+            Some('*') => "(?:/[^/]+)*",
+            _ => "/[^/]+",
+        });
+    }
+    if regex == "^" {
+        regex.push('/');
+    }
+    regex.push_str("/?$");
+    regex
+}
+
 impl Default for MiddlewareMatcher {
     fn default() -> Self {
         Self::new()
@@ -114,9 +218,30 @@ mod tests {
 
     #[test]
     fn test_regex_matcher() {
-        let matcher = PathMatcher::Regex(r"^/blog/\d+$".to_string());
+        let matcher = PathMatcher::regex(r"^/blog/\d+$");
         assert!(matcher.matches("/blog/123"));
         assert!(!matcher.matches("/blog/abc"));
+        assert!(PathMatcher::try_regex("^/blog/(").is_err());
+    }
+
+    #[test]
+    fn test_from_config_rejects_invalid_regex() {
+        let err = MiddlewareMatcher::from_config(vec!["^/blog/(\\d+"]).unwrap_err();
+        assert!(matches!(err, MatcherError::Invalid { .. }));
+
+        let err =
+            MiddlewareMatcher::from_config(vec!["/((?!_next/static|favicon.ico).*)"]).unwrap_err();
+        assert!(matches!(err, MatcherError::LookAround { .. }));
+        assert!(err.to_string().contains("exclude_internal()"));
+    }
+
+    #[test]
+    fn test_from_config_anchors_regexes() {
+        let matcher = MiddlewareMatcher::from_config(vec!["/api/(.*)", "^/blog/\\d+"]).unwrap();
+        assert!(matcher.matches("/api/users"));
+        assert!(!matcher.matches("/x/api/users"));
+        assert!(matcher.matches("/blog/12"));
+        assert!(!matcher.matches("/blog/12/comments"));
     }
 
     #[test]
@@ -144,11 +269,40 @@ mod tests {
 
     #[test]
     fn test_from_config() {
-        let matcher = MiddlewareMatcher::from_config(vec!["/api/*", "/admin/*", "/login"]);
+        let matcher = MiddlewareMatcher::from_config(vec!["/api/*", "/admin/*", "/login"]).unwrap();
 
         assert!(matcher.matches("/api/users"));
         assert!(matcher.matches("/admin/"));
         assert!(matcher.matches("/login"));
         assert!(!matcher.matches("/public"));
     }
+
+    #[test]
+    fn test_from_config_named_segments() {
+        let matcher =
+            MiddlewareMatcher::from_config(vec!["/dashboard/:path*", "/blog/:slug"]).unwrap();
+        assert!(matcher.matches("/dashboard"));
+        assert!(matcher.matches("/dashboard/settings/team"));
+        assert!(matcher.matches("/blog/hello"));
+        assert!(!matcher.matches("/blog"));
+        assert!(!matcher.matches("/blog/hello/comments"));
+        assert!(!matcher.matches("/dashboards"));
+
+        let matcher = MiddlewareMatcher::from_config(vec!["/docs/:path+"]).unwrap();
+        assert!(!matcher.matches("/docs"));
+        assert!(matcher.matches("/docs/intro"));
+    }
+
+    #[test]
+    fn test_exclude_internal() {
+        let matcher = MiddlewareMatcher::from_config(vec!["/:path*"])
+            .unwrap()
+            .exclude_internal();
+        assert!(matcher.matches("/"));
+        assert!(matcher.matches("/account/orders"));
+        assert!(!matcher.matches("/_next/static/app.js"));
+        assert!(!matcher.matches("/favicon.ico"));
+        assert!(!matcher.matches("/api"));
+        assert!(!matcher.matches("/api/users"));
+    }
 }
@@ -0,0 +1,67 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use next_rs_rsc::{RscEncoding, RscNode, RscPayload};
+use serde_json::json;
+
+/// A product listing with `rows` cards, about the shape of a large catalog page.
+fn large_page(rows: usize) -> RscPayload {
+    let cards = (0..rows)
+        .map(|i| {
+            RscNode::element(
+                "article",
+                json!({"class": "card", "data-id": i}),
+                vec![
+                    RscNode::element(
+                        "h2",
+                        json!({"class": "card-title"}),
+                        vec![RscNode::text(format!("Product {}", i))],
+                    ),
+                    RscNode::element(
+                        "p",
+                        json!({"class": "card-body"}),
+                        vec![RscNode::text("Lightweight, durable and ready to ship.")],
+                    ),
+                    RscNode::client_ref("AddToCart", json!({"productId": i, "inStock": true})),
+                ],
+            )
+        })
+        .collect();
+    let mut payload = RscPayload::new();
+    payload.add_node(RscNode::element("main", json!({"class": "grid"}), cards));
+    payload.add_client_reference(
+        "AddToCart".to_string(),
+        "./AddToCart.js".to_string(),
+        "AddToCart".to_string(),
+    );
+    payload
+}
+
+fn bench_payload_size(c: &mut Criterion) {
+    for rows in [10, 100, 1000] {
+        let payload = large_page(rows);
+        let text = payload.encode(RscEncoding::Text).len();
+        let msgpack = payload.encode(RscEncoding::MessagePack).len();
+        println!(
+            "payload_size/{}: text {} bytes, msgpack {} bytes ({:.0}%)",
+            rows,
+            text,
+            msgpack,
+            msgpack as f64 / text as f64 * 100.0
+        );
+    }
+
+    let payload = large_page(1000);
+    c.bench_function("encode_text_1000", |b| {
+        b.iter(|| black_box(payload.encode(RscEncoding::Text)));
+    });
+    c.bench_function("encode_msgpack_1000", |b| {
+        b.iter(|| black_box(payload.encode(RscEncoding::MessagePack)));
+    });
+
+    let bytes = payload.to_msgpack();
+    c.bench_function("decode_msgpack_1000", |b| {
+        b.iter(|| black_box(RscPayload::from_msgpack(black_box(&bytes)).unwrap()));
+    });
+}
+
+criterion_group!(benches, bench_payload_size);
+criterion_main!(benches);
@@ -1,8 +1,12 @@
 use react_rs_elements::{Element, Node};
-use std::future::Future;
+use std::future::{ready, Future};
 use std::pin::Pin;
+use std::time::Duration;
 
-use crate::RscPayload;
+use crate::component_cache::{self, Lookup};
+use crate::{RscNode, RscPayload, RscRenderer};
+
+pub type RscNodeFuture = Pin<Box<dyn Future<Output = RscNode> + Send>>;
 
 pub type AsyncRenderFn =
     Box<dyn Fn() -> Pin<Box<dyn Future<Output = Element> + Send>> + Send + Sync>;
@@ -10,6 +14,7 @@ pub type AsyncRenderFn =
 pub struct AsyncServerComponent {
     id: String,
     render_fn: AsyncRenderFn,
+    revalidate: Option<Duration>,
 }
 
 impl AsyncServerComponent {
@@ -21,13 +26,68 @@ impl AsyncServerComponent {
         Self {
             id: id.into(),
             render_fn: Box::new(move || Box::pin(render_fn())),
+            revalidate: None,
         }
     }
 
+    /// Caches the rendered subtree across requests and pages, re-rendering it in the
+    /// background once it is older than `after`. Meant for expensive widgets, such as
+    /// pricing or stats, on otherwise dynamic pages. See
+    /// [`crate::set_refresh_spawner`] and [`crate::revalidate_component`].
+    pub fn revalidate(mut self, after: Duration) -> Self {
+        self.revalidate = Some(after);
+        self
+    }
+
     pub fn id(&self) -> &str {
         &self.id
     }
 
+    /// The rendered subtree, from the cache when the component has a revalidate period.
+    pub async fn render_node(&self) -> RscNode {
+        self.content().await
+    }
+
+    fn content(&self) -> RscNodeFuture {
+        let render = || {
+            let content = (self.render_fn)();
+            async move {
+                let element = content.await;
+                RscRenderer::new().render_node(&Node::Element(element))
+            }
+        };
+        let Some(max_age) = self.revalidate else {
+            return Box::pin(render());
+        };
+
+        let id = self.id.clone();
+        match component_cache::lookup(&id, max_age) {
+            Lookup::Fresh(node) => Box::pin(ready(node)),
+            Lookup::Stale(node) if component_cache::can_refresh_in_background() => {
+                let refresh = render();
+                component_cache::spawn_refresh(Box::pin(async move {
+                    let guard = RefreshGuard(Some(id));
+                    let node = refresh.await;
+                    if let Some(id) = guard.into_id() {
+                        component_cache::store(&id, node);
+                    }
+                }));
+                Box::pin(ready(node))
+            }
+            Lookup::Stale(_) | Lookup::Miss => {
+                let fresh = render();
+                Box::pin(async move {
+                    let guard = RefreshGuard(Some(id));
+                    let node = fresh.await;
+                    if let Some(id) = guard.into_id() {
+                        component_cache::store(&id, node.clone());
+                    }
+                    node
+                })
+            }
+        }
+    }
+
     pub async fn render(&self) -> Element {
         (self.render_fn)().await
     }
@@ -37,6 +97,94 @@ impl AsyncServerComponent {
         let node = Node::Element(element);
         crate::render_to_rsc_payload(&node)
     }
+
+    /// Starts rendering without waiting for it: returns a suspense node holding `fallback`
+    /// to send in place of the component right away, and the [`RscHole`] that resolves to
+    /// its content, to be streamed afterwards as a `$<hole_id>:` row.
+    pub fn render_streaming(
+        &self,
+        hole_id: impl Into<String>,
+        fallback: &Node,
+    ) -> (RscNode, RscHole) {
+        self.render_streaming_with_key(hole_id, None, fallback)
+    }
+
+    /// Like [`AsyncServerComponent::render_streaming`], naming the content by `key` so a
+    /// client navigating between pages can keep it. See [`RscNode::keyed_suspense`].
+    pub fn render_streaming_keyed(
+        &self,
+        hole_id: impl Into<String>,
+        key: impl Into<String>,
+        fallback: &Node,
+    ) -> (RscNode, RscHole) {
+        self.render_streaming_with_key(hole_id, Some(key.into()), fallback)
+    }
+
+    fn render_streaming_with_key(
+        &self,
+        hole_id: impl Into<String>,
+        key: Option<String>,
+        fallback: &Node,
+    ) -> (RscNode, RscHole) {
+        let id = hole_id.into();
+        let placeholder = RscNode::Suspense {
+            id: id.clone(),
+            key,
+            fallback: Box::new(RscRenderer::new().render_node(fallback)),
+            children: Vec::new(),
+        };
+        let hole = RscHole {
+            id,
+            content: self.content(),
+        };
+        (placeholder, hole)
+    }
+}
+
+/// Clears a component's refreshing mark if its render panics or is dropped, so a later
+/// request retries it.
+struct RefreshGuard(Option<String>);
+
+impl RefreshGuard {
+    fn into_id(mut self) -> Option<String> {
+        self.0.take()
+    }
+}
+
+impl Drop for RefreshGuard {
+    fn drop(&mut self) {
+        if let Some(id) = &self.0 {
+            component_cache::abandon_refresh(id);
+        }
+    }
+}
+
+/// A subtree sent as a placeholder whose content follows later in the stream.
+pub struct RscHole {
+    id: String,
+    content: RscNodeFuture,
+}
+
+impl RscHole {
+    pub fn new(
+        id: impl Into<String>,
+        content: impl Future<Output = RscNode> + Send + 'static,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            content: Box::pin(content),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Waits for the content, returning the row that fills the hole.
+    pub async fn resolve(self) -> String {
+        let node = self.content.await;
+        RscPayload::suspense_row(&self.id, &node)
+    }
 }
 
 pub fn async_server_component<F, Fut>(id: impl Into<String>, render_fn: F) -> AsyncServerComponent
@@ -83,6 +231,8 @@ impl SuspenseWrapper {
 mod tests {
     use super::*;
     use react_rs_elements::html::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
 
     async fn mock_fetch_data() -> Vec<String> {
         vec!["Item 1".to_string(), "Item 2".to_string()]
@@ -129,6 +279,34 @@ mod tests {
         assert_eq!(content.tag(), "div");
     }
 
+    #[tokio::test]
+    async fn test_render_streaming_leaves_hole() {
+        let component = async_server_component("comments", || async {
+            let data = mock_fetch_data().await;
+            ul().child(li().text(&data[0]))
+        });
+
+        let fallback = Node::Element(p().text("Loading comments..."));
+        let (placeholder, hole) = component.render_streaming("comments-0", &fallback);
+        let RscNode::Suspense {
+            id,
+            fallback,
+            children,
+            ..
+        } = placeholder
+        else {
+            panic!("Expected Suspense");
+        };
+        assert_eq!(id, "comments-0");
+        assert!(matches!(*fallback, RscNode::Element { ref tag, .. } if tag == "p"));
+        assert!(children.is_empty());
+
+        assert_eq!(hole.id(), "comments-0");
+        let row = hole.resolve().await;
+        assert!(row.starts_with("$comments-0:{\"type\":\"element\",\"tag\":\"ul\""));
+        assert!(row.contains("Item 1"));
+    }
+
     #[tokio::test]
     async fn test_async_data_fetching() {
         let component = async_server_component("articles", || async {
@@ -143,4 +321,55 @@ mod tests {
         let element = component.render().await;
         assert_eq!(element.tag(), "ul");
     }
+
+    fn counting_component(id: &str, renders: &Arc<AtomicUsize>) -> AsyncServerComponent {
+        let renders = renders.clone();
+        async_server_component(id, move || {
+            let n = renders.fetch_add(1, Ordering::SeqCst) + 1;
+            async move { div().text(format!("render {}", n)) }
+        })
+    }
+
+    fn text_of(node: &RscNode) -> &str {
+        match node {
+            RscNode::Element { children, .. } => match &children[0] {
+                RscNode::Text { value } => value,
+                _ => panic!("Expected text"),
+            },
+            _ => panic!("Expected element"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_revalidate_caches_across_renders() {
+        let renders = Arc::new(AtomicUsize::new(0));
+        let pricing =
+            counting_component("pricing-widget", &renders).revalidate(Duration::from_secs(60));
+
+        assert_eq!(text_of(&pricing.render_node().await), "render 1");
+        let (_, hole) = pricing.render_streaming("pricing-0", &Node::Text("...".into()));
+        assert!(hole.resolve().await.contains("render 1"));
+        assert_eq!(renders.load(Ordering::SeqCst), 1);
+
+        assert!(crate::revalidate_component("pricing-widget"));
+        assert_eq!(text_of(&pricing.render_node().await), "render 2");
+    }
+
+    #[tokio::test]
+    async fn test_stale_component_refreshes_in_background() {
+        crate::set_refresh_spawner(|refresh| {
+            tokio::spawn(refresh);
+        });
+        let renders = Arc::new(AtomicUsize::new(0));
+        let stats = counting_component("stats-widget", &renders).revalidate(Duration::ZERO);
+
+        assert_eq!(text_of(&stats.render_node().await), "render 1");
+        std::thread::sleep(Duration::from_millis(2));
+        // Stale: served as is while the refresh runs.
+        assert_eq!(text_of(&stats.render_node().await), "render 1");
+        tokio::task::yield_now().await;
+        assert_eq!(renders.load(Ordering::SeqCst), 2);
+        std::thread::sleep(Duration::from_millis(2));
+        assert_eq!(text_of(&stats.render_node().await), "render 2");
+    }
 }
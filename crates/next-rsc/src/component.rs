@@ -1,5 +1,23 @@
 use std::any::Any;
 
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// The props contract for components rendered across the RSC boundary: the server
+/// serializes a component's props struct into the payload and the client deserializes it
+/// back into the same type.
+///
+/// ```rust,ignore
+/// #[derive(Serialize, Deserialize)]
+/// struct CounterProps { start: i32 }
+///
+/// #[client_component]
+/// fn counter(props: CounterProps) -> Element { ... }
+/// ```
+pub trait Props: Serialize + DeserializeOwned + 'static {}
+
+impl<T: Serialize + DeserializeOwned + 'static> Props for T {}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ComponentType {
     Server,
@@ -64,6 +82,14 @@ impl ClientComponentRef {
             props: props.into(),
         }
     }
+
+    pub fn with_props<P: Props>(
+        id: impl Into<String>,
+        module: impl Into<String>,
+        props: &P,
+    ) -> Result<Self, serde_json::Error> {
+        Ok(Self::new(id, module, serde_json::to_string(props)?))
+    }
 }
 
 impl ClientComponent for ClientComponentRef {
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::RscNode;
+
+pub type RefreshFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+type Spawner = Box<dyn Fn(RefreshFuture) + Send + Sync>;
+
+struct CachedComponent {
+    node: RscNode,
+    rendered_at: Instant,
+    refreshing: bool,
+}
+
+pub(crate) enum Lookup {
+    Fresh(RscNode),
+    /// Older than the component's revalidate period; the caller refreshes it.
+    Stale(RscNode),
+    Miss,
+}
+
+fn entries() -> &'static RwLock<HashMap<String, CachedComponent>> {
+    static ENTRIES: OnceLock<RwLock<HashMap<String, CachedComponent>>> = OnceLock::new();
+    ENTRIES.get_or_init(Default::default)
+}
+
+static SPAWNER: OnceLock<Spawner> = OnceLock::new();
+
+/// Runs background refreshes of stale server components, e.g. with `tokio::spawn`. Only the
+/// first call takes effect. Without a spawner a stale component re-renders while the request
+/// waits.
+pub fn set_refresh_spawner<F>(spawner: F)
+where
+    F: Fn(RefreshFuture) + Send + Sync + 'static,
+{
+    let _ = SPAWNER.set(Box::new(spawner));
+}
+
+pub(crate) fn can_refresh_in_background() -> bool {
+    SPAWNER.get().is_some()
+}
+
+pub(crate) fn spawn_refresh(refresh: RefreshFuture) {
+    if let Some(spawner) = SPAWNER.get() {
+        spawner(refresh);
+    }
+}
+
+/// The cached subtree of component `id`. A stale entry is handed out once for refreshing;
+/// until the refresh stores a new subtree, other callers get it as fresh.
+pub(crate) fn lookup(id: &str, max_age: Duration) -> Lookup {
+    let mut entries = entries().write().unwrap();
+    let Some(entry) = entries.get_mut(id) else {
+        return Lookup::Miss;
+    };
+    if entry.rendered_at.elapsed() <= max_age || entry.refreshing {
+        return Lookup::Fresh(entry.node.clone());
+    }
+    entry.refreshing = true;
+    Lookup::Stale(entry.node.clone())
+}
+
+pub(crate) fn store(id: &str, node: RscNode) {
+    entries().write().unwrap().insert(
+        id.to_string(),
+        CachedComponent {
+            node,
+            rendered_at: Instant::now(),
+            refreshing: false,
+        },
+    );
+}
+
+/// Lets a failed refresh be retried by the next request.
+pub(crate) fn abandon_refresh(id: &str) {
+    if let Some(entry) = entries().write().unwrap().get_mut(id) {
+        entry.refreshing = false;
+    }
+}
+
+/// Drops the cached subtree of component `id`, so its next render runs it again.
+pub fn revalidate_component(id: &str) -> bool {
+    entries().write().unwrap().remove(id).is_some()
+}
@@ -1,6 +1,7 @@
 mod async_component;
 mod boundary;
 mod component;
+mod component_cache;
 mod component_registry;
 #[doc(hidden)]
 pub mod directive;
@@ -9,16 +10,27 @@ mod macros;
 pub mod markers;
 mod payload;
 mod renderer;
+mod request_cache;
+mod taint;
 
-pub use async_component::{async_server_component, AsyncServerComponent, SuspenseWrapper};
+pub use async_component::{
+    async_server_component, AsyncServerComponent, RscHole, RscNodeFuture, SuspenseWrapper,
+};
 pub use boundary::{ClientBoundary, ClientModule, ServerBoundary};
 pub use component::{
-    ClientComponent, ClientComponentRef, ComponentType, ServerComponent, ServerComponentWrapper,
+    ClientComponent, ClientComponentRef, ComponentType, Props, ServerComponent,
+    ServerComponentWrapper,
 };
+pub use component_cache::{revalidate_component, set_refresh_spawner, RefreshFuture};
 pub use component_registry::{
     ClientComponentManifest, ClientModuleEntry, ComponentRegistry, ServerActionEntry,
     ServerActionManifest,
 };
-pub use macros::{ActionReference, ServerActionError, ServerActionResult, ServerActionWrapper};
-pub use payload::{RscNode, RscPayload, RscRef};
+pub use macros::{
+    ActionReference, FormActionExt, ServerActionError, ServerActionResult, ServerActionWrapper,
+    FORM_ACTION_ATTR,
+};
+pub use payload::{RowRef, RscEncoding, RscError, RscNode, RscPayload, RscRef, RSC_KEYS_HEADER};
 pub use renderer::{render_to_rsc_payload, RscRenderer};
+pub use request_cache::{cache, with_request_cache, RequestScoped};
+pub use taint::{check_taint, taint_object, taint_value, TaintError};
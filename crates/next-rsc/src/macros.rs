@@ -30,6 +30,9 @@ macro_rules! use_server {
     }};
 }
 
+use next_rs_actions::BOUND_ARGS_FIELD;
+use react_rs_elements::html::input;
+use react_rs_elements::{Element, FormMethod, InputType, Node};
 use serde::{Deserialize, Serialize};
 use std::future::Future;
 use std::pin::Pin;
@@ -119,6 +122,80 @@ impl ActionReference {
     pub fn has_bound_args(&self) -> bool {
         self.bound_args.is_some()
     }
+
+    pub fn url(&self) -> String {
+        format!("/_action/{}", self.id)
+    }
+
+    /// The action bound to a form or button by [`FormActionExt::action`].
+    pub fn from_element(element: &Element) -> Option<Self> {
+        let id = attr(element, FORM_ACTION_ATTR)?;
+        let bound = if element.tag() == "button" {
+            attr(element, "value").filter(|_| is_bound_args_field(element))
+        } else {
+            element.get_children().iter().find_map(|child| match child {
+                Node::Element(input) if is_bound_args_field(input) => attr(input, "value"),
+                _ => None,
+            })
+        };
+        Some(Self {
+            id,
+            bound_args: bound.and_then(|args| serde_json::from_str(&args).ok()),
+        })
+    }
+}
+
+fn attr(element: &Element, name: &str) -> Option<String> {
+    element
+        .attributes()
+        .iter()
+        .find(|attr| attr.name == name)
+        .map(|attr| attr.to_static_value())
+}
+
+/// Whether `element` carries an action's bound arguments: the hidden input of a form, or
+/// the name of a submit button.
+pub(crate) fn is_bound_args_field(element: &Element) -> bool {
+    attr(element, "name").as_deref() == Some(BOUND_ARGS_FIELD)
+}
+
+/// Attribute marking forms whose submission the client runtime can take over.
+pub const FORM_ACTION_ATTR: &str = "data-action";
+
+pub trait FormActionExt {
+    /// Submits the form, or the form of a submit button, to a server action. Without JS this
+    /// is a plain POST to the action endpoint; once hydrated, the client runtime submits it
+    /// with fetch instead.
+    fn action(self, action: &ActionReference) -> Self;
+}
+
+impl FormActionExt for Element {
+    fn action(self, action: &ActionReference) -> Self {
+        if self.tag() == "button" {
+            // A submit button posts its own name and value, which carry the bound arguments.
+            let button = self
+                .attr("formaction", &action.url())
+                .attr("formmethod", "post")
+                .attr(FORM_ACTION_ATTR, &action.id);
+            return match &action.bound_args {
+                Some(args) => button.name(BOUND_ARGS_FIELD).value(&args.to_string()),
+                None => button,
+            };
+        }
+        let form = self
+            .attr("action", &action.url())
+            .method(FormMethod::Post)
+            .attr(FORM_ACTION_ATTR, &action.id);
+        match &action.bound_args {
+            Some(args) => form.child(
+                input()
+                    .input_type(InputType::Hidden)
+                    .name(BOUND_ARGS_FIELD)
+                    .value(&args.to_string()),
+            ),
+            None => form,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -175,4 +252,30 @@ mod tests {
         let bound_ref = action.bind(serde_json::json!({"item_id": 42}));
         assert!(bound_ref.has_bound_args());
     }
+
+    #[test]
+    fn test_form_action_renders_post_form() {
+        use crate::FormActionExt;
+
+        let action = use_server!("./actions" => updateItem, |id: u64| {
+            Box::pin(async move { Ok(id) })
+        });
+
+        let plain = form().action(&action.to_action_reference());
+        let attr = |el: &react_rs_elements::Element, name: &str| {
+            el.attributes()
+                .iter()
+                .find(|a| a.name == name)
+                .map(|a| a.to_static_value())
+        };
+        assert_eq!(
+            attr(&plain, "action").as_deref(),
+            Some("/_action/updateItem")
+        );
+        assert_eq!(attr(&plain, "method").as_deref(), Some("post"));
+        assert!(plain.get_children().is_empty());
+
+        let bound = form().action(&action.bind(serde_json::json!({"item_id": 42})));
+        assert_eq!(bound.get_children().len(), 1);
+    }
 }
@@ -1,7 +1,13 @@
 use react_rs_elements::{Element, Node};
 use std::marker::PhantomData;
 
-use crate::RscPayload;
+use crate::{Props, RscPayload};
+
+/// Attributes marking the element a client component rendered on the server, so the RSC
+/// renderer can send a reference instead and the client can hydrate it.
+pub const CLIENT_MODULE_ATTR: &str = "data-client";
+pub const CLIENT_ID_ATTR: &str = "data-component-id";
+pub const CLIENT_PROPS_ATTR: &str = "data-props";
 
 pub struct Server;
 
@@ -77,6 +83,30 @@ where
     pub fn to_rsc_reference(&self, props: serde_json::Value) -> crate::RscNode {
         crate::RscNode::client_ref(&self.id, props)
     }
+
+    /// The server-rendered fallback marked with this component and its serialized props.
+    /// The RSC renderer replaces it with a client reference when the component is in its
+    /// client manifest.
+    pub fn render_with<P: Props>(&self, props: &P) -> Result<Element, serde_json::Error> {
+        let props = untainted_props(props)?;
+        Ok(self
+            .render_fallback()
+            .attr(CLIENT_MODULE_ATTR, &self.module)
+            .attr(CLIENT_ID_ATTR, &self.id)
+            .attr(CLIENT_PROPS_ATTR, &props.to_string()))
+    }
+
+    /// A reference carrying typed props, which the client's registry deserializes back
+    /// into `P` before calling the component.
+    pub fn reference<P: Props>(&self, props: &P) -> Result<crate::RscNode, serde_json::Error> {
+        Ok(self.to_rsc_reference(untainted_props(props)?))
+    }
+}
+
+fn untainted_props<P: Props>(props: &P) -> Result<serde_json::Value, serde_json::Error> {
+    let value = serde_json::to_value(props)?;
+    crate::taint::check_taint(&value).map_err(serde::ser::Error::custom)?;
+    Ok(value)
 }
 
 pub fn server_component<F>(id: impl Into<String>, render_fn: F) -> Component<Server, F>
@@ -147,6 +177,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_client_component_typed_reference() {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct LikeProps {
+            article_id: u32,
+            liked: bool,
+        }
+
+        let component =
+            client_component("like-button", "./LikeButton.js", || button().text("Like"));
+        let rsc_node = component
+            .reference(&LikeProps {
+                article_id: 42,
+                liked: true,
+            })
+            .unwrap();
+
+        let crate::RscNode::ClientReference { props, .. } = rsc_node else {
+            panic!("Expected ClientReference");
+        };
+        let props: LikeProps = serde_json::from_value(props).unwrap();
+        assert_eq!(props.article_id, 42);
+        assert!(props.liked);
+    }
+
     #[test]
     fn test_client_component_fallback_render() {
         let component = client_component("modal", "./Modal.js", || {
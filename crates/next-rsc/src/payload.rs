@@ -1,4 +1,9 @@
-use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::ActionReference;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RscPayload {
@@ -25,9 +30,76 @@ pub enum RscNode {
     #[serde(rename = "suspense")]
     Suspense {
         id: String,
+        /// Names the boundary's content across renders, e.g. `post:7:comments`. A client
+        /// that already resolved a boundary with this key keeps its content instead of
+        /// waiting for the server to send it again. Always written, as MessagePack payloads
+        /// encode fields by position.
+        #[serde(default)]
+        key: Option<String>,
         fallback: Box<RscNode>,
         children: Vec<RscNode>,
     },
+    /// A server action passed as a prop, e.g. a form's `action` or a button's `formAction`.
+    /// The client binds it to post to `/_action/<id>` with the bound arguments.
+    #[serde(rename = "action")]
+    Action(ActionReference),
+    /// The node sent in another row, written `"$<row>"`. The row can arrive later, so a
+    /// server can send slow parts of the tree after the parts that follow them.
+    #[serde(untagged)]
+    Reference(RowRef),
+}
+
+/// The request header listing the suspense keys whose content the client already has,
+/// comma-separated, so the server can leave them out.
+pub const RSC_KEYS_HEADER: &str = "X-RSC-Keys";
+
+/// A reference to the node in another row, e.g. `"$3"` on the wire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RowRef(pub usize);
+
+impl Serialize for RowRef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("${}", self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for RowRef {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.strip_prefix('$')
+            .and_then(|row| row.parse().ok())
+            .map(RowRef)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid row reference: {}", s)))
+    }
+}
+
+/// A server component failure, sent as `E:<id>:<json>` where `id` is the row or suspense
+/// boundary that failed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RscError {
+    /// A short hash of the error, for matching what the client shows to the server log.
+    #[serde(default)]
+    pub digest: String,
+    /// The full error, only sent in development.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl RscError {
+    /// The row for `error`, with its message stripped unless `include_message` is set.
+    pub fn new(error: &str, include_message: bool) -> Self {
+        let mut hasher = DefaultHasher::new();
+        error.hash(&mut hasher);
+        Self {
+            digest: format!("{:08x}", hasher.finish() as u32),
+            message: include_message.then(|| error.to_string()),
+        }
+    }
+
+    pub fn to_row(&self, id: &str) -> String {
+        let json = serde_json::to_string(self).unwrap_or_else(|_| "{}".to_string());
+        format!("E:{}:{}", id, json)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -71,11 +143,66 @@ impl RscPayload {
         lines.join("\n")
     }
 
+    /// The row that fills suspense boundary `id`, streamed after its placeholder.
+    pub fn suspense_row(id: &str, node: &RscNode) -> String {
+        let node_json = serde_json::to_string(node).unwrap_or_else(|_| "null".to_string());
+        format!("${}:{}", id, node_json)
+    }
+
+    /// The payload as MessagePack, a compact alternative to [`RscPayload::to_wire_format`]
+    /// for large pages. The whole payload is one message, so it does not stream.
+    pub fn to_msgpack(&self) -> Vec<u8> {
+        rmp_serde::to_vec(self).unwrap_or_default()
+    }
+
+    pub fn from_msgpack(bytes: &[u8]) -> Result<Self, rmp_serde::decode::Error> {
+        rmp_serde::from_slice(bytes)
+    }
+
+    pub fn encode(&self, encoding: RscEncoding) -> Vec<u8> {
+        match encoding {
+            RscEncoding::Text => self.to_wire_format().into_bytes(),
+            RscEncoding::MessagePack => self.to_msgpack(),
+        }
+    }
+
     pub fn to_json(&self) -> serde_json::Value {
         serde_json::to_value(self).unwrap_or(serde_json::json!({}))
     }
 }
 
+/// How a payload is sent to the client, negotiated from the request's `Accept` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RscEncoding {
+    /// The line-based wire format, one JSON row per line.
+    #[default]
+    Text,
+    MessagePack,
+}
+
+impl RscEncoding {
+    pub const TEXT_MEDIA_TYPE: &'static str = "text/x-component";
+    pub const MSGPACK_MEDIA_TYPE: &'static str = "application/x-component+msgpack";
+
+    /// The encoding an `Accept` header asks for, or `None` if it does not ask for RSC.
+    pub fn from_accept(accept: &str) -> Option<Self> {
+        if accept.contains(Self::MSGPACK_MEDIA_TYPE) {
+            Some(Self::MessagePack)
+        } else if accept.contains(Self::TEXT_MEDIA_TYPE) {
+            Some(Self::Text)
+        } else {
+            None
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Text => "text/x-component; charset=utf-8",
+            Self::MessagePack => Self::MSGPACK_MEDIA_TYPE,
+        }
+    }
+}
+
 impl Default for RscPayload {
     fn default() -> Self {
         Self::new()
@@ -108,9 +235,46 @@ impl RscNode {
         }
     }
 
+    pub fn reference(row: usize) -> Self {
+        Self::Reference(RowRef(row))
+    }
+
+    pub fn action(reference: ActionReference) -> Self {
+        Self::Action(reference)
+    }
+
+    /// Whether this node or one of its descendants references `row`.
+    pub fn references(&self, row: usize) -> bool {
+        match self {
+            Self::Reference(RowRef(target)) => *target == row,
+            Self::Element { children, .. } => children.iter().any(|c| c.references(row)),
+            Self::Suspense {
+                fallback, children, ..
+            } => fallback.references(row) || children.iter().any(|c| c.references(row)),
+            Self::Text { .. } | Self::ClientReference { .. } | Self::Action(_) => false,
+        }
+    }
+
     pub fn suspense(id: impl Into<String>, fallback: RscNode, children: Vec<RscNode>) -> Self {
         Self::Suspense {
             id: id.into(),
+            key: None,
+            fallback: Box::new(fallback),
+            children,
+        }
+    }
+
+    /// A suspense boundary whose content stays the same while `key` does. See
+    /// [`RscNode::Suspense`].
+    pub fn keyed_suspense(
+        id: impl Into<String>,
+        key: impl Into<String>,
+        fallback: RscNode,
+        children: Vec<RscNode>,
+    ) -> Self {
+        Self::Suspense {
+            id: id.into(),
+            key: Some(key.into()),
             fallback: Box::new(fallback),
             children,
         }
@@ -175,6 +339,7 @@ mod tests {
             id,
             fallback,
             children,
+            ..
         } = node
         {
             assert_eq!(id, "async-data");
@@ -185,6 +350,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_row_reference_wire_format() {
+        let node = RscNode::element(
+            "main",
+            serde_json::json!({}),
+            vec![RscNode::reference(3), RscNode::text("Footer")],
+        );
+        let json = serde_json::to_string(&node).unwrap();
+        assert!(json.contains(r#""children":["$3",{"type":"text","value":"Footer"}]"#));
+
+        let parsed: RscNode = serde_json::from_str(&json).unwrap();
+        assert!(parsed.references(3));
+        assert!(!parsed.references(1));
+        assert!(serde_json::from_str::<RscNode>(r#""$x""#).is_err());
+    }
+
     #[test]
     fn test_json_serialization() {
         let mut payload = RscPayload::new();
@@ -193,4 +374,60 @@ mod tests {
         let json = payload.to_json();
         assert!(json["nodes"].is_array());
     }
+
+    #[test]
+    fn test_msgpack_round_trip() {
+        let mut payload = RscPayload::new();
+        payload.add_node(RscNode::element(
+            "main",
+            serde_json::json!({"class": ["page", "wide"], "hidden": false, "tabindex": 2}),
+            vec![
+                RscNode::suspense("feed", RscNode::text("Loading..."), vec![]),
+                RscNode::keyed_suspense("ads", "ads:home", RscNode::text("..."), vec![]),
+                RscNode::reference(1),
+                RscNode::client_ref("Counter", serde_json::json!({"start": 1})),
+            ],
+        ));
+        payload.add_node(RscNode::text("Late row"));
+        payload.add_client_reference(
+            "Counter".to_string(),
+            "./Counter.js".to_string(),
+            "Counter".to_string(),
+        );
+
+        let bytes = payload.encode(RscEncoding::MessagePack);
+        assert!(bytes.len() < payload.to_wire_format().len());
+        let decoded = RscPayload::from_msgpack(&bytes).unwrap();
+        assert_eq!(decoded.to_wire_format(), payload.to_wire_format());
+    }
+
+    #[test]
+    fn test_encoding_from_accept() {
+        assert_eq!(
+            RscEncoding::from_accept("application/x-component+msgpack, text/x-component"),
+            Some(RscEncoding::MessagePack)
+        );
+        assert_eq!(
+            RscEncoding::from_accept("text/x-component"),
+            Some(RscEncoding::Text)
+        );
+        assert_eq!(RscEncoding::from_accept("text/html"), None);
+    }
+
+    #[test]
+    fn test_error_row_strips_message_in_production() {
+        let dev = RscError::new("db connection refused", true);
+        let prod = RscError::new("db connection refused", false);
+        assert_eq!(dev.digest, prod.digest);
+        assert_eq!(dev.digest.len(), 8);
+        assert_ne!(RscError::new("timeout", false).digest, prod.digest);
+
+        assert_eq!(
+            prod.to_row("feed"),
+            format!("E:feed:{{\"digest\":\"{}\"}}", prod.digest)
+        );
+        assert!(dev
+            .to_row("feed")
+            .contains("\"message\":\"db connection refused\""));
+    }
 }
@@ -1,28 +1,81 @@
+use react_rs_elements::attributes::AttributeValue;
 use react_rs_elements::{Element, Node};
 use serde_json::json;
 
-use crate::payload::{RscNode, RscPayload};
+use crate::component_registry::ClientComponentManifest;
+use crate::macros::is_bound_args_field;
+use crate::markers::{CLIENT_ID_ATTR, CLIENT_MODULE_ATTR, CLIENT_PROPS_ATTR};
+use crate::payload::{RscNode, RscPayload, RscRef};
+use crate::taint::{check_node_taint, TaintError};
+use crate::{ActionReference, FORM_ACTION_ATTR};
 
 pub struct RscRenderer {
     payload: RscPayload,
     client_id_counter: usize,
+    client_manifest: ClientComponentManifest,
 }
 
 impl RscRenderer {
+    /// A renderer that recognizes the client components registered globally, e.g. by
+    /// `#[client_component]` or `use_client!`.
     pub fn new() -> Self {
+        Self::with_client_manifest(ClientComponentManifest::from_registry(
+            crate::directive::global_registry(),
+        ))
+    }
+
+    /// A renderer that turns elements marked as one of `manifest`'s client components into
+    /// client references, adding an `M:` row for each component it meets.
+    pub fn with_client_manifest(manifest: ClientComponentManifest) -> Self {
         Self {
             payload: RscPayload::new(),
             client_id_counter: 0,
+            client_manifest: manifest,
         }
     }
 
     pub fn render_element(&mut self, element: &Element) -> RscNode {
+        if let Some(reference) = self.client_reference(element) {
+            return reference;
+        }
+
         let tag = element.tag().to_string();
 
-        let props = self.collect_props(element);
-        let children = self.render_children(element.get_children());
+        let mut props = self.collect_props(element);
+        let children = match ActionReference::from_element(element) {
+            Some(action) => {
+                action_props(element, &mut props, action);
+                // The bound arguments travel in the action node.
+                element
+                    .get_children()
+                    .iter()
+                    .filter(|child| {
+                        !matches!(child, Node::Element(input) if is_bound_args_field(input))
+                    })
+                    .map(|child| self.render_node(child))
+                    .collect()
+            }
+            None => self.render_children(element.get_children()),
+        };
+        let node = RscNode::element(tag, props, children);
+        if element.scoped_styles().is_empty() {
+            return node;
+        }
 
-        RscNode::element(tag, props, children)
+        // Payloads have no head to collect into, so extracted rules travel with the element.
+        let mut nodes: Vec<RscNode> = element
+            .scoped_styles()
+            .iter()
+            .map(|(class, css)| {
+                RscNode::element(
+                    "style",
+                    json!({ "data-style": class }),
+                    vec![RscNode::text(css)],
+                )
+            })
+            .collect();
+        nodes.push(node);
+        RscNode::element("fragment", json!({}), nodes)
     }
 
     pub fn render_node(&mut self, node: &Node) -> RscNode {
@@ -51,6 +104,13 @@ impl RscRenderer {
                     list_fn().iter().map(|n| self.render_node(n)).collect();
                 RscNode::element("fragment", json!({}), children)
             }
+            Node::DiffList(list) => {
+                let children: Vec<RscNode> = (list.items)()
+                    .iter()
+                    .map(|(_, n)| self.render_node(n))
+                    .collect();
+                RscNode::element("fragment", json!({}), children)
+            }
             Node::KeyedList(list_fn) => {
                 let children: Vec<RscNode> =
                     list_fn().iter().map(|(_, n)| self.render_node(n)).collect();
@@ -71,13 +131,44 @@ impl RscRenderer {
                     self.render_node(&eb.children)
                 }
             }
+            Node::Transition(_, child) => self.render_node(child),
+            Node::Template(template) => self.render_node(template.node()),
+            Node::Dynamic(view) => self.render_node(&view()),
+            Node::Portal(target, child) => {
+                let child = self.render_node(child);
+                RscNode::element("portal", json!({ "target": target }), vec![child])
+            }
         }
     }
 
-    pub fn render_to_payload(mut self, node: &Node) -> RscPayload {
-        let rsc_node = self.render_node(node);
+    /// # Panics
+    ///
+    /// If the payload contains a value registered with [`crate::taint_value`] or
+    /// [`crate::taint_object`]. See [`RscRenderer::try_render_to_payload`].
+    pub fn render_to_payload(self, node: &Node) -> RscPayload {
+        self.try_render_to_payload(node)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_render_to_payload(mut self, node: &Node) -> Result<RscPayload, TaintError> {
+        let rsc_node = self.render_tree(node)?;
         self.payload.add_node(rsc_node);
-        self.payload
+        Ok(self.payload)
+    }
+
+    /// Converts a tree built with the HTML builders, e.g. a server component's output, for
+    /// sending as one row. Registered client components become references, listed in
+    /// [`RscRenderer::client_references`].
+    pub fn render_tree(&mut self, node: &Node) -> Result<RscNode, TaintError> {
+        let rsc_node = self.render_node(node);
+        react_rs_core::effect::discard_mount_callbacks();
+        check_node_taint(&rsc_node)?;
+        Ok(rsc_node)
+    }
+
+    /// The client components met so far, one `M:` row each.
+    pub fn client_references(&self) -> &[RscRef] {
+        &self.payload.client_references
     }
 
     pub fn register_client_component(
@@ -98,12 +189,54 @@ impl RscRenderer {
         RscNode::client_ref(id, props)
     }
 
+    /// The reference for an element rendered by [`ClientMarker::render_with`], if its
+    /// component is in the client manifest.
+    ///
+    /// [`ClientMarker::render_with`]: crate::markers::ClientMarker::render_with
+    fn client_reference(&mut self, element: &Element) -> Option<RscNode> {
+        let attr = |name: &str| {
+            element
+                .attributes()
+                .iter()
+                .find(|attr| attr.name == name)
+                .map(|attr| attr.to_static_value())
+        };
+        let module = attr(CLIENT_MODULE_ATTR)?;
+        let export = attr(CLIENT_ID_ATTR)?;
+        self.client_manifest
+            .get_module(&format!("{}#{}", module, export))?;
+
+        let props = attr(CLIENT_PROPS_ATTR)
+            .and_then(|props| serde_json::from_str(&props).ok())
+            .unwrap_or_else(|| json!({}));
+        if !self
+            .payload
+            .client_references
+            .iter()
+            .any(|reference| reference.id == export)
+        {
+            self.payload
+                .add_client_reference(export.clone(), module, export.clone());
+        }
+        Some(RscNode::client_ref(export, props))
+    }
+
     fn collect_props(&self, element: &Element) -> serde_json::Value {
         let mut props = serde_json::Map::new();
 
         for attr in element.attributes() {
-            let value = attr.to_static_value();
-            props.insert(attr.name.to_string(), json!(value));
+            let value = match &attr.value {
+                AttributeValue::Bool(value) => json!(value),
+                AttributeValue::ReactiveBool(value) => json!(value.get()),
+                _ => json!(attr.to_static_value()),
+            };
+            props.insert(attr.name.to_string(), value);
+        }
+        if let Some(html) = element.get_inner_html() {
+            props.insert(
+                "dangerouslySetInnerHTML".to_string(),
+                json!({ "__html": html }),
+            );
         }
 
         json!(props)
@@ -117,6 +250,30 @@ impl RscRenderer {
     }
 }
 
+/// Replaces the attributes written by [`FormActionExt::action`] with an action node, which
+/// the client runtime binds to post to the action.
+///
+/// [`FormActionExt::action`]: crate::FormActionExt::action
+fn action_props(element: &Element, props: &mut serde_json::Value, action: ActionReference) {
+    let Some(props) = props.as_object_mut() else {
+        return;
+    };
+    props.remove(FORM_ACTION_ATTR);
+    let key = if element.tag() == "button" {
+        props.remove("formaction");
+        props.remove("formmethod");
+        if is_bound_args_field(element) {
+            props.remove("name");
+            props.remove("value");
+        }
+        "formAction"
+    } else {
+        props.remove("method");
+        "action"
+    };
+    props.insert(key.to_string(), json!(RscNode::action(action)));
+}
+
 impl Default for RscRenderer {
     fn default() -> Self {
         Self::new()
@@ -162,6 +319,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_render_boolean_props() {
+        let element = input().disabled(true).checked(false).attr("name", "q");
+        let payload = render_to_rsc_payload(&Node::Element(element));
+
+        let RscNode::Element { props, .. } = &payload.nodes[0] else {
+            panic!("Expected Element node");
+        };
+        assert_eq!(props["disabled"], json!(true));
+        assert_eq!(props["checked"], json!(false));
+        assert_eq!(props["name"], json!("q"));
+    }
+
     #[test]
     fn test_register_client_component() {
         let mut renderer = RscRenderer::new();
@@ -179,6 +349,47 @@ mod tests {
         assert_eq!(renderer.payload.client_references.len(), 1);
     }
 
+    #[test]
+    fn test_registered_client_component_becomes_reference() {
+        use crate::component_registry::ClientModuleEntry;
+
+        let mut manifest = ClientComponentManifest::new();
+        manifest.add_module(
+            "./Counter.js#Counter".to_string(),
+            ClientModuleEntry {
+                id: "./Counter.js#Counter".to_string(),
+                name: "Counter".to_string(),
+                chunks: vec!["./Counter.js.wasm".to_string()],
+                async_module: true,
+            },
+        );
+        let counter =
+            crate::markers::client_component("Counter", "./Counter.js", || button().text("0"));
+        let unregistered =
+            crate::markers::client_component("Chart", "./Chart.js", || div().text("Chart"));
+
+        let page = div()
+            .child(counter.render_with(&json!({"start": 1})).unwrap())
+            .child(counter.render_with(&json!({"start": 2})).unwrap())
+            .child(unregistered.render_with(&json!({})).unwrap());
+        let payload =
+            RscRenderer::with_client_manifest(manifest).render_to_payload(&Node::Element(page));
+
+        let RscNode::Element { children, .. } = &payload.nodes[0] else {
+            panic!("Expected Element node");
+        };
+        assert!(matches!(
+            &children[0],
+            RscNode::ClientReference { id, props } if id == "Counter" && props["start"] == 1
+        ));
+        assert!(matches!(&children[1], RscNode::ClientReference { .. }));
+        assert!(matches!(&children[2], RscNode::Element { tag, .. } if tag == "div"));
+        assert_eq!(payload.client_references.len(), 1);
+        assert!(payload
+            .to_wire_format()
+            .contains("M:Counter:./Counter.js:Counter"));
+    }
+
     #[test]
     fn test_fragment_rendering() {
         let fragment = Node::Fragment(vec![
@@ -194,4 +405,62 @@ mod tests {
             assert_eq!(children.len(), 2);
         }
     }
+
+    #[test]
+    fn test_portal_rendering() {
+        let node = react_rs_elements::portal("#modals", div().text("Dialog"));
+
+        let payload = render_to_rsc_payload(&node);
+        if let RscNode::Element {
+            tag,
+            props,
+            children,
+        } = &payload.nodes[0]
+        {
+            assert_eq!(tag, "portal");
+            assert_eq!(props["target"], "#modals");
+            assert_eq!(children.len(), 1);
+        } else {
+            panic!("Expected portal element");
+        }
+    }
+
+    #[test]
+    fn test_action_reference_props() {
+        use crate::{ActionReference, FormActionExt};
+
+        let delete = ActionReference {
+            id: "deletePost".to_string(),
+            bound_args: Some(json!({"post_id": 7})),
+        };
+        let page = div()
+            .child(form().action(&delete).child(button().text("Delete")))
+            .child(button().action(&delete).text("Delete now"));
+        let payload = render_to_rsc_payload(&Node::Element(page));
+
+        let RscNode::Element { children, .. } = &payload.nodes[0] else {
+            panic!("Expected Element node");
+        };
+        let RscNode::Element {
+            props,
+            children: form_children,
+            ..
+        } = &children[0]
+        else {
+            panic!("Expected form element");
+        };
+        assert_eq!(
+            props["action"],
+            json!({"type": "action", "id": "deletePost", "bound_args": {"post_id": 7}})
+        );
+        assert!(props.get(FORM_ACTION_ATTR).is_none());
+        assert_eq!(form_children.len(), 1);
+
+        let RscNode::Element { props, .. } = &children[1] else {
+            panic!("Expected button element");
+        };
+        assert_eq!(props["formAction"]["id"], "deletePost");
+        assert_eq!(props["formAction"]["bound_args"]["post_id"], 7);
+        assert!(props.get("name").is_none());
+    }
 }
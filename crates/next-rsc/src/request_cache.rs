@@ -0,0 +1,194 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+type CachedValue = Arc<dyn Any + Send + Sync>;
+
+enum Entry {
+    /// A fetch is in flight; these callers wait for it.
+    Pending(Vec<Waker>),
+    Ready(CachedValue),
+}
+
+/// The values fetched through [`cache`] while rendering one request.
+#[derive(Default)]
+struct RequestCache {
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+enum Claim {
+    Ready(CachedValue),
+    Fetch,
+    Wait,
+}
+
+impl RequestCache {
+    fn claim(&self, key: &str, cx: &mut Context<'_>) -> Claim {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get_mut(key) {
+            Some(Entry::Ready(value)) => Claim::Ready(value.clone()),
+            Some(Entry::Pending(waiters)) => {
+                waiters.push(cx.waker().clone());
+                Claim::Wait
+            }
+            None => {
+                entries.insert(key.to_string(), Entry::Pending(Vec::new()));
+                Claim::Fetch
+            }
+        }
+    }
+
+    /// Settles the in-flight fetch for `key`: stores its value, or with `None` clears it so
+    /// a waiting caller fetches instead.
+    fn settle(&self, key: &str, value: Option<CachedValue>) {
+        let mut entries = self.entries.lock().unwrap();
+        let previous = match value {
+            Some(value) => entries.insert(key.to_string(), Entry::Ready(value)),
+            None => entries.remove(key),
+        };
+        if let Some(Entry::Pending(waiters)) = previous {
+            waiters.into_iter().for_each(Waker::wake);
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<RequestCache>>> = const { RefCell::new(None) };
+}
+
+/// Runs `render` with its own [`cache`] scope, e.g. one page render.
+pub fn with_request_cache<F: Future>(render: F) -> RequestScoped<F> {
+    RequestScoped {
+        cache: Arc::new(RequestCache::default()),
+        inner: Box::pin(render),
+    }
+}
+
+/// A future running inside a request's [`cache`] scope. See [`with_request_cache`].
+pub struct RequestScoped<F> {
+    cache: Arc<RequestCache>,
+    inner: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for RequestScoped<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        let outer = CURRENT.with(|current| current.replace(Some(self.cache.clone())));
+        let result = self.inner.as_mut().poll(cx);
+        CURRENT.with(|current| *current.borrow_mut() = outer);
+        result
+    }
+}
+
+/// Memoizes `fetcher` under `key` for the current request, mirroring React's `cache()`:
+/// server components asking for the same data during one render share a single fetch,
+/// including calls made while the first is still in flight. Outside
+/// [`with_request_cache`] every call fetches.
+///
+/// ```rust,ignore
+/// async fn user(id: u64) -> User {
+///     cache(format!("user:{}", id), || db::find_user(id)).await
+/// }
+/// ```
+///
+/// # Panics
+///
+/// If `key` was cached with a different value type during the same request.
+pub async fn cache<T, F, Fut>(key: impl Into<String>, fetcher: F) -> T
+where
+    T: Clone + Send + Sync + 'static,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = T>,
+{
+    let key = key.into();
+    let Some(scope) = CURRENT.with(|current| current.borrow().clone()) else {
+        return fetcher().await;
+    };
+
+    let claimed = poll_fn(|cx| match scope.claim(&key, cx) {
+        Claim::Ready(value) => Poll::Ready(Some(value)),
+        Claim::Fetch => Poll::Ready(None),
+        Claim::Wait => Poll::Pending,
+    })
+    .await;
+    if let Some(value) = claimed {
+        return value
+            .downcast_ref::<T>()
+            .unwrap_or_else(|| panic!("cache key `{}` was used with different types", key))
+            .clone();
+    }
+
+    let mut guard = FetchGuard {
+        scope: &scope,
+        key: &key,
+        settled: false,
+    };
+    let value = fetcher().await;
+    scope.settle(&key, Some(Arc::new(value.clone())));
+    guard.settled = true;
+    value
+}
+
+/// Clears an in-flight entry whose fetch was dropped before finishing, so waiters retry.
+struct FetchGuard<'a> {
+    scope: &'a RequestCache,
+    key: &'a str,
+    settled: bool,
+}
+
+impl Drop for FetchGuard<'_> {
+    fn drop(&mut self) {
+        if !self.settled {
+            self.scope.settle(self.key, None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    async fn fetch_user(calls: &AtomicUsize, id: u32) -> String {
+        cache(format!("user:{}", id), || async move {
+            calls.fetch_add(1, Ordering::SeqCst);
+            tokio::task::yield_now().await;
+            format!("User {}", id)
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_cache_dedupes_within_request() {
+        let calls = AtomicUsize::new(0);
+        let (a, b, c) = with_request_cache(async {
+            tokio::join!(
+                fetch_user(&calls, 1),
+                fetch_user(&calls, 1),
+                fetch_user(&calls, 2)
+            )
+        })
+        .await;
+
+        assert_eq!(a, "User 1");
+        assert_eq!(b, "User 1");
+        assert_eq!(c, "User 2");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        with_request_cache(fetch_user(&calls, 1)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_cache_outside_request_always_fetches() {
+        let calls = AtomicUsize::new(0);
+        fetch_user(&calls, 1).await;
+        fetch_user(&calls, 1).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
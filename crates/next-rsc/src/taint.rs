@@ -0,0 +1,254 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::RscNode;
+
+struct ValueTaint {
+    message: String,
+    value: String,
+}
+
+struct ObjectTaint {
+    message: String,
+    value: Value,
+    /// Whether the tainted object is still alive; the taint is dropped once it is not.
+    alive: Box<dyn Fn() -> bool + Send>,
+}
+
+struct Taints {
+    /// Secrets such as API keys; any string containing one is rejected. Registered once
+    /// per distinct value, so they stay as few as the app's secrets.
+    values: Vec<ValueTaint>,
+    /// Values such as raw database rows, rejected wherever they appear serialized whole.
+    objects: Vec<ObjectTaint>,
+}
+
+impl Taints {
+    fn prune(&mut self) {
+        self.objects.retain(|taint| (taint.alive)());
+    }
+
+    fn is_empty(&self) -> bool {
+        self.values.is_empty() && self.objects.is_empty()
+    }
+}
+
+static TAINTS: Mutex<Taints> = Mutex::new(Taints {
+    values: Vec::new(),
+    objects: Vec::new(),
+});
+
+/// Marks `value` as never to be sent to the browser. Rendering an RSC payload or client
+/// props that contain it fails with `message`. Meant for secrets that live as long as the
+/// server; tainting the same value again keeps the first message.
+///
+/// ```rust,ignore
+/// taint_value("Do not pass the API key to the client", &config.api_key);
+/// ```
+pub fn taint_value(message: impl Into<String>, value: impl Into<String>) {
+    let value = value.into();
+    if value.is_empty() {
+        return;
+    }
+    let mut taints = TAINTS.lock().unwrap();
+    if taints.values.iter().any(|taint| taint.value == value) {
+        return;
+    }
+    taints.values.push(ValueTaint {
+        message: message.into(),
+        value,
+    });
+}
+
+/// Marks `object` as never to be sent to the browser whole, e.g. a database row holding a
+/// password hash. Pick the fields the client needs instead. The taint is held weakly and
+/// lapses once the last `Arc` to the object is dropped, typically at the end of the
+/// request that loaded it.
+pub fn taint_object<T>(message: impl Into<String>, object: &Arc<T>)
+where
+    T: Serialize + Send + Sync + 'static,
+{
+    let Ok(value) = serde_json::to_value(&**object) else {
+        return;
+    };
+    let weak = Arc::downgrade(object);
+    let mut taints = TAINTS.lock().unwrap();
+    taints.prune();
+    taints.objects.push(ObjectTaint {
+        message: message.into(),
+        value,
+        alive: Box::new(move || weak.strong_count() > 0),
+    });
+}
+
+/// A tainted value found in data bound for the browser.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaintError {
+    pub message: String,
+}
+
+impl fmt::Display for TaintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "tainted value in RSC payload: {}", self.message)
+    }
+}
+
+impl std::error::Error for TaintError {}
+
+/// Checks serialized props or payload data for tainted values.
+pub fn check_taint(value: &Value) -> Result<(), TaintError> {
+    let mut taints = TAINTS.lock().unwrap();
+    taints.prune();
+    if taints.is_empty() {
+        return Ok(());
+    }
+    check_value(&taints, value)
+}
+
+pub(crate) fn check_node_taint(node: &RscNode) -> Result<(), TaintError> {
+    let mut taints = TAINTS.lock().unwrap();
+    taints.prune();
+    if taints.is_empty() {
+        return Ok(());
+    }
+    check_node(&taints, node)
+}
+
+fn check_node(taints: &Taints, node: &RscNode) -> Result<(), TaintError> {
+    match node {
+        RscNode::Element {
+            tag,
+            props,
+            children,
+        } => {
+            check_string(taints, tag)?;
+            check_value(taints, props)?;
+            children.iter().try_for_each(|c| check_node(taints, c))
+        }
+        RscNode::Text { value } => check_string(taints, value),
+        RscNode::ClientReference { props, .. } => check_value(taints, props),
+        RscNode::Suspense {
+            fallback, children, ..
+        } => {
+            check_node(taints, fallback)?;
+            children.iter().try_for_each(|c| check_node(taints, c))
+        }
+        RscNode::Action(action) => match &action.bound_args {
+            Some(args) => check_value(taints, args),
+            None => Ok(()),
+        },
+        RscNode::Reference(_) => Ok(()),
+    }
+}
+
+fn check_value(taints: &Taints, value: &Value) -> Result<(), TaintError> {
+    if value.is_object() || value.is_array() {
+        if let Some(taint) = taints.objects.iter().find(|taint| taint.value == *value) {
+            return Err(TaintError {
+                message: taint.message.clone(),
+            });
+        }
+    }
+    match value {
+        Value::String(s) => check_string(taints, s),
+        Value::Array(items) => items.iter().try_for_each(|v| check_value(taints, v)),
+        Value::Object(map) => map.iter().try_for_each(|(key, v)| {
+            check_string(taints, key)?;
+            check_value(taints, v)
+        }),
+        Value::Null | Value::Bool(_) | Value::Number(_) => Ok(()),
+    }
+}
+
+fn check_string(taints: &Taints, s: &str) -> Result<(), TaintError> {
+    match taints
+        .values
+        .iter()
+        .find(|taint| s.contains(taint.value.as_str()))
+    {
+        Some(taint) => Err(TaintError {
+            message: taint.message.clone(),
+        }),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markers::client_component;
+    use crate::RscRenderer;
+    use react_rs_elements::html::*;
+    use react_rs_elements::Node;
+    use serde_json::json;
+
+    #[test]
+    fn test_tainted_value_fails_render() {
+        taint_value("API key must stay on the server", "sk_live_taint_test_1");
+        taint_value("Registered twice", "sk_live_taint_test_1");
+        let registered = TAINTS
+            .lock()
+            .unwrap()
+            .values
+            .iter()
+            .filter(|taint| taint.value == "sk_live_taint_test_1")
+            .count();
+        assert_eq!(registered, 1);
+
+        let page = div().child(p().text("key: sk_live_taint_test_1"));
+        let err = RscRenderer::new()
+            .try_render_to_payload(&Node::Element(page))
+            .unwrap_err();
+        assert_eq!(err.message, "API key must stay on the server");
+
+        let safe = div().text("safe");
+        assert!(RscRenderer::new()
+            .try_render_to_payload(&Node::Element(safe))
+            .is_ok());
+
+        let widget = client_component("Widget", "./Widget.js", || div().text("Widget"));
+        let err = widget
+            .reference(&json!({"config": ["sk_live_taint_test_1"]}))
+            .unwrap_err();
+        assert!(err.to_string().contains("API key must stay on the server"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Do not leak the session token")]
+    fn test_render_to_payload_panics_on_taint() {
+        taint_value("Do not leak the session token", "sess_taint_test_2");
+        let page = div().attr("data-token", "sess_taint_test_2");
+        RscRenderer::new().render_to_payload(&Node::Element(page));
+    }
+
+    #[test]
+    fn test_tainted_object() {
+        #[derive(Serialize)]
+        struct UserRow {
+            id: u32,
+            password_hash: &'static str,
+        }
+        let row = Arc::new(UserRow {
+            id: 4164,
+            password_hash: "$argon2id$taint-test",
+        });
+        taint_object("Pass only the fields the client needs", &row);
+
+        let profile = client_component("Profile", "./Profile.js", || div().text("Profile"));
+        let Err(err) = profile.render_with(&json!({"user": *row})) else {
+            panic!("Expected tainted props to be rejected");
+        };
+        assert!(err
+            .to_string()
+            .contains("Pass only the fields the client needs"));
+        assert!(profile.render_with(&json!({"user": {"id": 4164}})).is_ok());
+
+        // The taint lapses with the row instead of piling up for the life of the server.
+        let copy = json!({"user": *row});
+        drop(row);
+        assert!(profile.render_with(&copy).is_ok());
+    }
+}
@@ -1,16 +1,20 @@
+use std::convert::Infallible;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::Arc;
 
 use bytes::Bytes;
-use http_body_util::Full;
+use futures_util::StreamExt;
+use http_body_util::{Either, Full, StreamBody};
+use hyper::body::Frame;
 use hyper::{Request, Response, StatusCode};
 use next_rs_router::Router;
 
 use crate::api::{ApiRequest, ApiResponse, ApiRouteHandler};
-use crate::rsc_handler::RscHandler;
+use crate::rsc_handler::{RscHandler, RSC_CACHE_HEADER};
 use crate::ssr::{PageRegistry, SsrRenderer};
 use next_rs_middleware::{MiddlewareMatcher, MiddlewareResult, NextRequest};
+use next_rs_rsc::RscEncoding;
 
 const RSC_PREFIX: &str = "/_rsc";
 const API_PREFIX: &str = "/api";
@@ -18,6 +22,26 @@ const ACTION_PREFIX: &str = "/_action/";
 const WS_PREFIX: &str = "/ws/";
 
 type MiddlewareFn = Arc<dyn Fn(&NextRequest) -> MiddlewareResult + Send + Sync>;
+type FrameStream =
+    std::pin::Pin<Box<dyn futures_core::Stream<Item = Result<Frame<Bytes>, Infallible>> + Send>>;
+
+/// Body of the responses sent by [`RequestHandler::serve`]: buffered, or streamed for
+/// action calls that accept progress events.
+pub type ResponseBody = Either<Full<Bytes>, StreamBody<FrameStream>>;
+
+/// Receives each error an error boundary caught while rendering a page, with its route.
+pub type RenderErrorHook = Arc<dyn Fn(&str, &react_rs_dom::RenderError) + Send + Sync>;
+
+struct ParsedAction {
+    request: next_rs_actions::ActionRequest,
+    context: next_rs_actions::ActionContext,
+    form: Option<next_rs_actions::FormData>,
+    /// A form posted without JS, which expects a page rather than JSON. Forms submitted by
+    /// the client runtime ask for JSON.
+    is_form_post: bool,
+    /// Same-origin path of the submitting page, for forms posted without JS.
+    return_to: String,
+}
 
 pub struct RequestHandler {
     router: Router,
@@ -30,6 +54,8 @@ pub struct RequestHandler {
     action_registry: Arc<next_rs_actions::ActionRegistry>,
     ws_registry: Arc<crate::ws::WsRegistry>,
     middlewares: Vec<(MiddlewareMatcher, MiddlewareFn)>,
+    multipart_limits: next_rs_actions::MultipartLimits,
+    render_error_hook: Option<RenderErrorHook>,
 }
 
 impl RequestHandler {
@@ -37,7 +63,7 @@ impl RequestHandler {
         let renderer = SsrRenderer::new();
         let rsc_handler = RscHandler::new(app_dir.clone());
         let api_handler = ApiRouteHandler::new();
-        let action_registry = Arc::new(next_rs_actions::ActionRegistry::new());
+        let action_registry = Arc::new(next_rs_actions::ActionRegistry::with_server_actions());
         let ws_registry = Arc::new(crate::ws::WsRegistry::new());
         Self {
             router,
@@ -49,6 +75,8 @@ impl RequestHandler {
             action_registry,
             ws_registry,
             middlewares: Vec::new(),
+            multipart_limits: next_rs_actions::MultipartLimits::default(),
+            render_error_hook: None,
         }
     }
 
@@ -57,10 +85,42 @@ impl RequestHandler {
         self
     }
 
+    /// Replaces the registry of `#[server_action]`s, e.g. with one that also has
+    /// middleware or actions registered by hand.
+    pub fn with_action_registry(mut self, registry: next_rs_actions::ActionRegistry) -> Self {
+        self.action_registry = Arc::new(registry);
+        self
+    }
+
+    /// Limits for file uploads posted to form actions.
+    pub fn with_multipart_limits(mut self, limits: next_rs_actions::MultipartLimits) -> Self {
+        self.multipart_limits = limits;
+        self
+    }
+
+    /// Reports the errors error boundaries catch while rendering pages; without a hook they
+    /// only show in the rendered fallbacks.
+    pub fn on_render_error(
+        mut self,
+        hook: impl Fn(&str, &react_rs_dom::RenderError) + Send + Sync + 'static,
+    ) -> Self {
+        self.render_error_hook = Some(Arc::new(hook));
+        self
+    }
+
     pub fn action_registry(&self) -> &Arc<next_rs_actions::ActionRegistry> {
         &self.action_registry
     }
 
+    pub fn rsc_handler_mut(&mut self) -> &mut RscHandler {
+        &mut self.rsc_handler
+    }
+
+    /// Drops the cached RSC payloads of every route tagged `tag`.
+    pub fn revalidate_tag(&self, tag: &str) -> usize {
+        self.rsc_handler.revalidate_tag(tag)
+    }
+
     pub fn api_handler_mut(&mut self) -> &mut ApiRouteHandler {
         &mut self.api_handler
     }
@@ -77,15 +137,17 @@ impl RequestHandler {
         self.middlewares.push((matcher, Arc::new(handler)));
     }
 
-    fn run_middlewares(&self, path: &str) -> Option<MiddlewareResult> {
-        let request = NextRequest::new("GET", path);
+    fn run_middlewares<B>(&self, req: &Request<B>) -> Option<MiddlewareResult> {
+        let path = req.uri().path();
+        let mut request = None;
         for (matcher, handler) in &self.middlewares {
-            if matcher.matches(path) {
-                let result = handler(&request);
-                match result {
-                    MiddlewareResult::Next => continue,
-                    other => return Some(other),
-                }
+            if !matcher.matches(path) {
+                continue;
+            }
+            let request = request.get_or_insert_with(|| next_request(req));
+            match handler(request) {
+                MiddlewareResult::Next => continue,
+                other => return Some(other),
             }
         }
         None
@@ -98,12 +160,44 @@ impl RequestHandler {
         self.handle_with_dev_ws(req, None).await
     }
 
+    /// Handles `req` like [`RequestHandler::handle_with_dev_ws`], but streams the progress
+    /// of action calls that accept `application/x-ndjson`.
+    pub async fn serve(
+        &self,
+        req: Request<hyper::body::Incoming>,
+        reload_rx: Option<tokio::sync::broadcast::Receiver<String>>,
+    ) -> Result<Response<ResponseBody>, hyper::Error> {
+        let path = req.uri().path().to_string();
+        let mut middleware = None;
+        if path.starts_with(ACTION_PREFIX) && accepts_action_stream(&req) {
+            let result = self.run_middlewares(&req);
+            if matches!(result, None | Some(MiddlewareResult::Next)) {
+                return Ok(self.handle_action_stream(&path, req).await);
+            }
+            middleware = Some(result);
+        }
+        let response = self.respond(req, reload_rx, middleware).await?;
+        Ok(response.map(Either::Left))
+    }
+
     pub async fn handle_with_dev_ws(
         &self,
         req: Request<hyper::body::Incoming>,
         reload_rx: Option<tokio::sync::broadcast::Receiver<String>>,
+    ) -> Result<Response<Full<Bytes>>, hyper::Error> {
+        self.respond(req, reload_rx, None).await
+    }
+
+    /// Handles `req`. `middleware` is the result of the middlewares when the caller already
+    /// ran them, so they run once per request.
+    async fn respond(
+        &self,
+        req: Request<hyper::body::Incoming>,
+        reload_rx: Option<tokio::sync::broadcast::Receiver<String>>,
+        middleware: Option<Option<MiddlewareResult>>,
     ) -> Result<Response<Full<Bytes>>, hyper::Error> {
         let path = req.uri().path().to_string();
+        let csrf_token = request_csrf_token(&req);
 
         if path == "/__dev_ws" {
             if let Some(mut rx) = reload_rx {
@@ -115,7 +209,8 @@ impl RequestHandler {
             return self.handle_image_request(req.uri()).await;
         }
 
-        if let Some(mw_result) = self.run_middlewares(&path) {
+        let middleware = middleware.unwrap_or_else(|| self.run_middlewares(&req));
+        if let Some(mw_result) = middleware {
             match mw_result {
                 MiddlewareResult::Redirect(redirect) => {
                     return Ok(Response::builder()
@@ -125,7 +220,9 @@ impl RequestHandler {
                         .unwrap());
                 }
                 MiddlewareResult::Rewrite(new_path) => {
-                    return self.handle_html_request(&new_path).await;
+                    return self
+                        .handle_html_request(&new_path, csrf_token.as_deref())
+                        .await;
                 }
                 MiddlewareResult::Response(resp) => {
                     let mut builder = Response::builder().status(resp.status);
@@ -153,26 +250,27 @@ impl RequestHandler {
             return self.handle_action_request(&path, req).await;
         }
 
+        let rsc_encoding = req
+            .headers()
+            .get("Accept")
+            .and_then(|v| v.to_str().ok())
+            .and_then(RscEncoding::from_accept);
+
         if path.starts_with(RSC_PREFIX) {
-            return self.handle_rsc_request(&path).await;
+            return self
+                .handle_rsc_request(&path, rsc_encoding.unwrap_or_default())
+                .await;
         }
 
         if path.starts_with(API_PREFIX) {
             return self.handle_api_request(&req).await;
         }
 
-        let accepts_rsc = req
-            .headers()
-            .get("Accept")
-            .and_then(|v| v.to_str().ok())
-            .map(|v| v.contains("text/x-component"))
-            .unwrap_or(false);
-
-        if accepts_rsc {
-            return self.handle_rsc_navigation(&path).await;
+        if let Some(encoding) = rsc_encoding {
+            return self.handle_rsc_navigation(&path, encoding).await;
         }
 
-        self.handle_html_request(&path).await
+        self.handle_html_request(&path, csrf_token.as_deref()).await
     }
 
     async fn handle_dev_ws(
@@ -215,46 +313,180 @@ impl RequestHandler {
         path: &str,
         req: Request<hyper::body::Incoming>,
     ) -> Result<Response<Full<Bytes>>, hyper::Error> {
-        let action_id = path.strip_prefix(ACTION_PREFIX).unwrap_or("");
+        // Uploads live as long as the form, so keep it until the action has run.
+        let ParsedAction {
+            request,
+            context,
+            form: _form,
+            is_form_post,
+            return_to,
+        } = match self.read_action_request(path, req).await {
+            Ok(action) => action,
+            Err(response) => return Ok(response),
+        };
+
+        let response = self
+            .action_registry
+            .execute_with_context(request, context.clone())
+            .await;
+        self.rsc_handler.cache().revalidate(&context.revalidation());
 
-        let body_bytes = match http_body_util::BodyExt::collect(req.into_body()).await {
-            Ok(collected) => collected.to_bytes(),
-            Err(_) => {
-                let resp = next_rs_actions::ActionResponse::error(
-                    next_rs_actions::ActionError::new("Failed to read request body"),
-                );
-                let json = serde_json::to_string(&resp).unwrap_or_default();
+        // Forms submitted without JS expect a page, not JSON: go back to the submitting page.
+        if is_form_post {
+            if response.is_not_found() {
                 return Ok(Response::builder()
-                    .status(StatusCode::BAD_REQUEST)
-                    .header("Content-Type", "application/json")
-                    .body(Full::new(Bytes::from(json)))
+                    .status(StatusCode::NOT_FOUND)
+                    .header("Content-Type", "text/html; charset=utf-8")
+                    .body(Full::new(Bytes::from(self.renderer.render_not_found())))
                     .unwrap());
             }
-        };
-
-        let payload: serde_json::Value =
-            serde_json::from_slice(&body_bytes).unwrap_or(serde_json::Value::Null);
-
-        let request = next_rs_actions::ActionRequest {
-            action_id: action_id.to_string(),
-            payload,
-        };
+            return Ok(form_action_response(&response, &return_to, &self.renderer));
+        }
 
-        let response = self.action_registry.execute(request).await;
         let status = if response.success {
             StatusCode::OK
         } else {
-            StatusCode::BAD_REQUEST
+            action_error_status(&response)
         };
         let json = serde_json::to_string(&response).unwrap_or_default();
 
-        Ok(Response::builder()
+        Ok(retry_after(Response::builder(), &response)
             .status(status)
             .header("Content-Type", "application/json")
             .body(Full::new(Bytes::from(json)))
             .unwrap())
     }
 
+    /// Checks an action call against CSRF and reads its arguments, or responds with why it
+    /// was rejected.
+    async fn read_action_request(
+        &self,
+        path: &str,
+        req: Request<hyper::body::Incoming>,
+    ) -> Result<ParsedAction, Response<Full<Bytes>>> {
+        let action_id = path.strip_prefix(ACTION_PREFIX).unwrap_or("");
+        let csrf = self.action_registry.csrf_protection(action_id);
+        if csrf != next_rs_actions::CsrfProtection::Disabled && !same_origin_request(&req) {
+            return Err(csrf_error_response("Cross-origin action request"));
+        }
+        let csrf_cookie = request_csrf_token(&req);
+        let csrf_header = req
+            .headers()
+            .get(next_rs_actions::CSRF_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let content_type = req
+            .headers()
+            .get(hyper::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+            .to_string();
+        let boundary = next_rs_actions::multipart_boundary(&content_type).map(str::to_string);
+        let is_form_post =
+            content_type.starts_with("application/x-www-form-urlencoded") || boundary.is_some();
+        let return_to = form_return_path(&req);
+        let context = action_context(&req);
+        let wants_json = accepts_json(&req);
+
+        // Only multipart bodies are capped, and the cap applies while reading, so an oversized
+        // upload is rejected before it is buffered in full.
+        let limit = match boundary {
+            Some(_) => usize::try_from(self.multipart_limits.max_total_size).unwrap_or(usize::MAX),
+            None => usize::MAX,
+        };
+        let body_bytes = read_body(req.into_body(), limit).await?;
+
+        let form = match &boundary {
+            Some(boundary) => match next_rs_actions::FormData::from_multipart(
+                &body_bytes,
+                boundary,
+                &self.multipart_limits,
+            ) {
+                Ok(form) => Some(form),
+                Err(e) => return Err(multipart_error_response(&e)),
+            },
+            None if is_form_post => Some(next_rs_actions::FormData::from_urlencoded(
+                &String::from_utf8_lossy(&body_bytes),
+            )),
+            None => None,
+        };
+        if csrf == next_rs_actions::CsrfProtection::Full {
+            let submitted = csrf_header.as_deref().or_else(|| {
+                form.as_ref()
+                    .and_then(|form| form.get(next_rs_actions::CSRF_FIELD))
+            });
+            let valid = match (csrf_cookie.as_deref(), submitted) {
+                (Some(cookie), Some(submitted)) => next_rs_actions::tokens_match(cookie, submitted),
+                _ => false,
+            };
+            if !valid {
+                return Err(csrf_error_response("Missing or invalid CSRF token"));
+            }
+        }
+
+        let payload: serde_json::Value = if let Some(form) = &form {
+            form.to_action_payload()
+        } else {
+            serde_json::from_slice(&body_bytes).unwrap_or(serde_json::Value::Null)
+        };
+
+        let request = next_rs_actions::ActionRequest {
+            action_id: action_id.to_string(),
+            payload,
+        };
+        let context = match &form {
+            Some(form) => context.with_uploads(form.uploads()),
+            None => context,
+        };
+
+        Ok(ParsedAction {
+            request,
+            context,
+            form,
+            is_form_post: is_form_post && !wants_json,
+            return_to,
+        })
+    }
+
+    async fn handle_action_stream(
+        &self,
+        path: &str,
+        req: Request<hyper::body::Incoming>,
+    ) -> Response<ResponseBody> {
+        let ParsedAction {
+            request,
+            context,
+            form,
+            ..
+        } = match self.read_action_request(path, req).await {
+            Ok(action) => action,
+            Err(response) => return response.map(Either::Left),
+        };
+
+        let cache = self.rsc_handler.cache().clone();
+        let events = self
+            .action_registry
+            .execute_streaming(request, context.clone())
+            .map(move |event| {
+                // Moving the form in keeps its uploads until the stream ends.
+                let _uploads = &form;
+                if let next_rs_actions::ActionStreamEvent::Done(_) = &event {
+                    cache.revalidate(&context.revalidation());
+                }
+                let mut line = serde_json::to_vec(&event).unwrap_or_default();
+                line.push(b'\n');
+                Ok(Frame::data(Bytes::from(line)))
+            });
+
+        Response::builder()
+            .header("Content-Type", next_rs_actions::ACTION_STREAM_CONTENT_TYPE)
+            .header("Cache-Control", "no-cache")
+            .body(Either::Right(StreamBody::new(
+                Box::pin(events) as FrameStream
+            )))
+            .unwrap()
+    }
+
     async fn handle_api_request(
         &self,
         req: &Request<hyper::body::Incoming>,
@@ -368,17 +600,55 @@ impl RequestHandler {
             .unwrap())
     }
 
-    async fn handle_html_request(&self, path: &str) -> Result<Response<Full<Bytes>>, hyper::Error> {
+    /// Renders the page for `path`. Pages carry the visitor's CSRF token, minted here on
+    /// their first visit, for the forms that post to server actions.
+    async fn handle_html_request(
+        &self,
+        path: &str,
+        csrf_token: Option<&str>,
+    ) -> Result<Response<Full<Bytes>>, hyper::Error> {
         if let Some(matched) = self.router.match_path(path) {
-            let html = self
-                .renderer
-                .render(&matched.route.path, &matched.params, &self.registry);
+            // Rendering blocks on the page's resources, which would stall the connection's
+            // executor thread, or deadlock a current-thread runtime.
+            let renderer = self.renderer.clone();
+            let registry = self.registry.clone();
+            let (route_path, params) = (matched.route.path.clone(), matched.params.clone());
+            let rendered = match tokio::task::spawn_blocking(move || {
+                renderer.render(&route_path, &params, &registry)
+            })
+            .await
+            {
+                Ok(rendered) => rendered,
+                Err(_) => {
+                    return Ok(Response::builder()
+                        .status(StatusCode::INTERNAL_SERVER_ERROR)
+                        .header("Content-Type", "text/plain")
+                        .body(Full::new(Bytes::from("Internal Server Error")))
+                        .unwrap())
+                }
+            };
+            if let Some(hook) = &self.render_error_hook {
+                for error in &rendered.errors {
+                    hook(&matched.route.path, error);
+                }
+            }
 
-            Ok(Response::builder()
+            let mut builder = Response::builder()
                 .status(StatusCode::OK)
-                .header("Content-Type", "text/html; charset=utf-8")
-                .body(Full::new(Bytes::from(html)))
-                .unwrap())
+                .header("Content-Type", "text/html; charset=utf-8");
+            let token = match csrf_token {
+                Some(token) => token.to_string(),
+                None => {
+                    let token = next_rs_actions::generate_csrf_token();
+                    builder = builder.header(
+                        hyper::header::SET_COOKIE,
+                        next_rs_actions::csrf_cookie(&token),
+                    );
+                    token
+                }
+            };
+            let html = crate::ssr::embed_csrf_token(&rendered.html, &token);
+            Ok(builder.body(Full::new(Bytes::from(html))).unwrap())
         } else {
             let html = self.renderer.render_not_found();
 
@@ -390,7 +660,11 @@ impl RequestHandler {
         }
     }
 
-    async fn handle_rsc_request(&self, path: &str) -> Result<Response<Full<Bytes>>, hyper::Error> {
+    async fn handle_rsc_request(
+        &self,
+        path: &str,
+        encoding: RscEncoding,
+    ) -> Result<Response<Full<Bytes>>, hyper::Error> {
         let route_path = path.strip_prefix(RSC_PREFIX).unwrap_or("/");
         let route_path = if route_path.is_empty() {
             "/"
@@ -399,14 +673,16 @@ impl RequestHandler {
         };
 
         if let Some(matched) = self.router.match_path(route_path) {
-            let payload = self
+            let (payload, cache_status) = self
                 .rsc_handler
-                .render_to_wire_format(route_path, &matched.params);
+                .render_cached_as(route_path, &matched, encoding);
 
             Ok(Response::builder()
                 .status(StatusCode::OK)
-                .header("Content-Type", "text/x-component; charset=utf-8")
+                .header("Content-Type", encoding.content_type())
                 .header("Cache-Control", "no-cache")
+                .header("Vary", "Accept")
+                .header(RSC_CACHE_HEADER, cache_status.as_str())
                 .body(Full::new(Bytes::from(payload)))
                 .unwrap())
         } else {
@@ -423,16 +699,18 @@ impl RequestHandler {
     async fn handle_rsc_navigation(
         &self,
         path: &str,
+        encoding: RscEncoding,
     ) -> Result<Response<Full<Bytes>>, hyper::Error> {
         if let Some(matched) = self.router.match_path(path) {
-            let payload = self
-                .rsc_handler
-                .render_to_wire_format(path, &matched.params);
+            let (payload, cache_status) =
+                self.rsc_handler.render_cached_as(path, &matched, encoding);
 
             Ok(Response::builder()
                 .status(StatusCode::OK)
-                .header("Content-Type", "text/x-component; charset=utf-8")
+                .header("Content-Type", encoding.content_type())
                 .header("Cache-Control", "no-cache")
+                .header("Vary", "Accept")
+                .header(RSC_CACHE_HEADER, cache_status.as_str())
                 .body(Full::new(Bytes::from(payload)))
                 .unwrap())
         } else {
@@ -447,6 +725,210 @@ impl RequestHandler {
     }
 }
 
+fn request_csrf_token<B>(req: &Request<B>) -> Option<String> {
+    req.headers()
+        .get_all(hyper::header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .find_map(|cookies| next_rs_actions::cookie_value(cookies, next_rs_actions::CSRF_COOKIE))
+        .map(str::to_string)
+}
+
+/// Whether the request was sent by a page of this site, judging by its `Origin`, or its
+/// `Referer` when a browser leaves the origin out.
+fn same_origin_request<B>(req: &Request<B>) -> bool {
+    let header = |name| req.headers().get(name).and_then(|v| v.to_str().ok());
+    let Some(host) =
+        header(hyper::header::HOST).or_else(|| req.uri().authority().map(|a| a.as_str()))
+    else {
+        return false;
+    };
+    match header(hyper::header::ORIGIN).or_else(|| header(hyper::header::REFERER)) {
+        Some(origin) => next_rs_actions::same_origin(origin, host),
+        None => false,
+    }
+}
+
+fn action_context<B>(req: &Request<B>) -> next_rs_actions::ActionContext {
+    req.headers()
+        .iter()
+        .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)))
+        .fold(
+            next_rs_actions::ActionContext::new(),
+            |context, (name, value)| context.with_header(name, value),
+        )
+}
+
+fn accepts_action_stream<B>(req: &Request<B>) -> bool {
+    req.headers()
+        .get(hyper::header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|accept| accept.contains(next_rs_actions::ACTION_STREAM_CONTENT_TYPE))
+}
+
+fn accepts_json<B>(req: &Request<B>) -> bool {
+    req.headers()
+        .get(hyper::header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|accept| accept.contains("application/json"))
+}
+
+/// What middleware sees of `req`: method, URL, headers (by lowercase name) and cookies.
+fn next_request<B>(req: &Request<B>) -> NextRequest {
+    let url = req
+        .uri()
+        .path_and_query()
+        .map_or("/", |path_and_query| path_and_query.as_str());
+    let mut request = NextRequest::new(req.method().as_str(), url);
+    for (name, value) in req.headers() {
+        let Ok(value) = value.to_str() else {
+            continue;
+        };
+        if name == hyper::header::COOKIE {
+            for (key, value) in value.split(';').filter_map(|pair| pair.split_once('=')) {
+                request = request.with_cookie(key.trim(), value.trim());
+            }
+        }
+        request = request.with_header(name.as_str(), value);
+    }
+    request
+}
+
+/// The page to send a form back to: the `Referer`'s path when it is a page of this site,
+/// otherwise `/`, so a forged header cannot redirect elsewhere.
+fn form_return_path<B>(req: &Request<B>) -> String {
+    let header = |name| req.headers().get(name).and_then(|v| v.to_str().ok());
+    let host = header(hyper::header::HOST).or_else(|| req.uri().authority().map(|a| a.as_str()));
+    let referer = header(hyper::header::REFERER).and_then(|v| v.parse::<hyper::Uri>().ok());
+    let (Some(host), Some(referer)) = (host, referer) else {
+        return "/".to_string();
+    };
+    let same_origin = referer.scheme().is_some()
+        && referer
+            .authority()
+            .is_some_and(|authority| authority.as_str().eq_ignore_ascii_case(host));
+    referer
+        .path_and_query()
+        .map(|path| path.as_str())
+        // `//host/...` would be read as another origin.
+        .filter(|path| same_origin && path.starts_with('/') && !path.starts_with("//"))
+        .unwrap_or("/")
+        .to_string()
+}
+
+fn csrf_error_response(message: &str) -> Response<Full<Bytes>> {
+    let resp = next_rs_actions::ActionResponse::error(next_rs_actions::ActionError::with_code(
+        message,
+        "CSRF_FAILED",
+    ));
+    Response::builder()
+        .status(StatusCode::FORBIDDEN)
+        .header("Content-Type", "application/json")
+        .body(Full::new(Bytes::from(
+            serde_json::to_string(&resp).unwrap_or_default(),
+        )))
+        .unwrap()
+}
+
+/// Collects a request body of at most `limit` bytes, answering 413 past it.
+async fn read_body<B>(body: B, limit: usize) -> Result<Bytes, Response<Full<Bytes>>>
+where
+    B: hyper::body::Body,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    match http_body_util::BodyExt::collect(http_body_util::Limited::new(body, limit)).await {
+        Ok(collected) => Ok(collected.to_bytes()),
+        Err(e) if e.is::<http_body_util::LengthLimitError>() => Err(multipart_error_response(
+            &next_rs_actions::MultipartError::BodyTooLarge {
+                limit: limit as u64,
+            },
+        )),
+        Err(_) => {
+            let resp = next_rs_actions::ActionResponse::error(next_rs_actions::ActionError::new(
+                "Failed to read request body",
+            ));
+            let json = serde_json::to_string(&resp).unwrap_or_default();
+            Err(Response::builder()
+                .status(StatusCode::BAD_REQUEST)
+                .header("Content-Type", "application/json")
+                .body(Full::new(Bytes::from(json)))
+                .unwrap())
+        }
+    }
+}
+
+fn multipart_error_response(error: &next_rs_actions::MultipartError) -> Response<Full<Bytes>> {
+    let status = if error.is_limit() {
+        StatusCode::PAYLOAD_TOO_LARGE
+    } else {
+        StatusCode::BAD_REQUEST
+    };
+    let resp = next_rs_actions::ActionResponse::error(next_rs_actions::ActionError::with_code(
+        error.to_string(),
+        next_rs_actions::INVALID_INPUT,
+    ));
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(Full::new(Bytes::from(
+            serde_json::to_string(&resp).unwrap_or_default(),
+        )))
+        .unwrap()
+}
+
+fn action_error_status(response: &next_rs_actions::ActionResponse) -> StatusCode {
+    if response.is_not_found() {
+        StatusCode::NOT_FOUND
+    } else if response.is_too_many_requests() {
+        StatusCode::TOO_MANY_REQUESTS
+    } else {
+        StatusCode::BAD_REQUEST
+    }
+}
+
+/// Adds `Retry-After` in whole seconds when a limit rejected the action.
+fn retry_after(
+    builder: hyper::http::response::Builder,
+    response: &next_rs_actions::ActionResponse,
+) -> hyper::http::response::Builder {
+    match response.error.as_ref().and_then(|e| e.retry_after()) {
+        Some(wait) => builder.header(
+            hyper::header::RETRY_AFTER,
+            wait.as_secs_f64().ceil().max(1.0) as u64,
+        ),
+        None => builder,
+    }
+}
+
+fn form_action_response(
+    response: &next_rs_actions::ActionResponse,
+    return_to: &str,
+    renderer: &SsrRenderer,
+) -> Response<Full<Bytes>> {
+    if response.success {
+        let location = response.redirect.as_deref().unwrap_or(return_to);
+        return Response::builder()
+            .status(StatusCode::SEE_OTHER)
+            .header(hyper::header::LOCATION, location)
+            .body(Full::new(Bytes::new()))
+            .unwrap();
+    }
+
+    let mut message = response
+        .error
+        .as_ref()
+        .map(|e| e.message.clone())
+        .unwrap_or_else(|| "Action failed".to_string());
+    for (field, error) in &response.field_errors {
+        message.push_str(&format!("\n{}: {}", field, error));
+    }
+    retry_after(Response::builder(), response)
+        .status(action_error_status(response))
+        .header("Content-Type", "text/html; charset=utf-8")
+        .body(Full::new(Bytes::from(renderer.render_error(&message))))
+        .unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -473,4 +955,297 @@ mod tests {
         let handler = RequestHandler::new(router, app_dir, registry);
         assert_eq!(handler.router.routes.len(), 1);
     }
+
+    #[test]
+    fn test_middleware_runs_for_matching_paths() {
+        let (_temp, app_dir) = create_test_app();
+        let router = Router::from_routes(vec![Route::new("/").with_page(app_dir.join("page.rs"))]);
+        let mut handler = RequestHandler::new(router, app_dir, Arc::new(PageRegistry::new()));
+        handler.register_middleware(
+            MiddlewareMatcher::from_config(vec!["/account/:path*"])
+                .unwrap()
+                .exclude_internal(),
+            |request| match request.cookie("session") {
+                Some(_) => MiddlewareResult::Next,
+                None => next_rs_middleware::NextResponse::redirect(format!(
+                    "/login?next={}",
+                    request.path
+                )),
+            },
+        );
+
+        let request = |uri: &str, cookie: &str| {
+            Request::builder()
+                .uri(uri)
+                .header("Cookie", cookie)
+                .body(())
+                .unwrap()
+        };
+        match handler.run_middlewares(&request("/account/orders?page=2", "theme=dark")) {
+            Some(MiddlewareResult::Redirect(redirect)) => {
+                assert_eq!(redirect.url, "/login?next=/account/orders")
+            }
+            _ => panic!("expected a redirect"),
+        }
+        assert!(handler
+            .run_middlewares(&request("/account", "session=abc"))
+            .is_none());
+        assert!(handler
+            .run_middlewares(&request("/account/avatar.png", ""))
+            .is_none());
+        assert!(handler.run_middlewares(&request("/about", "")).is_none());
+    }
+
+    #[derive(serde::Deserialize)]
+    struct Rating {
+        stars: u32,
+    }
+
+    impl next_rs_actions::Validate for Rating {
+        fn validate(&self) -> Result<(), next_rs_actions::FieldErrors> {
+            let mut v = next_rs_actions::Validation::new();
+            v.check("stars", (1..=5).contains(&self.stars), "Pick 1 to 5 stars");
+            v.finish()
+        }
+    }
+
+    #[next_rs_macros::server_action(validate)]
+    async fn rate_post(rating: Rating) -> next_rs_actions::ActionResult<u32> {
+        Ok(rating.stars)
+    }
+
+    #[tokio::test]
+    async fn test_validated_server_action() {
+        let registry = next_rs_actions::ActionRegistry::with_server_actions();
+        let call = |stars: u32| {
+            registry.execute(next_rs_actions::ActionRequest {
+                action_id: format!("{}::rate_post", module_path!()),
+                payload: serde_json::json!({ "stars": stars }),
+            })
+        };
+        assert_eq!(call(4).await.data.unwrap(), 4);
+        let response = call(9).await;
+        assert_eq!(response.field_errors["stars"], "Pick 1 to 5 stars");
+
+        let resp = form_action_response(&response, "/posts/1", &SsrRenderer::new());
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[next_rs_macros::server_action]
+    async fn rename_post(id: u32, title: String) -> next_rs_actions::ActionResult<String> {
+        Ok(format!("{}: {}", id, title))
+    }
+
+    #[tokio::test]
+    async fn test_server_action_macro_registers_handler() {
+        let (_temp, app_dir) = create_test_app();
+        let router = Router::from_routes(vec![Route::new("/").with_page(app_dir.join("page.rs"))]);
+        let handler = RequestHandler::new(router, app_dir, Arc::new(PageRegistry::new()));
+
+        let request = next_rs_actions::ActionRequest {
+            action_id: format!("{}::rename_post", module_path!()),
+            payload: serde_json::json!([7, "Hello"]),
+        };
+        let response = handler.action_registry().execute(request).await;
+        assert!(response.success);
+        assert_eq!(response.data.unwrap(), "7: Hello");
+        assert_eq!(rename_post(8, "Direct".into()).await.unwrap(), "8: Direct");
+    }
+
+    #[tokio::test]
+    async fn test_html_request_awaits_resources_on_a_current_thread_runtime() {
+        use react_rs_elements::html::p;
+        use react_rs_elements::node::IntoNode;
+        use react_rs_elements::reactive::SignalExt;
+
+        let (_temp, app_dir) = create_test_app();
+        let router = Router::from_routes(vec![Route::new("/").with_page(app_dir.join("page.rs"))]);
+        let mut registry = PageRegistry::new();
+        registry.register_page("/", |_| {
+            // The timer is driven by the runtime the request is served on.
+            let user = react_rs_core::create_resource(
+                || 7,
+                |id| async move {
+                    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                    Ok::<_, String>(format!("User {}", id))
+                },
+            );
+            p().text_reactive(user.state().map(|state| match state.data() {
+                Some(name) => name.clone(),
+                None => "Loading...".to_string(),
+            }))
+            .into_node()
+        });
+        let handler = RequestHandler::new(router, app_dir, Arc::new(registry));
+
+        let response = handler
+            .handle_html_request("/", Some("token"))
+            .await
+            .unwrap();
+        let body = http_body_util::BodyExt::collect(response.into_body())
+            .await
+            .unwrap()
+            .to_bytes();
+        assert!(String::from_utf8_lossy(&body).contains("User 7"));
+    }
+
+    #[tokio::test]
+    async fn test_rsc_request_reports_cache_status() {
+        let (_temp, app_dir) = create_test_app();
+        let router = Router::from_routes(vec![Route::new("/").with_page(app_dir.join("page.rs"))]);
+        let mut handler = RequestHandler::new(router, app_dir, Arc::new(PageRegistry::new()));
+        handler.rsc_handler_mut().tag_route("/", ["home"]);
+
+        let first = handler
+            .handle_rsc_request("/_rsc/", RscEncoding::Text)
+            .await
+            .unwrap();
+        assert_eq!(first.headers()[RSC_CACHE_HEADER], "MISS");
+        let second = handler
+            .handle_rsc_request("/_rsc/", RscEncoding::Text)
+            .await
+            .unwrap();
+        assert_eq!(second.headers()[RSC_CACHE_HEADER], "HIT");
+
+        assert_eq!(handler.revalidate_tag("home"), 1);
+        let third = handler
+            .handle_rsc_request("/_rsc/", RscEncoding::Text)
+            .await
+            .unwrap();
+        assert_eq!(third.headers()[RSC_CACHE_HEADER], "MISS");
+    }
+
+    #[tokio::test]
+    async fn test_rsc_navigation_negotiates_msgpack() {
+        let (_temp, app_dir) = create_test_app();
+        let router = Router::from_routes(vec![Route::new("/").with_page(app_dir.join("page.rs"))]);
+        let handler = RequestHandler::new(router, app_dir, Arc::new(PageRegistry::new()));
+
+        let text = handler
+            .handle_rsc_navigation("/", RscEncoding::Text)
+            .await
+            .unwrap();
+        let packed = handler
+            .handle_rsc_navigation("/", RscEncoding::MessagePack)
+            .await
+            .unwrap();
+        assert_eq!(
+            packed.headers()["Content-Type"],
+            RscEncoding::MSGPACK_MEDIA_TYPE
+        );
+        assert_eq!(packed.headers()[RSC_CACHE_HEADER], "HIT");
+
+        let text = http_body_util::BodyExt::collect(text.into_body())
+            .await
+            .unwrap()
+            .to_bytes();
+        let packed = http_body_util::BodyExt::collect(packed.into_body())
+            .await
+            .unwrap()
+            .to_bytes();
+        let decoded = next_rs_rsc::RscPayload::from_msgpack(&packed).unwrap();
+        assert_eq!(decoded.to_wire_format().as_bytes(), &text[..]);
+    }
+
+    #[test]
+    fn test_form_action_response_redirects_on_success() {
+        let renderer = SsrRenderer::new();
+        let ok = next_rs_actions::ActionResponse::success(1);
+        let resp = form_action_response(&ok, "/posts/1", &renderer);
+        assert_eq!(resp.status(), StatusCode::SEE_OTHER);
+        assert_eq!(resp.headers()[hyper::header::LOCATION], "/posts/1");
+
+        let failed =
+            next_rs_actions::ActionResponse::error(next_rs_actions::ActionError::new("Nope"));
+        let resp = form_action_response(&failed, "/posts/1", &renderer);
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            resp.headers()[hyper::header::CONTENT_TYPE],
+            "text/html; charset=utf-8"
+        );
+
+        let redirected =
+            next_rs_actions::ActionResponse::error(next_rs_actions::redirect("/posts"));
+        let resp = form_action_response(&redirected, "/posts/1", &renderer);
+        assert_eq!(resp.status(), StatusCode::SEE_OTHER);
+        assert_eq!(resp.headers()[hyper::header::LOCATION], "/posts");
+
+        let limited = next_rs_actions::ActionResponse::error(next_rs_actions::too_many_requests(
+            Some(std::time::Duration::from_millis(1500)),
+        ));
+        let resp = form_action_response(&limited, "/posts/1", &renderer);
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(resp.headers()[hyper::header::RETRY_AFTER], "2");
+    }
+
+    #[test]
+    fn test_same_origin_request() {
+        let request = |headers: &[(&str, &str)]| {
+            let mut builder = Request::builder().method("POST").uri("/_action/save");
+            for (name, value) in headers {
+                builder = builder.header(*name, *value);
+            }
+            builder.body(()).unwrap()
+        };
+
+        let same = request(&[("Host", "example.com"), ("Origin", "https://example.com")]);
+        assert!(same_origin_request(&same));
+        let via_referer = request(&[
+            ("Host", "example.com"),
+            ("Referer", "https://example.com/a"),
+        ]);
+        assert!(same_origin_request(&via_referer));
+        let cross = request(&[("Host", "example.com"), ("Origin", "https://evil.test")]);
+        assert!(!same_origin_request(&cross));
+
+        let back =
+            |referer| form_return_path(&request(&[("Host", "example.com"), ("Referer", referer)]));
+        assert_eq!(back("https://example.com/posts/1?tab=2"), "/posts/1?tab=2");
+        assert_eq!(back("https://evil.test/phish"), "/");
+        assert_eq!(back("https://example.com//evil.test/phish"), "/");
+        assert_eq!(back("/relative"), "/");
+        assert_eq!(form_return_path(&request(&[("Host", "example.com")])), "/");
+        assert!(!same_origin_request(&request(&[("Host", "example.com")])));
+
+        let with_cookie = request(&[("Cookie", "a=1; __next_csrf=tok")]);
+        assert_eq!(request_csrf_token(&with_cookie).as_deref(), Some("tok"));
+
+        let context = action_context(&request(&[
+            ("Cookie", "a=1; session=abc"),
+            ("X-Tenant", "acme"),
+        ]));
+        assert_eq!(context.cookie("session"), Some("abc"));
+        assert_eq!(context.header("x-tenant"), Some("acme"));
+
+        let streaming = request(&[("Accept", "application/x-ndjson")]);
+        assert!(accepts_action_stream(&streaming));
+        assert!(accepts_json(&request(&[("Accept", "application/json")])));
+        assert!(!accepts_json(&request(&[(
+            "Accept",
+            "text/html,application/xhtml+xml,*/*;q=0.8"
+        )])));
+        assert!(!accepts_action_stream(&request(&[(
+            "Accept",
+            "application/json"
+        )])));
+    }
+
+    #[test]
+    fn test_multipart_error_response_status() {
+        let too_large = next_rs_actions::MultipartError::BodyTooLarge { limit: 10 };
+        let resp = multipart_error_response(&too_large);
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let malformed = next_rs_actions::MultipartError::Malformed("no parts".into());
+        let resp = multipart_error_response(&malformed);
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_read_body_rejects_bodies_over_the_limit() {
+        let body = Full::new(Bytes::from_static(b"0123456789"));
+        assert_eq!(read_body(body.clone(), 10).await.unwrap(), "0123456789");
+        let resp = read_body(body, 9).await.unwrap_err();
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
 }
@@ -8,9 +8,9 @@ mod streaming;
 pub mod ws;
 
 pub use api::{ApiRequest, ApiResponse, ApiRouteHandler};
-pub use handler::RequestHandler;
+pub use handler::{RenderErrorHook, RequestHandler, ResponseBody};
 pub use isr::{CacheEntry, IncrementalCache, IsrConfig};
-pub use rsc_handler::RscHandler;
+pub use rsc_handler::{CacheStatus, RscHandler, RscPayloadCache, RSC_CACHE_HEADER};
 pub use ssg::{GeneratedFile, GenerationResult, StaticGenerator, StaticParams};
 pub use ssr::{LayoutRenderFn, PageRegistry, PageRenderFn, SsrRenderer};
 pub use streaming::{HtmlStream, RscStream, RscStreamingRenderer, StreamingRenderer};
@@ -72,12 +72,14 @@ impl NextServer {
     pub async fn run(self) -> anyhow::Result<()> {
         let addr = self.addr();
         let listener = TcpListener::bind(addr).await?;
+        next_rs_rsc::set_refresh_spawner(|refresh| {
+            tokio::spawn(refresh);
+        });
 
-        let handler = Arc::new(RequestHandler::new(
-            self.router,
-            self.config.app_dir.clone(),
-            self.registry,
-        ));
+        let handler = Arc::new(
+            RequestHandler::new(self.router, self.config.app_dir.clone(), self.registry)
+                .on_render_error(log_render_error),
+        );
 
         loop {
             let (stream, _) = listener.accept().await?;
@@ -87,7 +89,7 @@ impl NextServer {
             tokio::spawn(async move {
                 let service = service_fn(move |req| {
                     let handler = handler.clone();
-                    async move { handler.handle(req).await }
+                    async move { handler.serve(req, None).await }
                 });
 
                 if let Err(e) = http1::Builder::new().serve_connection(io, service).await {
@@ -129,12 +131,18 @@ impl DevServer {
 
         let addr = self.addr();
         let listener = TcpListener::bind(addr).await?;
-
-        let handler = Arc::new(RequestHandler::new(
-            self.inner.router,
-            self.inner.config.app_dir.clone(),
-            self.inner.registry,
-        ));
+        next_rs_rsc::set_refresh_spawner(|refresh| {
+            tokio::spawn(refresh);
+        });
+
+        let handler = Arc::new(
+            RequestHandler::new(
+                self.inner.router,
+                self.inner.config.app_dir.clone(),
+                self.inner.registry,
+            )
+            .on_render_error(log_render_error),
+        );
 
         let reload_tx = self.reload_tx;
 
@@ -148,7 +156,7 @@ impl DevServer {
                 let service = service_fn(move |req| {
                     let handler = handler.clone();
                     let reload_rx = reload_rx.resubscribe();
-                    async move { handler.handle_with_dev_ws(req, Some(reload_rx)).await }
+                    async move { handler.serve(req, Some(reload_rx)).await }
                 });
 
                 if let Err(e) = http1::Builder::new().serve_connection(io, service).await {
@@ -161,6 +169,15 @@ impl DevServer {
     }
 }
 
+/// Logs a render error with its digest, so a report of a production page can be matched to
+/// the log.
+fn log_render_error(route: &str, error: &react_rs_dom::RenderError) {
+    eprintln!(
+        "Error rendering {} (digest {}): {}",
+        route, error.digest, error.message
+    );
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
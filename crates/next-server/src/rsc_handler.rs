@@ -1,13 +1,186 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
 
-use next_rs_rsc::{ClientBoundary, RscNode, RscPayload, ServerBoundary};
+use next_rs_router::MatchedRoute;
+use next_rs_rsc::{ClientBoundary, RscEncoding, RscNode, RscPayload, ServerBoundary};
 use serde_json::json;
 
+/// Whether an RSC response came from the payload cache, sent in the [`RSC_CACHE_HEADER`]
+/// response header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    Hit,
+    Miss,
+}
+
+impl CacheStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheStatus::Hit => "HIT",
+            CacheStatus::Miss => "MISS",
+        }
+    }
+}
+
+pub const RSC_CACHE_HEADER: &str = "X-RSC-Cache";
+
+#[derive(Clone)]
+struct CachedPayload {
+    payload: RscPayload,
+    wire: String,
+    tags: Vec<String>,
+    /// The URL path the payload was rendered for, e.g. `/blog/hello` for `/blog/[slug]`.
+    path: String,
+    inserted: Instant,
+}
+
+/// Rendered wire payloads keyed by route and params. Entries carry the tags of their route
+/// so [`RscPayloadCache::revalidate_tag`] can drop everything built from the same data.
+/// Past its capacity the oldest entry is evicted, so one route with endless params cannot
+/// grow the cache without bound.
+#[derive(Clone)]
+pub struct RscPayloadCache {
+    entries: Arc<RwLock<HashMap<String, CachedPayload>>>,
+    capacity: usize,
+}
+
+impl Default for RscPayloadCache {
+    fn default() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl RscPayloadCache {
+    pub const DEFAULT_CAPACITY: usize = 1024;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A cache holding at most `capacity` payloads.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Arc::default(),
+            capacity,
+        }
+    }
+
+    pub fn get(&self, route_path: &str, params: &HashMap<String, String>) -> Option<String> {
+        let entries = self.entries.read().unwrap();
+        entries
+            .get(&cache_key(route_path, params))
+            .map(|entry| entry.wire.clone())
+    }
+
+    /// The cached payload, e.g. to send in another encoding.
+    pub fn get_payload(
+        &self,
+        route_path: &str,
+        params: &HashMap<String, String>,
+    ) -> Option<RscPayload> {
+        let entries = self.entries.read().unwrap();
+        entries
+            .get(&cache_key(route_path, params))
+            .map(|entry| entry.payload.clone())
+    }
+
+    /// Caches `payload`, rendered for the URL `path` matching `route_path` with `params`.
+    pub fn set(
+        &self,
+        route_path: &str,
+        params: &HashMap<String, String>,
+        path: &str,
+        payload: RscPayload,
+        tags: Vec<String>,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+        let key = cache_key(route_path, params);
+        let wire = payload.to_wire_format();
+        let mut entries = self.entries.write().unwrap();
+        if !entries.contains_key(&key) && entries.len() >= self.capacity {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.inserted)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(
+            key,
+            CachedPayload {
+                payload,
+                wire,
+                tags,
+                path: path.to_string(),
+                inserted: Instant::now(),
+            },
+        );
+    }
+
+    /// Drops every entry tagged `tag`, returning how many were removed.
+    pub fn revalidate_tag(&self, tag: &str) -> usize {
+        let mut entries = self.entries.write().unwrap();
+        let before = entries.len();
+        entries.retain(|_, entry| !entry.tags.iter().any(|t| t == tag));
+        before - entries.len()
+    }
+
+    /// Drops the entries for `path`: every entry of a route pattern such as `/blog/[slug]`,
+    /// or the one rendered for a URL path such as `/blog/hello`.
+    pub fn revalidate_route(&self, path: &str) {
+        let mut entries = self.entries.write().unwrap();
+        let prefix = format!("{}?", path);
+        entries.retain(|key, entry| key != path && !key.starts_with(&prefix) && entry.path != path);
+    }
+
+    /// Drops what a server action invalidated with `revalidate_path` and `revalidate_tag`.
+    pub fn revalidate(&self, revalidation: &next_rs_actions::Revalidation) {
+        for path in &revalidation.paths {
+            self.revalidate_route(path);
+        }
+        for tag in &revalidation.tags {
+            self.revalidate_tag(tag);
+        }
+    }
+
+    pub fn clear(&self) {
+        self.entries.write().unwrap().clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// `route?a=1&b=2`, with params sorted so the key does not depend on map order.
+fn cache_key(route_path: &str, params: &HashMap<String, String>) -> String {
+    if params.is_empty() {
+        return route_path.to_string();
+    }
+    let mut params: Vec<_> = params.iter().collect();
+    params.sort();
+    let query: Vec<String> = params
+        .into_iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect();
+    format!("{}?{}", route_path, query.join("&"))
+}
+
 pub struct RscHandler {
     app_dir: PathBuf,
     server_boundary: ServerBoundary,
     client_boundary: ClientBoundary,
+    cache: RscPayloadCache,
+    route_tags: HashMap<String, Vec<String>>,
 }
 
 impl RscHandler {
@@ -16,7 +189,80 @@ impl RscHandler {
             app_dir,
             server_boundary: ServerBoundary::new(),
             client_boundary: ClientBoundary::new(),
+            cache: RscPayloadCache::new(),
+            route_tags: HashMap::new(),
+        }
+    }
+
+    /// Associates `route_path`'s cached payloads with `tags`, e.g. `["posts"]` for a blog
+    /// route, so [`RscHandler::revalidate_tag`] refreshes them.
+    pub fn tag_route<I, T>(&mut self, route_path: impl Into<String>, tags: I)
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.route_tags
+            .entry(route_path.into())
+            .or_default()
+            .extend(tags.into_iter().map(Into::into));
+    }
+
+    /// Replaces the payload cache, e.g. with one of a different capacity.
+    pub fn set_cache(&mut self, cache: RscPayloadCache) {
+        self.cache = cache;
+    }
+
+    /// The wire payload for `path`, which matched `route`, from the cache when an earlier
+    /// navigation rendered it. Entries are keyed and tagged by the route's pattern.
+    pub fn render_cached(&self, path: &str, route: &MatchedRoute) -> (String, CacheStatus) {
+        if let Some(wire) = self.cache.get(&route.route.path, &route.params) {
+            return (wire, CacheStatus::Hit);
+        }
+        let wire = self.render_and_cache(path, route).to_wire_format();
+        (wire, CacheStatus::Miss)
+    }
+
+    /// Like [`RscHandler::render_cached`], encoded for a client that asked for `encoding`.
+    pub fn render_cached_as(
+        &self,
+        path: &str,
+        route: &MatchedRoute,
+        encoding: RscEncoding,
+    ) -> (Vec<u8>, CacheStatus) {
+        if encoding == RscEncoding::Text {
+            let (wire, status) = self.render_cached(path, route);
+            return (wire.into_bytes(), status);
         }
+        if let Some(payload) = self.cache.get_payload(&route.route.path, &route.params) {
+            return (payload.encode(encoding), CacheStatus::Hit);
+        }
+        let payload = self.render_and_cache(path, route);
+        (payload.encode(encoding), CacheStatus::Miss)
+    }
+
+    fn render_and_cache(&self, path: &str, route: &MatchedRoute) -> RscPayload {
+        let payload = self.render_route(path, &route.params);
+        let tags = self
+            .route_tags
+            .get(&route.route.path)
+            .cloned()
+            .unwrap_or_default();
+        self.cache.set(
+            &route.route.path,
+            &route.params,
+            path,
+            payload.clone(),
+            tags,
+        );
+        payload
+    }
+
+    pub fn revalidate_tag(&self, tag: &str) -> usize {
+        self.cache.revalidate_tag(tag)
+    }
+
+    pub fn cache(&self) -> &RscPayloadCache {
+        &self.cache
     }
 
     pub fn render_route(&self, route_path: &str, params: &HashMap<String, String>) -> RscPayload {
@@ -136,6 +382,111 @@ mod tests {
         assert!(manifest["modules"].is_array());
     }
 
+    fn matched(pattern: &str, params: &[(&str, &str)]) -> MatchedRoute {
+        MatchedRoute {
+            route: next_rs_router::Route::new(pattern),
+            params: params
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_payload_cache_and_tag_revalidation() {
+        let mut handler = RscHandler::new(PathBuf::from("/app"));
+        handler.tag_route("/blog/[slug]", ["posts"]);
+        let hello = matched("/blog/[slug]", &[("slug", "hello")]);
+        let world = matched("/blog/[slug]", &[("slug", "world")]);
+        let about = matched("/about", &[]);
+
+        let (first, status) = handler.render_cached("/blog/hello", &hello);
+        assert_eq!(status, CacheStatus::Miss);
+        let (second, status) = handler.render_cached("/blog/hello", &hello);
+        assert_eq!(status, CacheStatus::Hit);
+        assert_eq!(first, second);
+        assert!(first.contains("Route: /blog/hello"));
+
+        handler.render_cached("/blog/world", &world);
+        handler.render_cached("/about", &about);
+        assert_eq!(handler.cache().len(), 3);
+
+        // URLs of a dynamic route carry the tags of its pattern.
+        assert_eq!(handler.revalidate_tag("posts"), 2);
+        assert_eq!(handler.cache().len(), 1);
+        assert_eq!(
+            handler.render_cached("/blog/hello", &hello).1,
+            CacheStatus::Miss
+        );
+
+        handler.render_cached("/blog/world", &world);
+        handler.cache().revalidate_route("/blog/world");
+        assert_eq!(
+            handler.render_cached("/blog/hello", &hello).1,
+            CacheStatus::Hit
+        );
+        handler.cache().revalidate_route("/blog/[slug]");
+        handler.cache().revalidate_route("/about");
+        assert!(handler.cache().is_empty());
+    }
+
+    #[test]
+    fn test_payload_cache_evicts_the_oldest_entry() {
+        let mut handler = RscHandler::new(PathBuf::from("/app"));
+        handler.set_cache(RscPayloadCache::with_capacity(2));
+        let page = |n: &str| matched("/docs/[...path]", &[("path", n)]);
+
+        for n in ["a", "b", "c"] {
+            handler.render_cached(&format!("/docs/{}", n), &page(n));
+        }
+        assert_eq!(handler.cache().len(), 2);
+        assert_eq!(
+            handler.render_cached("/docs/c", &page("c")).1,
+            CacheStatus::Hit
+        );
+        assert_eq!(
+            handler.render_cached("/docs/a", &page("a")).1,
+            CacheStatus::Miss
+        );
+    }
+
+    #[tokio::test]
+    async fn test_action_revalidation_drops_payloads() {
+        let mut handler = RscHandler::new(PathBuf::from("/app"));
+        handler.tag_route("/posts/[id]", ["posts"]);
+        handler.render_cached("/posts", &matched("/posts", &[]));
+        handler.render_cached("/posts/1", &matched("/posts/[id]", &[("id", "1")]));
+        handler.render_cached("/about", &matched("/about", &[]));
+
+        let mut registry = next_rs_actions::ActionRegistry::new();
+        registry.register_with_context(
+            "create_post",
+            |title: String, context: next_rs_actions::ActionContext| async move {
+                context.revalidate_path("/posts");
+                context.revalidate_tag("posts");
+                Ok(title)
+            },
+        );
+        let context = next_rs_actions::ActionContext::new();
+        let request = next_rs_actions::ActionRequest {
+            action_id: "create_post".to_string(),
+            payload: serde_json::json!("Hello"),
+        };
+        assert!(
+            registry
+                .execute_with_context(request, context.clone())
+                .await
+                .success
+        );
+
+        handler.cache().revalidate(&context.revalidation());
+        assert_eq!(handler.cache().len(), 1);
+        assert_eq!(
+            handler.render_cached("/about", &matched("/about", &[])).1,
+            CacheStatus::Hit
+        );
+    }
+
     #[test]
     fn test_render_to_json() {
         let handler = RscHandler::new(PathBuf::from("/app"));
@@ -26,6 +26,8 @@ pub struct GeneratedFile {
     pub route: String,
     pub file_path: PathBuf,
     pub size_bytes: u64,
+    /// Errors error boundaries caught while rendering the page; their fallbacks were written.
+    pub errors: Vec<react_rs_dom::RenderError>,
 }
 
 impl StaticGenerator {
@@ -62,11 +64,12 @@ impl StaticGenerator {
         };
 
         for route in static_routes {
-            let html = self.renderer.render(
+            let rendered = self.renderer.render(
                 &route.path,
                 &std::collections::HashMap::new(),
                 &self.registry,
             );
+            let html = rendered.html;
 
             let file_path = self.route_to_file_path(&route.path);
             let full_path = self.output_dir.join(&file_path);
@@ -84,6 +87,7 @@ impl StaticGenerator {
                 route: route.path.clone(),
                 file_path: full_path,
                 size_bytes: size,
+                errors: rendered.errors,
             });
         }
 
@@ -114,6 +118,7 @@ impl StaticGenerator {
             route: "404".to_string(),
             file_path,
             size_bytes: size,
+            errors: Vec::new(),
         });
 
         Ok(())
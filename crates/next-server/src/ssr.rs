@@ -1,9 +1,12 @@
 use std::collections::HashMap;
+use std::io::{self, Write};
 use std::sync::Arc;
 
-use react_rs_dom::render_to_string;
+use react_rs_core::create_runtime;
+use react_rs_dom::{render_shell, render_to_string, RenderOutput};
 use react_rs_elements::html::*;
 use react_rs_elements::node::{IntoNode, Node};
+use react_rs_elements::Head;
 
 pub type PageRenderFn = Arc<dyn Fn(&HashMap<String, String>) -> Node + Send + Sync>;
 pub type LayoutRenderFn = Arc<dyn Fn(Node) -> Node + Send + Sync>;
@@ -54,6 +57,7 @@ impl Default for PageRegistry {
     }
 }
 
+#[derive(Clone)]
 pub struct SsrRenderer {
     package_name: String,
     dev_mode: bool,
@@ -78,13 +82,76 @@ impl SsrRenderer {
         self.dev_mode = dev;
     }
 
+    /// Renders the document for `route_path`. Errors caught by error boundaries are left for
+    /// the caller to report, in `errors`. Blocks until the page's resources resolve, so async
+    /// callers should run it on a blocking thread.
     pub fn render(
         &self,
         route_path: &str,
         params: &HashMap<String, String>,
         registry: &PageRegistry,
-    ) -> String {
-        let page_node = if let Some(page_fn) = registry.get_page(route_path) {
+    ) -> RenderOutput {
+        // Each request renders in its own runtime, dropped afterwards, so requests sharing a
+        // worker thread neither see each other's reactive state nor accumulate effects.
+        let rendered = create_runtime().run(|| {
+            let content = self.page_content(route_path, params, registry);
+            // Resources started by the page resolve before rendering so the HTML has their data.
+            react_rs_core::task::block_on_pending_tasks();
+
+            render_to_string(&content)
+        });
+
+        RenderOutput {
+            html: format!(
+                "{}{}</div>{}{}",
+                self.document_start(route_path, params, &rendered.head),
+                rendered.html,
+                rendered.portals_html(),
+                self.document_end(&rendered.head),
+            ),
+            ..rendered
+        }
+    }
+
+    /// Like [`SsrRenderer::render`], but writes the document into `writer` as it renders: the
+    /// page is sent with suspense fallbacks in place, then each boundary follows as soon as
+    /// its data resolves. Heads rendered inside streamed boundaries cannot reach `<head>`;
+    /// render them outside suspense. Portals inside them are left to the client.
+    pub fn render_streaming<W: Write>(
+        &self,
+        route_path: &str,
+        params: &HashMap<String, String>,
+        registry: &PageRegistry,
+        writer: &mut W,
+    ) -> io::Result<()> {
+        create_runtime().run(|| {
+            let content = self.page_content(route_path, params, registry);
+            let shell = render_shell(&content);
+            let head = shell.output.head.clone();
+
+            writer.write_all(self.document_start(route_path, params, &head).as_bytes())?;
+            writer.write_all(shell.output.html.as_bytes())?;
+            writer.write_all(b"</div>")?;
+            writer.write_all(shell.output.portals_html().as_bytes())?;
+            writer.flush()?;
+            shell.stream_rest(writer)?;
+            // The document ends after the streamed content so the client hydrates it all.
+            writer.write_all(
+                self.document_end(&head)
+                    .trim_start_matches("</div>")
+                    .as_bytes(),
+            )?;
+            writer.flush()
+        })
+    }
+
+    fn page_content(
+        &self,
+        route_path: &str,
+        params: &HashMap<String, String>,
+        registry: &PageRegistry,
+    ) -> Node {
+        let mut content = if let Some(page_fn) = registry.get_page(route_path) {
             page_fn(params)
         } else {
             div()
@@ -93,33 +160,54 @@ impl SsrRenderer {
                 .into_node()
         };
 
-        let mut content = page_node;
-
         if let Some(root_layout_fn) = registry.get_layout("/") {
             content = root_layout_fn(content);
         }
+        content
+    }
 
-        let body_html = render_to_string(&content).html;
+    /// The document up to and including the opening `__next` container.
+    fn document_start(
+        &self,
+        route_path: &str,
+        params: &HashMap<String, String>,
+        head: &Head,
+    ) -> String {
+        // The page's tags replace the defaults they share a dedupe key with.
+        let defaults = Head::new()
+            .meta("viewport", "width=device-width, initial-scale=1")
+            .title(format!("next.rs | {}", route_path))
+            .link_stylesheet("/styles.css");
+        let head = Head::merge_all([&defaults, head]);
         let params_json = serde_json::to_string(params).unwrap_or_else(|_| "{}".to_string());
-        let pkg_name = &self.package_name;
-        let dev_script = if self.dev_mode {
-            r#"<script>(function(){var ws=new WebSocket('ws://'+location.host+'/__dev_ws');ws.onmessage=function(e){if(e.data==='reload')location.reload()};ws.onclose=function(){setTimeout(function(){location.reload()},1000)}})()</script>"#
-        } else {
-            ""
-        };
 
         format!(
             r#"<!DOCTYPE html>
 <html lang="en">
 <head>
     <meta charset="utf-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1">
-    <title>next.rs | {route}</title>
-    <link rel="stylesheet" href="/styles.css">
+    {head}
     <script>window.__NEXT_DATA__ = {{ route: "{route}", params: {params} }};</script>
 </head>
 <body>
-    <div id="__next">{body}</div>
+    <div id="__next">"#,
+            route = route_path,
+            head = head.to_html(),
+            params = params_json,
+        )
+    }
+
+    /// The document after the `__next` container.
+    fn document_end(&self, head: &Head) -> String {
+        let dev_script = if self.dev_mode {
+            r#"<script>(function(){var ws=new WebSocket('ws://'+location.host+'/__dev_ws');ws.onmessage=function(e){if(e.data==='reload')location.reload()};ws.onclose=function(){setTimeout(function(){location.reload()},1000)}})()</script>"#
+        } else {
+            ""
+        };
+
+        format!(
+            r#"
+    {body_scripts}
     {dev_script}
     <script type="module">
         import init from '/pkg/{pkg}.js';
@@ -127,11 +215,9 @@ impl SsrRenderer {
     </script>
 </body>
 </html>"#,
-            route = route_path,
-            params = params_json,
-            body = body_html,
+            body_scripts = head.body_scripts_html(),
             dev_script = dev_script,
-            pkg = pkg_name,
+            pkg = self.package_name,
         )
     }
 
@@ -180,6 +266,34 @@ impl SsrRenderer {
     }
 }
 
+/// Adds a hidden field with the CSRF `token` to every form that posts to a server action,
+/// i.e. holds an element bound with `FormActionExt::action`. The field goes last, after the
+/// children the client hydrates.
+pub(crate) fn embed_csrf_token(html: &str, token: &str) -> String {
+    let field = format!(
+        r#"<input type="hidden" name="{}" value="{}">"#,
+        next_rs_actions::CSRF_FIELD,
+        token
+    );
+    let action_attr = format!("{}=", next_rs_rsc::FORM_ACTION_ATTR);
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find("<form") {
+        let Some(end) = rest[start..].find("</form>").map(|end| start + end) else {
+            break;
+        };
+        out.push_str(&rest[..end]);
+        if rest[start..end].contains(&action_attr) {
+            out.push_str(&field);
+        }
+        rest = &rest[end..];
+        out.push_str("</form>");
+        rest = &rest["</form>".len()..];
+    }
+    out.push_str(rest);
+    out
+}
+
 impl Default for SsrRenderer {
     fn default() -> Self {
         Self::new()
@@ -226,7 +340,7 @@ mod tests {
         let registry = test_registry();
         let renderer = SsrRenderer::new();
 
-        let html = renderer.render("/", &HashMap::new(), &registry);
+        let html = renderer.render("/", &HashMap::new(), &registry).html;
 
         assert!(html.contains("<!DOCTYPE html>"));
         assert!(html.contains("__next"));
@@ -234,12 +348,183 @@ mod tests {
         assert!(html.contains("Home page content"));
     }
 
+    #[test]
+    fn test_render_page_head() {
+        let mut registry = PageRegistry::new();
+        registry.register_page("/", |_| {
+            div()
+                .child(
+                    react_rs_elements::Head::new()
+                        .title("Home")
+                        .og_title("Home"),
+                )
+                .into_node()
+        });
+        let html = SsrRenderer::new()
+            .render("/", &HashMap::new(), &registry)
+            .html;
+
+        assert!(html.contains("<title>Home</title>"));
+        assert!(!html.contains("next.rs | /"));
+        assert!(html.contains("<meta property=\"og:title\" content=\"Home\">"));
+        assert!(html.contains("<link rel=\"stylesheet\" href=\"/styles.css\">"));
+    }
+
+    #[test]
+    fn test_render_page_head_replaces_default_tags() {
+        let mut registry = PageRegistry::new();
+        registry.register_page("/", |_| {
+            react_rs_elements::Head::new()
+                .meta("viewport", "width=device-width")
+                .into_node()
+        });
+        let html = SsrRenderer::new()
+            .render("/", &HashMap::new(), &registry)
+            .html;
+
+        assert_eq!(html.matches("name=\"viewport\"").count(), 1);
+        assert!(html.contains("content=\"width=device-width\">"));
+        assert!(html.contains("<title>next.rs | /</title>"));
+    }
+
+    #[test]
+    fn test_render_page_scripts_by_strategy() {
+        use react_rs_elements::{Script, ScriptStrategy};
+
+        let mut registry = PageRegistry::new();
+        registry.register_page("/", |_| {
+            div()
+                .child(Script::new("/consent.js").strategy(ScriptStrategy::BeforeInteractive))
+                .child(Script::new("/widget.js"))
+                .into_node()
+        });
+        let html = SsrRenderer::new()
+            .render("/", &HashMap::new(), &registry)
+            .html;
+
+        let head_end = html.find("</head>").unwrap();
+        let consent = html.find("/consent.js").unwrap();
+        let widget = html.find("/widget.js").unwrap();
+        assert!(consent < head_end);
+        assert!(widget > html.find("__next\">").unwrap());
+    }
+
+    #[test]
+    fn test_render_waits_for_resources() {
+        use react_rs_elements::reactive::SignalExt;
+
+        let mut registry = PageRegistry::new();
+        registry.register_page("/", |_| {
+            let user = react_rs_core::create_resource(
+                || 7,
+                |id| async move { Ok::<_, String>(format!("User {}", id)) },
+            );
+            p().text_reactive(user.state().map(|state| match state.data() {
+                Some(name) => name.clone(),
+                None => "Loading...".to_string(),
+            }))
+            .into_node()
+        });
+
+        let html = SsrRenderer::new()
+            .render("/", &HashMap::new(), &registry)
+            .html;
+        assert!(html.contains("User 7"));
+    }
+
+    #[test]
+    fn test_render_streaming_sends_shell_before_data() {
+        use react_rs_elements::reactive::SignalExt;
+        use react_rs_elements::suspense::suspense;
+
+        let mut registry = PageRegistry::new();
+        registry.register_page("/", |_| {
+            let user = react_rs_core::create_resource(
+                || 7,
+                |id| async move { Ok::<_, String>(format!("User {}", id)) },
+            );
+            let name = user
+                .state()
+                .map(|state| state.data().cloned().unwrap_or_default());
+            div()
+                .child(h1().text("Profile"))
+                .child(suspense(
+                    &user,
+                    p().text("Loading..."),
+                    p().text_reactive(name),
+                ))
+                .into_node()
+        });
+
+        let mut out = Vec::new();
+        SsrRenderer::new()
+            .render_streaming("/", &HashMap::new(), &registry, &mut out)
+            .unwrap();
+        let html = String::from_utf8(out).unwrap();
+
+        let fallback = html.find("Loading...").unwrap();
+        let data = html.find("User 7").unwrap();
+        assert!(fallback < html.find("<div hidden").unwrap());
+        assert!(fallback < data);
+        assert!(data < html.find("<script type=\"module\">").unwrap());
+        assert!(html.ends_with("</html>"));
+    }
+
+    #[test]
+    fn test_render_is_isolated_from_thread_runtime() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let mut registry = PageRegistry::new();
+        registry.register_page("/", |_| {
+            let theme = react_rs_core::use_context::<String>();
+            p().text(theme.unwrap_or_else(|| "no theme".to_string()))
+                .into_node()
+        });
+
+        react_rs_core::provide_context("dark".to_string());
+        let ran = Rc::new(Cell::new(false));
+        let task_ran = ran.clone();
+        react_rs_core::spawn_local(async move { task_ran.set(true) });
+
+        let html = SsrRenderer::new()
+            .render("/", &HashMap::new(), &registry)
+            .html;
+        assert!(html.contains("no theme"));
+        assert!(!ran.get());
+
+        react_rs_core::block_on_pending_tasks();
+        assert!(ran.get());
+        react_rs_core::clear_context::<String>();
+    }
+
+    #[test]
+    fn test_render_returns_caught_errors() {
+        let mut registry = PageRegistry::new();
+        registry.register_page("/", |_| {
+            let user = react_rs_core::resource::create_manual_resource::<String>();
+            user.set_ready("Ada".to_string());
+            div()
+                .child(react_rs_elements::error_boundary(
+                    &user,
+                    |error| p().text(format!("Failed: {}", error)).into_node(),
+                    react_rs_elements::dyn_view(|| -> Node { panic!("profile unavailable") }),
+                ))
+                .into_node()
+        });
+        let rendered = SsrRenderer::new().render("/", &HashMap::new(), &registry);
+
+        assert!(rendered.html.contains("Failed: profile unavailable"));
+        assert_eq!(rendered.errors.len(), 1);
+        assert_eq!(rendered.errors[0].message, "profile unavailable");
+    }
+
     #[test]
     fn test_render_with_layout() {
         let registry = test_registry();
         let renderer = SsrRenderer::new();
 
-        let html = renderer.render("/", &HashMap::new(), &registry);
+        let html = renderer.render("/", &HashMap::new(), &registry).html;
 
         assert!(html.contains("class=\"layout\""));
         assert!(html.contains("<nav>"));
@@ -254,12 +539,27 @@ mod tests {
         let mut params = HashMap::new();
         params.insert("slug".to_string(), "hello-world".to_string());
 
-        let html = renderer.render("/blog/[slug]", &params, &registry);
+        let html = renderer.render("/blog/[slug]", &params, &registry).html;
 
         assert!(html.contains("Blog: hello-world"));
         assert!(html.contains("hello-world"));
     }
 
+    #[test]
+    fn test_embed_csrf_token() {
+        let html = concat!(
+            r#"<form action="/_action/save" data-action="save"><input name="title"></form>"#,
+            r#"<form action="/search"><input name="q"></form>"#,
+            r#"<form><button data-action="remove">Remove</button></form>"#,
+        );
+        let embedded = embed_csrf_token(html, "abc123");
+
+        let field = r#"<input type="hidden" name="$csrf" value="abc123">"#;
+        assert_eq!(embedded.matches(field).count(), 2);
+        assert!(embedded.contains(&format!(r#"<input name="title">{}</form>"#, field)));
+        assert!(embedded.contains(r#"<input name="q"></form>"#));
+    }
+
     #[test]
     fn test_render_not_found() {
         let renderer = SsrRenderer::new();
@@ -283,7 +583,7 @@ mod tests {
         let registry = PageRegistry::new();
         let renderer = SsrRenderer::new();
 
-        let html = renderer.render("/unknown", &HashMap::new(), &registry);
+        let html = renderer.render("/unknown", &HashMap::new(), &registry).html;
 
         assert!(html.contains("No page component registered"));
     }
@@ -305,7 +605,7 @@ mod tests {
         let mut params = HashMap::new();
         params.insert("slug".to_string(), "test".to_string());
 
-        let html = renderer.render("/blog/[slug]", &params, &registry);
+        let html = renderer.render("/blog/[slug]", &params, &registry).html;
 
         assert!(html.contains("__NEXT_DATA__"));
         assert!(html.contains("\"slug\":\"test\""));
@@ -1,3 +1,7 @@
+use std::any::Any;
+use std::collections::HashSet;
+use std::future::{poll_fn, Future};
+use std::panic::{self, AssertUnwindSafe};
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
@@ -169,9 +173,13 @@ impl RscStream {
         self.chunks.push(format!("H:{}:{}\n", hint_type, data));
     }
 
+    /// Sends `error` in full, e.g. in development. See [`RscStreamingRenderer::render_error`].
     pub fn push_error(&mut self, id: &str, error: &str) {
-        let error_json = serde_json::json!({"message": error}).to_string();
-        self.chunks.push(format!("E:{}:{}\n", id, error_json));
+        self.push_rsc_error(id, &next_rs_rsc::RscError::new(error, true));
+    }
+
+    pub fn push_rsc_error(&mut self, id: &str, error: &next_rs_rsc::RscError) {
+        self.chunks.push(format!("{}\n", error.to_row(id)));
     }
 
     pub fn complete(&mut self) {
@@ -207,17 +215,100 @@ impl Stream for RscStream {
 
 pub struct RscStreamingRenderer {
     node_counter: usize,
+    hole_counter: usize,
+    sent_client_references: HashSet<String>,
+    dev_mode: bool,
+    client_keys: HashSet<String>,
+    /// Full errors behind the `E:` rows sent, by row or boundary id.
+    errors: Vec<(String, react_rs_dom::RenderError)>,
 }
 
 impl RscStreamingRenderer {
     pub fn new() -> Self {
-        Self { node_counter: 0 }
+        Self {
+            node_counter: 0,
+            hole_counter: 0,
+            sent_client_references: HashSet::new(),
+            dev_mode: false,
+            client_keys: HashSet::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Records the suspense keys the client already has content for, from the
+    /// [`next_rs_rsc::RSC_KEYS_HEADER`] request header.
+    pub fn set_client_keys(&mut self, header: &str) {
+        self.client_keys = header
+            .split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(String::from)
+            .collect();
+    }
+
+    /// In dev mode error rows carry the full error; otherwise only its digest.
+    pub fn set_dev_mode(&mut self, dev: bool) {
+        self.dev_mode = dev;
+    }
+
+    /// Sends an `E:` row for the row or suspense boundary `id`, keeping the full error with
+    /// its digest for [`RscStreamingRenderer::take_errors`], so a production report can be
+    /// matched to the server's log.
+    pub fn render_error(&mut self, stream: &mut RscStream, id: &str, error: &str) {
+        let rsc_error = next_rs_rsc::RscError::new(error, self.dev_mode);
+        stream.push_rsc_error(id, &rsc_error);
+        self.errors.push((
+            id.to_string(),
+            react_rs_dom::RenderError {
+                message: error.to_string(),
+                digest: rsc_error.digest,
+            },
+        ));
+    }
+
+    /// The errors sent since the last call, for the caller to log.
+    pub fn take_errors(&mut self) -> Vec<(String, react_rs_dom::RenderError)> {
+        std::mem::take(&mut self.errors)
+    }
+
+    /// Sends a tree built with the HTML builders as the next row, preceded by an `M:` row
+    /// for each registered client component in it that the stream has not announced yet.
+    /// Returns the row.
+    pub fn render_tree(
+        &mut self,
+        stream: &mut RscStream,
+        node: &react_rs_elements::Node,
+    ) -> Result<usize, next_rs_rsc::TaintError> {
+        let mut renderer = next_rs_rsc::RscRenderer::new();
+        let rsc_node = renderer.render_tree(node)?;
+        for reference in renderer.client_references() {
+            if self.sent_client_references.insert(reference.id.clone()) {
+                stream.push_client_reference(&reference.id, &reference.module, &reference.export);
+            }
+        }
+        let row = self.reserve_row();
+        self.fill_row(stream, row, &rsc_node);
+        Ok(row)
     }
 
     pub fn render_node(&mut self, stream: &mut RscStream, node: &next_rs_rsc::RscNode) {
-        let node_json = serde_json::to_string(node).unwrap_or_else(|_| "null".to_string());
-        stream.push_node(self.node_counter, &node_json);
+        let row = self.reserve_row();
+        self.fill_row(stream, row, node);
+    }
+
+    /// Claims the next row id without sending anything, so a parent can reference the row
+    /// with [`next_rs_rsc::RscNode::reference`] before its content is ready.
+    pub fn reserve_row(&mut self) -> usize {
+        let row = self.node_counter;
         self.node_counter += 1;
+        row
+    }
+
+    /// Sends the content of a row claimed with [`RscStreamingRenderer::reserve_row`]. Rows
+    /// can be filled in any order; the client renders each where it is referenced.
+    pub fn fill_row(&mut self, stream: &mut RscStream, row: usize, node: &next_rs_rsc::RscNode) {
+        let node_json = serde_json::to_string(node).unwrap_or_else(|_| "null".to_string());
+        stream.push_node(row, &node_json);
     }
 
     pub fn render_payload(&mut self, stream: &mut RscStream, payload: &next_rs_rsc::RscPayload) {
@@ -236,15 +327,8 @@ impl RscStreamingRenderer {
         id: &str,
         fallback: &next_rs_rsc::RscNode,
     ) {
-        let fallback_json = serde_json::to_string(fallback).unwrap_or_else(|_| "null".to_string());
-        stream.push_node(
-            self.node_counter,
-            &format!(
-                r#"{{"type":"suspense","id":"{}","fallback":{}}}"#,
-                id, fallback_json
-            ),
-        );
-        self.node_counter += 1;
+        let placeholder = next_rs_rsc::RscNode::suspense(id, fallback.clone(), Vec::new());
+        self.render_node(stream, &placeholder);
     }
 
     pub fn render_suspense_content(
@@ -253,8 +337,72 @@ impl RscStreamingRenderer {
         id: &str,
         content: &next_rs_rsc::RscNode,
     ) {
-        let content_json = serde_json::to_string(content).unwrap_or_else(|_| "null".to_string());
-        stream.chunks.push(format!("${}:{}\n", id, content_json));
+        stream.chunks.push(format!(
+            "{}\n",
+            next_rs_rsc::RscPayload::suspense_row(id, content)
+        ));
+    }
+
+    /// Sends `fallback` in place of an async server component and returns the hole its
+    /// content fills; pass it to [`RscStreamingRenderer::fill_hole`] once the rest of the
+    /// page is out.
+    pub fn render_async_component(
+        &mut self,
+        stream: &mut RscStream,
+        component: &next_rs_rsc::AsyncServerComponent,
+        fallback: &react_rs_elements::Node,
+    ) -> next_rs_rsc::RscHole {
+        let id = self.next_hole_id(component);
+        let (placeholder, hole) = component.render_streaming(id, fallback);
+        self.render_node(stream, &placeholder);
+        hole
+    }
+
+    /// Like [`RscStreamingRenderer::render_async_component`], for content named by `key`.
+    /// If the client already has that content, only the placeholder is sent, the component
+    /// does not run, and there is no hole to fill.
+    pub fn render_keyed_async_component(
+        &mut self,
+        stream: &mut RscStream,
+        component: &next_rs_rsc::AsyncServerComponent,
+        key: &str,
+        fallback: &react_rs_elements::Node,
+    ) -> Option<next_rs_rsc::RscHole> {
+        let id = self.next_hole_id(component);
+        if self.client_keys.contains(key) {
+            let fallback = next_rs_rsc::RscRenderer::new().render_node(fallback);
+            let placeholder = next_rs_rsc::RscNode::keyed_suspense(id, key, fallback, Vec::new());
+            self.render_node(stream, &placeholder);
+            return None;
+        }
+        let (placeholder, hole) = component.render_streaming_keyed(id, key, fallback);
+        self.render_node(stream, &placeholder);
+        Some(hole)
+    }
+
+    fn next_hole_id(&mut self, component: &next_rs_rsc::AsyncServerComponent) -> String {
+        let id = format!("{}-{}", component.id(), self.hole_counter);
+        self.hole_counter += 1;
+        id
+    }
+
+    /// Sends the row filling `hole`, or an error row for its boundary if the component
+    /// panics.
+    pub async fn fill_hole(&mut self, stream: &mut RscStream, hole: next_rs_rsc::RscHole) {
+        let id = hole.id().to_string();
+        let mut resolve = Box::pin(hole.resolve());
+        let resolved = poll_fn(|cx| {
+            match panic::catch_unwind(AssertUnwindSafe(|| resolve.as_mut().poll(cx))) {
+                Ok(Poll::Ready(row)) => Poll::Ready(Ok(row)),
+                Ok(Poll::Pending) => Poll::Pending,
+                Err(payload) => Poll::Ready(Err(panic_message(payload.as_ref()))),
+            }
+        })
+        .await;
+        match resolved {
+            Ok(row) => stream.chunks.push(format!("{}\n", row)),
+            Err(message) => self.render_error(stream, &id, &message),
+        }
     }
 
     pub fn node_counter(&self) -> usize {
@@ -262,6 +410,16 @@ impl RscStreamingRenderer {
     }
 }
 
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Server component panicked".to_string()
+    }
+}
+
 impl Default for RscStreamingRenderer {
     fn default() -> Self {
         Self::new()
@@ -369,6 +527,159 @@ mod tests {
         assert!(chunks[0].contains("Hello World"));
     }
 
+    #[test]
+    fn test_rsc_streaming_out_of_order_rows() {
+        use next_rs_rsc::RscNode;
+
+        let mut renderer = RscStreamingRenderer::new();
+        let mut stream = RscStream::new();
+
+        let root = renderer.reserve_row();
+        let slow = renderer.reserve_row();
+        let fast = renderer.reserve_row();
+        renderer.fill_row(
+            &mut stream,
+            root,
+            &RscNode::element(
+                "main",
+                serde_json::json!({}),
+                vec![RscNode::reference(slow), RscNode::reference(fast)],
+            ),
+        );
+        renderer.fill_row(&mut stream, fast, &RscNode::text("Sidebar"));
+        renderer.fill_row(&mut stream, slow, &RscNode::text("Feed"));
+        stream.complete();
+
+        let chunks = stream.into_chunks();
+        assert!(chunks[0].starts_with("0:") && chunks[0].contains(r#"["$1","$2"]"#));
+        assert!(chunks[1].starts_with("2:") && chunks[1].contains("Sidebar"));
+        assert!(chunks[2].starts_with("1:") && chunks[2].contains("Feed"));
+        assert_eq!(renderer.node_counter(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_rsc_streaming_async_component() {
+        use react_rs_elements::html::*;
+        use react_rs_elements::node::IntoNode;
+
+        let component =
+            next_rs_rsc::async_server_component("feed", || async { ul().child(li().text("Post")) });
+        let mut renderer = RscStreamingRenderer::new();
+        let mut stream = RscStream::new();
+
+        let fallback = p().text("Loading feed...").into_node();
+        let hole = renderer.render_async_component(&mut stream, &component, &fallback);
+        renderer.render_node(&mut stream, &next_rs_rsc::RscNode::text("Footer"));
+        renderer.fill_hole(&mut stream, hole).await;
+        stream.complete();
+
+        let chunks = stream.into_chunks();
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks[0].starts_with(
+            r#"0:{"type":"suspense","id":"feed-0","key":null,"fallback":{"type":"element","tag":"p""#
+        ));
+        assert!(chunks[0].ends_with("\"children\":[]}\n"));
+        assert!(chunks[1].contains("Footer"));
+        assert!(chunks[2].starts_with(r#"$feed-0:{"type":"element","tag":"ul""#));
+    }
+
+    #[tokio::test]
+    async fn test_rsc_streaming_failed_component_sends_error_row() {
+        use react_rs_elements::node::IntoNode;
+
+        async fn load_comments() -> react_rs_elements::Element {
+            panic!("db connection refused")
+        }
+        let component = next_rs_rsc::async_server_component("comments", load_comments);
+        let fallback = "Loading...".into_node();
+
+        let mut renderer = RscStreamingRenderer::new();
+        let mut stream = RscStream::new();
+        let hole = renderer.render_async_component(&mut stream, &component, &fallback);
+        renderer.fill_hole(&mut stream, hole).await;
+        let prod = stream.into_chunks();
+        let error = next_rs_rsc::RscError::new("db connection refused", false);
+        assert_eq!(prod[1], format!("{}\n", error.to_row("comments-0")));
+        let errors = renderer.take_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, "comments-0");
+        assert_eq!(errors[0].1.message, "db connection refused");
+        assert_eq!(errors[0].1.digest, error.digest);
+
+        renderer.set_dev_mode(true);
+        let mut stream = RscStream::new();
+        let hole = renderer.render_async_component(&mut stream, &component, &fallback);
+        renderer.fill_hole(&mut stream, hole).await;
+        let dev = stream.into_chunks();
+        assert!(dev[1].starts_with("E:comments-1:"));
+        assert!(dev[1].contains(&error.digest));
+        assert!(dev[1].contains("db connection refused"));
+    }
+
+    #[tokio::test]
+    async fn test_rsc_streaming_skips_keyed_content_client_has() {
+        use react_rs_elements::html::*;
+        use react_rs_elements::node::IntoNode;
+
+        let comments = next_rs_rsc::async_server_component("comments", || async {
+            ul().child(li().text("Hi"))
+        });
+        let sidebar =
+            next_rs_rsc::async_server_component("sidebar", || async { nav().text("Links") });
+        let fallback = "Loading...".into_node();
+
+        let mut renderer = RscStreamingRenderer::new();
+        renderer.set_client_keys("sidebar:v1, post:6:comments");
+        let mut stream = RscStream::new();
+        let fresh = renderer.render_keyed_async_component(
+            &mut stream,
+            &comments,
+            "post:7:comments",
+            &fallback,
+        );
+        let kept =
+            renderer.render_keyed_async_component(&mut stream, &sidebar, "sidebar:v1", &fallback);
+        assert!(kept.is_none());
+        renderer.fill_hole(&mut stream, fresh.unwrap()).await;
+
+        let chunks = stream.into_chunks();
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks[0].contains(r#""key":"post:7:comments""#));
+        assert!(chunks[1].contains(r#""id":"sidebar-1","key":"sidebar:v1""#));
+        assert!(chunks[2].starts_with("$comments-0:"));
+    }
+
+    #[test]
+    fn test_rsc_streaming_element_tree() {
+        use next_rs_rsc::markers::client_component;
+        use react_rs_elements::html::*;
+        use react_rs_elements::node::IntoNode;
+
+        next_rs_rsc::directive::global_registry().register_client("./Likes.js", "Likes");
+        let likes = client_component("Likes", "./Likes.js", || button().text("0"));
+
+        let mut renderer = RscStreamingRenderer::new();
+        let mut stream = RscStream::new();
+        let header = h1().text("Post").into_node();
+        let body = article()
+            .child(p().text("Hello"))
+            .child(likes.render_with(&serde_json::json!({"count": 3})).unwrap())
+            .into_node();
+        let footer = footer()
+            .child(likes.render_with(&serde_json::json!({"count": 4})).unwrap())
+            .into_node();
+        assert_eq!(renderer.render_tree(&mut stream, &header).unwrap(), 0);
+        assert_eq!(renderer.render_tree(&mut stream, &body).unwrap(), 1);
+        renderer.render_tree(&mut stream, &footer).unwrap();
+
+        let chunks = stream.into_chunks();
+        assert!(chunks[0].starts_with("0:{\"type\":\"element\""));
+        assert_eq!(chunks[1], "M:Likes:./Likes.js:Likes\n");
+        assert!(chunks[2].starts_with("1:"));
+        assert!(chunks[2].contains(r#"{"type":"client","id":"Likes","props":{"count":3}}"#));
+        assert_eq!(chunks.len(), 4);
+    }
+
     #[test]
     fn test_rsc_streaming_renderer_payload() {
         let mut renderer = RscStreamingRenderer::new();
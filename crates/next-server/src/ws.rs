@@ -28,6 +28,10 @@ impl WsSender {
         let _ = self.tx.send(Message::Binary(data.into()));
     }
 
+    pub fn send(&self, message: WsMessage) {
+        let _ = self.tx.send(message.into_message());
+    }
+
     pub fn close(&self) {
         let _ = self.tx.send(Message::Close(None));
     }
@@ -51,12 +55,47 @@ impl WsReceiver {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum WsMessage {
     Text(String),
     Binary(Vec<u8>),
     Close,
 }
 
+impl WsMessage {
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Self::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::Text(text) => Some(text.as_bytes()),
+            Self::Binary(data) => Some(data),
+            Self::Close => None,
+        }
+    }
+
+    pub fn from_message(message: Message) -> Option<Self> {
+        match message {
+            Message::Text(text) => Some(Self::Text(text.to_string())),
+            Message::Binary(data) => Some(Self::Binary(data.to_vec())),
+            Message::Close(_) => Some(Self::Close),
+            _ => None,
+        }
+    }
+
+    pub fn into_message(self) -> Message {
+        match self {
+            Self::Text(text) => Message::Text(text.into()),
+            Self::Binary(data) => Message::Binary(data.into()),
+            Self::Close => Message::Close(None),
+        }
+    }
+}
+
 pub struct WsRegistry {
     handlers: HashMap<String, WsHandlerFn>,
 }
@@ -188,6 +227,19 @@ mod tests {
         sender2.send_text("world");
     }
 
+    #[test]
+    fn test_ws_message_binary_roundtrip() {
+        let msg = WsMessage::Binary(vec![0, 159, 146, 150]);
+        let wire = msg.clone().into_message();
+        assert!(matches!(wire, Message::Binary(_)));
+        assert_eq!(WsMessage::from_message(wire), Some(msg));
+
+        let text = WsMessage::from_message(Message::Text("hi".into())).unwrap();
+        assert_eq!(text.as_text(), Some("hi"));
+        assert_eq!(text.as_bytes(), Some(&b"hi"[..]));
+        assert_eq!(WsMessage::from_message(Message::Ping(vec![].into())), None);
+    }
+
     #[test]
     fn test_compute_accept_key() {
         let key = "dGhlIHNhbXBsZSBub25jZQ==";
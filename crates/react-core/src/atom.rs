@@ -0,0 +1,145 @@
+use crate::runtime::RUNTIME;
+use crate::signal::{create_rw_signal, ReadSignal, RwSignal, WriteSignal};
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+/// App-wide state identified by a key, e.g. the signed-in user. Atoms are usually declared
+/// as constants and can be read or written from any component, effect or action handler
+/// without threading them through props or context:
+///
+/// ```
+/// use react_rs_core::{create_atom, Atom};
+///
+/// static CURRENT_USER: Atom<Option<String>> = create_atom("current_user", || None);
+///
+/// CURRENT_USER.set(Some("ada".to_string()));
+/// assert_eq!(CURRENT_USER.get_untracked(), Some("ada".to_string()));
+/// ```
+///
+/// The value lives in the current runtime, so each server request starts from the default.
+pub struct Atom<T: 'static> {
+    key: &'static str,
+    default: fn() -> T,
+    _marker: PhantomData<fn() -> T>,
+}
+
+pub const fn create_atom<T: 'static>(key: &'static str, default: fn() -> T) -> Atom<T> {
+    Atom {
+        key,
+        default,
+        _marker: PhantomData,
+    }
+}
+
+impl<T: 'static> Atom<T> {
+    pub fn key(&self) -> &'static str {
+        self.key
+    }
+
+    /// The signal backing this atom, created from the default on first use.
+    ///
+    /// # Panics
+    ///
+    /// Panics if another atom with a different value type uses the same key.
+    pub fn signal(&self) -> RwSignal<T> {
+        if let Some(existing) = RUNTIME.with(|rt| rt.borrow().atom(self.key)) {
+            return existing
+                .downcast_ref::<RwSignal<T>>()
+                .unwrap_or_else(|| panic!("atom key {:?} is used with different types", self.key))
+                .clone();
+        }
+        let signal = create_rw_signal((self.default)());
+        RUNTIME.with(|rt| {
+            rt.borrow_mut()
+                .insert_atom(self.key, Rc::new(signal.clone()))
+        });
+        signal
+    }
+
+    pub fn read_only(&self) -> ReadSignal<T> {
+        self.signal().read_only()
+    }
+
+    pub fn write_only(&self) -> WriteSignal<T> {
+        self.signal().write_only()
+    }
+
+    pub fn set(&self, value: T) {
+        self.signal().set(value);
+    }
+
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        self.signal().update(f);
+    }
+
+    /// Restores the default value, notifying subscribers.
+    pub fn reset(&self) {
+        self.set((self.default)());
+    }
+}
+
+impl<T: Clone + 'static> Atom<T> {
+    pub fn get(&self) -> T {
+        self.signal().get()
+    }
+
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.signal().with(f)
+    }
+
+    pub fn get_untracked(&self) -> T {
+        self.signal().get_untracked()
+    }
+}
+
+/// Drops every atom's value in the current runtime, so they start from their defaults again.
+/// Existing subscribers keep the old signals and are not notified.
+pub fn clear_atoms() {
+    RUNTIME.with(|rt| rt.borrow_mut().clear_atoms());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::effect::create_effect;
+    use std::cell::RefCell;
+
+    static COUNTER: Atom<u32> = create_atom("test_counter", || 0);
+    static THEME: Atom<String> = create_atom("test_theme", || "light".to_string());
+
+    #[test]
+    fn test_atom_is_shared_and_reactive() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let seen_clone = seen.clone();
+        create_effect(move || seen_clone.borrow_mut().push(COUNTER.get()));
+
+        // A separate "component" writes through its own handle.
+        let write = COUNTER.write_only();
+        write.set(2);
+        COUNTER.update(|n| *n += 1);
+
+        assert_eq!(*seen.borrow(), vec![0, 2, 3]);
+        COUNTER.reset();
+        assert_eq!(COUNTER.get_untracked(), 0);
+    }
+
+    #[test]
+    fn test_atoms_are_per_runtime() {
+        THEME.set("dark".to_string());
+        let mut request = crate::runtime::create_runtime();
+        assert_eq!(request.run(|| THEME.get_untracked()), "light");
+        assert_eq!(THEME.get_untracked(), "dark");
+
+        clear_atoms();
+        assert_eq!(THEME.get_untracked(), "light");
+    }
+
+    #[test]
+    #[should_panic(expected = "different types")]
+    fn test_key_collision_panics() {
+        static A: Atom<u32> = create_atom("test_collision", || 0);
+        static B: Atom<String> = create_atom("test_collision", String::new);
+        A.get_untracked();
+        B.get_untracked();
+    }
+}
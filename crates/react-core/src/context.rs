@@ -1,34 +1,40 @@
-use std::any::{Any, TypeId};
-use std::cell::RefCell;
-use std::collections::HashMap;
-
-thread_local! {
-    static CONTEXT_MAP: RefCell<HashMap<TypeId, Box<dyn Any>>> = RefCell::new(HashMap::new());
-}
+use crate::effect::run_in_new_scope;
+use crate::runtime::RUNTIME;
+use std::any::TypeId;
+use std::rc::Rc;
 
+/// Provides `value` to the current scope and every scope created beneath it. A provider in
+/// a nested scope shadows outer providers of the same type without affecting siblings.
 pub fn provide_context<T: Clone + 'static>(value: T) {
-    CONTEXT_MAP.with(|map| {
-        map.borrow_mut().insert(TypeId::of::<T>(), Box::new(value));
+    RUNTIME.with(|rt| {
+        rt.borrow_mut()
+            .provide_context(TypeId::of::<T>(), Rc::new(value))
     });
 }
 
+/// Returns the value from the nearest enclosing provider of `T`.
 pub fn use_context<T: Clone + 'static>() -> Option<T> {
-    CONTEXT_MAP.with(|map| {
-        map.borrow()
-            .get(&TypeId::of::<T>())
-            .and_then(|v| v.downcast_ref::<T>())
-            .cloned()
-    })
+    let value = RUNTIME.with(|rt| rt.borrow().lookup_context(TypeId::of::<T>()))?;
+    value.downcast_ref::<T>().cloned()
 }
 
 pub fn use_context_or<T: Clone + 'static>(default: T) -> T {
     use_context::<T>().unwrap_or(default)
 }
 
+/// Removes a context provided in the current scope. Outer providers become visible again.
 pub fn clear_context<T: 'static>() {
-    CONTEXT_MAP.with(|map| {
-        map.borrow_mut().remove(&TypeId::of::<T>());
-    });
+    RUNTIME.with(|rt| rt.borrow_mut().remove_context(TypeId::of::<T>()));
+}
+
+/// Runs `f` in a new child scope that provides `value`, so everything built by `f` sees it
+/// while siblings keep seeing the outer value.
+pub fn with_context<T: Clone + 'static, R>(value: T, f: impl FnOnce() -> R) -> R {
+    run_in_new_scope(|| {
+        provide_context(value);
+        f()
+    })
+    .1
 }
 
 #[cfg(test)]
@@ -117,6 +123,45 @@ mod tests {
         cleanup();
     }
 
+    #[test]
+    fn test_sibling_providers_are_isolated() {
+        cleanup();
+        provide_context(0i32);
+
+        let first = with_context(1i32, || {
+            let inner = with_context(2i32, use_context::<i32>);
+            (use_context::<i32>(), inner)
+        });
+        let second = with_context(3i32, use_context::<i32>);
+
+        assert_eq!(first, (Some(1), Some(2)));
+        assert_eq!(second, Some(3));
+        assert_eq!(use_context::<i32>(), Some(0));
+
+        cleanup();
+    }
+
+    #[test]
+    fn test_effects_see_provider_scope() {
+        use crate::effect::create_effect;
+        use crate::signal::create_signal;
+        use std::cell::RefCell;
+
+        let (tick, set_tick) = create_signal(0);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let seen_clone = seen.clone();
+        with_context("inner", move || {
+            create_effect(move || {
+                let _ = tick.get();
+                seen_clone.borrow_mut().push(use_context::<&str>());
+            });
+        });
+
+        set_tick.set(1);
+        assert_eq!(*seen.borrow(), vec![Some("inner"), Some("inner")]);
+        assert_eq!(use_context::<&str>(), None);
+    }
+
     #[test]
     fn test_context_override() {
         cleanup();
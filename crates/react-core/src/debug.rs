@@ -0,0 +1,263 @@
+use crate::effect::run_effect;
+use crate::runtime::{EffectId, SignalId, WriteWarningFn, RUNTIME};
+use crate::signal::{create_signal, ReadSignal, WriteSignal};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::rc::Rc;
+
+/// Like [`create_signal`], but the signal is labelled `name` in [`dependency_graph`] dumps
+/// and write warnings.
+pub fn create_signal_named<T>(name: &str, value: T) -> (ReadSignal<T>, WriteSignal<T>) {
+    let (read, write) = create_signal(value);
+    RUNTIME.with(|rt| rt.borrow_mut().set_signal_name(read.id(), name.to_string()));
+    (read, write)
+}
+
+/// Like [`crate::create_effect`], but the effect is labelled `name` in debugging output.
+pub fn create_effect_named<F>(name: &str, f: F)
+where
+    F: Fn() + 'static,
+{
+    let effect_id = RUNTIME.with(|rt| {
+        let mut rt = rt.borrow_mut();
+        let id = rt.register_effect(f);
+        if let Some(debug) = rt.effect_debug_mut(id) {
+            debug.name = Some(name.to_string());
+        }
+        id
+    });
+    run_effect(effect_id);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalInfo {
+    pub id: SignalId,
+    pub name: Option<String>,
+    pub subscribers: Vec<EffectId>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EffectInfo {
+    pub id: EffectId,
+    pub name: Option<String>,
+    pub sources: Vec<SignalId>,
+    pub disposed: bool,
+}
+
+/// A snapshot of which effects read which signals. Its `Display` output lists each live
+/// effect with its sources, which makes over-subscription easy to spot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DependencyGraph {
+    pub signals: Vec<SignalInfo>,
+    pub effects: Vec<EffectInfo>,
+}
+
+/// Captures the dependency graph of the current runtime. Only signals that are named or
+/// have been read by an effect appear.
+pub fn dependency_graph() -> DependencyGraph {
+    RUNTIME.with(|rt| {
+        let rt = rt.borrow();
+        let mut signals: BTreeMap<SignalId, SignalInfo> = BTreeMap::new();
+        let mut effects = Vec::new();
+
+        for (id, debug) in rt.effect_debug().iter().enumerate() {
+            let disposed = rt.is_effect_disposed(id);
+            if !disposed {
+                for &source in &debug.sources {
+                    signals
+                        .entry(source)
+                        .or_insert_with(|| SignalInfo {
+                            id: source,
+                            name: None,
+                            subscribers: Vec::new(),
+                        })
+                        .subscribers
+                        .push(id);
+                }
+            }
+            effects.push(EffectInfo {
+                id,
+                name: debug.name.clone(),
+                sources: debug.sources.clone(),
+                disposed,
+            });
+        }
+        for signal in signals.values_mut() {
+            signal.name = rt.signal_name(signal.id).map(str::to_string);
+        }
+        for (id, name) in rt.signal_names() {
+            signals.entry(*id).or_insert_with(|| SignalInfo {
+                id: *id,
+                name: Some(name.clone()),
+                subscribers: Vec::new(),
+            });
+        }
+
+        DependencyGraph {
+            signals: signals.into_values().collect(),
+            effects,
+        }
+    })
+}
+
+impl DependencyGraph {
+    fn signal_label(&self, id: SignalId) -> String {
+        match self
+            .signals
+            .iter()
+            .find(|s| s.id == id)
+            .and_then(|s| s.name.as_ref())
+        {
+            Some(name) => format!("signal #{} \"{}\"", id, name),
+            None => format!("signal #{}", id),
+        }
+    }
+}
+
+impl fmt::Display for DependencyGraph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for effect in self.effects.iter().filter(|e| !e.disposed) {
+            match &effect.name {
+                Some(name) => write!(f, "effect #{} \"{}\"", effect.id, name)?,
+                None => write!(f, "effect #{}", effect.id)?,
+            }
+            let sources: Vec<String> = effect
+                .sources
+                .iter()
+                .map(|&id| self.signal_label(id))
+                .collect();
+            if sources.is_empty() {
+                writeln!(f)?;
+            } else {
+                writeln!(f, " <- {}", sources.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A signal written while an effect was running.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WriteWarning {
+    pub signal: SignalId,
+    pub signal_name: Option<String>,
+    pub effect: EffectId,
+    pub effect_name: Option<String>,
+    /// The running effect also reads the signal, so the write re-triggers it and may loop.
+    pub self_triggering: bool,
+}
+
+impl fmt::Display for WriteWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let signal = self.signal_name.as_deref().unwrap_or("<unnamed>");
+        let effect = self.effect_name.as_deref().unwrap_or("<unnamed>");
+        write!(
+            f,
+            "signal #{} ({}) was written inside effect #{} ({})",
+            self.signal, signal, self.effect, effect
+        )?;
+        if self.self_triggering {
+            write!(
+                f,
+                "; the effect reads it too and will re-run, possibly forever"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Calls `handler` whenever a signal is written while a (non-derived) effect runs. Writes
+/// from memos and selectors are not reported.
+pub fn set_write_warning_handler(handler: impl Fn(&WriteWarning) + 'static) {
+    RUNTIME.with(|rt| rt.borrow_mut().set_write_warning(Some(Rc::new(handler))));
+}
+
+/// Reports writes inside effects on stderr.
+pub fn warn_on_write_in_effect() {
+    set_write_warning_handler(|warning| eprintln!("warning: {}", warning));
+}
+
+pub fn clear_write_warning_handler() {
+    RUNTIME.with(|rt| rt.borrow_mut().set_write_warning(None));
+}
+
+pub(crate) fn write_in_effect_warning(
+    signal: SignalId,
+    subscribers: &[EffectId],
+) -> Option<(WriteWarningFn, WriteWarning)> {
+    RUNTIME.with(|rt| {
+        let mut rt = rt.borrow_mut();
+        let handler = rt.write_warning()?;
+        let effect = rt.current_effect()?;
+        let debug = rt.effect_debug_mut(effect)?;
+        if debug.derived {
+            return None;
+        }
+        let effect_name = debug.name.clone();
+        Some((
+            handler,
+            WriteWarning {
+                signal,
+                signal_name: rt.signal_name(signal).map(str::to_string),
+                effect,
+                effect_name,
+                self_triggering: subscribers.contains(&effect),
+            },
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_dependency_graph_lists_named_sources() {
+        let (query, _) = create_signal_named("query", String::new());
+        let (page, _) = create_signal(1);
+        create_effect_named("results", move || {
+            query.with(|_| ());
+            page.get();
+        });
+
+        let graph = dependency_graph();
+        let results = graph
+            .effects
+            .iter()
+            .find(|e| e.name.as_deref() == Some("results"))
+            .unwrap();
+        assert_eq!(results.sources.len(), 2);
+        let line = format!("effect #{} \"results\" <- signal #", results.id);
+        assert!(graph.to_string().contains(&line));
+        assert!(graph.to_string().contains("\"query\", signal #"));
+    }
+
+    #[test]
+    fn test_warns_on_write_inside_effect() {
+        let warnings = Rc::new(RefCell::new(Vec::new()));
+        let seen = warnings.clone();
+        set_write_warning_handler(move |w| seen.borrow_mut().push(w.clone()));
+
+        let (count, set_count) = create_signal_named("count", 0);
+        let (doubled, set_doubled) = create_signal(0);
+        let _memo = crate::memo::create_memo(move || doubled.get() + 1);
+        create_effect_named("sync", move || {
+            let n = count.get();
+            set_doubled.set(n * 2);
+            if n < 2 {
+                set_count.set(n + 1);
+            }
+        });
+        clear_write_warning_handler();
+
+        let warnings = warnings.borrow();
+        assert!(warnings
+            .iter()
+            .all(|w| w.effect_name.as_deref() == Some("sync")));
+        assert!(warnings
+            .iter()
+            .any(|w| w.self_triggering && w.signal_name.as_deref() == Some("count")));
+        assert!(warnings.iter().any(|w| !w.self_triggering));
+    }
+}
@@ -1,4 +1,5 @@
-use crate::runtime::{ScopeId, RUNTIME};
+use crate::runtime::{FlushRequest, ScopeId, RUNTIME};
+use std::rc::Rc;
 
 pub fn create_effect<F>(f: F)
 where
@@ -8,18 +9,124 @@ where
     run_effect(effect_id);
 }
 
+/// Creates an effect for a derived value (memos, selectors, resources) whose signal writes
+/// are expected and therefore not reported by the write-in-effect warning.
+pub(crate) fn create_derived_effect<F>(f: F)
+where
+    F: Fn() + 'static,
+{
+    let effect_id = RUNTIME.with(|rt| {
+        let mut rt = rt.borrow_mut();
+        let id = rt.register_effect(f);
+        if let Some(debug) = rt.effect_debug_mut(id) {
+            debug.derived = true;
+        }
+        id
+    });
+    run_effect(effect_id);
+}
+
+/// Creates an effect whose return value is a cleanup closure. The cleanup runs before the
+/// effect re-executes and when its scope is disposed, which suits subscriptions, timers and
+/// event listeners set up by the effect.
+pub fn create_effect_with_cleanup<F, C>(f: F)
+where
+    F: Fn() -> C + 'static,
+    C: FnOnce() + 'static,
+{
+    create_effect(move || on_cleanup(f()));
+}
+
+/// Registers `f` to run before the current effect re-executes or is disposed. Outside an
+/// effect it runs when the owning scope is disposed.
 pub fn on_cleanup(f: impl FnOnce() + 'static) {
     RUNTIME.with(|rt| {
         rt.borrow_mut().add_cleanup(f);
     });
 }
 
+/// Runs `f` once after the DOM being built has been attached to the document. Only the
+/// browser renderer runs mount callbacks; server rendering discards them, so `f` may use
+/// browser APIs freely. `f` runs in the scope `on_mount` was called from, so its
+/// [`on_cleanup`] registrations are released with that scope.
+pub fn on_mount(f: impl FnOnce() + 'static) {
+    RUNTIME.with(|rt| rt.borrow_mut().queue_mount(f));
+}
+
+/// Runs queued [`on_mount`] callbacks whose scope is still alive. Called by renderers once
+/// their output is attached.
+pub fn run_mount_callbacks() {
+    loop {
+        let mounts = RUNTIME.with(|rt| rt.borrow_mut().take_mounts());
+        if mounts.is_empty() {
+            break;
+        }
+        for (scope, callback) in mounts {
+            let alive = RUNTIME.with(|rt| {
+                let mut rt = rt.borrow_mut();
+                if scope.is_some_and(|id| rt.is_scope_disposed(id)) {
+                    return None;
+                }
+                Some((rt.set_current_scope(scope), rt.set_current_effect(None)))
+            });
+            if let Some((prev_scope, prev_effect)) = alive {
+                callback();
+                RUNTIME.with(|rt| {
+                    let mut rt = rt.borrow_mut();
+                    rt.set_current_scope(prev_scope);
+                    rt.set_current_effect(prev_effect);
+                });
+            }
+        }
+    }
+}
+
+/// Drops queued [`on_mount`] callbacks without running them, for renderers that never
+/// attach to a live document.
+pub fn discard_mount_callbacks() {
+    let mounts = RUNTIME.with(|rt| rt.borrow_mut().take_mounts());
+    drop(mounts);
+}
+
 pub fn create_scope() -> ScopeId {
     RUNTIME.with(|rt| rt.borrow_mut().create_scope())
 }
 
+/// Runs `f` with `scope_id` as the owner of the effects, cleanups and scopes it creates.
+pub fn run_in_scope<R>(scope_id: ScopeId, f: impl FnOnce() -> R) -> R {
+    struct ScopeGuard(Option<ScopeId>);
+
+    impl Drop for ScopeGuard {
+        fn drop(&mut self) {
+            RUNTIME.with(|rt| rt.borrow_mut().set_current_scope(self.0));
+        }
+    }
+
+    let _guard = ScopeGuard(RUNTIME.with(|rt| rt.borrow_mut().set_current_scope(Some(scope_id))));
+    f()
+}
+
+/// Creates a scope owned by the current one and runs `f` in it, returning the scope so its
+/// contents can be released with [`dispose_scope`], e.g. when a component unmounts. Unlike
+/// [`create_scope`], the current scope is restored afterwards.
+pub fn run_in_new_scope<R>(f: impl FnOnce() -> R) -> (ScopeId, R) {
+    let scope = RUNTIME.with(|rt| {
+        let mut rt = rt.borrow_mut();
+        let parent = rt.current_scope();
+        rt.create_child_scope(parent)
+    });
+    (scope, run_in_scope(scope, f))
+}
+
+pub fn current_scope() -> Option<ScopeId> {
+    RUNTIME.with(|rt| rt.borrow().current_scope())
+}
+
 pub fn dispose_scope(scope_id: ScopeId) {
-    RUNTIME.with(|rt| rt.borrow_mut().dispose_scope(scope_id));
+    let cleanups = RUNTIME.with(|rt| rt.borrow_mut().dispose_scope(scope_id));
+    for cleanup in cleanups {
+        cleanup();
+    }
 }
 
 pub(crate) fn run_effect(id: usize) {
@@ -27,19 +134,123 @@ pub(crate) fn run_effect(id: usize) {
         if rt.borrow().is_effect_disposed(id) {
             return;
         }
-        rt.borrow_mut().run_cleanups(id);
+        let (cleanups, run_scope) = rt.borrow_mut().begin_effect_run(id);
+        for cleanup in cleanups {
+            cleanup();
+        }
         let prev = rt.borrow_mut().set_current_effect(Some(id));
+        let prev_scope = rt.borrow_mut().set_current_scope(Some(run_scope));
         let effect_fn = rt.borrow().clone_effect(id);
 
         if let Some(f) = effect_fn {
             f();
         }
 
-        rt.borrow_mut().set_current_effect(prev);
+        let mut rt = rt.borrow_mut();
+        rt.set_current_scope(prev_scope);
+        rt.set_current_effect(prev);
     });
 }
 
-pub(crate) fn flush_effects() {
+/// Stops a [`watch`] when called. Dropping it leaves the watcher running with its owner.
+pub struct WatchHandle {
+    scope: ScopeId,
+}
+
+impl WatchHandle {
+    pub fn stop(self) {
+        dispose_scope(self.scope);
+    }
+}
+
+/// Calls `callback(new, previous)` whenever a signal read by `deps` changes. Only `deps` is
+/// tracked; signals read inside the callback are not. With `immediate`, the callback also
+/// runs once right away with no previous value.
+pub fn watch<T, D, C>(deps: D, callback: C, immediate: bool) -> WatchHandle
+where
+    T: Clone + 'static,
+    D: Fn() -> T + 'static,
+    C: Fn(&T, Option<&T>) + 'static,
+{
+    let previous: std::cell::RefCell<Option<T>> = std::cell::RefCell::new(None);
+    let first = std::cell::Cell::new(true);
+    let (scope, ()) = run_in_new_scope(|| {
+        create_derived_effect(move || {
+            let value = deps();
+            let prev = previous.replace(Some(value.clone()));
+            if first.replace(false) && !immediate {
+                return;
+            }
+            untrack(|| callback(&value, prev.as_ref()));
+        });
+    });
+    WatchHandle { scope }
+}
+
+/// Installs a scheduler that is called (at most once per flush) instead of running effects
+/// synchronously after a signal write. The scheduler must eventually call [`flush_effects`].
+pub fn set_effect_scheduler(scheduler: impl Fn() + 'static) {
+    RUNTIME.with(|rt| rt.borrow_mut().set_scheduler(Some(Rc::new(scheduler))));
+}
+
+/// Restores synchronous effect execution.
+pub fn clear_effect_scheduler() {
+    RUNTIME.with(|rt| {
+        let mut rt = rt.borrow_mut();
+        rt.set_scheduler(None);
+        rt.clear_flush_scheduled();
+    });
+    flush_effects();
+}
+
+/// Runs `f` without subscribing the current effect or memo to the signals it reads.
+pub fn untrack<R>(f: impl FnOnce() -> R) -> R {
+    struct TrackingGuard(Option<usize>);
+
+    impl Drop for TrackingGuard {
+        fn drop(&mut self) {
+            RUNTIME.with(|rt| rt.borrow_mut().set_current_effect(self.0));
+        }
+    }
+
+    let _guard = TrackingGuard(RUNTIME.with(|rt| rt.borrow_mut().set_current_effect(None)));
+    f()
+}
+
+/// Runs `f` with effect notifications deferred until it returns, so writing several signals
+/// re-runs each dependent effect once. Nested batches flush when the outermost one ends.
+pub fn batch<R>(f: impl FnOnce() -> R) -> R {
+    struct BatchGuard(bool);
+
+    impl Drop for BatchGuard {
+        fn drop(&mut self) {
+            let flush = RUNTIME.with(|rt| {
+                let mut rt = rt.borrow_mut();
+                rt.end_batch(self.0);
+                !self.0 && rt.has_pending_effects()
+            });
+            if flush && !std::thread::panicking() {
+                request_flush();
+            }
+        }
+    }
+
+    let _guard = BatchGuard(RUNTIME.with(|rt| rt.borrow_mut().start_batch()));
+    f()
+}
+
+pub(crate) fn request_flush() {
+    let request = RUNTIME.with(|rt| rt.borrow_mut().request_flush());
+    match request {
+        FlushRequest::Now => flush_effects(),
+        FlushRequest::Deferred(scheduler) => scheduler(),
+        FlushRequest::AlreadyScheduled => {}
+    }
+}
+
+/// Runs all pending effects now.
+pub fn flush_effects() {
+    RUNTIME.with(|rt| rt.borrow_mut().clear_flush_scheduled());
     loop {
         let effect_id = RUNTIME.with(|rt| rt.borrow_mut().pop_pending_effect());
         match effect_id {
@@ -190,6 +401,252 @@ mod tests {
         assert_eq!(*cleanup_count.borrow(), 2);
     }
 
+    #[test]
+    fn test_effect_returned_cleanup() {
+        let (count, set_count) = create_signal(0);
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let log_clone = log.clone();
+
+        let scope = create_scope();
+        create_effect_with_cleanup(move || {
+            let value = count.get();
+            log_clone.borrow_mut().push(format!("run {}", value));
+            let log = log_clone.clone();
+            move || log.borrow_mut().push(format!("cleanup {}", value))
+        });
+
+        set_count.set(1);
+        dispose_scope(scope);
+        set_count.set(2);
+
+        assert_eq!(
+            *log.borrow(),
+            vec!["run 0", "cleanup 0", "run 1", "cleanup 1"]
+        );
+    }
+
+    #[test]
+    fn test_cleanup_can_write_signals() {
+        let (count, set_count) = create_signal(0);
+        let (disposed, set_disposed) = create_signal(false);
+
+        let scope = create_scope();
+        create_effect(move || {
+            let _ = count.get();
+            let set_disposed = set_disposed.clone();
+            on_cleanup(move || set_disposed.set(true));
+        });
+
+        set_count.set(1);
+        assert!(disposed.get_untracked());
+        dispose_scope(scope);
+        assert!(disposed.get_untracked());
+    }
+
+    #[test]
+    fn test_run_in_new_scope_restores_owner() {
+        let (count, set_count) = create_signal(0);
+        let runs = Rc::new(RefCell::new(0));
+        let runs_clone = runs.clone();
+        let before = current_scope();
+
+        let (scope, ()) = run_in_new_scope(|| {
+            create_effect(move || {
+                let _ = count.get();
+                *runs_clone.borrow_mut() += 1;
+            });
+        });
+        assert_eq!(current_scope(), before);
+
+        set_count.set(1);
+        dispose_scope(scope);
+        set_count.set(2);
+        assert_eq!(*runs.borrow(), 2);
+    }
+
+    #[test]
+    fn test_rerun_disposes_inner_effects() {
+        let (outer, set_outer) = create_signal(0);
+        let (inner, set_inner) = create_signal(0);
+        let inner_runs = Rc::new(RefCell::new(0));
+        let inner_cleanups = Rc::new(RefCell::new(0));
+
+        let runs = inner_runs.clone();
+        let cleanups = inner_cleanups.clone();
+        create_effect(move || {
+            let _ = outer.get();
+            let runs = runs.clone();
+            let cleanups = cleanups.clone();
+            let inner = inner.clone();
+            create_effect(move || {
+                let _ = inner.get();
+                *runs.borrow_mut() += 1;
+            });
+            on_cleanup(move || *cleanups.borrow_mut() += 1);
+        });
+
+        set_outer.set(1);
+        set_outer.set(2);
+        assert_eq!(*inner_cleanups.borrow(), 2);
+        assert_eq!(*inner_runs.borrow(), 3);
+
+        set_inner.set(1);
+        assert_eq!(*inner_runs.borrow(), 4);
+    }
+
+    #[test]
+    fn test_watch_reports_previous_values() {
+        let (count, set_count) = create_signal(1);
+        let (other, set_other) = create_signal(0);
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let calls_clone = calls.clone();
+
+        let handle = watch(
+            move || count.get(),
+            move |new, old| {
+                let _ = other.get();
+                calls_clone.borrow_mut().push((*new, old.copied()));
+            },
+            false,
+        );
+
+        set_other.set(1);
+        set_count.set(2);
+        set_count.set(3);
+        handle.stop();
+        set_count.set(4);
+        assert_eq!(*calls.borrow(), vec![(2, Some(1)), (3, Some(2))]);
+
+        let (name, _) = create_signal("a");
+        let immediate = Rc::new(RefCell::new(Vec::new()));
+        let immediate_clone = immediate.clone();
+        watch(
+            move || name.get(),
+            move |new, old| immediate_clone.borrow_mut().push((*new, old.copied())),
+            true,
+        );
+        assert_eq!(*immediate.borrow(), vec![("a", None)]);
+    }
+
+    #[test]
+    fn test_untrack_does_not_subscribe() {
+        let (tracked, set_tracked) = create_signal(0);
+        let (peeked, set_peeked) = create_signal(0);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let seen_clone = seen.clone();
+
+        create_effect(move || {
+            let peeked = peeked.clone();
+            let value = tracked.get() + untrack(move || peeked.get());
+            seen_clone.borrow_mut().push(value);
+        });
+
+        set_peeked.set(10);
+        assert_eq!(*seen.borrow(), vec![0]);
+        set_tracked.set(1);
+        assert_eq!(*seen.borrow(), vec![0, 11]);
+    }
+
+    #[test]
+    fn test_batch_runs_effect_once() {
+        let (a, set_a) = create_signal(0);
+        let (b, set_b) = create_signal(0);
+        let runs = Rc::new(RefCell::new(Vec::new()));
+        let runs_clone = runs.clone();
+        create_effect(move || runs_clone.borrow_mut().push(a.get() + b.get()));
+
+        let result = batch(|| {
+            set_a.set(1);
+            batch(|| set_b.set(2));
+            assert_eq!(*runs.borrow(), vec![0]);
+            "done"
+        });
+
+        assert_eq!(result, "done");
+        assert_eq!(*runs.borrow(), vec![0, 3]);
+    }
+
+    #[test]
+    fn test_scope_cleanup_outside_effect() {
+        let cleaned = Rc::new(RefCell::new(false));
+        let cleaned_clone = cleaned.clone();
+
+        let scope = create_scope();
+        on_cleanup(move || *cleaned_clone.borrow_mut() = true);
+
+        assert!(!*cleaned.borrow());
+        dispose_scope(scope);
+        assert!(*cleaned.borrow());
+    }
+
+    #[test]
+    fn test_on_mount_runs_in_owner_scope() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        let scope = create_scope();
+        let log_clone = log.clone();
+        on_mount(move || {
+            log_clone.borrow_mut().push("mount");
+            let log = log_clone.clone();
+            on_cleanup(move || log.borrow_mut().push("cleanup"));
+        });
+        let disposed = create_scope();
+        let log_clone = log.clone();
+        on_mount(move || log_clone.borrow_mut().push("disposed"));
+        dispose_scope(disposed);
+
+        assert!(log.borrow().is_empty());
+        run_mount_callbacks();
+        run_mount_callbacks();
+        assert_eq!(*log.borrow(), vec!["mount"]);
+
+        dispose_scope(scope);
+        assert_eq!(*log.borrow(), vec!["mount", "cleanup"]);
+    }
+
+    #[test]
+    fn test_discarded_mounts_never_run() {
+        let ran = Rc::new(RefCell::new(false));
+        let ran_clone = ran.clone();
+        on_mount(move || *ran_clone.borrow_mut() = true);
+
+        discard_mount_callbacks();
+        run_mount_callbacks();
+        assert!(!*ran.borrow());
+    }
+
+    #[test]
+    fn test_effect_scheduler_coalesces_runs() {
+        let (count, set_count) = create_signal(0);
+        let runs = Rc::new(RefCell::new(Vec::new()));
+        let runs_clone = runs.clone();
+        create_effect(move || {
+            runs_clone.borrow_mut().push(count.get());
+        });
+
+        let requests = Rc::new(RefCell::new(0));
+        let requests_clone = requests.clone();
+        set_effect_scheduler(move || *requests_clone.borrow_mut() += 1);
+
+        set_count.set(1);
+        set_count.set(2);
+        set_count.set(3);
+        assert_eq!(*requests.borrow(), 1);
+        assert_eq!(*runs.borrow(), vec![0]);
+
+        flush_effects();
+        assert_eq!(*runs.borrow(), vec![0, 3]);
+
+        set_count.set(4);
+        assert_eq!(*requests.borrow(), 2);
+
+        clear_effect_scheduler();
+        assert_eq!(*runs.borrow(), vec![0, 3, 4]);
+
+        set_count.set(5);
+        assert_eq!(*runs.borrow(), vec![0, 3, 4, 5]);
+    }
+
     #[test]
     fn test_multiple_effects() {
         let (count, set_count) = create_signal(0);
@@ -1,17 +1,45 @@
+pub mod atom;
 pub mod children;
 pub mod component;
 pub mod context;
+pub mod debug;
 pub mod effect;
 pub mod memo;
+pub mod reducer;
 pub mod resource;
 pub mod runtime;
+pub mod selector;
 pub mod signal;
+pub mod signal_vec;
+pub mod store;
+pub mod suspense;
+pub mod task;
+pub mod timer;
 
+pub use atom::{clear_atoms, create_atom, Atom};
 pub use children::Children;
 pub use component::{component, Component, IntoView};
-pub use context::{clear_context, provide_context, use_context, use_context_or};
-pub use effect::{create_effect, create_scope, dispose_scope, on_cleanup};
+pub use context::{clear_context, provide_context, use_context, use_context_or, with_context};
+pub use debug::{
+    clear_write_warning_handler, create_effect_named, create_signal_named, dependency_graph,
+    set_write_warning_handler, warn_on_write_in_effect, DependencyGraph, WriteWarning,
+};
+pub use effect::{
+    batch, clear_effect_scheduler, create_effect, create_effect_with_cleanup, create_scope,
+    current_scope, discard_mount_callbacks, dispose_scope, flush_effects, on_cleanup, on_mount,
+    run_in_new_scope, run_in_scope, run_mount_callbacks, set_effect_scheduler, untrack, watch,
+    WatchHandle,
+};
 pub use memo::{create_memo, Memo};
-pub use resource::{create_resource, create_resource_with, Resource, ResourceState};
-pub use runtime::ScopeId;
-pub use signal::{create_signal, ReadSignal, WriteSignal};
+pub use reducer::{create_reducer, Dispatch};
+pub use resource::{
+    create_manual_resource, create_resource, create_resource_with, Resource, ResourceState,
+};
+pub use runtime::{create_runtime, RuntimeInstance, ScopeId};
+pub use selector::{create_selector, Selector};
+pub use signal::{create_rw_signal, create_signal, ReadSignal, RwSignal, WriteSignal};
+pub use signal_vec::{create_keyed_signal, SignalVec, VecDiff, VecDiffReader};
+pub use store::{create_store, Store, StoreField};
+pub use suspense::{create_suspense_scope, use_suspense_context, SuspenseContext};
+pub use task::{block_on_pending_tasks, run_pending_tasks, set_task_spawner, spawn_local};
+pub use timer::{clear_timer, set_timer, TimerCancel};
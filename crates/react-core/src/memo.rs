@@ -1,4 +1,4 @@
-use crate::effect::create_effect;
+use crate::effect::create_derived_effect;
 use crate::signal::{create_signal, ReadSignal};
 
 pub struct Memo<T> {
@@ -13,6 +13,14 @@ impl<T: Clone> Memo<T> {
     pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
         self.read.with(f)
     }
+
+    pub fn get_untracked(&self) -> T {
+        self.read.get_untracked()
+    }
+
+    pub fn with_untracked<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.read.with_untracked(f)
+    }
 }
 
 impl<T> Clone for Memo<T> {
@@ -32,7 +40,7 @@ where
     let initial = f();
     let (read, write) = create_signal(initial);
 
-    create_effect(move || {
+    create_derived_effect(move || {
         let new_value = f();
         write.set_if_changed(new_value);
     });
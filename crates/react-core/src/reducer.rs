@@ -0,0 +1,91 @@
+use crate::signal::{create_signal, ReadSignal, WriteSignal};
+use std::rc::Rc;
+
+/// Sends actions to a reducer created with [`create_reducer`].
+pub struct Dispatch<A> {
+    send: Rc<dyn Fn(A)>,
+}
+
+impl<A> Clone for Dispatch<A> {
+    fn clone(&self) -> Self {
+        Self {
+            send: self.send.clone(),
+        }
+    }
+}
+
+impl<A> Dispatch<A> {
+    pub fn dispatch(&self, action: A) {
+        (self.send)(action)
+    }
+}
+
+/// Creates state that changes only through `reducer(&state, action)`, keeping the
+/// transitions of a form or wizard in one place.
+pub fn create_reducer<S, A, R>(initial: S, reducer: R) -> (ReadSignal<S>, Dispatch<A>)
+where
+    S: 'static,
+    A: 'static,
+    R: Fn(&S, A) -> S + 'static,
+{
+    let (state, set_state): (ReadSignal<S>, WriteSignal<S>) = create_signal(initial);
+    let dispatch = Dispatch {
+        send: Rc::new(move |action| {
+            set_state.update(|current| {
+                let next = reducer(current, action);
+                *current = next;
+            })
+        }),
+    };
+    (state, dispatch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Step {
+        Details,
+        Review { name: String },
+        Done,
+    }
+
+    enum Action {
+        Submit(String),
+        Back,
+        Confirm,
+    }
+
+    fn wizard(step: &Step, action: Action) -> Step {
+        match (step, action) {
+            (Step::Details, Action::Submit(name)) => Step::Review { name },
+            (Step::Review { .. }, Action::Back) => Step::Details,
+            (Step::Review { .. }, Action::Confirm) => Step::Done,
+            (step, _) => step.clone(),
+        }
+    }
+
+    #[test]
+    fn test_reducer_transitions() {
+        let (step, dispatch) = create_reducer(Step::Details, wizard);
+
+        dispatch.dispatch(Action::Confirm);
+        assert_eq!(step.get_untracked(), Step::Details);
+
+        dispatch.dispatch(Action::Submit("Ada".to_string()));
+        assert_eq!(
+            step.get_untracked(),
+            Step::Review {
+                name: "Ada".to_string()
+            }
+        );
+
+        dispatch.dispatch(Action::Back);
+        assert_eq!(step.get_untracked(), Step::Details);
+
+        dispatch.dispatch(Action::Submit("Ada".to_string()));
+        dispatch.clone().dispatch(Action::Confirm);
+        assert_eq!(step.get_untracked(), Step::Done);
+    }
+}
@@ -1,4 +1,10 @@
+use crate::effect::{create_derived_effect, untrack};
 use crate::signal::{create_signal, ReadSignal, WriteSignal};
+use crate::suspense::SuspenseTracker;
+use crate::task::spawn_local;
+use std::cell::Cell;
+use std::future::Future;
+use std::rc::Rc;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ResourceState<T> {
@@ -38,6 +44,8 @@ impl<T> ResourceState<T> {
 pub struct Resource<T: Clone + 'static> {
     state: ReadSignal<ResourceState<T>>,
     set_state: WriteSignal<ResourceState<T>>,
+    refetch: Option<WriteSignal<u64>>,
+    suspense: SuspenseTracker,
 }
 
 impl<T: Clone + 'static> Resource<T> {
@@ -61,15 +69,27 @@ impl<T: Clone + 'static> Resource<T> {
     }
 
     pub fn set_ready(&self, data: T) {
-        self.set_state.set(ResourceState::Ready(data));
+        self.transition(ResourceState::Ready(data));
     }
 
     pub fn set_error(&self, error: impl Into<String>) {
-        self.set_state.set(ResourceState::Error(error.into()));
+        self.transition(ResourceState::Error(error.into()));
     }
 
     pub fn set_loading(&self) {
-        self.set_state.set(ResourceState::Loading);
+        self.transition(ResourceState::Loading);
+    }
+
+    fn transition(&self, state: ResourceState<T>) {
+        self.suspense.set_loading(state.is_loading());
+        self.set_state.set(state);
+    }
+
+    /// Runs the fetcher again with the current source. Does nothing for manual resources.
+    pub fn refetch(&self) {
+        if let Some(refetch) = &self.refetch {
+            refetch.update(|n| *n += 1);
+        }
     }
 }
 
@@ -78,18 +98,81 @@ impl<T: Clone + 'static> Clone for Resource<T> {
         Self {
             state: self.state.clone(),
             set_state: self.set_state.clone(),
+            refetch: self.refetch.clone(),
+            suspense: self.suspense.clone(),
         }
     }
 }
 
-pub fn create_resource<T: Clone + 'static>() -> Resource<T> {
+/// Creates a resource that fetches `fetcher(source())` and fetches again whenever the
+/// signals read by `source` change. The future runs on the task spawner; during server
+/// rendering it is queued and awaited before the HTML is produced. Responses to stale
+/// sources are ignored.
+pub fn create_resource<S, T, E, Fu>(
+    source: impl Fn() -> S + 'static,
+    fetcher: impl Fn(S) -> Fu + 'static,
+) -> Resource<T>
+where
+    S: 'static,
+    T: Clone + 'static,
+    E: std::fmt::Display,
+    Fu: Future<Output = Result<T, E>> + 'static,
+{
+    let resource = create_manual_resource();
+    let (refetches, set_refetches) = create_signal(0u64);
+    let latest = Rc::new(Cell::new(0u64));
+
+    let handle = resource.clone();
+    create_derived_effect(move || {
+        let _ = refetches.get();
+        let source = source();
+        let request = latest.get() + 1;
+        latest.set(request);
+
+        let future = untrack(|| {
+            handle.set_loading();
+            fetcher(source)
+        });
+        let latest = latest.clone();
+        let handle = handle.clone();
+        spawn_local(async move {
+            let state = match future.await {
+                Ok(data) => ResourceState::Ready(data),
+                Err(e) => ResourceState::Error(e.to_string()),
+            };
+            if latest.get() == request {
+                handle.transition(state);
+            }
+        });
+    });
+
+    Resource {
+        refetch: Some(set_refetches),
+        ..resource
+    }
+}
+
+/// Creates a resource in the loading state whose state is set by hand.
+pub fn create_manual_resource<T: Clone + 'static>() -> Resource<T> {
     let (state, set_state) = create_signal(ResourceState::Loading);
-    Resource { state, set_state }
+    let suspense = SuspenseTracker::new();
+    suspense.set_loading(true);
+    Resource {
+        state,
+        set_state,
+        refetch: None,
+        suspense,
+    }
 }
 
 pub fn create_resource_with<T: Clone + 'static>(initial: T) -> Resource<T> {
     let (state, set_state) = create_signal(ResourceState::Ready(initial));
-    Resource { state, set_state }
+    Resource {
+        state,
+        set_state,
+        refetch: None,
+        suspense: SuspenseTracker::new(),
+    }
 }
 
 #[cfg(test)]
@@ -98,14 +181,14 @@ mod tests {
 
     #[test]
     fn test_resource_initial_loading() {
-        let resource = create_resource::<String>();
+        let resource = create_manual_resource::<String>();
         assert!(resource.loading());
         assert!(resource.data().is_none());
     }
 
     #[test]
     fn test_resource_set_ready() {
-        let resource = create_resource::<String>();
+        let resource = create_manual_resource::<String>();
         resource.set_ready("hello".to_string());
         assert!(!resource.loading());
         assert_eq!(resource.data(), Some("hello".to_string()));
@@ -113,7 +196,7 @@ mod tests {
 
     #[test]
     fn test_resource_set_error() {
-        let resource = create_resource::<String>();
+        let resource = create_manual_resource::<String>();
         resource.set_error("network error");
         assert!(resource.read().is_error());
         assert_eq!(resource.read().error(), Some("network error"));
@@ -126,9 +209,40 @@ mod tests {
         assert_eq!(resource.data(), Some(42));
     }
 
+    #[test]
+    fn test_resource_fetches_and_refetches() {
+        use crate::task::block_on_pending_tasks;
+
+        let (id, set_id) = create_signal(1u32);
+        let resource = create_resource(
+            move || id.get(),
+            |id| async move {
+                if id == 0 {
+                    Err("not found")
+                } else {
+                    Ok(format!("user {}", id))
+                }
+            },
+        );
+        assert!(resource.loading());
+        block_on_pending_tasks();
+        assert_eq!(resource.data(), Some("user 1".to_string()));
+
+        set_id.set(2);
+        set_id.set(0);
+        assert!(resource.loading());
+        block_on_pending_tasks();
+        assert_eq!(resource.read().error(), Some("not found"));
+
+        resource.refetch();
+        assert!(resource.loading());
+        block_on_pending_tasks();
+        assert!(resource.read().is_error());
+    }
+
     #[test]
     fn test_resource_state_transitions() {
-        let resource = create_resource::<Vec<String>>();
+        let resource = create_manual_resource::<Vec<String>>();
         assert!(resource.loading());
 
         resource.set_ready(vec!["a".to_string()]);
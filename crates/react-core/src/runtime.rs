@@ -1,20 +1,43 @@
+use std::any::{Any, TypeId};
 use std::cell::RefCell;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::rc::Rc;
 
+use crate::task::{swap_queued_tasks, LocalTask};
+
 thread_local! {
     pub static RUNTIME: RefCell<Runtime> = RefCell::new(Runtime::new());
 }
 
 pub type EffectId = usize;
 pub type ScopeId = usize;
+pub type SignalId = usize;
 type EffectFn = Rc<dyn Fn()>;
-type CleanupFn = Box<dyn FnOnce()>;
+pub type CleanupFn = Box<dyn FnOnce()>;
+pub type SchedulerFn = Rc<dyn Fn()>;
+pub(crate) type WriteWarningFn = Rc<dyn Fn(&crate::debug::WriteWarning)>;
+
+pub enum FlushRequest {
+    Now,
+    Deferred(SchedulerFn),
+    AlreadyScheduled,
+}
+
+/// Debugging metadata kept for each effect.
+#[derive(Default)]
+pub(crate) struct EffectDebug {
+    pub(crate) name: Option<String>,
+    /// Set for the effects behind memos, selectors and the like, which write signals by
+    /// design and are exempt from write warnings.
+    pub(crate) derived: bool,
+    pub(crate) sources: Vec<SignalId>,
+}
 
 struct Scope {
     effects: Vec<EffectId>,
     children: Vec<ScopeId>,
-    #[allow(dead_code)]
+    cleanups: Vec<CleanupFn>,
+    contexts: HashMap<TypeId, Rc<dyn Any>>,
     parent: Option<ScopeId>,
     disposed: bool,
 }
@@ -23,11 +46,21 @@ pub struct Runtime {
     effects: Vec<Option<EffectFn>>,
     effect_cleanups: Vec<Vec<CleanupFn>>,
     effect_disposed: Vec<bool>,
+    effect_owners: Vec<Option<ScopeId>>,
+    effect_run_scopes: Vec<Option<ScopeId>>,
     current_effect: Option<EffectId>,
     pending_effects: VecDeque<EffectId>,
     is_batching: bool,
     scopes: Vec<Scope>,
     current_scope: Option<ScopeId>,
+    scheduler: Option<SchedulerFn>,
+    flush_scheduled: bool,
+    pending_mounts: Vec<(Option<ScopeId>, CleanupFn)>,
+    effect_debug: Vec<EffectDebug>,
+    next_signal_id: SignalId,
+    signal_names: HashMap<SignalId, String>,
+    write_warning: Option<WriteWarningFn>,
+    atoms: HashMap<&'static str, Rc<dyn Any>>,
 }
 
 impl Runtime {
@@ -35,6 +68,8 @@ impl Runtime {
         let root_scope = Scope {
             effects: Vec::new(),
             children: Vec::new(),
+            cleanups: Vec::new(),
+            contexts: HashMap::new(),
             parent: None,
             disposed: false,
         };
@@ -42,11 +77,21 @@ impl Runtime {
             effects: Vec::new(),
             effect_cleanups: Vec::new(),
             effect_disposed: Vec::new(),
+            effect_owners: Vec::new(),
+            effect_run_scopes: Vec::new(),
             current_effect: None,
             pending_effects: VecDeque::new(),
             is_batching: false,
             scopes: vec![root_scope],
             current_scope: Some(0),
+            scheduler: None,
+            flush_scheduled: false,
+            pending_mounts: Vec::new(),
+            effect_debug: Vec::new(),
+            next_signal_id: 0,
+            signal_names: HashMap::new(),
+            write_warning: None,
+            atoms: HashMap::new(),
         }
     }
 
@@ -59,6 +104,9 @@ impl Runtime {
         self.effects.push(Some(Rc::new(f)));
         self.effect_cleanups.push(Vec::new());
         self.effect_disposed.push(false);
+        self.effect_owners.push(self.current_scope);
+        self.effect_run_scopes.push(None);
+        self.effect_debug.push(EffectDebug::default());
 
         if let Some(scope_id) = self.current_scope {
             if scope_id < self.scopes.len() {
@@ -108,6 +156,10 @@ impl Runtime {
         }
     }
 
+    pub fn has_pending_effects(&self) -> bool {
+        !self.pending_effects.is_empty()
+    }
+
     pub fn start_batch(&mut self) -> bool {
         let was_batching = self.is_batching;
         self.is_batching = true;
@@ -118,16 +170,46 @@ impl Runtime {
         self.is_batching = was_batching;
     }
 
+    pub fn set_scheduler(&mut self, scheduler: Option<SchedulerFn>) {
+        self.scheduler = scheduler;
+    }
+
+    /// Decides how pending effects should be flushed after a signal write. With a scheduler
+    /// installed, only the first write before a flush requests one; later writes are coalesced.
+    pub fn request_flush(&mut self) -> FlushRequest {
+        match &self.scheduler {
+            None => FlushRequest::Now,
+            Some(_) if self.flush_scheduled => FlushRequest::AlreadyScheduled,
+            Some(scheduler) => {
+                self.flush_scheduled = true;
+                FlushRequest::Deferred(scheduler.clone())
+            }
+        }
+    }
+
+    pub fn clear_flush_scheduled(&mut self) {
+        self.flush_scheduled = false;
+    }
+
     pub fn is_effect_disposed(&self, id: EffectId) -> bool {
         self.effect_disposed.get(id).copied().unwrap_or(true)
     }
 
+    /// Creates a scope owned by the current scope and makes it current.
     pub fn create_scope(&mut self) -> ScopeId {
+        let id = self.create_child_scope(self.current_scope);
+        self.current_scope = Some(id);
+        id
+    }
+
+    /// Creates a scope owned by `parent` without changing the current scope.
+    pub fn create_child_scope(&mut self, parent: Option<ScopeId>) -> ScopeId {
         let id = self.scopes.len();
-        let parent = self.current_scope;
         self.scopes.push(Scope {
             effects: Vec::new(),
             children: Vec::new(),
+            cleanups: Vec::new(),
+            contexts: HashMap::new(),
             parent,
             disposed: false,
         });
@@ -136,61 +218,217 @@ impl Runtime {
                 self.scopes[parent_id].children.push(id);
             }
         }
-        self.current_scope = Some(id);
         id
     }
 
+    /// Starts a new run of `effect_id`: everything the previous run owned is disposed, and the
+    /// effect's run scope is emptied to own what this run creates. Returns the cleanups to run
+    /// and the run scope.
+    pub fn begin_effect_run(&mut self, effect_id: EffectId) -> (Vec<CleanupFn>, ScopeId) {
+        let mut cleanups = self.take_cleanups(effect_id);
+        let previous = self.effect_run_scopes.get(effect_id).copied().flatten();
+        if let Some(scope) = previous.filter(|&scope| !self.is_scope_disposed(scope)) {
+            // Reused rather than replaced, so re-running an effect does not grow `scopes`.
+            self.release_contents(scope, &mut cleanups);
+            self.drop_pending_mounts(scope, &mut cleanups);
+            return (cleanups, scope);
+        }
+        let owner = self.effect_owners.get(effect_id).copied().flatten();
+        let scope = self.create_child_scope(owner);
+        if let Some(slot) = self.effect_run_scopes.get_mut(effect_id) {
+            *slot = Some(scope);
+        }
+        (cleanups, scope)
+    }
+
+    pub fn current_scope(&self) -> Option<ScopeId> {
+        self.current_scope
+    }
+
+    pub fn is_scope_disposed(&self, scope_id: ScopeId) -> bool {
+        self.scopes
+            .get(scope_id)
+            .map(|s| s.disposed)
+            .unwrap_or(true)
+    }
+
+    pub fn provide_context(&mut self, type_id: TypeId, value: Rc<dyn Any>) {
+        if let Some(scope) = self.current_scope.and_then(|id| self.scopes.get_mut(id)) {
+            scope.contexts.insert(type_id, value);
+        }
+    }
+
+    pub fn remove_context(&mut self, type_id: TypeId) {
+        if let Some(scope) = self.current_scope.and_then(|id| self.scopes.get_mut(id)) {
+            scope.contexts.remove(&type_id);
+        }
+    }
+
+    /// Finds the context value provided by the nearest enclosing scope.
+    pub fn lookup_context(&self, type_id: TypeId) -> Option<Rc<dyn Any>> {
+        let mut scope_id = self.current_scope;
+        while let Some(scope) = scope_id.and_then(|id| self.scopes.get(id)) {
+            if let Some(value) = scope.contexts.get(&type_id) {
+                return Some(value.clone());
+            }
+            scope_id = scope.parent;
+        }
+        None
+    }
+
     pub fn set_current_scope(&mut self, scope: Option<ScopeId>) -> Option<ScopeId> {
         let prev = self.current_scope;
         self.current_scope = scope;
         prev
     }
 
-    pub fn dispose_scope(&mut self, scope_id: ScopeId) {
+    /// Marks `scope_id`, its child scopes and their effects as disposed. The returned cleanups
+    /// must be run by the caller once the runtime is no longer borrowed, since they are free
+    /// to read and write signals.
+    pub fn dispose_scope(&mut self, scope_id: ScopeId) -> Vec<CleanupFn> {
+        let mut cleanups = Vec::new();
+        self.collect_disposed(scope_id, &mut cleanups);
+        if let Some(parent) = self.scopes.get(scope_id).and_then(|s| s.parent) {
+            self.scopes[parent]
+                .children
+                .retain(|&child| child != scope_id);
+        }
+        cleanups
+    }
+
+    fn collect_disposed(&mut self, scope_id: ScopeId, cleanups: &mut Vec<CleanupFn>) {
         if scope_id >= self.scopes.len() || self.scopes[scope_id].disposed {
             return;
         }
+        self.release_contents(scope_id, cleanups);
+        self.scopes[scope_id].disposed = true;
+    }
 
-        let children: Vec<ScopeId> = self.scopes[scope_id].children.clone();
+    /// Disposes what `scope_id` owns, leaving the scope itself empty and alive.
+    fn release_contents(&mut self, scope_id: ScopeId, cleanups: &mut Vec<CleanupFn>) {
+        let children = std::mem::take(&mut self.scopes[scope_id].children);
         for child_id in children {
-            self.dispose_scope(child_id);
+            self.collect_disposed(child_id, cleanups);
         }
 
-        let effects: Vec<EffectId> = self.scopes[scope_id].effects.clone();
+        let effects = std::mem::take(&mut self.scopes[scope_id].effects);
         for effect_id in effects {
-            self.dispose_effect(effect_id);
+            cleanups.extend(self.dispose_effect(effect_id));
+        }
+        cleanups.append(&mut self.scopes[scope_id].cleanups);
+        let contexts = std::mem::take(&mut self.scopes[scope_id].contexts);
+        if !contexts.is_empty() {
+            cleanups.push(Box::new(move || drop(contexts)));
         }
+    }
 
-        self.scopes[scope_id].disposed = true;
+    /// Drops the [`on_mount`](crate::effect::on_mount) callbacks queued by the previous run of
+    /// a reused scope, which a disposed scope would have skipped.
+    fn drop_pending_mounts(&mut self, scope_id: ScopeId, cleanups: &mut Vec<CleanupFn>) {
+        let (stale, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut self.pending_mounts)
+            .into_iter()
+            .partition(|(scope, _)| *scope == Some(scope_id));
+        self.pending_mounts = pending;
+        if !stale.is_empty() {
+            cleanups.push(Box::new(move || drop(stale)));
+        }
     }
 
-    fn dispose_effect(&mut self, effect_id: EffectId) {
+    fn dispose_effect(&mut self, effect_id: EffectId) -> Vec<CleanupFn> {
         if effect_id >= self.effect_disposed.len() {
-            return;
+            return Vec::new();
+        }
+        let mut cleanups = self.take_cleanups(effect_id);
+        if let Some(run_scope) = self.effect_run_scopes[effect_id].take() {
+            self.collect_disposed(run_scope, &mut cleanups);
         }
-        self.run_cleanups(effect_id);
         self.effect_disposed[effect_id] = true;
         if let Some(slot) = self.effects.get_mut(effect_id) {
             *slot = None;
         }
+        cleanups
     }
 
+    /// Ties `f` to the current effect, or to the current scope when no effect is running.
     pub fn add_cleanup(&mut self, f: impl FnOnce() + 'static) {
         if let Some(effect_id) = self.current_effect {
             if effect_id < self.effect_cleanups.len() {
                 self.effect_cleanups[effect_id].push(Box::new(f));
             }
+        } else if let Some(scope_id) = self.current_scope {
+            if let Some(scope) = self.scopes.get_mut(scope_id) {
+                scope.cleanups.push(Box::new(f));
+            }
         }
     }
 
-    pub fn run_cleanups(&mut self, effect_id: EffectId) {
-        if effect_id < self.effect_cleanups.len() {
-            let cleanups = std::mem::take(&mut self.effect_cleanups[effect_id]);
-            for cleanup in cleanups {
-                cleanup();
-            }
+    pub fn queue_mount(&mut self, f: impl FnOnce() + 'static) {
+        self.pending_mounts.push((self.current_scope, Box::new(f)));
+    }
+
+    pub fn take_mounts(&mut self) -> Vec<(Option<ScopeId>, CleanupFn)> {
+        std::mem::take(&mut self.pending_mounts)
+    }
+
+    pub fn atom(&self, key: &str) -> Option<Rc<dyn Any>> {
+        self.atoms.get(key).cloned()
+    }
+
+    pub fn insert_atom(&mut self, key: &'static str, value: Rc<dyn Any>) {
+        self.atoms.insert(key, value);
+    }
+
+    pub fn clear_atoms(&mut self) {
+        self.atoms.clear();
+    }
+
+    pub fn next_signal_id(&mut self) -> SignalId {
+        self.next_signal_id += 1;
+        self.next_signal_id
+    }
+
+    pub(crate) fn effect_debug_mut(&mut self, effect_id: EffectId) -> Option<&mut EffectDebug> {
+        self.effect_debug.get_mut(effect_id)
+    }
+
+    pub(crate) fn effect_debug(&self) -> &[EffectDebug] {
+        &self.effect_debug
+    }
+
+    /// Records that `effect_id` subscribed to `signal_id`, for [`crate::debug::dependency_graph`].
+    pub fn record_dependency(&mut self, signal_id: SignalId, effect_id: EffectId) {
+        if let Some(debug) = self.effect_debug.get_mut(effect_id) {
+            debug.sources.push(signal_id);
         }
     }
+
+    pub fn set_signal_name(&mut self, signal_id: SignalId, name: String) {
+        self.signal_names.insert(signal_id, name);
+    }
+
+    pub fn signal_name(&self, signal_id: SignalId) -> Option<&str> {
+        self.signal_names.get(&signal_id).map(String::as_str)
+    }
+
+    pub fn signal_names(&self) -> impl Iterator<Item = (&SignalId, &String)> {
+        self.signal_names.iter()
+    }
+
+    pub(crate) fn set_write_warning(&mut self, handler: Option<WriteWarningFn>) {
+        self.write_warning = handler;
+    }
+
+    pub(crate) fn write_warning(&self) -> Option<WriteWarningFn> {
+        self.write_warning.clone()
+    }
+
+    /// Removes the cleanups registered by the last run of `effect_id`, in registration order.
+    pub fn take_cleanups(&mut self, effect_id: EffectId) -> Vec<CleanupFn> {
+        self.effect_cleanups
+            .get_mut(effect_id)
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
 }
 
 impl Default for Runtime {
@@ -198,3 +436,127 @@ impl Default for Runtime {
         Self::new()
     }
 }
+
+/// A reactive graph of its own, e.g. one per server request, so renders that share a thread
+/// never see each other's effects, scopes, context or queued tasks. Signals are bound to the
+/// instance they were created in and must not be used from another one.
+pub struct RuntimeInstance {
+    runtime: Runtime,
+    tasks: Vec<LocalTask>,
+}
+
+pub fn create_runtime() -> RuntimeInstance {
+    RuntimeInstance {
+        runtime: Runtime::new(),
+        tasks: Vec::new(),
+    }
+}
+
+impl RuntimeInstance {
+    /// Makes this instance the current runtime while `f` runs, restoring the previous one
+    /// afterwards (also on panic).
+    pub fn run<R>(&mut self, f: impl FnOnce() -> R) -> R {
+        struct SwapGuard<'a>(&'a mut RuntimeInstance);
+
+        impl Drop for SwapGuard<'_> {
+            fn drop(&mut self) {
+                self.0.swap();
+            }
+        }
+
+        self.swap();
+        let _guard = SwapGuard(self);
+        f()
+    }
+
+    fn swap(&mut self) {
+        RUNTIME.with(|rt| std::mem::swap(&mut *rt.borrow_mut(), &mut self.runtime));
+        swap_queued_tasks(&mut self.tasks);
+    }
+}
+
+impl Drop for RuntimeInstance {
+    /// Disposes everything the instance still owns, running cleanups inside it.
+    fn drop(&mut self) {
+        self.run(|| {
+            let cleanups = RUNTIME.with(|rt| rt.borrow_mut().dispose_scope(0));
+            for cleanup in cleanups {
+                cleanup();
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::effect::{create_effect, on_cleanup};
+    use crate::signal::create_signal;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_runtime_instances_are_isolated() {
+        let outer_effects = RUNTIME.with(|rt| rt.borrow().effects.len());
+        let cleaned = Rc::new(Cell::new(false));
+        let runs = Rc::new(Cell::new(0));
+
+        let mut instance = create_runtime();
+        let set_count = instance.run(|| {
+            let (count, set_count) = create_signal(0);
+            let cleaned = cleaned.clone();
+            let runs = runs.clone();
+            create_effect(move || {
+                count.get();
+                runs.set(runs.get() + 1);
+            });
+            on_cleanup(move || cleaned.set(true));
+            set_count
+        });
+        assert_eq!(RUNTIME.with(|rt| rt.borrow().effects.len()), outer_effects);
+
+        instance.run(|| set_count.set(1));
+        assert_eq!(runs.get(), 2);
+
+        drop(instance);
+        assert!(cleaned.get());
+    }
+
+    #[test]
+    fn test_effect_reruns_reuse_their_scope() {
+        let mut instance = create_runtime();
+        instance.run(|| {
+            let (count, set_count) = create_signal(0);
+            create_effect(move || {
+                count.get();
+                on_cleanup(|| {});
+            });
+            let scopes = || RUNTIME.with(|rt| rt.borrow().scopes.len());
+            let before = scopes();
+            for i in 1..1000 {
+                set_count.set(i);
+            }
+            assert_eq!(scopes(), before);
+        });
+    }
+
+    #[test]
+    fn test_rerun_forgets_disposed_child_scopes() {
+        let mut instance = create_runtime();
+        instance.run(|| {
+            let (count, set_count) = create_signal(0);
+            create_effect(move || {
+                count.get();
+                create_effect(|| {});
+            });
+            for i in 1..100 {
+                set_count.set(i);
+            }
+            RUNTIME.with(|rt| {
+                let rt = rt.borrow();
+                let run_scope = rt.effect_run_scopes[0].unwrap();
+                assert_eq!(rt.scopes[run_scope].children.len(), 1);
+                assert_eq!(rt.scopes[0].children, vec![run_scope]);
+            });
+        });
+    }
+}
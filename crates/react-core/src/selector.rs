@@ -0,0 +1,119 @@
+use crate::effect::{batch, create_derived_effect};
+use crate::signal::{create_signal, ReadSignal, WriteSignal};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::rc::Rc;
+
+type Row = (ReadSignal<bool>, WriteSignal<bool>);
+
+struct SelectorInner<K> {
+    current: RefCell<Option<K>>,
+    rows: RefCell<HashMap<K, Row>>,
+}
+
+/// Per-key selection state derived from a single source, created with [`create_selector`].
+pub struct Selector<K> {
+    inner: Rc<SelectorInner<K>>,
+}
+
+impl<K> Clone for Selector<K> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+/// Derives per-key "is selected" signals from `source`. When the selection moves, only the
+/// signals of the previously and newly selected keys change, so each row of a list can
+/// subscribe to its own state instead of the whole selection.
+pub fn create_selector<K, F>(source: F) -> Selector<K>
+where
+    K: Eq + Hash + Clone + 'static,
+    F: Fn() -> K + 'static,
+{
+    let inner = Rc::new(SelectorInner {
+        current: RefCell::new(None),
+        rows: RefCell::new(HashMap::new()),
+    });
+
+    let effect_inner = inner.clone();
+    create_derived_effect(move || {
+        let next = source();
+        let previous = effect_inner.current.replace(Some(next.clone()));
+        if previous.as_ref() == Some(&next) {
+            return;
+        }
+        let (old_row, new_row) = {
+            let rows = effect_inner.rows.borrow();
+            let lookup = |key: &K| rows.get(key).map(|(_, write)| write.clone());
+            (previous.as_ref().and_then(lookup), lookup(&next))
+        };
+        batch(|| {
+            if let Some(write) = old_row {
+                write.set(false);
+            }
+            if let Some(write) = new_row {
+                write.set(true);
+            }
+        });
+    });
+
+    Selector { inner }
+}
+
+impl<K: Eq + Hash + Clone + 'static> Selector<K> {
+    /// A signal that is `true` while `key` is the selected key.
+    pub fn is_selected(&self, key: &K) -> ReadSignal<bool> {
+        let selected = self.inner.current.borrow().as_ref() == Some(key);
+        self.inner
+            .rows
+            .borrow_mut()
+            .entry(key.clone())
+            .or_insert_with(|| create_signal(selected))
+            .0
+            .clone()
+    }
+
+    /// Whether `key` is selected, subscribing the current effect to that key only.
+    pub fn selected(&self, key: &K) -> bool {
+        self.is_selected(key).get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::effect::create_effect;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_selector_notifies_changed_rows_only() {
+        let (selected, set_selected) = create_signal(1u32);
+        let selector = create_selector(move || selected.get());
+
+        let runs: Vec<Rc<Cell<u32>>> = (1..=3)
+            .map(|key| {
+                let runs = Rc::new(Cell::new(0));
+                let row = selector.is_selected(&key);
+                let runs_clone = runs.clone();
+                create_effect(move || {
+                    let _ = row.get();
+                    runs_clone.set(runs_clone.get() + 1);
+                });
+                runs
+            })
+            .collect();
+
+        assert!(selector.selected(&1));
+        set_selected.set(3);
+        assert!(!selector.selected(&1));
+        assert!(selector.selected(&3));
+        let counts: Vec<u32> = runs.iter().map(|r| r.get()).collect();
+        assert_eq!(counts, vec![2, 1, 2]);
+
+        set_selected.set(3);
+        assert_eq!(runs[2].get(), 2);
+    }
+}
@@ -1,12 +1,14 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use crate::effect::flush_effects;
-use crate::runtime::RUNTIME;
+use crate::debug::write_in_effect_warning;
+use crate::effect::{batch, request_flush};
+use crate::runtime::{SignalId, RUNTIME};
 
 type SubscriberId = usize;
 
 struct SignalInner<T> {
+    id: SignalId,
     value: T,
     subscribers: Vec<SubscriberId>,
     version: u64,
@@ -38,7 +40,9 @@ impl<T> Clone for WriteSignal<T> {
 
 /// Creates a reactive signal with the given initial value. Returns a (read, write) pair.
 pub fn create_signal<T>(value: T) -> (ReadSignal<T>, WriteSignal<T>) {
+    let id = RUNTIME.with(|rt| rt.borrow_mut().next_signal_id());
     let inner = Rc::new(RefCell::new(SignalInner {
+        id,
         value,
         subscribers: Vec::new(),
         version: 0,
@@ -52,6 +56,12 @@ pub fn create_signal<T>(value: T) -> (ReadSignal<T>, WriteSignal<T>) {
     )
 }
 
+impl<T> ReadSignal<T> {
+    pub(crate) fn id(&self) -> SignalId {
+        self.inner.borrow().id
+    }
+}
+
 impl<T: Clone> ReadSignal<T> {
     /// Reads the current value. Subscribes the current effect to this signal.
     pub fn get(&self) -> T {
@@ -68,6 +78,10 @@ impl<T: Clone> ReadSignal<T> {
         self.inner.borrow().value.clone()
     }
 
+    pub fn with_untracked<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.inner.borrow().value)
+    }
+
     fn track(&self) {
         RUNTIME.with(|rt| {
             let rt_ref = rt.borrow();
@@ -77,6 +91,7 @@ impl<T: Clone> ReadSignal<T> {
                     let mut inner = self.inner.borrow_mut();
                     if !inner.subscribers.contains(&effect_id) {
                         inner.subscribers.push(effect_id);
+                        rt.borrow_mut().record_dependency(inner.id, effect_id);
                     }
                 }
             }
@@ -95,13 +110,17 @@ impl<T> WriteSignal<T> {
         self.notify_subscribers();
     }
 
+    /// Mutates the value in place and notifies subscribers once. Writes to other signals made
+    /// inside `f` are batched with this one.
     pub fn update(&self, f: impl FnOnce(&mut T)) {
-        {
-            let mut inner = self.inner.borrow_mut();
-            f(&mut inner.value);
-            inner.version += 1;
-        }
-        self.notify_subscribers();
+        batch(|| {
+            {
+                let mut inner = self.inner.borrow_mut();
+                f(&mut inner.value);
+                inner.version += 1;
+            }
+            self.notify_subscribers();
+        });
     }
 
     pub fn set_if_changed(&self, value: T)
@@ -118,6 +137,14 @@ impl<T> WriteSignal<T> {
     }
 
     fn notify_subscribers(&self) {
+        let warning = {
+            let inner = self.inner.borrow();
+            write_in_effect_warning(inner.id, &inner.subscribers)
+        };
+        if let Some((handler, warning)) = warning {
+            handler(&warning);
+        }
+
         let inner = self.inner.borrow();
         let should_flush = RUNTIME.with(|rt| {
             let mut rt = rt.borrow_mut();
@@ -131,7 +158,93 @@ impl<T> WriteSignal<T> {
         drop(inner);
 
         if should_flush {
-            flush_effects();
+            request_flush();
+        }
+    }
+}
+
+/// A signal with both read and write access in one handle, for components that need both.
+pub struct RwSignal<T> {
+    inner: Rc<RefCell<SignalInner<T>>>,
+}
+
+impl<T> Clone for RwSignal<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+pub fn create_rw_signal<T>(value: T) -> RwSignal<T> {
+    let (read, _) = create_signal(value);
+    RwSignal { inner: read.inner }
+}
+
+impl<T> RwSignal<T> {
+    pub fn read_only(&self) -> ReadSignal<T> {
+        ReadSignal {
+            inner: self.inner.clone(),
+        }
+    }
+
+    pub fn write_only(&self) -> WriteSignal<T> {
+        WriteSignal {
+            inner: self.inner.clone(),
+        }
+    }
+
+    /// Splits into the `(read, write)` pair returned by [`create_signal`].
+    pub fn split(&self) -> (ReadSignal<T>, WriteSignal<T>) {
+        (self.read_only(), self.write_only())
+    }
+
+    pub fn set(&self, value: T) {
+        self.write_only().set(value);
+    }
+
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        self.write_only().update(f);
+    }
+
+    pub fn set_if_changed(&self, value: T)
+    where
+        T: PartialEq,
+    {
+        self.write_only().set_if_changed(value);
+    }
+}
+
+impl<T: Clone> RwSignal<T> {
+    pub fn get(&self) -> T {
+        self.read_only().get()
+    }
+
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.read_only().with(f)
+    }
+
+    pub fn get_untracked(&self) -> T {
+        self.inner.borrow().value.clone()
+    }
+
+    pub fn with_untracked<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.inner.borrow().value)
+    }
+}
+
+impl<T> From<RwSignal<T>> for ReadSignal<T> {
+    fn from(signal: RwSignal<T>) -> Self {
+        Self {
+            inner: signal.inner,
+        }
+    }
+}
+
+impl<T> From<RwSignal<T>> for WriteSignal<T> {
+    fn from(signal: RwSignal<T>) -> Self {
+        Self {
+            inner: signal.inner,
         }
     }
 }
@@ -155,6 +268,40 @@ mod tests {
         assert_eq!(read.get_untracked(), vec![1, 2, 3]);
     }
 
+    #[test]
+    fn test_update_batches_nested_writes() {
+        use crate::effect::create_effect;
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let (items, set_items) = create_signal(Vec::<u32>::new());
+        let (count, set_count) = create_signal(0);
+        let runs = Rc::new(Cell::new(0));
+        let runs_clone = runs.clone();
+        create_effect(move || {
+            let _ = (items.get(), count.get());
+            runs_clone.set(runs_clone.get() + 1);
+        });
+
+        set_items.update(|v| {
+            v.push(1);
+            set_count.set(1);
+        });
+        assert_eq!(runs.get(), 2);
+    }
+
+    #[test]
+    fn test_rw_signal_shares_state_with_halves() {
+        let count = create_rw_signal(1);
+        let (read, write) = count.split();
+
+        count.update(|n| *n += 1);
+        assert_eq!(read.get_untracked(), 2);
+        write.set(5);
+        assert_eq!(count.get_untracked(), 5);
+        assert_eq!(ReadSignal::from(count.clone()).get_untracked(), 5);
+    }
+
     #[test]
     fn test_signal_with() {
         let (read, _write) = create_signal(String::from("hello"));
@@ -0,0 +1,259 @@
+use crate::signal::{create_signal, ReadSignal, WriteSignal};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::{Rc, Weak};
+
+/// One granular change to a [`SignalVec`]. Indices refer to the list as it was when the
+/// change was applied, so diffs must be replayed in order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VecDiff<T> {
+    Insert {
+        index: usize,
+        value: T,
+    },
+    Update {
+        index: usize,
+        value: T,
+    },
+    Remove {
+        index: usize,
+    },
+    /// The item at `from` was removed and reinserted at `to`.
+    Move {
+        from: usize,
+        to: usize,
+    },
+    Replace(Vec<T>),
+}
+
+type Queue<T> = RefCell<VecDeque<VecDiff<T>>>;
+type DiffQueue<T> = Rc<Queue<T>>;
+
+struct SignalVecInner<T> {
+    items: RefCell<Vec<T>>,
+    key: Rc<dyn Fn(&T) -> String>,
+    readers: RefCell<Vec<Weak<Queue<T>>>>,
+    version: ReadSignal<u64>,
+    set_version: WriteSignal<u64>,
+}
+
+/// A keyed list that records insertions, removals and moves instead of replacing the whole
+/// `Vec`, so renderers can patch only the affected rows.
+pub struct SignalVec<T> {
+    inner: Rc<SignalVecInner<T>>,
+}
+
+impl<T> Clone for SignalVec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+/// Creates a [`SignalVec`] whose items are identified by `key`.
+pub fn create_keyed_signal<T, K>(items: Vec<T>, key: impl Fn(&T) -> K + 'static) -> SignalVec<T>
+where
+    T: Clone + 'static,
+    K: ToString,
+{
+    let (version, set_version) = create_signal(0);
+    SignalVec {
+        inner: Rc::new(SignalVecInner {
+            items: RefCell::new(items),
+            key: Rc::new(move |item| key(item).to_string()),
+            readers: RefCell::new(Vec::new()),
+            version,
+            set_version,
+        }),
+    }
+}
+
+/// Receives the diffs of a [`SignalVec`] made after the reader was created.
+pub struct VecDiffReader<T> {
+    queue: DiffQueue<T>,
+    version: ReadSignal<u64>,
+}
+
+impl<T> VecDiffReader<T> {
+    /// Drains the pending diffs, subscribing the current effect to future ones.
+    pub fn take(&self) -> Vec<VecDiff<T>> {
+        let _ = self.version.get();
+        self.queue.borrow_mut().drain(..).collect()
+    }
+}
+
+impl<T: Clone + 'static> SignalVec<T> {
+    pub fn key_of(&self, item: &T) -> String {
+        (self.inner.key)(item)
+    }
+
+    pub fn with<R>(&self, f: impl FnOnce(&[T]) -> R) -> R {
+        let _ = self.inner.version.get();
+        f(&self.inner.items.borrow())
+    }
+
+    pub fn with_untracked<R>(&self, f: impl FnOnce(&[T]) -> R) -> R {
+        f(&self.inner.items.borrow())
+    }
+
+    pub fn get(&self) -> Vec<T> {
+        self.with(<[T]>::to_vec)
+    }
+
+    pub fn len(&self) -> usize {
+        self.with(<[T]>::len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn position(&self, key: &str) -> Option<usize> {
+        self.with_untracked(|items| items.iter().position(|item| self.key_of(item) == key))
+    }
+
+    pub fn diffs(&self) -> VecDiffReader<T> {
+        let queue: DiffQueue<T> = Rc::new(RefCell::new(VecDeque::new()));
+        self.inner.readers.borrow_mut().push(Rc::downgrade(&queue));
+        VecDiffReader {
+            queue,
+            version: self.inner.version.clone(),
+        }
+    }
+
+    pub fn push(&self, value: T) {
+        let index = self.inner.items.borrow().len();
+        self.insert(index, value);
+    }
+
+    pub fn insert(&self, index: usize, value: T) {
+        self.inner.items.borrow_mut().insert(index, value.clone());
+        self.emit(VecDiff::Insert { index, value });
+    }
+
+    pub fn remove(&self, index: usize) -> T {
+        let value = self.inner.items.borrow_mut().remove(index);
+        self.emit(VecDiff::Remove { index });
+        value
+    }
+
+    pub fn move_item(&self, from: usize, to: usize) {
+        if from == to {
+            return;
+        }
+        {
+            let mut items = self.inner.items.borrow_mut();
+            let value = items.remove(from);
+            items.insert(to, value);
+        }
+        self.emit(VecDiff::Move { from, to });
+    }
+
+    pub fn set(&self, index: usize, value: T) {
+        self.inner.items.borrow_mut()[index] = value.clone();
+        self.emit(VecDiff::Update { index, value });
+    }
+
+    pub fn update(&self, index: usize, f: impl FnOnce(&mut T)) {
+        let value = {
+            let mut items = self.inner.items.borrow_mut();
+            f(&mut items[index]);
+            items[index].clone()
+        };
+        self.emit(VecDiff::Update { index, value });
+    }
+
+    /// Removes the items for which `keep` returns `false`, emitting one removal per item.
+    pub fn retain(&self, keep: impl Fn(&T) -> bool) {
+        let removed: Vec<usize> = self.with_untracked(|items| {
+            items
+                .iter()
+                .enumerate()
+                .filter(|(_, item)| !keep(item))
+                .map(|(i, _)| i)
+                .collect()
+        });
+        for index in removed.into_iter().rev() {
+            self.remove(index);
+        }
+    }
+
+    pub fn replace(&self, items: Vec<T>) {
+        *self.inner.items.borrow_mut() = items.clone();
+        self.emit(VecDiff::Replace(items));
+    }
+
+    pub fn clear(&self) {
+        self.replace(Vec::new());
+    }
+
+    fn emit(&self, diff: VecDiff<T>) {
+        self.inner
+            .readers
+            .borrow_mut()
+            .retain(|reader| match reader.upgrade() {
+                Some(queue) => {
+                    queue.borrow_mut().push_back(diff.clone());
+                    true
+                }
+                None => false,
+            });
+        self.inner.set_version.update(|v| *v += 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::effect::create_effect;
+
+    #[test]
+    fn test_signal_vec_emits_granular_diffs() {
+        let list = create_keyed_signal(vec![(1, "a"), (2, "b")], |(id, _)| *id);
+        let reader = list.diffs();
+
+        list.push((3, "c"));
+        list.move_item(2, 0);
+        list.update(1, |item| item.1 = "A");
+        list.retain(|(id, _)| *id != 2);
+
+        assert_eq!(
+            reader.take(),
+            vec![
+                VecDiff::Insert {
+                    index: 2,
+                    value: (3, "c")
+                },
+                VecDiff::Move { from: 2, to: 0 },
+                VecDiff::Update {
+                    index: 1,
+                    value: (1, "A")
+                },
+                VecDiff::Remove { index: 2 },
+            ]
+        );
+        assert_eq!(list.get(), vec![(3, "c"), (1, "A")]);
+        assert_eq!(list.position("1"), Some(1));
+        assert!(reader.take().is_empty());
+    }
+
+    #[test]
+    fn test_reader_subscribes_effect() {
+        let list = create_keyed_signal(Vec::<u32>::new(), |n| *n);
+        let reader = list.diffs();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let seen_clone = seen.clone();
+        create_effect(move || seen_clone.borrow_mut().extend(reader.take()));
+
+        list.push(7);
+        list.clear();
+        assert_eq!(
+            *seen.borrow(),
+            vec![
+                VecDiff::Insert { index: 0, value: 7 },
+                VecDiff::Replace(Vec::new())
+            ]
+        );
+    }
+}
@@ -0,0 +1,349 @@
+use crate::effect::batch;
+use crate::signal::{create_signal, ReadSignal, WriteSignal};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum PathKey {
+    Index(usize),
+    /// Identified by the name given to [`StoreField::field`], so every accessor of the same
+    /// field shares its path.
+    Field(&'static str),
+}
+
+type Path = Vec<PathKey>;
+type Lens<T, V> = Rc<dyn Fn(&T) -> Option<&V>>;
+type LensMut<T, V> = Rc<dyn Fn(&mut T) -> Option<&mut V>>;
+
+struct Trigger {
+    read: ReadSignal<()>,
+    write: WriteSignal<()>,
+}
+
+impl Trigger {
+    fn new() -> Self {
+        let (read, write) = create_signal(());
+        Self { read, write }
+    }
+}
+
+/// Per-path triggers. `shallow` fires when the value at a path is replaced (directly or via
+/// an ancestor); `deep` additionally fires when anything below it changes.
+#[derive(Default)]
+struct Triggers {
+    shallow: Option<Trigger>,
+    deep: Option<Trigger>,
+}
+
+struct StoreRoot<T> {
+    value: RefCell<T>,
+    triggers: RefCell<HashMap<Path, Triggers>>,
+}
+
+impl<T> StoreRoot<T> {
+    fn track(&self, path: &Path, deep: bool) {
+        let read = {
+            let mut triggers = self.triggers.borrow_mut();
+            let entry = triggers.entry(path.clone()).or_default();
+            let slot = if deep {
+                &mut entry.deep
+            } else {
+                &mut entry.shallow
+            };
+            slot.get_or_insert_with(Trigger::new).read.clone()
+        };
+        read.with(|_| ());
+    }
+
+    fn notify(&self, path: &Path) {
+        let writes: Vec<WriteSignal<()>> = self
+            .triggers
+            .borrow()
+            .iter()
+            .flat_map(|(other, triggers)| {
+                let at_or_below = other.starts_with(path);
+                let above = !at_or_below && path.starts_with(other);
+                let shallow = triggers.shallow.as_ref().filter(|_| at_or_below);
+                let deep = triggers.deep.as_ref().filter(|_| at_or_below || above);
+                shallow.into_iter().chain(deep).map(|t| t.write.clone())
+            })
+            .collect();
+        batch(|| {
+            for write in writes {
+                write.set(());
+            }
+        });
+    }
+}
+
+/// A reactive view of the part of a store's value at one path. Reads subscribe only to that
+/// path, so writing `store.at(2).field("done", |t| &t.done, |t| &mut t.done)` re-runs only
+/// the effects that read that field, the todo at index 2, or the store as a whole.
+pub struct StoreField<T: 'static, V: 'static> {
+    root: Rc<StoreRoot<T>>,
+    path: Path,
+    lens: Lens<T, V>,
+    lens_mut: LensMut<T, V>,
+}
+
+/// The root of a store created with [`create_store`].
+pub type Store<T> = StoreField<T, T>;
+
+impl<T: 'static, V: 'static> Clone for StoreField<T, V> {
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root.clone(),
+            path: self.path.clone(),
+            lens: self.lens.clone(),
+            lens_mut: self.lens_mut.clone(),
+        }
+    }
+}
+
+/// Creates a store holding `value`. Unlike a signal, nested values can be read and written
+/// through [`StoreField::field`] and [`StoreField::at`] without invalidating their siblings.
+pub fn create_store<T: 'static>(value: T) -> Store<T> {
+    StoreField {
+        root: Rc::new(StoreRoot {
+            value: RefCell::new(value),
+            triggers: RefCell::new(HashMap::new()),
+        }),
+        path: Vec::new(),
+        lens: Rc::new(|t: &T| Some(t)),
+        lens_mut: Rc::new(|t: &mut T| Some(t)),
+    }
+}
+
+impl<T: 'static, V: 'static> StoreField<T, V> {
+    /// Narrows the store to the field `name`, selected by `get` for reads and `get_mut` for
+    /// writes, e.g. `field("completed", |t| &t.completed, |t| &mut t.completed)`. Fields are
+    /// told apart by name, so both accessors must select the same field, and every call
+    /// with that name reaches the same readers.
+    pub fn field<U: 'static>(
+        &self,
+        name: &'static str,
+        get: impl Fn(&V) -> &U + 'static,
+        get_mut: impl Fn(&mut V) -> &mut U + 'static,
+    ) -> StoreField<T, U> {
+        let lens = self.lens.clone();
+        let lens_mut = self.lens_mut.clone();
+        let mut path = self.path.clone();
+        path.push(PathKey::Field(name));
+        StoreField {
+            root: self.root.clone(),
+            path,
+            lens: Rc::new(move |t| lens(t).map(&get)),
+            lens_mut: Rc::new(move |t| lens_mut(t).map(&get_mut)),
+        }
+    }
+
+    /// Reads the value, subscribing to changes at or below this path. Returns `None` if the
+    /// path no longer exists, e.g. an index past the end of a list that shrank.
+    pub fn try_with<R>(&self, f: impl FnOnce(&V) -> R) -> Option<R> {
+        self.root.track(&self.path, true);
+        self.try_with_untracked(f)
+    }
+
+    pub fn try_with_untracked<R>(&self, f: impl FnOnce(&V) -> R) -> Option<R> {
+        let value = self.root.value.borrow();
+        (self.lens)(&value).map(f)
+    }
+
+    /// Reads the value, subscribing to changes at or below this path.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path no longer exists; use [`StoreField::try_with`] for indices that
+    /// may have been removed.
+    pub fn with<R>(&self, f: impl FnOnce(&V) -> R) -> R {
+        self.try_with(f).expect("store path no longer exists")
+    }
+
+    pub fn get(&self) -> V
+    where
+        V: Clone,
+    {
+        self.with(V::clone)
+    }
+
+    pub fn try_get(&self) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.try_with(V::clone)
+    }
+
+    pub fn get_untracked(&self) -> V
+    where
+        V: Clone,
+    {
+        self.try_with_untracked(V::clone)
+            .expect("store path no longer exists")
+    }
+
+    /// Replaces the value at this path. Does nothing if the path no longer exists.
+    pub fn set(&self, value: V) {
+        self.update(move |v| *v = value);
+    }
+
+    /// Mutates the value at this path and notifies readers of this path, of anything below it
+    /// and of its ancestors. Sibling paths are not notified.
+    pub fn update(&self, f: impl FnOnce(&mut V)) {
+        let updated = {
+            let mut value = self.root.value.borrow_mut();
+            (self.lens_mut)(&mut value).map(f).is_some()
+        };
+        if updated {
+            self.root.notify(&self.path);
+        }
+    }
+}
+
+impl<T: 'static, E: 'static> StoreField<T, Vec<E>> {
+    /// Narrows the store to the element at `index`.
+    pub fn at(&self, index: usize) -> StoreField<T, E> {
+        let lens = self.lens.clone();
+        let lens_mut = self.lens_mut.clone();
+        let mut path = self.path.clone();
+        path.push(PathKey::Index(index));
+        StoreField {
+            root: self.root.clone(),
+            path,
+            lens: Rc::new(move |t| lens(t).and_then(|v| v.get(index))),
+            lens_mut: Rc::new(move |t| lens_mut(t).and_then(|v| v.get_mut(index))),
+        }
+    }
+
+    /// The list length. Subscribes only to the list itself being replaced or mutated, not to
+    /// changes inside its elements.
+    pub fn len(&self) -> usize {
+        self.root.track(&self.path, false);
+        self.try_with_untracked(Vec::len).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::effect::create_effect;
+    use std::cell::Cell;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Todo {
+        title: String,
+        completed: bool,
+    }
+
+    fn todos() -> Store<Vec<Todo>> {
+        create_store(vec![
+            Todo {
+                title: "write".to_string(),
+                completed: false,
+            },
+            Todo {
+                title: "ship".to_string(),
+                completed: false,
+            },
+        ])
+    }
+
+    fn count_runs(f: impl Fn() + 'static) -> Rc<Cell<u32>> {
+        let runs = Rc::new(Cell::new(0));
+        let runs_clone = runs.clone();
+        create_effect(move || {
+            f();
+            runs_clone.set(runs_clone.get() + 1);
+        });
+        runs
+    }
+
+    #[test]
+    fn test_field_write_only_notifies_its_path() {
+        let store = todos();
+        let first = store
+            .at(0)
+            .field("completed", |t| &t.completed, |t| &mut t.completed);
+        let second = store
+            .at(1)
+            .field("completed", |t| &t.completed, |t| &mut t.completed);
+
+        let first_runs = count_runs({
+            let first = first.clone();
+            move || {
+                first.get();
+            }
+        });
+        let second_runs = count_runs(move || {
+            second.get();
+        });
+        let len_runs = count_runs({
+            let store = store.clone();
+            move || {
+                store.len();
+            }
+        });
+        let whole_runs = count_runs({
+            let store = store.clone();
+            move || {
+                store.get();
+            }
+        });
+
+        first.set(true);
+        assert_eq!(first_runs.get(), 2);
+        assert_eq!(second_runs.get(), 1);
+        assert_eq!(len_runs.get(), 1);
+        assert_eq!(whole_runs.get(), 2);
+        assert!(store.get_untracked()[0].completed);
+    }
+
+    #[test]
+    fn test_parent_write_notifies_children() {
+        let store = todos();
+        let title = store.at(1).field("title", |t| &t.title, |t| &mut t.title);
+        let runs = count_runs({
+            let title = title.clone();
+            move || {
+                title.try_get();
+            }
+        });
+
+        store.update(|todos| todos.truncate(1));
+        assert_eq!(runs.get(), 2);
+        assert_eq!(title.try_get(), None);
+        assert_eq!(store.len(), 1);
+
+        title.set("ignored".to_string());
+        assert_eq!(store.get_untracked().len(), 1);
+    }
+
+    #[test]
+    fn test_field_accessors_share_a_path_by_name() {
+        let store = todos();
+        let completed = |store: &Store<Vec<Todo>>| {
+            store
+                .at(0)
+                .field("completed", |t| &t.completed, |t| &mut t.completed)
+        };
+        let runs = count_runs({
+            let store = store.clone();
+            move || {
+                completed(&store).get();
+            }
+        });
+
+        // A handler building its own accessor still reaches the reader.
+        completed(&store).set(true);
+        assert_eq!(runs.get(), 2);
+
+        // Reads of the same store may nest.
+        let title = store.at(0).field("title", |t| &t.title, |t| &mut t.title);
+        let both = store.with(|todos| (todos.len(), title.get_untracked()));
+        assert_eq!(both, (2, "write".to_string()));
+    }
+}
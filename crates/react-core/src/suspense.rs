@@ -0,0 +1,122 @@
+use crate::signal::{create_signal, ReadSignal, WriteSignal};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+thread_local! {
+    static SUSPENSE_STACK: RefCell<Vec<SuspenseContext>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Counts the resources created beneath a suspense boundary that are still loading.
+#[derive(Clone)]
+pub struct SuspenseContext {
+    pending: ReadSignal<usize>,
+    set_pending: WriteSignal<usize>,
+}
+
+impl SuspenseContext {
+    fn new() -> Self {
+        let (pending, set_pending) = create_signal(0);
+        Self {
+            pending,
+            set_pending,
+        }
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.pending.get()
+    }
+
+    /// `true` while any resource beneath the boundary is loading. Tracked.
+    pub fn is_pending(&self) -> bool {
+        self.pending_count() > 0
+    }
+}
+
+/// Runs `f` as the body of a suspense boundary: every resource it creates, however deeply
+/// nested, is counted by the returned context until it settles. Nested boundaries capture
+/// their own resources, so an outer boundary does not wait on them.
+pub fn create_suspense_scope<R>(f: impl FnOnce() -> R) -> (SuspenseContext, R) {
+    struct PopGuard;
+
+    impl Drop for PopGuard {
+        fn drop(&mut self) {
+            SUSPENSE_STACK.with(|stack| stack.borrow_mut().pop());
+        }
+    }
+
+    let context = SuspenseContext::new();
+    SUSPENSE_STACK.with(|stack| stack.borrow_mut().push(context.clone()));
+    let _guard = PopGuard;
+    (context, f())
+}
+
+pub fn use_suspense_context() -> Option<SuspenseContext> {
+    SUSPENSE_STACK.with(|stack| stack.borrow().last().cloned())
+}
+
+/// A resource's registration with the boundary it was created under.
+#[derive(Clone)]
+pub(crate) struct SuspenseTracker {
+    context: Option<SuspenseContext>,
+    counted: Rc<Cell<bool>>,
+}
+
+impl SuspenseTracker {
+    pub(crate) fn new() -> Self {
+        Self {
+            context: use_suspense_context(),
+            counted: Rc::new(Cell::new(false)),
+        }
+    }
+
+    /// Records whether the resource is loading; each resource counts at most once.
+    pub(crate) fn set_loading(&self, loading: bool) {
+        let context = match &self.context {
+            Some(context) => context,
+            None => return,
+        };
+        if self.counted.replace(loading) != loading {
+            context
+                .set_pending
+                .update(|n| *n = if loading { *n + 1 } else { n.saturating_sub(1) });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resource::create_manual_resource;
+
+    #[test]
+    fn test_boundary_waits_for_all_resources() {
+        let (context, (first, second)) = create_suspense_scope(|| {
+            (
+                create_manual_resource::<u32>(),
+                create_manual_resource::<u32>(),
+            )
+        });
+        let outside = create_manual_resource::<u32>();
+
+        assert_eq!(context.pending_count(), 2);
+        first.set_ready(1);
+        first.set_loading();
+        first.set_ready(1);
+        assert!(context.is_pending());
+        second.set_error("failed");
+        outside.set_ready(3);
+        assert!(!context.is_pending());
+        assert!(use_suspense_context().is_none());
+    }
+
+    #[test]
+    fn test_nested_boundaries_capture_their_own_resources() {
+        let (outer, (inner, resource)) =
+            create_suspense_scope(|| create_suspense_scope(create_manual_resource::<u32>));
+
+        assert_eq!(inner.pending_count(), 1);
+        assert_eq!(outer.pending_count(), 0);
+        resource.set_ready(1);
+        assert!(!inner.is_pending());
+    }
+}
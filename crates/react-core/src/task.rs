@@ -0,0 +1,107 @@
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::Thread;
+
+pub type LocalTask = Pin<Box<dyn Future<Output = ()>>>;
+type SpawnerFn = Rc<dyn Fn(LocalTask)>;
+
+thread_local! {
+    static SPAWNER: RefCell<Option<SpawnerFn>> = const { RefCell::new(None) };
+    static QUEUED: RefCell<Vec<LocalTask>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Installs the executor used by [`spawn_local`], e.g. `wasm_bindgen_futures::spawn_local`
+/// in the browser. Tasks queued before a spawner existed are handed to it immediately.
+pub fn set_task_spawner(spawner: impl Fn(LocalTask) + 'static) {
+    let spawner: SpawnerFn = Rc::new(spawner);
+    SPAWNER.with(|s| *s.borrow_mut() = Some(spawner.clone()));
+    for task in QUEUED.with(|q| std::mem::take(&mut *q.borrow_mut())) {
+        spawner(task);
+    }
+}
+
+/// Runs `future` on the installed spawner. Without one (e.g. during server rendering) the
+/// task is queued until [`run_pending_tasks`] or [`block_on_pending_tasks`] drives it.
+pub fn spawn_local(future: impl Future<Output = ()> + 'static) {
+    let task: LocalTask = Box::pin(future);
+    match SPAWNER.with(|s| s.borrow().clone()) {
+        Some(spawner) => spawner(task),
+        None => QUEUED.with(|q| q.borrow_mut().push(task)),
+    }
+}
+
+/// Exchanges the queued tasks with `tasks`, for switching between runtime instances.
+pub(crate) fn swap_queued_tasks(tasks: &mut Vec<LocalTask>) {
+    QUEUED.with(|q| std::mem::swap(&mut *q.borrow_mut(), tasks));
+}
+
+pub fn has_pending_tasks() -> bool {
+    QUEUED.with(|q| !q.borrow().is_empty())
+}
+
+/// Drives queued tasks, including any they queue in turn, until all have completed.
+pub async fn run_pending_tasks() {
+    let mut running: Vec<LocalTask> = Vec::new();
+    std::future::poll_fn(|cx| {
+        running.extend(QUEUED.with(|q| std::mem::take(&mut *q.borrow_mut())));
+        while !running.is_empty() {
+            running.retain_mut(|task| task.as_mut().poll(cx).is_pending());
+            let queued = QUEUED.with(|q| std::mem::take(&mut *q.borrow_mut()));
+            if queued.is_empty() {
+                break;
+            }
+            running.extend(queued);
+        }
+        if running.is_empty() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Blocks the current thread on [`run_pending_tasks`], for synchronous server renderers that
+/// need resource data before producing HTML.
+pub fn block_on_pending_tasks() {
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut run = std::pin::pin!(run_pending_tasks());
+    while run.as_mut().poll(&mut cx).is_pending() {
+        std::thread::park();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_queued_tasks_run_on_block() {
+        let done = Rc::new(Cell::new(0));
+        let outer = done.clone();
+        spawn_local(async move {
+            outer.set(outer.get() + 1);
+            let inner = outer.clone();
+            spawn_local(async move { inner.set(inner.get() + 1) });
+        });
+
+        assert!(has_pending_tasks());
+        block_on_pending_tasks();
+        assert_eq!(done.get(), 2);
+        assert!(!has_pending_tasks());
+    }
+}
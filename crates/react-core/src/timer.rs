@@ -0,0 +1,207 @@
+use crate::effect::{create_derived_effect, on_cleanup};
+use crate::signal::{create_signal, ReadSignal};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+
+/// Cancels a timer started by the installed [`set_timer`] function.
+pub type TimerCancel = Box<dyn FnOnce()>;
+type TimerFn = Rc<dyn Fn(Duration, Box<dyn FnOnce()>) -> TimerCancel>;
+
+thread_local! {
+    static TIMER: RefCell<Option<TimerFn>> = const { RefCell::new(None) };
+}
+
+/// Installs the function that runs a callback after a delay, e.g. `setTimeout` in the
+/// browser. Without one, time-based combinators such as [`ReadSignal::debounce`] apply
+/// changes immediately, which is what server rendering wants.
+pub fn set_timer(timer: impl Fn(Duration, Box<dyn FnOnce()>) -> TimerCancel + 'static) {
+    TIMER.with(|t| *t.borrow_mut() = Some(Rc::new(timer)));
+}
+
+pub fn clear_timer() {
+    TIMER.with(|t| *t.borrow_mut() = None);
+}
+
+/// Runs `f` after `delay`, or returns it unrun when no timer is installed.
+fn start_timer(delay: Duration, f: Box<dyn FnOnce()>) -> Result<TimerCancel, Box<dyn FnOnce()>> {
+    match TIMER.with(|t| t.borrow().clone()) {
+        Some(timer) => Ok(timer(delay, f)),
+        None => Err(f),
+    }
+}
+
+impl<T: Clone + 'static> ReadSignal<T> {
+    /// A signal that follows this one once it has stopped changing for `delay`, e.g. for
+    /// search-as-you-type.
+    pub fn debounce(&self, delay: Duration) -> ReadSignal<T> {
+        let source = self.clone();
+        let (output, set_output) = create_signal(self.get_untracked());
+        let first = Cell::new(true);
+        create_derived_effect(move || {
+            let value = source.get();
+            if first.replace(false) {
+                return;
+            }
+            let set_output = set_output.clone();
+            match start_timer(delay, Box::new(move || set_output.set(value))) {
+                // Cleanups run before the next change, restarting the wait.
+                Ok(cancel) => on_cleanup(cancel),
+                Err(apply) => apply(),
+            }
+        });
+        output
+    }
+
+    /// A signal that follows this one at most once per `interval`: the first change is
+    /// applied immediately and the latest change during the interval is applied when it ends.
+    pub fn throttle(&self, interval: Duration) -> ReadSignal<T> {
+        struct Throttle<T> {
+            cooling: bool,
+            pending: Option<T>,
+            cancel: Option<TimerCancel>,
+        }
+
+        fn emit<T: Clone + 'static>(
+            state: &Rc<RefCell<Throttle<T>>>,
+            set: &crate::signal::WriteSignal<T>,
+            value: T,
+            interval: Duration,
+        ) {
+            set.set(value);
+            let state_clone = state.clone();
+            let set_clone = set.clone();
+            let end = Box::new(move || {
+                let pending = {
+                    let mut s = state_clone.borrow_mut();
+                    s.cooling = false;
+                    s.cancel = None;
+                    s.pending.take()
+                };
+                if let Some(value) = pending {
+                    emit(&state_clone, &set_clone, value, interval);
+                }
+            });
+            if let Ok(cancel) = start_timer(interval, end) {
+                let mut s = state.borrow_mut();
+                s.cooling = true;
+                s.cancel = Some(cancel);
+            }
+        }
+
+        let source = self.clone();
+        let (output, set_output) = create_signal(self.get_untracked());
+        let state = Rc::new(RefCell::new(Throttle {
+            cooling: false,
+            pending: None,
+            cancel: None,
+        }));
+        let cleanup_state = state.clone();
+        on_cleanup(move || {
+            if let Some(cancel) = cleanup_state.borrow_mut().cancel.take() {
+                cancel();
+            }
+        });
+
+        let first = Cell::new(true);
+        create_derived_effect(move || {
+            let value = source.get();
+            if first.replace(false) {
+                return;
+            }
+            let cooling = {
+                let mut s = state.borrow_mut();
+                if s.cooling {
+                    s.pending = Some(value.clone());
+                }
+                s.cooling
+            };
+            if !cooling {
+                emit(&state, &set_output, value, interval);
+            }
+        });
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal::create_signal;
+
+    type Timers = Rc<RefCell<Vec<Option<Box<dyn FnOnce()>>>>>;
+
+    /// A manual timer: callbacks run only when the test fires them.
+    fn manual_timer() -> Timers {
+        let timers: Timers = Rc::new(RefCell::new(Vec::new()));
+        let registry = timers.clone();
+        set_timer(move |_, f| {
+            let mut timers = registry.borrow_mut();
+            let index = timers.len();
+            timers.push(Some(f));
+            let registry = registry.clone();
+            Box::new(move || {
+                registry.borrow_mut()[index] = None;
+            })
+        });
+        timers
+    }
+
+    fn fire_all(timers: &Timers) {
+        let pending: Vec<Box<dyn FnOnce()>> = timers
+            .borrow_mut()
+            .iter_mut()
+            .filter_map(Option::take)
+            .collect();
+        for f in pending {
+            f();
+        }
+    }
+
+    #[test]
+    fn test_debounce_applies_last_value_after_quiet_period() {
+        let timers = manual_timer();
+        let (query, set_query) = create_signal(String::new());
+        let debounced = query.debounce(Duration::from_millis(300));
+
+        set_query.set("r".to_string());
+        set_query.set("ru".to_string());
+        set_query.set("rust".to_string());
+        assert_eq!(debounced.get_untracked(), "");
+
+        fire_all(&timers);
+        assert_eq!(debounced.get_untracked(), "rust");
+        clear_timer();
+    }
+
+    #[test]
+    fn test_throttle_applies_leading_and_trailing_values() {
+        let timers = manual_timer();
+        let (width, set_width) = create_signal(0);
+        let throttled = width.throttle(Duration::from_millis(100));
+
+        set_width.set(1);
+        assert_eq!(throttled.get_untracked(), 1);
+        set_width.set(2);
+        set_width.set(3);
+        assert_eq!(throttled.get_untracked(), 1);
+
+        fire_all(&timers);
+        assert_eq!(throttled.get_untracked(), 3);
+        fire_all(&timers);
+        set_width.set(4);
+        assert_eq!(throttled.get_untracked(), 4);
+        clear_timer();
+    }
+
+    #[test]
+    fn test_without_timer_changes_apply_immediately() {
+        let (value, set_value) = create_signal(1);
+        let debounced = value.debounce(Duration::from_secs(1));
+        let throttled = value.throttle(Duration::from_secs(1));
+
+        set_value.set(2);
+        assert_eq!(debounced.get_untracked(), 2);
+        assert_eq!(throttled.get_untracked(), 2);
+    }
+}
@@ -3,10 +3,20 @@
 //! Server-side rendering for react.rs components.
 //!
 //! This crate provides [`render_to_string()`] which converts a component tree
-//! into HTML string output for server-side rendering (SSR).
+//! into HTML string output for server-side rendering (SSR), and [`render_to_stream()`]
+//! which writes the page as it renders, finishing suspense boundaries once their data
+//! arrives.
 //!
 //! For client-side rendering and hydration, use `react-rs-wasm` instead.
 
+mod manifest;
+mod pretty;
 mod render;
+mod stream;
 
-pub use render::{render_to_string, RenderOutput};
+pub use manifest::{ClientMarker, HandlerSite, HydrationManifest, Region};
+pub use pretty::render_to_string_pretty;
+pub use render::{
+    render_to_static_markup, render_to_string, RenderError, RenderOutput, RenderedPortal,
+};
+pub use stream::{render_shell, render_to_stream, PendingBoundary, StreamingShell};
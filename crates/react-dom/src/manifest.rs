@@ -0,0 +1,123 @@
+/// What a server render left for the client to hydrate, so the runtime can go straight to
+/// the elements that need work instead of walking the whole tree.
+///
+/// Elements are identified by a hydration key the render writes into them as `data-hk`
+/// (prefixed with [`HydrationManifest::key_prefix`]), so they are found by attribute rather
+/// than by position; see [`HydrationManifest::selector`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HydrationManifest {
+    pub handlers: Vec<HandlerSite>,
+    pub client_components: Vec<ClientMarker>,
+    pub regions: Vec<Region>,
+    /// How many keys were given out; keys run from 0 to `key_count - 1`.
+    pub key_count: usize,
+    /// Keeps keys unique when parts of a page are rendered separately, e.g. `"3:"` for
+    /// streamed suspense boundary 3; empty for the page itself.
+    pub key_prefix: String,
+}
+
+/// An element with event handlers to attach.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandlerSite {
+    pub key: usize,
+    pub events: Vec<String>,
+}
+
+/// An element marked `data-client`, hydrated by the client component registered under `id`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientMarker {
+    pub key: usize,
+    pub id: String,
+    /// The serialized props from the element's `data-props` attribute.
+    pub props: Option<String>,
+}
+
+/// The wrapper of a reactive region, such as a list, conditional or suspense boundary.
+/// A region's id is its index in [`HydrationManifest::regions`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Region {
+    pub key: usize,
+    /// The wrapper's marker attribute, e.g. `data-list`.
+    pub kind: &'static str,
+}
+
+impl HydrationManifest {
+    /// The CSS selector of the element holding `key`, e.g. `[data-hk="4"]`.
+    pub fn selector(&self, key: usize) -> String {
+        format!("[data-hk=\"{}{}\"]", self.key_prefix, key)
+    }
+
+    /// The manifest as JSON, e.g. for a `<script type="application/json">` next to the page.
+    pub fn to_json(&self) -> String {
+        let handlers = self
+            .handlers
+            .iter()
+            .map(|site| {
+                let events = site
+                    .events
+                    .iter()
+                    .map(|event| json_string(event))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!("{{\"key\":{},\"events\":[{}]}}", site.key, events)
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let client_components = self
+            .client_components
+            .iter()
+            .map(|marker| {
+                format!(
+                    "{{\"key\":{},\"id\":{},\"props\":{}}}",
+                    marker.key,
+                    json_string(&marker.id),
+                    marker
+                        .props
+                        .as_deref()
+                        .map_or("null".to_string(), json_string)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let regions = self
+            .regions
+            .iter()
+            .map(|region| {
+                format!(
+                    "{{\"key\":{},\"kind\":{}}}",
+                    region.key,
+                    json_string(region.kind)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            "{{\"handlers\":[{}],\"clientComponents\":[{}],\"regions\":[{}],\"keyCount\":{},\"keyPrefix\":{}}}",
+            handlers,
+            client_components,
+            regions,
+            self.key_count,
+            json_string(&self.key_prefix)
+        )
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            // Keeps the JSON safe to embed in a `<script>` element.
+            '<' => out.push_str("\\u003c"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -0,0 +1,155 @@
+use react_rs_elements::node::Node;
+use react_rs_elements::Element;
+
+use crate::render::{render_to_string, RenderOutput};
+
+/// Elements whose content is written exactly as rendered, since whitespace in it is
+/// significant or it is not HTML.
+const VERBATIM: [&str; 4] = ["pre", "textarea", "script", "style"];
+
+/// Like [`render_to_string`], but with one tag per line, indented by nesting depth, for
+/// snapshot tests and inspecting build output. Elements holding only text stay on one line.
+/// The added whitespace can change how inline content renders, so serve the compact form.
+pub fn render_to_string_pretty(node: &Node) -> RenderOutput {
+    let output = render_to_string(node);
+    RenderOutput {
+        html: prettify(&output.html),
+        ..output
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Open(&'a str, &'a str),
+    Close(&'a str),
+    /// Void elements, comments and verbatim elements with their content.
+    Leaf(&'a str),
+    Text(&'a str),
+}
+
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token::Text(&rest[..start]));
+            rest = &rest[start..];
+            continue;
+        }
+        let end = if rest.starts_with("<!--") {
+            rest.find("-->").map(|i| i + 3)
+        } else {
+            rest.find('>').map(|i| i + 1)
+        }
+        .unwrap_or(rest.len());
+        let tag = &rest[..end];
+
+        if tag.starts_with("<!") {
+            tokens.push(Token::Leaf(tag));
+        } else if let Some(name) = tag.strip_prefix("</") {
+            tokens.push(Token::Close(name.trim_end_matches('>')));
+        } else {
+            let name = tag[1..]
+                .split(|c: char| c.is_whitespace() || c == '>')
+                .next()
+                .unwrap_or_default();
+            if Element::is_void_tag(name) {
+                tokens.push(Token::Leaf(tag));
+            } else if VERBATIM.contains(&name) {
+                let close = format!("</{}>", name);
+                let len = rest.find(&close).map_or(rest.len(), |i| i + close.len());
+                tokens.push(Token::Leaf(&rest[..len]));
+                rest = &rest[len..];
+                continue;
+            } else {
+                tokens.push(Token::Open(tag, name));
+            }
+        }
+        rest = &rest[end..];
+    }
+    tokens
+}
+
+fn prettify(html: &str) -> String {
+    let tokens = tokenize(html);
+    let mut lines = Vec::new();
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i < tokens.len() {
+        let indent = "  ".repeat(depth);
+        match (&tokens[i], tokens.get(i + 1), tokens.get(i + 2)) {
+            (Token::Open(tag, name), Some(Token::Text(text)), Some(Token::Close(close)))
+                if name == close =>
+            {
+                lines.push(format!("{}{}{}</{}>", indent, tag, text, close));
+                i += 3;
+            }
+            (Token::Open(tag, name), Some(Token::Close(close)), _) if name == close => {
+                lines.push(format!("{}{}</{}>", indent, tag, close));
+                i += 2;
+            }
+            (Token::Open(tag, _), _, _) => {
+                lines.push(format!("{}{}", indent, tag));
+                depth += 1;
+                i += 1;
+            }
+            (Token::Close(name), _, _) => {
+                depth = depth.saturating_sub(1);
+                lines.push(format!("{}</{}>", "  ".repeat(depth), name));
+                i += 1;
+            }
+            (Token::Leaf(leaf), _, _) => {
+                lines.push(format!("{}{}", indent, leaf));
+                i += 1;
+            }
+            (Token::Text(text), _, _) => {
+                if !text.trim().is_empty() {
+                    lines.push(format!("{}{}", indent, text.trim()));
+                }
+                i += 1;
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use react_rs_elements::html::*;
+    use react_rs_elements::node::IntoNode;
+
+    #[test]
+    fn test_render_pretty() {
+        let node = div()
+            .class("card")
+            .child(h2().text("Title"))
+            .child(img().src("/a.png"))
+            .child(ul().child(li().text("One")).child(li()))
+            .child(pre().text("  keep\n  this"))
+            .into_node();
+
+        assert_eq!(
+            render_to_string_pretty(&node).html,
+            "<div class=\"card\">\n  <h2>Title</h2>\n  <img src=\"/a.png\">\n  <ul>\n    <li>One</li>\n    <li></li>\n  </ul>\n  <pre>  keep\n  this</pre>\n</div>"
+        );
+    }
+
+    #[test]
+    fn test_render_pretty_keeps_markers_and_script_content() {
+        let node = vec![
+            p().text("a < b").into_node(),
+            script().text("if (a<b) { go() }").into_node(),
+        ]
+        .into_node();
+
+        assert_eq!(
+            render_to_string_pretty(&node).html,
+            "<!--[-->\n<p>a &lt; b</p>\n<script>if (a<b) { go() }</script>\n<!--]-->"
+        );
+    }
+}
@@ -1,14 +1,204 @@
-use react_rs_elements::attributes::AttributeValue;
+use std::cell::{Cell, RefCell};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::panic::{self, AssertUnwindSafe};
+
+use react_rs_elements::attributes::{Attribute, AttributeValue};
+use react_rs_elements::head::Head;
 use react_rs_elements::node::Node;
 use react_rs_elements::Element;
 
+use crate::manifest::{ClientMarker, HandlerSite, HydrationManifest, Region};
+
 pub struct RenderOutput {
     pub html: String,
+    /// Every [`Head`] rendered in the tree, merged from outermost to innermost.
+    pub head: Head,
+    /// Panics caught by error boundaries while rendering, whose fallbacks were rendered in
+    /// their place.
+    pub errors: Vec<RenderError>,
+    pub hydration: HydrationManifest,
+    /// Content of the portals in the tree, in document order, for the page to place at
+    /// the end of `<body>`; see [`RenderOutput::portals_html`].
+    pub portals: Vec<RenderedPortal>,
 }
 
+impl RenderOutput {
+    /// The portals' content, each in a `data-portal-root` container the client replaces
+    /// with its own when it hydrates.
+    pub fn portals_html(&self) -> String {
+        self.portals
+            .iter()
+            .map(|portal| format!("<div data-portal-root data-ssr>{}</div>", portal.html))
+            .collect()
+    }
+}
+
+/// The server-rendered content of a portal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedPortal {
+    /// The selector the client mounts the content into.
+    pub target: String,
+    pub html: String,
+}
+
+/// A panic caught by an error boundary during a server render.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderError {
+    pub message: String,
+    /// A short hash of the message, for matching log lines to a page without exposing the
+    /// message itself.
+    pub digest: String,
+}
+
+impl RenderError {
+    fn new(message: String) -> Self {
+        let mut hasher = DefaultHasher::new();
+        message.hash(&mut hasher);
+        Self {
+            digest: format!("{:08x}", hasher.finish() as u32),
+            message,
+        }
+    }
+}
+
+thread_local! {
+    static COLLECTED_HEADS: RefCell<Vec<Head>> = const { RefCell::new(Vec::new()) };
+    static COLLECTED_PORTALS: RefCell<Vec<RenderedPortal>> = const { RefCell::new(Vec::new()) };
+    /// The value of the `<select>` being rendered, so the matching `<option>` is marked.
+    static SELECT_VALUE: RefCell<Option<String>> = const { RefCell::new(None) };
+    /// Set by [`render_to_static_markup`] to leave out everything only hydration uses.
+    static STATIC_MARKUP: Cell<bool> = const { Cell::new(false) };
+    static RENDER_ERRORS: RefCell<Vec<RenderError>> = const { RefCell::new(Vec::new()) };
+    static HYDRATION: RefCell<HydrationManifest> = const {
+        RefCell::new(HydrationManifest {
+            handlers: Vec::new(),
+            client_components: Vec::new(),
+            regions: Vec::new(),
+            key_count: 0,
+            key_prefix: String::new(),
+        })
+    };
+}
+
+/// Renders `node` to HTML. Mount callbacks queued by components are dropped, since server
+/// output is never attached to a live document.
 pub fn render_to_string(node: &Node) -> RenderOutput {
+    crate::stream::without_deferral(|| render_collecting(node, ""))
+}
+
+/// Renders `node` to plain HTML that no client runtime will attach to, e.g. for emails, RSS
+/// content or static pages: hydration markers and the wrappers around reactive regions are
+/// left out, only the current branch of a conditional is written, and handlers' default
+/// prevention is dropped.
+pub fn render_to_static_markup(node: &Node) -> RenderOutput {
+    let outer = STATIC_MARKUP.with(|mode| mode.replace(true));
+    let output = render_to_string(node);
+    STATIC_MARKUP.with(|mode| mode.set(outer));
+    output
+}
+
+fn is_static_markup() -> bool {
+    STATIC_MARKUP.with(Cell::get)
+}
+
+/// Wraps a reactive region's HTML in the container the client attaches to.
+fn region(attr: &'static str, content: impl FnOnce() -> String) -> String {
+    if is_static_markup() {
+        return content();
+    }
+    let key = next_key();
+    HYDRATION.with(|manifest| {
+        manifest
+            .borrow_mut()
+            .regions
+            .push(Region { key, kind: attr })
+    });
+    format!(
+        "<span {}{} style=\"display:contents\">{}</span>",
+        attr,
+        key_attr(key),
+        content()
+    )
+}
+
+/// Gives out the next hydration key; call it before rendering the element's children.
+fn next_key() -> usize {
+    HYDRATION.with(|manifest| {
+        let mut manifest = manifest.borrow_mut();
+        manifest.key_count += 1;
+        manifest.key_count - 1
+    })
+}
+
+/// The `data-hk` attribute marking the element that holds `key`.
+fn key_attr(key: usize) -> String {
+    HYDRATION.with(|manifest| format!(" data-hk=\"{}{}\"", manifest.borrow().key_prefix, key))
+}
+
+/// Delimits HTML that renders to zero or several DOM nodes with comment markers.
+fn delimit(open: &str, close: &str, content: String) -> String {
+    if is_static_markup() {
+        content
+    } else {
+        format!("<!--{}-->{}<!--{}-->", open, content, close)
+    }
+}
+
+/// Renders `node`, catching a panic so one failing component does not take down the whole
+/// response. Heads, portals, errors and deferred boundaries recorded by the partial render are
+/// discarded with it.
+fn catch_render_panic(node: &Node) -> Result<String, RenderError> {
+    let heads = COLLECTED_HEADS.with(|heads| heads.borrow().len());
+    let portals = COLLECTED_PORTALS.with(|portals| portals.borrow().len());
+    let errors = RENDER_ERRORS.with(|errors| errors.borrow().len());
+    let deferred = crate::stream::deferred_len();
+    let select = SELECT_VALUE.with(|value| value.borrow().clone());
+    let hydration = HYDRATION.with(|manifest| manifest.borrow().clone());
+
+    panic::catch_unwind(AssertUnwindSafe(|| render_node(node))).map_err(|payload| {
+        COLLECTED_HEADS.with(|collected| collected.borrow_mut().truncate(heads));
+        COLLECTED_PORTALS.with(|collected| collected.borrow_mut().truncate(portals));
+        RENDER_ERRORS.with(|recorded| recorded.borrow_mut().truncate(errors));
+        crate::stream::truncate_deferred(deferred);
+        SELECT_VALUE.with(|value| *value.borrow_mut() = select);
+        HYDRATION.with(|manifest| *manifest.borrow_mut() = hydration);
+
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "render panicked".to_string()
+        };
+        RenderError::new(message)
+    })
+}
+
+/// Renders `node`, collecting the heads rendered within it. Hydration keys start with
+/// `key_prefix`.
+pub(crate) fn render_collecting(node: &Node, key_prefix: &str) -> RenderOutput {
+    let outer = COLLECTED_HEADS.with(|heads| heads.take());
+    let outer_portals = COLLECTED_PORTALS.with(|portals| portals.take());
+    let outer_errors = RENDER_ERRORS.with(|errors| errors.take());
+    let outer_hydration = HYDRATION.with(|manifest| {
+        manifest.replace(HydrationManifest {
+            key_prefix: key_prefix.to_string(),
+            ..HydrationManifest::default()
+        })
+    });
+    let html = render_node(node);
+    let heads = COLLECTED_HEADS.with(|heads| heads.replace(outer));
+    let portals = COLLECTED_PORTALS.with(|portals| portals.replace(outer_portals));
+    let errors = RENDER_ERRORS.with(|errors| errors.replace(outer_errors));
+    let hydration = HYDRATION.with(|manifest| manifest.replace(outer_hydration));
+    react_rs_core::effect::discard_mount_callbacks();
     RenderOutput {
-        html: render_node(node),
+        html,
+        head: Head::merge_all(&heads),
+        errors,
+        hydration,
+        portals,
     }
 }
 
@@ -16,96 +206,251 @@ fn render_node(node: &Node) -> String {
     match node {
         Node::Element(element) => render_element(element),
         Node::Text(text) => escape_html(text),
-        Node::ReactiveText(reactive) => escape_html(&reactive.get()),
-        Node::Fragment(children) => children
-            .iter()
-            .map(render_node)
-            .collect::<Vec<_>>()
-            .join(""),
+        // Comment markers delimit nodes that render to zero or several DOM nodes, so the
+        // client can hydrate without relying on child positions.
+        Node::ReactiveText(reactive) => delimit("t", "/t", escape_html(&reactive.get())),
+        Node::Fragment(children) => delimit(
+            "[",
+            "]",
+            children.iter().map(render_node).collect::<String>(),
+        ),
+        Node::Conditional(condition, then_node, else_node) if is_static_markup() => {
+            match (condition.get(), else_node) {
+                (true, _) => render_node(then_node),
+                (false, Some(else_node)) => render_node(else_node),
+                (false, None) => String::new(),
+            }
+        }
         Node::Conditional(condition, then_node, else_node) => {
             let show = condition.get();
+            let key = next_key();
+            HYDRATION.with(|manifest| {
+                manifest.borrow_mut().regions.push(Region {
+                    key,
+                    kind: "data-cond",
+                })
+            });
             let then_html = render_node(then_node);
-            let else_html = else_node
-                .as_ref()
-                .map(|n| render_node(n))
-                .unwrap_or_default();
+            let else_html = else_node.as_deref().map(render_node).unwrap_or_default();
 
             let then_style = if show { "" } else { " style=\"display:none\"" };
             let else_style = if show { " style=\"display:none\"" } else { "" };
 
-            if else_html.is_empty() {
+            if else_node.is_none() {
                 format!(
-                    "<span data-cond style=\"display:contents\"><span{}>{}</span></span>",
-                    then_style, then_html
+                    "<span data-cond{} style=\"display:contents\"><span{}>{}</span></span>",
+                    key_attr(key),
+                    then_style,
+                    then_html
                 )
             } else {
                 format!(
-                    "<span data-cond style=\"display:contents\"><span{}>{}</span><span{}>{}</span></span>",
-                    then_style, then_html, else_style, else_html
+                    "<span data-cond{} style=\"display:contents\"><span{}>{}</span><span{}>{}</span></span>",
+                    key_attr(key),
+                    then_style,
+                    then_html,
+                    else_style,
+                    else_html
                 )
             }
         }
         Node::ReactiveList(list_fn) => {
-            let items_html = list_fn()
-                .iter()
-                .map(render_node)
-                .collect::<Vec<_>>()
-                .join("");
-            format!(
-                "<span data-list style=\"display:contents\">{}</span>",
-                items_html
-            )
+            region("data-list", || list_fn().iter().map(render_node).collect())
         }
-        Node::KeyedList(list_fn) => {
-            let items_html = list_fn()
+        Node::DiffList(list) => region("data-list", || {
+            (list.items)()
                 .iter()
                 .map(|(_, node)| render_node(node))
-                .collect::<Vec<_>>()
-                .join("");
-            format!(
-                "<span data-list style=\"display:contents\">{}</span>",
-                items_html
-            )
+                .collect()
+        }),
+        Node::KeyedList(list_fn) => region("data-list", || {
+            list_fn()
+                .iter()
+                .map(|(_, node)| render_node(node))
+                .collect()
+        }),
+        Node::Head(head) => {
+            COLLECTED_HEADS.with(|heads| heads.borrow_mut().push(head.clone()));
+            String::new()
         }
-        Node::Head(_) => String::new(),
-        Node::Suspense(sus) => {
-            if (sus.loading_signal)() {
-                render_node(&sus.fallback)
-            } else {
-                render_node(&sus.children)
+        Node::Suspense(sus) => region("data-suspense", || {
+            if !(sus.loading_signal)() {
+                return render_node(&sus.children);
             }
-        }
-        Node::ErrorBoundary(eb) => {
+            match crate::stream::defer(sus) {
+                Some(id) => crate::stream::placeholder(id, &render_node(&sus.fallback)),
+                None => render_node(&sus.fallback),
+            }
+        }),
+        Node::ErrorBoundary(eb) => region("data-error-boundary", || {
             if let Some(error) = (eb.error_signal)() {
-                render_node(&(eb.error_fallback)(error))
-            } else {
-                render_node(&eb.children)
+                return render_node(&(eb.error_fallback)(error));
+            }
+            match catch_render_panic(&eb.children) {
+                Ok(html) => html,
+                Err(error) => {
+                    let fallback = render_node(&(eb.error_fallback)(error.message.clone()));
+                    RENDER_ERRORS.with(|errors| errors.borrow_mut().push(error));
+                    fallback
+                }
             }
+        }),
+        Node::Transition(_, child) => render_node(child),
+        Node::Template(template) => render_node(template.node()),
+        Node::Dynamic(view) => region("data-dyn", || render_node(&view())),
+        // The placeholder is a region like any other. The content is rendered out of place,
+        // and the client mounts it afresh, so it is plain markup that takes no hydration keys.
+        Node::Portal(target, child) => {
+            let placeholder = region("data-portal", String::new);
+            let index = COLLECTED_PORTALS.with(|portals| {
+                let mut portals = portals.borrow_mut();
+                portals.push(RenderedPortal {
+                    target: target.clone(),
+                    html: String::new(),
+                });
+                portals.len() - 1
+            });
+            let outer_mode = STATIC_MARKUP.with(|mode| mode.replace(true));
+            let hydration = HYDRATION.with(|manifest| manifest.borrow().clone());
+            let html = render_node(child);
+            HYDRATION.with(|manifest| *manifest.borrow_mut() = hydration);
+            STATIC_MARKUP.with(|mode| mode.set(outer_mode));
+            COLLECTED_PORTALS.with(|portals| portals.borrow_mut()[index].html = html);
+            placeholder
         }
     }
 }
 
 fn render_element(element: &Element) -> String {
+    if !element.scoped_styles().is_empty() {
+        let head = element
+            .scoped_styles()
+            .iter()
+            .fold(Head::new(), |head, (class, rules)| {
+                head.inline_style(class, rules)
+            });
+        COLLECTED_HEADS.with(|heads| heads.borrow_mut().push(head));
+    }
+    let key = record_hydration(element);
     let tag = element.tag();
-    let attrs = render_attributes(element);
-    let children = element
-        .get_children()
+    let mut attrs = render_attributes(element);
+    let value = element
+        .attributes()
         .iter()
-        .map(render_node)
-        .collect::<Vec<_>>()
-        .join("");
+        .find(|attr| attr.name == "value")
+        .map(|attr| attr.to_static_value());
 
-    if is_void_element(tag) {
-        format!("<{}{} />", tag, attrs)
+    // Form controls without a `value` attribute of their own carry the bound value elsewhere.
+    if matches!(tag, "textarea" | "select") && value.is_some() {
+        attrs = render_attributes_except(element, "value");
+    }
+    if tag == "option" {
+        let selected =
+            SELECT_VALUE.with(|select| select.borrow().is_some() && *select.borrow() == value);
+        if selected {
+            attrs.push_str(" selected");
+        }
+    }
+    if let Some(key) = key {
+        attrs.push_str(&key_attr(key));
+    }
+
+    let children = match (tag, element.get_inner_html()) {
+        (_, Some(html)) => html.to_string(),
+        ("textarea", None) if value.is_some() => escape_html(value.as_deref().unwrap_or("")),
+        ("select", None) => {
+            let outer = SELECT_VALUE.with(|select| select.replace(value));
+            let html = render_children(element);
+            SELECT_VALUE.with(|select| select.replace(outer));
+            html
+        }
+        ("script" | "style", None) => render_raw_text(element),
+        (_, None) => render_children(element),
+    };
+
+    if element.is_void() {
+        format!("<{}{}>", tag, attrs)
     } else {
         format!("<{}{}>{}</{}>", tag, attrs, children, tag)
     }
 }
 
+/// Records what the client must hydrate on `element`, returning the key it is marked with,
+/// or `None` when it needs nothing.
+fn record_hydration(element: &Element) -> Option<usize> {
+    if is_static_markup() {
+        return None;
+    }
+    let mut events: Vec<String> = element
+        .event_handlers()
+        .iter()
+        .map(|handler| handler.event_type().to_string())
+        .collect();
+    events.dedup();
+    let client = element
+        .attributes()
+        .iter()
+        .any(|attr| attr.name == "data-client");
+    if events.is_empty() && !client {
+        return None;
+    }
+
+    let attribute = |name: &str| {
+        element
+            .attributes()
+            .iter()
+            .find(|attr| attr.name == name)
+            .map(|attr| attr.to_static_value())
+    };
+    let key = next_key();
+    HYDRATION.with(|manifest| {
+        let mut manifest = manifest.borrow_mut();
+        if !events.is_empty() {
+            manifest.handlers.push(HandlerSite { key, events });
+        }
+        if client {
+            manifest.client_components.push(ClientMarker {
+                key,
+                id: attribute("data-component-id").unwrap_or_default(),
+                props: attribute("data-props"),
+            });
+        }
+    });
+    Some(key)
+}
+
+fn render_children(element: &Element) -> String {
+    element
+        .get_children()
+        .iter()
+        .map(render_node)
+        .collect::<Vec<_>>()
+        .join("")
+}
+
+/// `<script>` and `<style>` content is not entity-decoded by the browser, so it is written
+/// as-is except for anything that would close the element early.
+fn render_raw_text(element: &Element) -> String {
+    element
+        .get_children()
+        .iter()
+        .map(|child| match child {
+            Node::Text(text) => escape_raw_text(text),
+            Node::ReactiveText(reactive) => escape_raw_text(&reactive.get()),
+            other => render_node(other),
+        })
+        .collect()
+}
+
 fn render_attributes(element: &Element) -> String {
+    render_attributes_except(element, "")
+}
+
+fn render_attributes_except(element: &Element, skip: &str) -> String {
     let attrs: Vec<String> = element
         .attributes()
         .iter()
+        .filter(|attr| attr.name != skip && Attribute::is_valid_name(&attr.name))
         .filter_map(|attr| match &attr.value {
             AttributeValue::String(s) => Some(format!(" {}=\"{}\"", attr.name, escape_attr(s))),
             AttributeValue::Bool(b) => {
@@ -130,7 +475,26 @@ fn render_attributes(element: &Element) -> String {
         })
         .collect();
 
-    attrs.join("")
+    attrs.join("") + &render_default_prevention(element)
+}
+
+/// Handlers marked `prevent_default` must also block the browser's default action before
+/// the client takes over, e.g. a form submitting natively while the bundle is loading.
+fn render_default_prevention(element: &Element) -> String {
+    if is_static_markup() {
+        return String::new();
+    }
+    let mut types: Vec<&str> = element
+        .event_handlers()
+        .iter()
+        .filter(|handler| handler.modifiers().prevent_default)
+        .map(|handler| handler.event_type())
+        .collect();
+    types.dedup();
+    types
+        .into_iter()
+        .map(|event_type| format!(" on{}=\"event.preventDefault()\"", event_type))
+        .collect()
 }
 
 fn escape_html(s: &str) -> String {
@@ -142,28 +506,13 @@ fn escape_html(s: &str) -> String {
 fn escape_attr(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('"', "&quot;")
+        .replace('\'', "&#39;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
 }
 
-fn is_void_element(tag: &str) -> bool {
-    matches!(
-        tag,
-        "area"
-            | "base"
-            | "br"
-            | "col"
-            | "embed"
-            | "hr"
-            | "img"
-            | "input"
-            | "link"
-            | "meta"
-            | "param"
-            | "source"
-            | "track"
-            | "wbr"
-    )
+fn escape_raw_text(s: &str) -> String {
+    s.replace("</", "<\\/").replace("<!--", "<\\!--")
 }
 
 #[cfg(test)]
@@ -192,13 +541,71 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_render_inner_html_unescaped() {
+        let element = div().class("post").inner_html("<p>A &amp; B</p>");
+        let output = render_to_string(&element.into_node());
+        assert_eq!(output.html, "<div class=\"post\"><p>A &amp; B</p></div>");
+    }
+
+    #[test]
+    fn test_render_prevent_default_handler() {
+        let element = form().on_submit(|_| {}).prevent_default().on_click(|_| {});
+        let output = render_to_string(&element.into_node());
+        assert_eq!(
+            output.html,
+            "<form onsubmit=\"event.preventDefault()\" data-hk=\"0\"></form>"
+        );
+    }
+
+    #[test]
+    fn test_render_bound_textarea_and_select() {
+        use react_rs_core::signal::create_rw_signal;
+
+        let bio = create_rw_signal("<hi>".to_string());
+        let html = render_to_string(&textarea().bind(bio).into_node()).html;
+        assert_eq!(html, "<textarea data-hk=\"0\">&lt;hi&gt;</textarea>");
+
+        let size = create_rw_signal("m".to_string());
+        let html = render_to_string(
+            &select()
+                .bind_selected(size)
+                .child(option().value("s").text("Small"))
+                .child(option().value("m").text("Medium"))
+                .into_node(),
+        )
+        .html;
+        assert_eq!(
+            html,
+            "<select data-hk=\"0\"><option value=\"s\">Small</option><option value=\"m\" selected>Medium</option></select>"
+        );
+    }
+
+    #[test]
+    fn test_render_custom_element() {
+        let element = custom("my-widget").attr("theme", "dark").text("Hi");
+        let output = render_to_string(&element.into_node());
+        assert_eq!(output.html, "<my-widget theme=\"dark\">Hi</my-widget>");
+    }
+
     #[test]
     fn test_render_void_element() {
         let element = input().type_("text").placeholder("Enter name");
         let output = render_to_string(&element.into_node());
         assert_eq!(
             output.html,
-            "<input type=\"text\" placeholder=\"Enter name\" />"
+            "<input type=\"text\" placeholder=\"Enter name\">"
+        );
+
+        let element = picture()
+            .child(source().attr("srcset", "/a.webp"))
+            .child(img().src("/a.png"))
+            .child(br())
+            .child(hr());
+        let output = render_to_string(&element.into_node());
+        assert_eq!(
+            output.html,
+            "<picture><source srcset=\"/a.webp\"><img src=\"/a.png\"><br><hr></picture>"
         );
     }
 
@@ -212,6 +619,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_render_escapes_attributes() {
+        let element = a()
+            .href("/search?q=\"><script>alert(1)</script>&x='y'")
+            .attr("onclick=\"alert(1)\" data-x", "ignored");
+        let output = render_to_string(&element.into_node());
+        assert_eq!(
+            output.html,
+            "<a href=\"/search?q=&quot;&gt;&lt;script&gt;alert(1)&lt;/script&gt;&amp;x=&#39;y&#39;\"></a>"
+        );
+    }
+
+    #[test]
+    fn test_render_raw_text_elements() {
+        let element = style().text(".a > .b { content: \"&\" }</style><script>");
+        let output = render_to_string(&element.into_node());
+        assert_eq!(
+            output.html,
+            "<style>.a > .b { content: \"&\" }<\\/style><script></style>"
+        );
+    }
+
     #[test]
     fn test_render_boolean_attribute() {
         let element = input().disabled(true);
@@ -223,11 +652,57 @@ mod tests {
         assert!(!output_enabled.html.contains("disabled"));
     }
 
+    #[test]
+    fn test_render_to_static_markup() {
+        use react_rs_core::signal::create_signal;
+        use react_rs_elements::reactive::SignalExt;
+
+        let (count, _) = create_signal(2);
+        let node = form()
+            .on_submit(|_| {})
+            .prevent_default()
+            .child(
+                p().text("Count: ")
+                    .text_reactive(count.map(|n| n.to_string())),
+            )
+            .child(vec![strong().text("a"), strong().text("b")])
+            .child(
+                react_rs_elements::when(count.map(|n| *n > 1))
+                    .then(|| "many")
+                    .otherwise(|| "few"),
+            )
+            .child(react_rs_elements::dyn_view(|| em().text("dyn")))
+            .into_node();
+
+        assert_eq!(
+            render_to_static_markup(&node).html,
+            "<form><p>Count: 2</p><strong>a</strong><strong>b</strong>many<em>dyn</em></form>"
+        );
+        assert!(render_to_string(&node).html.contains("<!--t-->"));
+    }
+
     #[test]
     fn test_render_fragment() {
         let fragment = vec![span().text("A"), span().text("B")];
         let output = render_to_string(&fragment.into_node());
-        assert_eq!(output.html, "<span>A</span><span>B</span>");
+        assert_eq!(output.html, "<!--[--><span>A</span><span>B</span><!--]-->");
+    }
+
+    #[test]
+    fn test_render_hydration_markers() {
+        use react_rs_core::signal::create_signal;
+        use react_rs_elements::reactive::SignalExt;
+
+        let (count, _) = create_signal(0);
+        let node = p()
+            .text("Count: ")
+            .text_reactive(count.map(|n| n.to_string()))
+            .child(None::<Node>)
+            .into_node();
+        assert_eq!(
+            render_to_string(&node).html,
+            "<p>Count: <!--t-->0<!--/t--><!--[--><!--]--></p>"
+        );
     }
 
     #[test]
@@ -249,4 +724,161 @@ mod tests {
         assert!(output.html.contains("<a href=\"/\">Home</a>"));
         assert!(output.html.contains("</html>"));
     }
+
+    #[test]
+    fn test_render_dyn_view() {
+        let node = div()
+            .child(react_rs_elements::dyn_view(|| span().text("Tab")))
+            .into_node();
+        assert_eq!(
+            render_to_string(&node).html,
+            "<div><span data-dyn data-hk=\"0\" style=\"display:contents\"><span>Tab</span></span></div>"
+        );
+    }
+
+    #[test]
+    fn test_render_collects_heads() {
+        let page = div()
+            .child(Head::new().title("Site").description("Layout"))
+            .child(div().child(Head::new().title("Post").description("Page")))
+            .into_node();
+        let output = render_to_string(&page);
+
+        assert_eq!(output.html, "<div><div></div></div>");
+        assert_eq!(output.head.title.as_deref(), Some("Post"));
+        assert_eq!(output.head.meta_tags.len(), 1);
+        assert_eq!(output.head.meta_tags[0].content, "Page");
+    }
+
+    #[test]
+    fn test_render_collects_extracted_styles() {
+        let button = || {
+            react_rs_elements::style()
+                .color("white")
+                .hover(|s| s.color("black"))
+        };
+        let class = button().class_name();
+        let page = div()
+            .child(react_rs_elements::button().styled(button()))
+            .child(react_rs_elements::button().styled(button()))
+            .into_node();
+        let output = render_to_string(&page);
+
+        assert!(output
+            .html
+            .contains(&format!("<button class=\"{}\">", class)));
+        assert_eq!(output.head.styles.len(), 1);
+        assert!(output
+            .head
+            .to_html()
+            .contains(&format!(".{}:hover {{ color: black }}</style>", class)));
+    }
+
+    #[test]
+    fn test_portal_renders_placeholder() {
+        let node = div()
+            .child(react_rs_elements::portal("body", p().text("Toast")))
+            .into_node();
+        let output = render_to_string(&node);
+        assert!(output.html.contains("data-portal"));
+        assert!(!output.html.contains("Toast"));
+        assert_eq!(
+            output.portals,
+            vec![RenderedPortal {
+                target: "body".to_string(),
+                html: "<p>Toast</p>".to_string(),
+            }]
+        );
+        assert_eq!(
+            output.portals_html(),
+            "<div data-portal-root data-ssr><p>Toast</p></div>"
+        );
+        assert!(output.html.contains("data-portal data-hk=\"0\""));
+        assert_eq!(output.hydration.key_count, 1);
+    }
+
+    #[test]
+    fn test_error_boundary_catches_render_panic() {
+        let user = react_rs_core::resource::create_manual_resource::<String>();
+        user.set_ready("Ada".to_string());
+        let page = div()
+            .child(react_rs_elements::error_boundary(
+                &user,
+                |error| p().text(format!("Failed: {}", error)).into_node(),
+                div()
+                    .child(Head::new().title("Broken"))
+                    .child(react_rs_elements::dyn_view(|| -> Node {
+                        panic!("profile unavailable")
+                    })),
+            ))
+            .child(p().text("Footer"))
+            .into_node();
+        let output = render_to_string(&page);
+
+        assert!(output
+            .html
+            .contains("<p>Failed: profile unavailable</p></span><p>Footer</p>"));
+        assert_eq!(output.head.title, None);
+        assert_eq!(output.errors.len(), 1);
+        assert_eq!(output.errors[0].message, "profile unavailable");
+        assert_eq!(output.errors[0].digest.len(), 8);
+    }
+
+    #[test]
+    fn test_render_hydration_manifest() {
+        let (items, _) = react_rs_core::create_signal(vec!["a".to_string()]);
+        let page = div()
+            .child(button().text("Save").on_click(|_| {}))
+            .child(react_rs_elements::each(items, |item, _| {
+                li().text(item).into_node()
+            }))
+            .child(
+                div()
+                    .attr("data-client", "")
+                    .attr("data-component-id", "Counter")
+                    .attr("data-props", "{\"start\":1}")
+                    // Markup the renderer cannot see into does not shift any key.
+                    .inner_html("<!-- <i> --><b>a<b</b><table><tr><td>0</td></tr></table>"),
+            )
+            .child(input().on_input(|_| {}))
+            .into_node();
+        let output = render_to_string(&page);
+        let manifest = output.hydration;
+
+        assert!(output
+            .html
+            .contains("<button data-hk=\"0\">Save</button><span data-list data-hk=\"1\""));
+        assert!(output.html.contains("<input data-hk=\"3\">"));
+        assert_eq!(manifest.key_count, 4);
+        assert_eq!(
+            manifest.handlers,
+            vec![
+                HandlerSite {
+                    key: 0,
+                    events: vec!["click".to_string()],
+                },
+                HandlerSite {
+                    key: 3,
+                    events: vec!["input".to_string()],
+                },
+            ]
+        );
+        assert_eq!(
+            manifest.regions,
+            vec![Region {
+                key: 1,
+                kind: "data-list",
+            }]
+        );
+        assert_eq!(manifest.client_components[0].key, 2);
+        assert_eq!(manifest.client_components[0].id, "Counter");
+        assert_eq!(manifest.selector(2), "[data-hk=\"2\"]");
+        assert_eq!(
+            manifest.to_json(),
+            "{\"handlers\":[{\"key\":0,\"events\":[\"click\"]},{\"key\":3,\"events\":[\"input\"]}],\"clientComponents\":[{\"key\":2,\"id\":\"Counter\",\"props\":\"{\\\"start\\\":1}\"}],\"regions\":[{\"key\":1,\"kind\":\"data-list\"}],\"keyCount\":4,\"keyPrefix\":\"\"}"
+        );
+
+        let plain = render_to_static_markup(&div().child(button().on_click(|_| {})).into_node());
+        assert_eq!(plain.html, "<div><button></button></div>");
+    }
 }
@@ -0,0 +1,310 @@
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::io::{self, Write};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::task::{Context, Wake, Waker};
+use std::thread::Thread;
+
+use react_rs_elements::head::Head;
+use react_rs_elements::node::Node;
+use react_rs_elements::suspense::SuspenseData;
+
+use crate::render::{render_collecting, RenderOutput};
+
+/// A suspense boundary that was still loading when the shell rendered. Its placeholder is
+/// `<template id="S:{id}"></template><span id="P:{id}">fallback</span>`, the shape
+/// `next-rs-server`'s `HtmlStream::suspense_replacement` swaps content into.
+pub struct PendingBoundary {
+    id: usize,
+    loading: Rc<dyn Fn() -> bool>,
+    children: Rc<Node>,
+}
+
+impl PendingBoundary {
+    /// The id in the placeholder's `S:` and `P:` element ids.
+    pub fn id(&self) -> String {
+        self.id.to_string()
+    }
+
+    pub fn is_loading(&self) -> bool {
+        (self.loading)()
+    }
+
+    /// Keeps the hydration keys of the boundary's content apart from the shell's.
+    fn key_prefix(&self) -> String {
+        format!("{}:", self.id)
+    }
+
+    /// Renders the boundary's content in its current state, wrapped in a single
+    /// `display:contents` element so it can replace the placeholder as one node.
+    pub fn render(&self) -> RenderOutput {
+        let output = without_deferral(|| render_collecting(&self.children, &self.key_prefix()));
+        RenderOutput {
+            html: format!("<span style=\"display:contents\">{}</span>", output.html),
+            ..output
+        }
+    }
+
+    /// Waits for the queued tasks, such as the resources the boundary is loading, then
+    /// renders it.
+    pub async fn resolve(self) -> RenderOutput {
+        react_rs_core::task::run_pending_tasks().await;
+        self.render()
+    }
+}
+
+thread_local! {
+    /// Boundaries deferred by the render in progress; `None` renders them in place.
+    static DEFERRED: RefCell<Option<Vec<PendingBoundary>>> = const { RefCell::new(None) };
+    static NEXT_ID: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Swaps a finished boundary's content in for its placeholder.
+const SWAP_SCRIPT: &str = "(function(i){var g=function(k){return document.getElementById(k+i)},r=g(\"R:\"),p=g(\"P:\"),t=g(\"S:\");if(r&&p){while(r.firstChild)p.parentNode.insertBefore(r.firstChild,p);p.remove();r.remove();if(t)t.remove()}})";
+
+/// The first part of a streamed page: everything outside loading suspense boundaries, with
+/// their fallbacks in place.
+pub struct StreamingShell {
+    pub output: RenderOutput,
+    pending: Vec<PendingBoundary>,
+}
+
+/// Renders `node` without waiting for data, deferring every suspense boundary that is still
+/// loading. Write `output.html` to the client, then call [`StreamingShell::stream_rest`].
+pub fn render_shell(node: &Node) -> StreamingShell {
+    let (output, pending) = with_deferral(|| render_collecting(node, ""));
+    StreamingShell { output, pending }
+}
+
+/// Writes `node` as HTML into `writer`, flushing the shell first and then each suspense
+/// boundary as its resources resolve, so the page can be sent before its slowest data is
+/// ready. Returns the heads rendered anywhere in the tree.
+pub fn render_to_stream<W: Write>(node: &Node, writer: &mut W) -> io::Result<Head> {
+    let shell = render_shell(node);
+    writer.write_all(shell.output.html.as_bytes())?;
+    writer.flush()?;
+    let shell_head = shell.output.head.clone();
+    let rest = shell.stream_rest(writer)?;
+    Ok(Head::merge_all([&shell_head, &rest]))
+}
+
+impl StreamingShell {
+    /// Whether any boundary is waiting to be streamed.
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// The deferred boundaries, for servers that stream them with their own chunk format
+    /// instead of [`StreamingShell::stream_rest`].
+    pub fn into_pending(self) -> Vec<PendingBoundary> {
+        self.pending
+    }
+
+    /// Drives pending tasks and writes each deferred boundary as soon as it stops loading,
+    /// as hidden markup plus a script that swaps it in for the fallback. Boundaries nested in
+    /// streamed content are deferred in turn. Returns the heads rendered by the streamed
+    /// content.
+    pub fn stream_rest<W: Write>(self, writer: &mut W) -> io::Result<Head> {
+        let mut pending = self.pending;
+        let mut heads = Vec::new();
+        let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        let mut run = Box::pin(react_rs_core::task::run_pending_tasks());
+
+        while !pending.is_empty() {
+            let settled = run.as_mut().poll(&mut cx).is_ready();
+            // Once no task is left to resolve them, boundaries render whatever they have.
+            let (ready, waiting): (Vec<_>, Vec<_>) = pending
+                .into_iter()
+                .partition(|boundary| settled || !(boundary.loading)());
+            pending = waiting;
+
+            for boundary in &ready {
+                let (output, nested) =
+                    with_deferral(|| render_collecting(&boundary.children, &boundary.key_prefix()));
+                pending.extend(nested);
+                heads.push(output.head);
+                write!(
+                    writer,
+                    "<div hidden id=\"R:{id}\">{html}</div><script>{swap}({id})</script>",
+                    id = boundary.id,
+                    html = output.html,
+                    swap = SWAP_SCRIPT,
+                )?;
+                writer.flush()?;
+            }
+
+            if settled {
+                run = Box::pin(react_rs_core::task::run_pending_tasks());
+            } else if ready.is_empty() {
+                std::thread::park();
+            }
+        }
+        Ok(Head::merge_all(&heads))
+    }
+}
+
+pub(crate) fn deferred_len() -> usize {
+    DEFERRED.with(|deferred| deferred.borrow().as_ref().map_or(0, Vec::len))
+}
+
+/// Drops boundaries deferred after the first `len`, e.g. by a render that panicked.
+pub(crate) fn truncate_deferred(len: usize) {
+    DEFERRED.with(|deferred| {
+        if let Some(deferred) = deferred.borrow_mut().as_mut() {
+            deferred.truncate(len);
+        }
+    });
+}
+
+/// Records `boundary` for streaming if a streaming render is in progress, returning the id
+/// its placeholder should carry.
+pub(crate) fn defer(boundary: &SuspenseData) -> Option<usize> {
+    DEFERRED.with(|deferred| {
+        let mut deferred = deferred.borrow_mut();
+        let deferred = deferred.as_mut()?;
+        let id = NEXT_ID.with(|next| next.replace(next.get() + 1));
+        deferred.push(PendingBoundary {
+            id,
+            loading: boundary.loading_signal.clone(),
+            children: boundary.children.clone(),
+        });
+        Some(id)
+    })
+}
+
+pub(crate) fn placeholder(id: usize, fallback: &str) -> String {
+    format!(
+        "<template id=\"S:{id}\"></template><span id=\"P:{id}\" style=\"display:contents\">{fallback}</span>",
+        id = id,
+        fallback = fallback
+    )
+}
+
+/// Runs `f` with suspense boundaries rendered in place, as [`render_to_string`] does.
+///
+/// [`render_to_string`]: crate::render_to_string
+pub(crate) fn without_deferral<T>(f: impl FnOnce() -> T) -> T {
+    let outer = DEFERRED.with(|deferred| deferred.take());
+    let result = f();
+    DEFERRED.with(|deferred| *deferred.borrow_mut() = outer);
+    result
+}
+
+fn with_deferral<T>(f: impl FnOnce() -> T) -> (T, Vec<PendingBoundary>) {
+    let outer = DEFERRED.with(|deferred| deferred.replace(Some(Vec::new())));
+    let result = f();
+    let deferred = DEFERRED.with(|deferred| deferred.replace(outer));
+    (result, deferred.unwrap_or_default())
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use react_rs_core::resource::create_manual_resource;
+    use react_rs_elements::html::*;
+    use react_rs_elements::node::IntoNode;
+    use react_rs_elements::reactive::SignalExt;
+    use react_rs_elements::suspense::suspense;
+
+    /// Records each write separately so tests can see what was flushed when.
+    #[derive(Default)]
+    struct Chunks(Vec<String>);
+
+    impl Write for Chunks {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.push(String::from_utf8_lossy(buf).into_owned());
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_stream_without_suspense_matches_string() {
+        let page = div().class("app").child(p().text("Hello")).into_node();
+        let mut chunks = Chunks::default();
+        render_to_stream(&page, &mut chunks).unwrap();
+        assert_eq!(chunks.0.concat(), crate::render_to_string(&page).html);
+    }
+
+    #[test]
+    fn test_stream_defers_loading_boundary() {
+        let user = react_rs_core::create_resource(
+            || 7,
+            |id| async move { Ok::<_, String>(format!("User {}", id)) },
+        );
+        let name = user
+            .state()
+            .map(|state| state.data().cloned().unwrap_or_default());
+        let page = div()
+            .child(h1().text("Profile"))
+            .child(suspense(
+                &user,
+                p().text("Loading..."),
+                p().text_reactive(name),
+            ))
+            .into_node();
+
+        let shell = render_shell(&page);
+        assert!(shell.has_pending());
+        assert!(shell.output.html.contains("<h1>Profile</h1>"));
+        assert!(shell
+            .output
+            .html
+            .contains("style=\"display:contents\"><p>Loading...</p></span>"));
+
+        let mut chunks = Chunks::default();
+        shell.stream_rest(&mut chunks).unwrap();
+        let rest = chunks.0.concat();
+        assert!(rest.contains("<div hidden id=\"R:"));
+        assert!(rest.contains("<p><!--t-->User 7<!--/t--></p></div><script>"));
+    }
+
+    #[test]
+    fn test_pending_boundaries_for_external_streaming() {
+        let user = create_manual_resource::<String>();
+        let name = user
+            .state()
+            .map(|state| state.data().cloned().unwrap_or_default());
+        let page = suspense(&user, p().text("Loading..."), p().text_reactive(name));
+
+        let shell = render_shell(&page);
+        assert!(shell.output.html.contains(
+            "<template id=\"S:0\"></template><span id=\"P:0\" style=\"display:contents\"><p>Loading...</p></span>"
+        ));
+
+        let pending = shell.into_pending();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id(), "0");
+        assert!(pending[0].is_loading());
+
+        user.set_ready("Ada".to_string());
+        assert!(!pending[0].is_loading());
+        assert_eq!(
+            pending[0].render().html,
+            "<span style=\"display:contents\"><p><!--t-->Ada<!--/t--></p></span>"
+        );
+    }
+
+    #[test]
+    fn test_render_to_string_renders_fallback_in_place() {
+        let user = create_manual_resource::<String>();
+        let page = suspense(&user, p().text("Loading..."), p().text("Done"));
+        assert_eq!(
+            crate::render_to_string(&page).html,
+            "<span data-suspense data-hk=\"0\" style=\"display:contents\"><p>Loading...</p></span>"
+        );
+    }
+}
@@ -41,6 +41,17 @@ impl Attribute {
         }
     }
 
+    /// Whether the name can be written into markup without ending the tag or starting
+    /// another attribute. Renderers skip attributes that fail this check.
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty()
+            && !name.chars().any(|c| {
+                c.is_whitespace()
+                    || c.is_control()
+                    || matches!(c, '"' | '\'' | '>' | '<' | '/' | '=' | '`')
+            })
+    }
+
     pub fn to_static_value(&self) -> String {
         match &self.value {
             AttributeValue::String(s) => s.clone(),
@@ -0,0 +1,123 @@
+use std::rc::Rc;
+
+use crate::attributes::AttributeValue;
+use crate::reactive::{IntoReactiveBool, IntoReactiveString, ReactiveValue};
+
+/// A `class` attribute assembled from static and conditional fragments. Only the fragments
+/// whose condition currently holds are rendered, so
+/// `classes().class("btn").class_if(active, "active")` renders `btn` or `btn active`.
+#[derive(Clone, Default)]
+pub struct ClassList {
+    fragments: Vec<(ReactiveValue<String>, ReactiveValue<bool>)>,
+}
+
+impl ClassList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn class(mut self, class: impl IntoReactiveString) -> Self {
+        self.fragments
+            .push((class.into_reactive_string(), ReactiveValue::Static(true)));
+        self
+    }
+
+    pub fn class_if(mut self, condition: impl IntoReactiveBool, class: &str) -> Self {
+        self.fragments.push((
+            ReactiveValue::Static(class.to_string()),
+            condition.into_reactive_bool(),
+        ));
+        self
+    }
+
+    pub fn is_static(&self) -> bool {
+        self.fragments.iter().all(|(class, condition)| {
+            matches!(class, ReactiveValue::Static(_))
+                && matches!(condition, ReactiveValue::Static(_))
+        })
+    }
+
+    /// The space-separated classes that currently apply.
+    pub fn get(&self) -> String {
+        self.fragments
+            .iter()
+            .filter(|(_, condition)| condition.get())
+            .map(|(class, _)| class.get())
+            .filter(|class| !class.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub(crate) fn from_attribute(value: &AttributeValue) -> Self {
+        match value {
+            AttributeValue::String(s) => Self::new().class(s.as_str()),
+            AttributeValue::ReactiveString(r) => Self::new().class(r.clone()),
+            AttributeValue::Bool(_) | AttributeValue::ReactiveBool(_) => Self::new(),
+        }
+    }
+
+    pub(crate) fn into_attribute_value(self) -> AttributeValue {
+        if self.is_static() {
+            AttributeValue::String(self.get())
+        } else {
+            AttributeValue::ReactiveString(self.into_reactive_string())
+        }
+    }
+}
+
+impl IntoReactiveString for ClassList {
+    fn into_reactive_string(self) -> ReactiveValue<String> {
+        ReactiveValue::Dynamic(Rc::new(move || self.get()))
+    }
+}
+
+pub fn classes() -> ClassList {
+    ClassList::new()
+}
+
+/// Builds a [`ClassList`] from static classes and `class => condition` pairs:
+///
+/// ```ignore
+/// div().classes(classes!["btn", "active" => is_active, "disabled" => false])
+/// ```
+#[macro_export]
+macro_rules! classes {
+    ($($class:expr $(=> $condition:expr)?),* $(,)?) => {{
+        let list = $crate::class_list::ClassList::new();
+        $(let list = $crate::classes!(@fragment list, $class $(, $condition)?);)*
+        list
+    }};
+    (@fragment $list:ident, $class:expr) => {
+        $list.class($class)
+    };
+    (@fragment $list:ident, $class:expr, $condition:expr) => {
+        $list.class_if($condition, $class)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use react_rs_core::signal::create_signal;
+
+    #[test]
+    fn test_static_classes_join() {
+        let list = classes()
+            .class("btn")
+            .class_if(true, "primary")
+            .class_if(false, "ghost");
+        assert!(list.is_static());
+        assert_eq!(list.get(), "btn primary");
+    }
+
+    #[test]
+    fn test_conditional_class_follows_signal() {
+        let (active, set_active) = create_signal(false);
+        let list = crate::classes!["tab", "active" => active];
+        assert!(!list.is_static());
+        assert_eq!(list.get(), "tab");
+
+        set_active.set(true);
+        assert_eq!(list.get(), "tab active");
+    }
+}
@@ -1,4 +1,11 @@
-use crate::node::Node;
+//! Components are plain functions returning a [`Node`]. Content a component wraps is taken as
+//! `impl IntoNode` parameters: `children` for the main content and one parameter per named
+//! slot, so `card(children)` and `modal(header, body)` accept elements, text, `Vec`s,
+//! [`Children`] or `Option`s alike. Inside the component, wrap each into a [`Slot`] to check
+//! whether it was filled or fall back to default content.
+
+use crate::node::{IntoNode, Node};
+use react_rs_core::Children;
 
 pub trait Component {
     type Props;
@@ -12,11 +19,65 @@ where
     render(props)
 }
 
+/// Collects nodes of different kinds into one [`Children`] list to pass to a component.
+pub fn children<I, N>(nodes: I) -> Children<Node>
+where
+    I: IntoIterator<Item = N>,
+    N: IntoNode,
+{
+    nodes.into_iter().map(IntoNode::into_node).collect()
+}
+
+/// Content passed into a component's slot. An empty slot (`None`, an empty `Vec` or
+/// fragment) renders nothing, so optional regions like footers can be left out entirely.
+pub struct Slot(Node);
+
+impl Slot {
+    pub fn new(content: impl IntoNode) -> Self {
+        Self(content.into_node())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        is_empty(&self.0)
+    }
+
+    /// The slot's content, or `fallback` when nothing was passed.
+    pub fn or(self, fallback: impl IntoNode) -> Node {
+        if self.is_empty() {
+            fallback.into_node()
+        } else {
+            self.0
+        }
+    }
+
+    /// Wraps filled content, e.g. in the element that frames it; an empty slot stays empty
+    /// instead of rendering an empty wrapper.
+    pub fn wrap<N: IntoNode>(self, wrapper: impl FnOnce(Node) -> N) -> Node {
+        if self.is_empty() {
+            self.0
+        } else {
+            wrapper(self.0).into_node()
+        }
+    }
+}
+
+impl IntoNode for Slot {
+    fn into_node(self) -> Node {
+        self.0
+    }
+}
+
+fn is_empty(node: &Node) -> bool {
+    match node {
+        Node::Fragment(children) => children.iter().all(is_empty),
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::html;
-    use crate::node::IntoNode;
 
     struct Greeting;
 
@@ -46,4 +107,50 @@ mod tests {
             _ => panic!("Expected Element node"),
         }
     }
+
+    fn card(children: impl IntoNode) -> Node {
+        html::div().class("card").child(children).into_node()
+    }
+
+    fn modal(header: impl IntoNode, body: impl IntoNode, footer: impl IntoNode) -> Node {
+        html::div()
+            .class("modal")
+            .child(Slot::new(header).or("Untitled"))
+            .child(body)
+            .child(Slot::new(footer).wrap(|footer| html::footer().child(footer)))
+            .into_node()
+    }
+
+    #[test]
+    fn test_children_slot_into_component() {
+        let node = card(children([
+            html::h2().text("Title").into_node(),
+            "Body".into_node(),
+        ]));
+        let Node::Element(el) = node else {
+            panic!("Expected Element node");
+        };
+        let Node::Fragment(slotted) = &el.get_children()[0] else {
+            panic!("Expected children as a fragment");
+        };
+        assert_eq!(slotted.len(), 2);
+        assert!(matches!(&slotted[1], Node::Text(text) if text == "Body"));
+    }
+
+    #[test]
+    fn test_named_slots_with_fallback_and_wrap() {
+        let Node::Element(empty) = modal(None::<Node>, "Body", Vec::<Node>::new()) else {
+            panic!("Expected Element node");
+        };
+        let children = empty.get_children();
+        assert!(matches!(&children[0], Node::Text(text) if text == "Untitled"));
+        assert!(matches!(&children[2], Node::Fragment(nodes) if nodes.is_empty()));
+
+        let Node::Element(filled) = modal("Confirm", "Body", html::button().text("OK")) else {
+            panic!("Expected Element node");
+        };
+        let children = filled.get_children();
+        assert!(matches!(&children[0], Node::Text(text) if text == "Confirm"));
+        assert!(matches!(&children[2], Node::Element(el) if el.tag() == "footer"));
+    }
 }
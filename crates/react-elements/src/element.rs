@@ -1,27 +1,97 @@
 use crate::attributes::Attribute;
-use crate::events::{Event, EventHandler};
+use crate::events::{Event, EventHandler, Modifiers};
 use crate::node::{IntoNode, Node};
-use crate::reactive::{IntoReactiveBool, IntoReactiveString};
+use crate::reactive::{IntoReactiveBool, IntoReactiveString, ReactiveValue};
+use std::borrow::Cow;
+use std::rc::Rc;
 
 pub struct Element {
-    tag: &'static str,
+    tag: Cow<'static, str>,
+    namespace: Option<&'static str>,
     attributes: Vec<Attribute>,
     children: Vec<Node>,
+    inner_html: Option<String>,
     event_handlers: Vec<EventHandler>,
+    scoped_styles: Vec<(String, String)>,
 }
 
 impl Element {
     pub fn new(tag: &'static str) -> Self {
         Self {
-            tag,
+            tag: Cow::Borrowed(tag),
+            namespace: None,
             attributes: Vec::new(),
             children: Vec::new(),
+            inner_html: None,
             event_handlers: Vec::new(),
+            scoped_styles: Vec::new(),
+        }
+    }
+
+    /// Creates an element in the given XML namespace, e.g. SVG or MathML.
+    pub fn new_ns(tag: &'static str, namespace: &'static str) -> Self {
+        Self {
+            namespace: Some(namespace),
+            ..Self::new(tag)
         }
     }
 
-    pub fn tag(&self) -> &'static str {
-        self.tag
+    /// Creates an element with a tag name only known at runtime, such as a web component
+    /// like `my-widget`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tag` is not a valid tag name; see [`Element::is_valid_tag`].
+    pub fn custom(tag: impl Into<String>) -> Self {
+        let tag = tag.into();
+        assert!(Self::is_valid_tag(&tag), "invalid tag name {:?}", tag);
+        Self {
+            tag: Cow::Owned(tag),
+            ..Self::new("")
+        }
+    }
+
+    /// Whether `tag` can be written into markup as-is: an ASCII letter followed by letters,
+    /// digits, `-`, `_`, `.` or `:`.
+    pub fn is_valid_tag(tag: &str) -> bool {
+        let mut chars = tag.chars();
+        chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+            && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+    }
+
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Whether this is an HTML void element such as `<img>` or `<input>`, which is written
+    /// without a closing tag and cannot have content.
+    pub fn is_void(&self) -> bool {
+        self.namespace.is_none() && Self::is_void_tag(&self.tag)
+    }
+
+    /// Whether `tag` names an HTML void element.
+    pub fn is_void_tag(tag: &str) -> bool {
+        matches!(
+            tag,
+            "area"
+                | "base"
+                | "br"
+                | "col"
+                | "embed"
+                | "hr"
+                | "img"
+                | "input"
+                | "link"
+                | "meta"
+                | "param"
+                | "source"
+                | "track"
+                | "wbr"
+        )
+    }
+
+    pub fn namespace(&self) -> Option<&'static str> {
+        self.namespace
     }
 
     pub fn attributes(&self) -> &[Attribute] {
@@ -32,6 +102,17 @@ impl Element {
         &self.children
     }
 
+    /// `(class, rules)` pairs extracted by [`Element::styled`], to be added to the document's
+    /// stylesheet once per class.
+    pub fn scoped_styles(&self) -> &[(String, String)] {
+        &self.scoped_styles
+    }
+
+    /// Markup set with [`Element::inner_html`], if any.
+    pub fn get_inner_html(&self) -> Option<&str> {
+        self.inner_html.as_deref()
+    }
+
     pub fn class(mut self, class: &str) -> Self {
         self.attributes.push(Attribute::new("class", class));
         self
@@ -45,6 +126,27 @@ impl Element {
         self
     }
 
+    /// Sets the `class` attribute from a [`ClassList`], replacing any classes set so far.
+    pub fn classes(mut self, list: crate::class_list::ClassList) -> Self {
+        self.attributes.retain(|attr| attr.name != "class");
+        self.attributes.push(Attribute {
+            name: "class".to_string(),
+            value: list.into_attribute_value(),
+        });
+        self
+    }
+
+    /// Adds `class` while `condition` holds, merged with the element's other classes.
+    pub fn class_if(self, condition: impl IntoReactiveBool, class: &str) -> Self {
+        let list = self
+            .attributes
+            .iter()
+            .find(|attr| attr.name == "class")
+            .map(|attr| crate::class_list::ClassList::from_attribute(&attr.value))
+            .unwrap_or_default();
+        self.classes(list.class_if(condition, class))
+    }
+
     pub fn visible_reactive(mut self, visible: impl IntoReactiveBool) -> Self {
         self.attributes.push(Attribute::reactive_bool(
             "data-visible",
@@ -63,12 +165,53 @@ impl Element {
         self
     }
 
-    pub fn styled(self, style: crate::style::Style) -> Self {
-        let css = style.to_css();
-        if css.is_empty() {
-            self
+    pub fn style_reactive(mut self, style: impl IntoReactiveString) -> Self {
+        self.attributes.push(Attribute::reactive_string(
+            "style",
+            style.into_reactive_string(),
+        ));
+        self
+    }
+
+    /// Applies `style` through a generated class: its static declarations and `hover`,
+    /// `focus` or `media` blocks become stylesheet rules shared by every element with the
+    /// same style, and only its reactive properties stay inline. Renderers emit the rules
+    /// from [`Element::scoped_styles`].
+    pub fn styled(mut self, style: crate::style::Style) -> Self {
+        let class = style.class_name();
+        let rules = style.static_rules(&format!(".{}", class));
+        if !rules.is_empty() {
+            self.scoped_styles.push((class.clone(), rules));
+            self = self.class_if(true, &class);
+        }
+        let reactive = style.into_reactive_part();
+        if reactive.is_reactive() {
+            self.style_reactive(reactive)
         } else {
-            self.style(&css)
+            self
+        }
+    }
+
+    /// Sets the CSS `view-transition-name`, merging it into any existing inline style.
+    pub fn view_transition_name(mut self, name: &str) -> Self {
+        let declaration = format!("view-transition-name: {}", name);
+        let existing = self.attributes.iter_mut().find(|attr| {
+            attr.name == "style"
+                && matches!(attr.value, crate::attributes::AttributeValue::String(_))
+        });
+        match existing {
+            Some(attr) => {
+                if let crate::attributes::AttributeValue::String(css) = &mut attr.value {
+                    let trimmed = css.trim_end().trim_end_matches(';');
+                    *css = if trimmed.is_empty() {
+                        declaration
+                    } else {
+                        format!("{}; {}", trimmed, declaration)
+                    };
+                }
+                self
+            }
+            None => self.style(&declaration),
         }
     }
 
@@ -77,6 +220,36 @@ impl Element {
         self
     }
 
+    /// Sets a boolean attribute such as `hidden`, written without a value when `true` and
+    /// left out when `false`.
+    pub fn attr_bool(mut self, name: &str, value: bool) -> Self {
+        self.attributes.push(Attribute::boolean(name, value));
+        self
+    }
+
+    /// Sets `name` only when `value` is `Some`.
+    pub fn attr_opt(self, name: &str, value: Option<impl AsRef<str>>) -> Self {
+        match value {
+            Some(value) => self.attr(name, value.as_ref()),
+            None => self,
+        }
+    }
+
+    /// Spreads caller-supplied attributes onto the element, e.g. the output of
+    /// `Image::render_attrs`. Each replaces an attribute of the same name set earlier.
+    pub fn attrs<K, V>(mut self, attrs: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        for (name, value) in attrs {
+            let name = name.into();
+            self.attributes.retain(|attr| attr.name != name);
+            self.attributes.push(Attribute::new(name, value));
+        }
+        self
+    }
+
     pub fn href(mut self, href: &str) -> Self {
         self.attributes.push(Attribute::new("href", href));
         self
@@ -143,45 +316,318 @@ impl Element {
             })
     }
 
+    /// Two-way binds the input value to `signal`, like [`Element::bind_value`]. Also works
+    /// for `<textarea>`.
+    pub fn bind(self, signal: react_rs_core::signal::RwSignal<String>) -> Self {
+        let (read, write) = signal.split();
+        self.bind_value(read, write)
+    }
+
+    /// Two-way binds a typed input, e.g. a quantity or price field, to `signal`. Input that
+    /// fails to parse leaves `signal` unchanged and puts the parse error in `error`. The text
+    /// the user typed is kept while it still parses to the signal's value, so `1.` or `01`
+    /// are not reformatted mid-edit.
+    pub fn bind_value_parsed<T>(
+        self,
+        signal: react_rs_core::signal::RwSignal<T>,
+        error: react_rs_core::signal::RwSignal<Option<String>>,
+    ) -> Self
+    where
+        T: std::str::FromStr + std::fmt::Display + PartialEq + Clone + 'static,
+        T::Err: std::fmt::Display,
+    {
+        let typed = std::rc::Rc::new(std::cell::RefCell::new(None::<String>));
+        let read = signal.clone();
+        let last = typed.clone();
+        let value = crate::reactive::ReactiveValue::Dynamic(std::rc::Rc::new(move || {
+            read.with(|current| match last.borrow().as_deref() {
+                Some(text) if text.parse::<T>().is_ok_and(|parsed| parsed == *current) => {
+                    text.to_string()
+                }
+                _ => current.to_string(),
+            })
+        }));
+
+        self.value_reactive(value).on_input(move |e| {
+            let text = e.value().to_string();
+            let parsed = text.parse::<T>();
+            *typed.borrow_mut() = Some(text);
+            match parsed {
+                Ok(parsed) => {
+                    error.set(None);
+                    signal.set(parsed);
+                }
+                Err(err) => error.set(Some(err.to_string())),
+            }
+        })
+    }
+
+    /// Binds the input to a [`Field`](crate::form::Field): its name and value, validation when
+    /// the user leaves it, and `aria-invalid` while it has an error.
+    pub fn bind_field(self, field: &crate::form::Field) -> Self {
+        use crate::reactive::SignalExt;
+        let (on_input, on_blur, invalid) = (field.clone(), field.clone(), field.clone());
+        self.name(field.name())
+            .value_reactive(field.value().map(|s| s.clone()))
+            .on_input(move |e| on_input.set(e.value()))
+            .on_blur(move |_| {
+                on_blur.validate();
+            })
+            .aria_reactive(
+                "invalid",
+                ReactiveValue::Dynamic(Rc::new(move || invalid.error().is_some().to_string())),
+            )
+    }
+
+    pub fn checked(mut self, checked: bool) -> Self {
+        self.attributes.push(Attribute::boolean("checked", checked));
+        self
+    }
+
+    pub fn checked_reactive(mut self, checked: impl IntoReactiveBool) -> Self {
+        self.attributes.push(Attribute::reactive_bool(
+            "checked",
+            checked.into_reactive_bool(),
+        ));
+        self
+    }
+
+    /// Two-way binds a checkbox's checked state to `signal`.
+    pub fn bind_checked(self, signal: react_rs_core::signal::RwSignal<bool>) -> Self {
+        let write = signal.clone();
+        self.checked_reactive(signal)
+            .on_change(move |e| write.set(e.checked()))
+    }
+
+    /// Two-way binds a `<select>`'s chosen option value to `signal`.
+    pub fn bind_selected(self, signal: react_rs_core::signal::RwSignal<String>) -> Self {
+        use crate::reactive::SignalExt;
+        self.value_reactive(signal.map(|s| s.clone()))
+            .on_change(move |e| signal.set(e.value().to_string()))
+    }
+
+    /// Binds one radio button of a group: it is checked while `signal` equals `option`, and
+    /// choosing it sets `signal` to `option`.
+    pub fn bind_group(self, signal: react_rs_core::signal::RwSignal<String>, option: &str) -> Self {
+        use crate::reactive::SignalExt;
+        let selected = option.to_string();
+        let chosen = option.to_string();
+        let write = signal.clone();
+        self.value(option)
+            .checked_reactive(signal.map(move |current| *current == selected))
+            .on_change(move |e| {
+                if e.checked() {
+                    write.set(chosen.clone());
+                }
+            })
+    }
+
     pub fn placeholder(mut self, placeholder: &str) -> Self {
         self.attributes
             .push(Attribute::new("placeholder", placeholder));
         self
     }
 
+    pub fn required(mut self, required: bool) -> Self {
+        self.attributes
+            .push(Attribute::boolean("required", required));
+        self
+    }
+
+    pub fn readonly(mut self, readonly: bool) -> Self {
+        self.attributes
+            .push(Attribute::boolean("readonly", readonly));
+        self
+    }
+
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.attributes
+            .push(Attribute::boolean("selected", selected));
+        self
+    }
+
+    pub fn autofocus(mut self, autofocus: bool) -> Self {
+        self.attributes
+            .push(Attribute::boolean("autofocus", autofocus));
+        self
+    }
+
+    pub fn multiple(mut self, multiple: bool) -> Self {
+        self.attributes
+            .push(Attribute::boolean("multiple", multiple));
+        self
+    }
+
+    /// Whether a `<details>` or `<dialog>` is open.
+    pub fn open(mut self, open: bool) -> Self {
+        self.attributes.push(Attribute::boolean("open", open));
+        self
+    }
+
+    pub fn open_reactive(mut self, open: impl IntoReactiveBool) -> Self {
+        self.attributes
+            .push(Attribute::reactive_bool("open", open.into_reactive_bool()));
+        self
+    }
+
     pub fn disabled(mut self, disabled: bool) -> Self {
         self.attributes
             .push(Attribute::boolean("disabled", disabled));
         self
     }
 
-    pub fn text(mut self, text: impl Into<String>) -> Self {
-        self.children.push(Node::Text(text.into()));
+    /// Sets `aria-{name}`, e.g. `aria("label", "Close")`.
+    pub fn aria(mut self, name: &str, value: &str) -> Self {
+        self.attributes
+            .push(Attribute::new(prefixed("aria-", name), value));
+        self
+    }
+
+    pub fn aria_reactive(mut self, name: &str, value: impl IntoReactiveString) -> Self {
+        self.attributes.push(Attribute::reactive_string(
+            prefixed("aria-", name),
+            value.into_reactive_string(),
+        ));
+        self
+    }
+
+    /// Sets `data-{name}`, readable in scripts and CSS as `[data-name]`.
+    pub fn data(mut self, name: &str, value: &str) -> Self {
+        self.attributes
+            .push(Attribute::new(prefixed("data-", name), value));
+        self
+    }
+
+    pub fn data_reactive(mut self, name: &str, value: impl IntoReactiveString) -> Self {
+        self.attributes.push(Attribute::reactive_string(
+            prefixed("data-", name),
+            value.into_reactive_string(),
+        ));
+        self
+    }
+
+    pub fn role(mut self, role: &str) -> Self {
+        self.attributes.push(Attribute::new("role", role));
         self
     }
 
-    pub fn text_reactive(mut self, text: impl IntoReactiveString) -> Self {
-        self.children
-            .push(Node::ReactiveText(text.into_reactive_string()));
+    pub fn aria_label(self, label: &str) -> Self {
+        self.aria("label", label)
+    }
+
+    pub fn aria_labelledby(self, id: &str) -> Self {
+        self.aria("labelledby", id)
+    }
+
+    pub fn aria_describedby(self, id: &str) -> Self {
+        self.aria("describedby", id)
+    }
+
+    pub fn aria_controls(self, id: &str) -> Self {
+        self.aria("controls", id)
+    }
+
+    pub fn aria_current(self, value: &str) -> Self {
+        self.aria("current", value)
+    }
+
+    pub fn aria_live(self, live: crate::types::AriaLive) -> Self {
+        self.aria("live", live.as_str())
+    }
+
+    pub fn aria_hidden(self, hidden: impl IntoReactiveBool) -> Self {
+        self.aria_state("hidden", hidden)
+    }
+
+    /// For disclosure widgets such as menus and accordions.
+    pub fn aria_expanded(self, expanded: impl IntoReactiveBool) -> Self {
+        self.aria_state("expanded", expanded)
+    }
+
+    pub fn aria_pressed(self, pressed: impl IntoReactiveBool) -> Self {
+        self.aria_state("pressed", pressed)
+    }
+
+    pub fn aria_selected(self, selected: impl IntoReactiveBool) -> Self {
+        self.aria_state("selected", selected)
+    }
+
+    pub fn aria_checked(self, checked: impl IntoReactiveBool) -> Self {
+        self.aria_state("checked", checked)
+    }
+
+    pub fn aria_disabled(self, disabled: impl IntoReactiveBool) -> Self {
+        self.aria_state("disabled", disabled)
+    }
+
+    /// ARIA states are the strings `"true"`/`"false"` rather than present/absent attributes.
+    fn aria_state(mut self, name: &str, value: impl IntoReactiveBool) -> Self {
+        let value = match value.into_reactive_bool() {
+            ReactiveValue::Static(b) => ReactiveValue::Static(b.to_string()),
+            ReactiveValue::Dynamic(f) => ReactiveValue::Dynamic(Rc::new(move || f().to_string())),
+        };
+        self.attributes
+            .push(Attribute::reactive_string(prefixed("aria-", name), value));
         self
     }
 
-    pub fn child(mut self, child: impl IntoNode) -> Self {
-        self.children.push(child.into_node());
+    /// Uses `html` as the element's content **without escaping**, like React's
+    /// `dangerouslySetInnerHTML`: it is written verbatim by the server and assigned to
+    /// `innerHTML` in the browser. Only pass trusted or sanitized markup, e.g. rendered
+    /// markdown or CMS output.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element already has children, since the two are mutually exclusive, or
+    /// is a void element.
+    pub fn inner_html(mut self, html: impl Into<String>) -> Self {
+        assert!(
+            self.children.is_empty(),
+            "<{}> cannot have both children and inner_html",
+            self.tag
+        );
+        assert!(!self.is_void(), "<{}> cannot have content", self.tag);
+        self.inner_html = Some(html.into());
         self
     }
 
+    pub fn text(self, text: impl Into<String>) -> Self {
+        self.push_child(Node::Text(text.into()))
+    }
+
+    pub fn text_reactive(self, text: impl IntoReactiveString) -> Self {
+        self.push_child(Node::ReactiveText(text.into_reactive_string()))
+    }
+
+    pub fn child(self, child: impl IntoNode) -> Self {
+        self.push_child(child.into_node())
+    }
+
     pub fn children<I, C>(mut self, children: I) -> Self
     where
         I: IntoIterator<Item = C>,
         C: IntoNode,
     {
         for child in children {
-            self.children.push(child.into_node());
+            self = self.push_child(child.into_node());
         }
         self
     }
 
+    /// # Panics
+    ///
+    /// Panics on void elements, whose children browsers would move out of them.
+    fn push_child(mut self, child: Node) -> Self {
+        assert!(
+            self.inner_html.is_none(),
+            "<{}> cannot have both inner_html and children",
+            self.tag
+        );
+        assert!(!self.is_void(), "<{}> cannot have children", self.tag);
+        self.children.push(child);
+        self
+    }
+
     pub fn on_click<F>(mut self, handler: F) -> Self
     where
         F: Fn(Event) + 'static,
@@ -209,6 +655,14 @@ impl Element {
         self
     }
 
+    pub fn on_blur<F: Fn(Event) + 'static>(self, handler: F) -> Self {
+        self.on("blur", handler)
+    }
+
+    pub fn on_focus<F: Fn(Event) + 'static>(self, handler: F) -> Self {
+        self.on("focus", handler)
+    }
+
     pub fn on_change<F>(mut self, handler: F) -> Self
     where
         F: Fn(Event) + 'static,
@@ -218,6 +672,176 @@ impl Element {
         self
     }
 
+    /// Registers `handler` for any DOM event type, e.g. `"animationend"`.
+    pub fn on<F>(mut self, event_type: &str, handler: F) -> Self
+    where
+        F: Fn(Event) + 'static,
+    {
+        self.event_handlers
+            .push(EventHandler::new(event_type, handler));
+        self
+    }
+
+    /// Like [`Element::on`], with registration modifiers such as
+    /// [`Modifiers::prevent_default`].
+    pub fn on_with<F>(mut self, event_type: &str, modifiers: Modifiers, handler: F) -> Self
+    where
+        F: Fn(Event) + 'static,
+    {
+        self.event_handlers
+            .push(EventHandler::new(event_type, handler).with_modifiers(modifiers));
+        self
+    }
+
+    pub fn on_click_with<F: Fn(Event) + 'static>(self, modifiers: Modifiers, handler: F) -> Self {
+        self.on_with("click", modifiers, handler)
+    }
+
+    pub fn on_submit_with<F: Fn(Event) + 'static>(self, modifiers: Modifiers, handler: F) -> Self {
+        self.on_with("submit", modifiers, handler)
+    }
+
+    /// Calls `preventDefault` before the most recently added handler runs, e.g.
+    /// `form().on_submit(save).prevent_default()`.
+    pub fn prevent_default(self) -> Self {
+        self.modify_last_handler(Modifiers::prevent_default())
+    }
+
+    /// Stops the most recently added handler's event from reaching ancestor handlers.
+    pub fn stop_propagation(self) -> Self {
+        self.modify_last_handler(Modifiers::stop_propagation())
+    }
+
+    /// Removes the most recently added handler after it first runs.
+    pub fn once(self) -> Self {
+        self.modify_last_handler(Modifiers::once())
+    }
+
+    /// Registers the most recently added handler as passive.
+    pub fn passive(self) -> Self {
+        self.modify_last_handler(Modifiers::passive())
+    }
+
+    fn modify_last_handler(mut self, modifiers: Modifiers) -> Self {
+        let handler = self
+            .event_handlers
+            .last_mut()
+            .expect("event modifiers must follow an event handler");
+        handler.modifiers = handler.modifiers | modifiers;
+        self
+    }
+
+    pub fn on_dblclick<F: Fn(Event) + 'static>(self, handler: F) -> Self {
+        self.on("dblclick", handler)
+    }
+
+    pub fn on_contextmenu<F: Fn(Event) + 'static>(self, handler: F) -> Self {
+        self.on("contextmenu", handler)
+    }
+
+    pub fn on_mousedown<F: Fn(Event) + 'static>(self, handler: F) -> Self {
+        self.on("mousedown", handler)
+    }
+
+    pub fn on_mouseup<F: Fn(Event) + 'static>(self, handler: F) -> Self {
+        self.on("mouseup", handler)
+    }
+
+    pub fn on_mousemove<F: Fn(Event) + 'static>(self, handler: F) -> Self {
+        self.on("mousemove", handler)
+    }
+
+    /// Fires only for this element, not when the pointer moves between its children.
+    pub fn on_mouseenter<F: Fn(Event) + 'static>(self, handler: F) -> Self {
+        self.on("mouseenter", handler)
+    }
+
+    pub fn on_mouseleave<F: Fn(Event) + 'static>(self, handler: F) -> Self {
+        self.on("mouseleave", handler)
+    }
+
+    pub fn on_pointerdown<F: Fn(Event) + 'static>(self, handler: F) -> Self {
+        self.on("pointerdown", handler)
+    }
+
+    pub fn on_pointerup<F: Fn(Event) + 'static>(self, handler: F) -> Self {
+        self.on("pointerup", handler)
+    }
+
+    pub fn on_pointermove<F: Fn(Event) + 'static>(self, handler: F) -> Self {
+        self.on("pointermove", handler)
+    }
+
+    pub fn on_touchstart<F: Fn(Event) + 'static>(self, handler: F) -> Self {
+        self.on("touchstart", handler)
+    }
+
+    pub fn on_touchmove<F: Fn(Event) + 'static>(self, handler: F) -> Self {
+        self.on("touchmove", handler)
+    }
+
+    pub fn on_touchend<F: Fn(Event) + 'static>(self, handler: F) -> Self {
+        self.on("touchend", handler)
+    }
+
+    pub fn on_wheel<F: Fn(Event) + 'static>(self, handler: F) -> Self {
+        self.on("wheel", handler)
+    }
+
+    pub fn on_keydown<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(Event) + 'static,
+    {
+        self.event_handlers
+            .push(EventHandler::new("keydown", handler));
+        self
+    }
+
+    pub fn on_keyup<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(Event) + 'static,
+    {
+        self.event_handlers
+            .push(EventHandler::new("keyup", handler));
+        self
+    }
+
+    pub fn on_keypress<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(Event) + 'static,
+    {
+        self.event_handlers
+            .push(EventHandler::new("keypress", handler));
+        self
+    }
+
+    /// Calls `handler` on keydown of `key` (an `Event::key` value such as `"Escape"`).
+    pub fn on_key<F>(self, key: &'static str, handler: F) -> Self
+    where
+        F: Fn(Event) + 'static,
+    {
+        self.on_keydown(move |event| {
+            if event.key() == key {
+                handler(event);
+            }
+        })
+    }
+
+    /// Calls `handler` when Enter is pressed, e.g. to submit a single input.
+    pub fn on_enter<F>(self, handler: F) -> Self
+    where
+        F: Fn(Event) + 'static,
+    {
+        self.on_key("Enter", handler)
+    }
+
+    pub fn on_escape<F>(self, handler: F) -> Self
+    where
+        F: Fn(Event) + 'static,
+    {
+        self.on_key("Escape", handler)
+    }
+
     pub fn show_when(self, condition: impl IntoReactiveBool) -> crate::node::Node {
         crate::node::Node::Conditional(
             condition.into_reactive_bool(),
@@ -249,6 +873,14 @@ impl Element {
     }
 }
 
+fn prefixed(prefix: &str, name: &str) -> String {
+    if name.starts_with(prefix) {
+        name.to_string()
+    } else {
+        format!("{}{}", prefix, name)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::html::*;
@@ -292,6 +924,241 @@ mod tests {
         let _element = div().text_reactive(count.map(|n| format!("Count: {}", n)));
     }
 
+    #[test]
+    fn test_view_transition_name() {
+        let element = div().view_transition_name("hero");
+        assert_eq!(
+            element.attributes()[0].to_static_value(),
+            "view-transition-name: hero"
+        );
+
+        let element = div().style("color: red;").view_transition_name("hero");
+        assert_eq!(element.attributes().len(), 1);
+        assert_eq!(
+            element.attributes()[0].to_static_value(),
+            "color: red; view-transition-name: hero"
+        );
+    }
+
+    #[test]
+    fn test_aria_role_and_data_attributes() {
+        let (open, set_open) = create_signal(false);
+        let element = button()
+            .role("switch")
+            .aria_label("Menu")
+            .aria("aria-controls", "menu")
+            .data("row-id", "42")
+            .aria_expanded(open.map(|o| *o))
+            .aria_hidden(false);
+
+        let attrs: Vec<(String, String)> = element
+            .attributes()
+            .iter()
+            .map(|a| (a.name.clone(), a.to_static_value()))
+            .collect();
+        let get = |name: &str| {
+            attrs
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(get("role"), Some("switch"));
+        assert_eq!(get("aria-label"), Some("Menu"));
+        assert_eq!(get("aria-controls"), Some("menu"));
+        assert_eq!(get("data-row-id"), Some("42"));
+        assert_eq!(get("aria-expanded"), Some("false"));
+        assert_eq!(get("aria-hidden"), Some("false"));
+
+        set_open.set(true);
+        let expanded = element
+            .attributes()
+            .iter()
+            .find(|a| a.name == "aria-expanded")
+            .unwrap();
+        assert_eq!(expanded.to_static_value(), "true");
+    }
+
+    #[test]
+    fn test_inner_html_is_kept_verbatim() {
+        let element = article().inner_html("<p>From <b>CMS</b></p>");
+        assert_eq!(element.get_inner_html(), Some("<p>From <b>CMS</b></p>"));
+        assert!(element.get_children().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot have both")]
+    fn test_inner_html_excludes_children() {
+        let _ = div().inner_html("<hr>").text("more");
+    }
+
+    #[test]
+    #[should_panic(expected = "<img> cannot have children")]
+    fn test_void_element_rejects_children() {
+        assert!(img().is_void());
+        assert!(!crate::svg::svg().is_void());
+        let _ = img().text("caption");
+    }
+
+    #[test]
+    fn test_on_enter_filters_keys() {
+        use crate::events::Event;
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let submitted = Rc::new(Cell::new(0));
+        let counter = submitted.clone();
+        let element = input().on_enter(move |_| counter.set(counter.get() + 1));
+        let handler = &element.event_handlers()[0];
+        assert_eq!(handler.event_type(), "keydown");
+
+        handler.invoke(Event::new("keydown").with_key("a", "KeyA"));
+        handler.invoke(Event::new("keydown").with_key("Enter", "Enter"));
+        assert_eq!(submitted.get(), 1);
+    }
+
+    #[test]
+    fn test_bind_value_parsed() {
+        use crate::events::Event;
+        use react_rs_core::signal::create_rw_signal;
+
+        let price = create_rw_signal(2.5_f64);
+        let error = create_rw_signal(None::<String>);
+        let field = input().bind_value_parsed(price.clone(), error.clone());
+        let value = field
+            .attributes()
+            .iter()
+            .find(|attr| attr.name == "value")
+            .unwrap();
+        assert_eq!(value.to_static_value(), "2.5");
+
+        let handler = &field.event_handlers()[0];
+        handler.invoke(Event::new("input").with_target_value("3.".to_string()));
+        assert_eq!(price.get(), 3.0);
+        assert_eq!(value.to_static_value(), "3.");
+
+        handler.invoke(Event::new("input").with_target_value("abc".to_string()));
+        assert_eq!(price.get(), 3.0);
+        assert!(error.get().is_some());
+
+        handler.invoke(Event::new("input").with_target_value("4".to_string()));
+        assert_eq!(error.get(), None);
+        price.set(10.0);
+        assert_eq!(value.to_static_value(), "10");
+    }
+
+    #[test]
+    fn test_bind_checked_and_group() {
+        use crate::events::Event;
+        use react_rs_core::signal::create_rw_signal;
+
+        let agreed = create_rw_signal(false);
+        let checkbox = input().bind_checked(agreed.clone());
+        checkbox.event_handlers()[0].invoke(Event::new("change").with_checked(true));
+        assert!(agreed.get());
+
+        let size = create_rw_signal("s".to_string());
+        let medium = input().type_("radio").bind_group(size.clone(), "m");
+        let checked = medium
+            .attributes()
+            .iter()
+            .find(|attr| attr.name == "checked")
+            .unwrap();
+        assert_eq!(checked.to_static_value(), "false");
+
+        medium.event_handlers()[0].invoke(Event::new("change").with_checked(true));
+        assert_eq!(size.get(), "m");
+        assert_eq!(checked.to_static_value(), "true");
+    }
+
+    #[test]
+    fn test_spread_and_optional_attributes() {
+        let element = img()
+            .attr("alt", "placeholder")
+            .attrs(vec![
+                ("alt".to_string(), "Photo".to_string()),
+                ("loading".to_string(), "lazy".to_string()),
+            ])
+            .attr_opt("width", Some("640"))
+            .attr_opt("height", None::<&str>);
+        let attrs: Vec<_> = element
+            .attributes()
+            .iter()
+            .map(|attr| (attr.name.as_str(), attr.to_static_value()))
+            .collect();
+        assert_eq!(
+            attrs,
+            vec![
+                ("alt", "Photo".to_string()),
+                ("loading", "lazy".to_string()),
+                ("width", "640".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_media_and_disclosure_elements() {
+        let view = details().open(true).child(summary().text("More")).child(
+            picture()
+                .child(source().attr("srcset", "/a.avif"))
+                .child(img().src("/a.jpg")),
+        );
+        assert_eq!(view.tag(), "details");
+        assert_eq!(view.attributes()[0].name, "open");
+        assert_eq!(view.get_children().len(), 2);
+
+        let field = input()
+            .required(true)
+            .readonly(false)
+            .autofocus(true)
+            .multiple(true);
+        let names: Vec<_> = field.attributes().iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["required", "readonly", "autofocus", "multiple"]);
+    }
+
+    #[test]
+    fn test_custom_tag() {
+        use crate::Element;
+
+        let widget = custom(format!("{}-{}", "my", "widget")).attr("size", "large");
+        assert_eq!(widget.tag(), "my-widget");
+        assert!(!Element::is_valid_tag("x><script"));
+        assert!(!Element::is_valid_tag("1abc"));
+        assert!(std::panic::catch_unwind(|| Element::custom("bad tag")).is_err());
+    }
+
+    #[test]
+    fn test_event_modifiers() {
+        use crate::events::Modifiers;
+
+        let element = form()
+            .on_submit(|_| {})
+            .prevent_default()
+            .once()
+            .on_click_with(Modifiers::stop_propagation(), |_| {});
+        let handlers = element.event_handlers();
+        assert_eq!(
+            handlers[0].modifiers(),
+            Modifiers::prevent_default() | Modifiers::once()
+        );
+        assert!(handlers[1].modifiers().stop_propagation);
+    }
+
+    #[test]
+    fn test_class_if_merges_with_static_class() {
+        let (active, set_active) = create_signal(false);
+        let element = button().class("btn").class_if(active, "active");
+        let classes: Vec<_> = element
+            .attributes()
+            .iter()
+            .filter(|attr| attr.name == "class")
+            .collect();
+        assert_eq!(classes.len(), 1);
+        assert_eq!(classes[0].to_static_value(), "btn");
+
+        set_active.set(true);
+        assert_eq!(classes[0].to_static_value(), "btn active");
+    }
+
     #[test]
     fn test_reactive_class() {
         let (active, _set_active) = create_signal(false);
@@ -1,7 +1,43 @@
+/// Modifier keys held while a keyboard or pointer event fired.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyModifiers {
+    pub alt: bool,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub meta: bool,
+}
+
+/// Coordinates and buttons for mouse, pointer and touch events. Touch events report the
+/// first changed touch.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PointerInfo {
+    pub client_x: f64,
+    pub client_y: f64,
+    pub page_x: f64,
+    pub page_y: f64,
+    /// Relative to the target element's padding edge.
+    pub offset_x: f64,
+    pub offset_y: f64,
+    /// The button that changed: 0 main, 1 auxiliary, 2 secondary.
+    pub button: i16,
+    /// Bitmask of the buttons held down.
+    pub buttons: u16,
+}
+
 pub struct Event {
     pub event_type: String,
     pub target_value: Option<String>,
     pub checked: Option<bool>,
+    /// The key value for keyboard events, e.g. `"Enter"` or `"a"`.
+    pub key: Option<String>,
+    /// The physical key for keyboard events, e.g. `"KeyA"`.
+    pub code: Option<String>,
+    pub modifiers: KeyModifiers,
+    /// Whether a keyboard event is an auto-repeat from a held key.
+    pub repeat: bool,
+    pub pointer: Option<PointerInfo>,
+    /// Scroll amounts `(x, y)` for wheel events.
+    pub wheel_delta: Option<(f64, f64)>,
 }
 
 impl Event {
@@ -10,9 +46,86 @@ impl Event {
             event_type: event_type.into(),
             target_value: None,
             checked: None,
+            key: None,
+            code: None,
+            modifiers: KeyModifiers::default(),
+            repeat: false,
+            pointer: None,
+            wheel_delta: None,
         }
     }
 
+    pub fn with_pointer(mut self, pointer: PointerInfo) -> Self {
+        self.pointer = Some(pointer);
+        self
+    }
+
+    pub fn with_wheel_delta(mut self, delta_x: f64, delta_y: f64) -> Self {
+        self.wheel_delta = Some((delta_x, delta_y));
+        self
+    }
+
+    /// Viewport coordinates of a mouse, pointer or touch event; `0.0` for other events.
+    pub fn client_x(&self) -> f64 {
+        self.pointer.map_or(0.0, |p| p.client_x)
+    }
+
+    pub fn client_y(&self) -> f64 {
+        self.pointer.map_or(0.0, |p| p.client_y)
+    }
+
+    pub fn offset_x(&self) -> f64 {
+        self.pointer.map_or(0.0, |p| p.offset_x)
+    }
+
+    pub fn offset_y(&self) -> f64 {
+        self.pointer.map_or(0.0, |p| p.offset_y)
+    }
+
+    pub fn button(&self) -> i16 {
+        self.pointer.map_or(0, |p| p.button)
+    }
+
+    pub fn with_key(mut self, key: impl Into<String>, code: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self.code = Some(code.into());
+        self
+    }
+
+    pub fn with_key_modifiers(mut self, modifiers: KeyModifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    pub fn with_repeat(mut self, repeat: bool) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    pub fn key(&self) -> &str {
+        self.key.as_deref().unwrap_or("")
+    }
+
+    pub fn code(&self) -> &str {
+        self.code.as_deref().unwrap_or("")
+    }
+
+    pub fn alt_key(&self) -> bool {
+        self.modifiers.alt
+    }
+
+    pub fn ctrl_key(&self) -> bool {
+        self.modifiers.ctrl
+    }
+
+    pub fn shift_key(&self) -> bool {
+        self.modifiers.shift
+    }
+
+    pub fn meta_key(&self) -> bool {
+        self.modifiers.meta
+    }
+
     pub fn with_target_value(mut self, value: String) -> Self {
         self.target_value = Some(value);
         self
@@ -26,10 +139,73 @@ impl Event {
     pub fn value(&self) -> &str {
         self.target_value.as_deref().unwrap_or("")
     }
+
+    /// Whether the checkbox or radio button that fired the event is checked.
+    pub fn checked(&self) -> bool {
+        self.checked.unwrap_or(false)
+    }
+}
+
+/// How a handler is registered, mirroring the DOM's listener options and Vue-style event
+/// modifiers. Combine them with `|`, e.g.
+/// `Modifiers::prevent_default() | Modifiers::stop_propagation()`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub prevent_default: bool,
+    pub stop_propagation: bool,
+    /// The handler is removed after it first runs.
+    pub once: bool,
+    /// The handler promises not to call `preventDefault`, letting the browser scroll without
+    /// waiting for it. Useful for touch and wheel handlers.
+    pub passive: bool,
+}
+
+impl Modifiers {
+    pub fn prevent_default() -> Self {
+        Self {
+            prevent_default: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn stop_propagation() -> Self {
+        Self {
+            stop_propagation: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn once() -> Self {
+        Self {
+            once: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn passive() -> Self {
+        Self {
+            passive: true,
+            ..Self::default()
+        }
+    }
+}
+
+impl std::ops::BitOr for Modifiers {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self {
+            prevent_default: self.prevent_default || other.prevent_default,
+            stop_propagation: self.stop_propagation || other.stop_propagation,
+            once: self.once || other.once,
+            passive: self.passive || other.passive,
+        }
+    }
 }
 
 pub struct EventHandler {
     pub event_type: String,
+    pub modifiers: Modifiers,
     handler: std::rc::Rc<dyn Fn(Event)>,
 }
 
@@ -40,14 +216,24 @@ impl EventHandler {
     {
         Self {
             event_type: event_type.into(),
+            modifiers: Modifiers::default(),
             handler: std::rc::Rc::new(handler),
         }
     }
 
+    pub fn with_modifiers(mut self, modifiers: Modifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
     pub fn event_type(&self) -> &str {
         &self.event_type
     }
 
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
     pub fn invoke(&self, event: Event) {
         (self.handler)(event);
     }
@@ -56,3 +242,41 @@ impl EventHandler {
         self.handler.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyboard_event_info() {
+        let event = Event::new("keydown")
+            .with_key("s", "KeyS")
+            .with_key_modifiers(KeyModifiers {
+                ctrl: true,
+                ..KeyModifiers::default()
+            });
+        assert_eq!(event.key(), "s");
+        assert_eq!(event.code(), "KeyS");
+        assert!(event.ctrl_key());
+        assert!(!event.shift_key());
+        assert_eq!(Event::new("click").key(), "");
+    }
+
+    #[test]
+    fn test_modifiers_combine() {
+        let modifiers = Modifiers::prevent_default() | Modifiers::once();
+        assert!(modifiers.prevent_default && modifiers.once);
+        assert!(!modifiers.stop_propagation && !modifiers.passive);
+    }
+
+    #[test]
+    fn test_pointer_event_info() {
+        let event = Event::new("pointermove").with_pointer(PointerInfo {
+            client_x: 10.0,
+            client_y: 20.0,
+            ..PointerInfo::default()
+        });
+        assert_eq!((event.client_x(), event.client_y()), (10.0, 20.0));
+        assert_eq!(Event::new("keydown").client_x(), 0.0);
+    }
+}
@@ -0,0 +1,313 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use react_rs_core::signal::{create_rw_signal, RwSignal};
+
+use crate::events::Event;
+
+/// Checks a field's value, returning the message to show when it is invalid.
+pub type Validator = Rc<dyn Fn(&str) -> Result<(), String>>;
+
+pub fn required(message: &str) -> Validator {
+    let message = message.to_string();
+    Rc::new(move |value: &str| {
+        if value.trim().is_empty() {
+            Err(message.clone())
+        } else {
+            Ok(())
+        }
+    })
+}
+
+pub fn min_length(min: usize, message: &str) -> Validator {
+    let message = message.to_string();
+    Rc::new(move |value: &str| {
+        if value.chars().count() < min {
+            Err(message.clone())
+        } else {
+            Ok(())
+        }
+    })
+}
+
+pub fn max_length(max: usize, message: &str) -> Validator {
+    let message = message.to_string();
+    Rc::new(move |value: &str| {
+        if value.chars().count() > max {
+            Err(message.clone())
+        } else {
+            Ok(())
+        }
+    })
+}
+
+/// One input registered with a [`Form`]. Handles are cheap to clone and share state.
+#[derive(Clone)]
+pub struct Field {
+    name: Rc<str>,
+    initial: Rc<RefCell<String>>,
+    value: RwSignal<String>,
+    touched: RwSignal<bool>,
+    error: RwSignal<Option<String>>,
+    validators: Rc<RefCell<Vec<Validator>>>,
+}
+
+impl Field {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            initial: Rc::new(RefCell::new(String::new())),
+            value: create_rw_signal(String::new()),
+            touched: create_rw_signal(false),
+            error: create_rw_signal(None),
+            validators: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    /// Sets the value the field starts from and resets to; dirtiness is measured against it.
+    pub fn initial(self, value: impl Into<String>) -> Self {
+        let value = value.into();
+        self.value.set(value.clone());
+        *self.initial.borrow_mut() = value;
+        self
+    }
+
+    pub fn validate_with(self, validator: Validator) -> Self {
+        self.validators.borrow_mut().push(validator);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The value signal, e.g. for [`Element::bind`](crate::Element::bind).
+    pub fn value(&self) -> RwSignal<String> {
+        self.value.clone()
+    }
+
+    pub fn get(&self) -> String {
+        self.value.get()
+    }
+
+    /// Sets the value, clearing any error reported by the server for the previous one and
+    /// re-validating if the user has already left the field.
+    pub fn set(&self, value: impl Into<String>) {
+        self.value.set(value.into());
+        if self.touched.get_untracked() {
+            self.validate();
+        } else {
+            self.error.set(None);
+        }
+    }
+
+    /// Whether the user has left the field since it was created or reset.
+    pub fn is_touched(&self) -> bool {
+        self.touched.get()
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.value.with(|value| *value != *self.initial.borrow())
+    }
+
+    /// The message to show next to the input: the last validation failure, or the error
+    /// reported by the server.
+    pub fn error(&self) -> Option<String> {
+        self.error.get()
+    }
+
+    /// Marks the field touched and runs its validators, recording the first failure.
+    pub fn validate(&self) -> bool {
+        self.touched.set(true);
+        let error = self.check();
+        let valid = error.is_none();
+        self.error.set(error);
+        valid
+    }
+
+    /// Sets an error from outside the validators, e.g. one returned by a server action.
+    pub fn set_error(&self, message: impl Into<String>) {
+        self.error.set(Some(message.into()));
+    }
+
+    pub fn reset(&self) {
+        self.value.set(self.initial.borrow().clone());
+        self.touched.set(false);
+        self.error.set(None);
+    }
+
+    fn check(&self) -> Option<String> {
+        let validators = self.validators.borrow().clone();
+        self.value.with(|value| {
+            validators
+                .iter()
+                .find_map(|validator| validator(value).err())
+        })
+    }
+}
+
+/// A set of [`Field`]s validated and submitted together.
+///
+/// ```ignore
+/// let signup = Form::new();
+/// let email = signup.field("email").validate_with(required("Email is required"));
+///
+/// form()
+///     .on_submit(signup.handle_submit(|values| save(values)))
+///     .prevent_default()
+///     .child(input().bind_field(&email))
+/// ```
+#[derive(Clone, Default)]
+pub struct Form {
+    fields: Rc<RefCell<Vec<Field>>>,
+}
+
+impl Form {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a field, or returns the existing one with the same name.
+    pub fn field(&self, name: &str) -> Field {
+        if let Some(field) = self.get(name) {
+            return field;
+        }
+        let field = Field::new(name);
+        self.fields.borrow_mut().push(field.clone());
+        field
+    }
+
+    pub fn get(&self, name: &str) -> Option<Field> {
+        self.fields
+            .borrow()
+            .iter()
+            .find(|field| field.name() == name)
+            .cloned()
+    }
+
+    /// Whether every field passes its validators and has no server error. Reading it inside
+    /// an effect tracks every field, so it suits a submit button's `disabled` state.
+    pub fn is_valid(&self) -> bool {
+        self.fields
+            .borrow()
+            .iter()
+            .all(|field| field.error().is_none() && field.check().is_none())
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.fields.borrow().iter().any(Field::is_dirty)
+    }
+
+    /// Validates every field, marking them all touched so their errors show.
+    pub fn validate(&self) -> bool {
+        let fields = self.fields.borrow().clone();
+        // Every field is validated, not just up to the first failure.
+        let results: Vec<bool> = fields.iter().map(Field::validate).collect();
+        results.into_iter().all(|valid| valid)
+    }
+
+    /// The current `(name, value)` pairs in registration order.
+    pub fn values(&self) -> Vec<(String, String)> {
+        self.fields
+            .borrow()
+            .iter()
+            .map(|field| (field.name().to_string(), field.value.get_untracked()))
+            .collect()
+    }
+
+    /// Shows per-field errors returned by a server action next to their inputs. Errors for
+    /// unregistered fields are ignored.
+    pub fn set_field_errors<K, V>(&self, errors: impl IntoIterator<Item = (K, V)>)
+    where
+        K: AsRef<str>,
+        V: Into<String>,
+    {
+        for (name, message) in errors {
+            if let Some(field) = self.get(name.as_ref()) {
+                field.set_error(message);
+            }
+        }
+    }
+
+    pub fn reset(&self) {
+        let fields = self.fields.borrow().clone();
+        for field in &fields {
+            field.reset();
+        }
+    }
+
+    /// A submit handler that validates the form and calls `on_valid` with its values only
+    /// when every field passes.
+    pub fn handle_submit<F>(&self, on_valid: F) -> impl Fn(Event) + 'static
+    where
+        F: Fn(Vec<(String, String)>) + 'static,
+    {
+        let form = self.clone();
+        move |_| {
+            if form.validate() {
+                on_valid(form.values());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_validation_and_state() {
+        let form = Form::new();
+        let name = form
+            .field("name")
+            .initial("Al")
+            .validate_with(required("Name is required"))
+            .validate_with(min_length(3, "Too short"));
+
+        assert!(!name.is_touched());
+        assert!(!name.is_dirty());
+        assert!(!form.is_valid());
+        assert_eq!(name.error(), None);
+
+        assert!(!form.validate());
+        assert!(name.is_touched());
+        assert_eq!(name.error().as_deref(), Some("Too short"));
+
+        name.set("");
+        assert_eq!(name.error().as_deref(), Some("Name is required"));
+
+        name.set("Alice");
+        assert!(name.is_dirty());
+        assert!(form.is_valid());
+
+        form.reset();
+        assert_eq!(name.get(), "Al");
+        assert!(!name.is_touched());
+    }
+
+    #[test]
+    fn test_submit_and_server_errors() {
+        let form = Form::new();
+        let email = form.field("email").validate_with(required("Required"));
+        let submitted = Rc::new(RefCell::new(None));
+
+        let sink = submitted.clone();
+        let submit = form.handle_submit(move |values| *sink.borrow_mut() = Some(values));
+        submit(Event::new("submit"));
+        assert!(submitted.borrow().is_none());
+
+        email.set("taken@example.com");
+        submit(Event::new("submit"));
+        assert_eq!(
+            submitted.borrow().clone().unwrap(),
+            vec![("email".to_string(), "taken@example.com".to_string())]
+        );
+
+        form.set_field_errors([("email", "Already registered"), ("unknown", "ignored")]);
+        assert_eq!(email.error().as_deref(), Some("Already registered"));
+        assert!(!form.is_valid());
+
+        email.set("new@example.com");
+        assert_eq!(email.error(), None);
+    }
+}
@@ -3,18 +3,136 @@ pub struct Head {
     pub title: Option<String>,
     pub meta_tags: Vec<MetaTag>,
     pub links: Vec<LinkTag>,
+    pub scripts: Vec<ScriptTag>,
+    pub styles: Vec<StyleTag>,
+    last: Option<TagKind>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TagKind {
+    Meta,
+    Link,
+    Script,
 }
 
 #[derive(Debug, Clone)]
 pub struct MetaTag {
     pub name: String,
     pub content: String,
+    /// Rendered as `property="..."` (Open Graph) rather than `name="..."`.
+    pub property: bool,
+    pub key: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct LinkTag {
     pub rel: String,
     pub href: String,
+    pub key: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScriptTag {
+    pub src: String,
+    pub strategy: ScriptStrategy,
+    pub key: Option<String>,
+}
+
+/// An inline `<style>` block, emitted once per key.
+#[derive(Debug, Clone)]
+pub struct StyleTag {
+    pub key: String,
+    pub css: String,
+}
+
+/// When a script loads, mirroring `next/script`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScriptStrategy {
+    /// Rendered into `<head>` and run before the page hydrates, for bot detection or consent
+    /// managers that must run first.
+    BeforeInteractive,
+    /// Rendered at the end of `<body>` and run once the page has parsed.
+    #[default]
+    AfterInteractive,
+    /// Loaded only after the window `load` event, for chat widgets and other low-priority
+    /// third-party code.
+    LazyOnLoad,
+}
+
+/// A third-party script with a loading strategy. Render it anywhere in the tree; it is
+/// collected with the page's [`Head`] and injected where its strategy requires.
+#[derive(Debug, Clone)]
+pub struct Script {
+    src: String,
+    strategy: ScriptStrategy,
+    key: Option<String>,
+}
+
+impl Script {
+    pub fn new(src: impl Into<String>) -> Self {
+        Self {
+            src: src.into(),
+            strategy: ScriptStrategy::default(),
+            key: None,
+        }
+    }
+
+    pub fn strategy(mut self, strategy: ScriptStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+}
+
+impl crate::node::IntoNode for Script {
+    fn into_node(self) -> crate::node::Node {
+        let mut head = Head::new().script(self.src, self.strategy);
+        if let Some(key) = self.key {
+            head = head.key(key);
+        }
+        crate::node::Node::Head(head)
+    }
+}
+
+/// Shorthand for [`Script::new`].
+pub fn next_script(src: impl Into<String>) -> Script {
+    Script::new(src)
+}
+
+/// Links that only make sense once per document, so a page's replaces its layout's.
+const SINGLETON_RELS: [&str; 3] = ["canonical", "icon", "manifest"];
+
+impl MetaTag {
+    /// Tags sharing a key replace each other when heads are merged.
+    pub fn dedupe_key(&self) -> String {
+        match &self.key {
+            Some(key) => key.clone(),
+            None if self.property => format!("property:{}", self.name),
+            None => format!("name:{}", self.name),
+        }
+    }
+}
+
+impl LinkTag {
+    pub fn dedupe_key(&self) -> String {
+        match &self.key {
+            Some(key) => key.clone(),
+            None if SINGLETON_RELS.contains(&self.rel.as_str()) => format!("link:{}", self.rel),
+            None => format!("link:{}:{}", self.rel, self.href),
+        }
+    }
+}
+
+impl ScriptTag {
+    pub fn dedupe_key(&self) -> String {
+        self.key
+            .clone()
+            .unwrap_or_else(|| format!("script:{}", self.src))
+    }
 }
 
 impl Head {
@@ -31,7 +149,22 @@ impl Head {
         self.meta_tags.push(MetaTag {
             name: name.into(),
             content: content.into(),
+            property: false,
+            key: None,
+        });
+        self.last = Some(TagKind::Meta);
+        self
+    }
+
+    /// An Open Graph `<meta property="og:...">`; `property` is given without the prefix.
+    pub fn og(mut self, property: &str, content: impl Into<String>) -> Self {
+        self.meta_tags.push(MetaTag {
+            name: format!("og:{}", property),
+            content: content.into(),
+            property: true,
+            key: None,
         });
+        self.last = Some(TagKind::Meta);
         self
     }
 
@@ -44,52 +177,175 @@ impl Head {
     }
 
     pub fn og_title(self, title: impl Into<String>) -> Self {
-        self.meta("og:title", title)
+        self.og("title", title)
     }
 
     pub fn og_description(self, desc: impl Into<String>) -> Self {
-        self.meta("og:description", desc)
+        self.og("description", desc)
     }
 
     pub fn og_image(self, url: impl Into<String>) -> Self {
-        self.meta("og:image", url)
+        self.og("image", url)
     }
 
-    pub fn link_stylesheet(mut self, href: impl Into<String>) -> Self {
-        self.links.push(LinkTag {
-            rel: "stylesheet".to_string(),
-            href: href.into(),
-        });
-        self
+    pub fn link_stylesheet(self, href: impl Into<String>) -> Self {
+        self.link("stylesheet", href)
     }
 
     pub fn link(mut self, rel: impl Into<String>, href: impl Into<String>) -> Self {
         self.links.push(LinkTag {
             rel: rel.into(),
             href: href.into(),
+            key: None,
         });
+        self.last = Some(TagKind::Link);
         self
     }
 
+    pub fn link_rel(self, rel: impl Into<String>, href: impl Into<String>) -> Self {
+        self.link(rel, href)
+    }
+
+    pub fn script_src(self, src: impl Into<String>) -> Self {
+        self.script(src, ScriptStrategy::AfterInteractive)
+    }
+
+    pub fn script(mut self, src: impl Into<String>, strategy: ScriptStrategy) -> Self {
+        self.scripts.push(ScriptTag {
+            src: src.into(),
+            strategy,
+            key: None,
+        });
+        self.last = Some(TagKind::Script);
+        self
+    }
+
+    /// Adds inline CSS under `key`; blocks sharing a key are emitted once.
+    pub fn inline_style(mut self, key: impl Into<String>, css: impl Into<String>) -> Self {
+        self.styles.push(StyleTag {
+            key: key.into(),
+            css: css.into(),
+        });
+        self
+    }
+
+    /// Sets the dedupe key of the most recently added tag, so a nested page's tag with the
+    /// same key replaces it, e.g. `.meta("theme-color", "#fff").key("theme")`.
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        let key = Some(key.into());
+        match self.last {
+            Some(TagKind::Meta) => self.meta_tags.last_mut().expect("meta tag").key = key,
+            Some(TagKind::Link) => self.links.last_mut().expect("link tag").key = key,
+            Some(TagKind::Script) => self.scripts.last_mut().expect("script tag").key = key,
+            None => panic!("Head::key must follow a meta, link or script tag"),
+        }
+        self
+    }
+
+    /// Combines heads from outermost layout to innermost page. Later heads win: they set the
+    /// title and replace earlier tags with the same dedupe key in place.
+    pub fn merge_all<'a>(heads: impl IntoIterator<Item = &'a Head>) -> Head {
+        let mut merged = Head::new();
+        for head in heads {
+            if head.title.is_some() {
+                merged.title = head.title.clone();
+            }
+            upsert(&mut merged.meta_tags, &head.meta_tags, MetaTag::dedupe_key);
+            upsert(&mut merged.links, &head.links, LinkTag::dedupe_key);
+            upsert(&mut merged.scripts, &head.scripts, ScriptTag::dedupe_key);
+            upsert(&mut merged.styles, &head.styles, |style| style.key.clone());
+        }
+        merged
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.meta_tags.is_empty()
+            && self.links.is_empty()
+            && self.scripts.is_empty()
+            && self.styles.is_empty()
+    }
+
     pub fn to_html(&self) -> String {
         let mut parts = Vec::new();
         if let Some(title) = &self.title {
-            parts.push(format!("<title>{}</title>", title));
+            parts.push(format!("<title>{}</title>", escape(title)));
         }
         for meta in &self.meta_tags {
             parts.push(format!(
-                "<meta name=\"{}\" content=\"{}\">",
-                meta.name, meta.content
+                "<meta {}=\"{}\" content=\"{}\">",
+                if meta.property { "property" } else { "name" },
+                escape(&meta.name),
+                escape(&meta.content)
             ));
         }
         for link in &self.links {
             parts.push(format!(
                 "<link rel=\"{}\" href=\"{}\">",
-                link.rel, link.href
+                escape(&link.rel),
+                escape(&link.href)
+            ));
+        }
+        for script in &self.scripts {
+            if script.strategy == ScriptStrategy::BeforeInteractive {
+                parts.push(format!("<script src=\"{}\"></script>", escape(&script.src)));
+            }
+        }
+        for style in &self.styles {
+            parts.push(format!(
+                "<style data-style=\"{}\">{}</style>",
+                escape(&style.key),
+                style.css.replace("</", "<\\/")
             ));
         }
         parts.join("\n    ")
     }
+
+    /// The scripts that belong at the end of `<body>`: `AfterInteractive` ones as deferred
+    /// tags and `LazyOnLoad` ones behind a `load` listener.
+    pub fn body_scripts_html(&self) -> String {
+        self.scripts
+            .iter()
+            .filter_map(|script| match script.strategy {
+                ScriptStrategy::BeforeInteractive => None,
+                ScriptStrategy::AfterInteractive => Some(format!(
+                    "<script src=\"{}\" defer></script>",
+                    escape(&script.src)
+                )),
+                ScriptStrategy::LazyOnLoad => Some(format!(
+                    "<script>addEventListener(\"load\",function(){{var s=document.createElement(\"script\");s.src={};document.body.appendChild(s)}})</script>",
+                    js_string(&script.src)
+                )),
+            })
+            .collect::<Vec<_>>()
+            .join("\n    ")
+    }
+}
+
+fn upsert<T: Clone>(target: &mut Vec<T>, incoming: &[T], key: fn(&T) -> String) {
+    for tag in incoming {
+        let tag_key = key(tag);
+        match target.iter_mut().find(|existing| key(existing) == tag_key) {
+            Some(existing) => *existing = tag.clone(),
+            None => target.push(tag.clone()),
+        }
+    }
+}
+
+fn js_string(s: &str) -> String {
+    format!(
+        "\"{}\"",
+        s.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('<', "\\u003c")
+    )
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
 }
 
 #[cfg(test)]
@@ -128,6 +384,56 @@ mod tests {
         assert!(html.contains("<link rel=\"stylesheet\" href=\"/styles.css\">"));
     }
 
+    #[test]
+    fn test_head_og_uses_property() {
+        let html = Head::new().og("type", "article").to_html();
+        assert_eq!(html, "<meta property=\"og:type\" content=\"article\">");
+    }
+
+    #[test]
+    fn test_merge_all_overrides_by_key() {
+        let layout = Head::new()
+            .title("Site")
+            .description("Site description")
+            .link("canonical", "/")
+            .link_stylesheet("/app.css")
+            .meta("theme-color", "#fff")
+            .key("theme")
+            .script_src("/analytics.js");
+        let page = Head::new()
+            .title("Post")
+            .description("Post description")
+            .link_rel("canonical", "/post")
+            .link_stylesheet("/post.css")
+            .meta("color-scheme", "dark")
+            .key("theme")
+            .script_src("/analytics.js");
+
+        let merged = Head::merge_all([&layout, &page]);
+        assert_eq!(merged.title.as_deref(), Some("Post"));
+        assert_eq!(merged.meta_tags.len(), 2);
+        assert_eq!(merged.meta_tags[0].content, "Post description");
+        assert_eq!(merged.meta_tags[1].name, "color-scheme");
+        assert_eq!(merged.links.len(), 3);
+        assert_eq!(merged.links[0].href, "/post");
+        assert_eq!(merged.scripts.len(), 1);
+    }
+
+    #[test]
+    fn test_script_strategies() {
+        let head = Head::new()
+            .script("/consent.js", ScriptStrategy::BeforeInteractive)
+            .script_src("/app-extra.js")
+            .script("/chat.js", ScriptStrategy::LazyOnLoad);
+
+        assert_eq!(head.to_html(), "<script src=\"/consent.js\"></script>");
+        let body = head.body_scripts_html();
+        assert!(body.contains("<script src=\"/app-extra.js\" defer></script>"));
+        assert!(body.contains("addEventListener(\"load\""));
+        assert!(body.contains("s.src=\"/chat.js\""));
+        assert!(!body.contains("consent"));
+    }
+
     #[test]
     fn test_head_empty() {
         let head = Head::new();
@@ -1,5 +1,10 @@
 use crate::Element;
 
+/// An element with any tag name, e.g. `custom("my-widget")` for a web component.
+pub fn custom(tag: impl Into<String>) -> Element {
+    Element::custom(tag)
+}
+
 pub fn html() -> Element {
     Element::new("html")
 }
@@ -114,6 +119,48 @@ pub fn video() -> Element {
 pub fn audio() -> Element {
     Element::new("audio")
 }
+pub fn source() -> Element {
+    Element::new("source")
+}
+pub fn picture() -> Element {
+    Element::new("picture")
+}
+pub fn canvas() -> Element {
+    Element::new("canvas")
+}
+pub fn iframe() -> Element {
+    Element::new("iframe")
+}
+pub fn figure() -> Element {
+    Element::new("figure")
+}
+pub fn figcaption() -> Element {
+    Element::new("figcaption")
+}
+pub fn dialog() -> Element {
+    Element::new("dialog")
+}
+pub fn details() -> Element {
+    Element::new("details")
+}
+pub fn summary() -> Element {
+    Element::new("summary")
+}
+pub fn fieldset() -> Element {
+    Element::new("fieldset")
+}
+pub fn legend() -> Element {
+    Element::new("legend")
+}
+pub fn datalist() -> Element {
+    Element::new("datalist")
+}
+pub fn progress() -> Element {
+    Element::new("progress")
+}
+pub fn meter() -> Element {
+    Element::new("meter")
+}
 
 pub fn table() -> Element {
     Element::new("table")
@@ -1,21 +1,34 @@
 pub mod attributes;
+pub mod class_list;
 pub mod component;
 pub mod element;
 pub mod events;
+pub mod form;
 pub mod head;
 pub mod html;
 pub mod node;
 pub mod reactive;
 pub mod style;
 pub mod suspense;
+pub mod svg;
+pub mod template;
+pub mod transition;
 pub mod types;
 
-pub use component::{component, Component};
+pub use class_list::{classes, ClassList};
+pub use component::{children, component, Component, Slot};
 pub use element::Element;
-pub use head::Head;
+pub use form::{Field, Form};
+pub use head::{next_script, Head, Script, ScriptStrategy};
 pub use html::*;
-pub use node::{each, each_keyed, IntoNode, Node};
+pub use node::{
+    dyn_view, each, each_keyed, each_keyed_vec, fragment, portal, show, when, DiffListData,
+    Fragment, IntoNode, ListChange, Node,
+};
+pub use react_rs_core::Children;
 pub use reactive::{IntoReactiveBool, IntoReactiveString, ReactiveValue, SignalExt};
-pub use style::{style, Style};
-pub use suspense::{error_boundary, suspense};
-pub use types::{FormMethod, InputType, LinkTarget};
+pub use style::{percent, px, style, Color, IntoCssValue, Length, Style};
+pub use suspense::{error_boundary, suspense, suspense_boundary};
+pub use template::Template;
+pub use transition::Transition;
+pub use types::{AriaLive, FormMethod, InputType, LinkTarget};
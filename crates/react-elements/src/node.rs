@@ -1,7 +1,12 @@
 use crate::head::Head;
-use crate::reactive::ReactiveValue;
+use crate::reactive::{IntoReactiveBool, ReactiveValue};
 use crate::suspense::{ErrorBoundaryData, SuspenseData};
+use crate::template::Template;
+use crate::transition::Transition;
 use crate::Element;
+use react_rs_core::effect::untrack;
+use react_rs_core::signal_vec::{SignalVec, VecDiff};
+use react_rs_core::Children;
 use std::rc::Rc;
 
 pub enum Node {
@@ -12,9 +17,20 @@ pub enum Node {
     Conditional(ReactiveValue<bool>, Box<Node>, Option<Box<Node>>),
     ReactiveList(Rc<dyn Fn() -> Vec<Node>>),
     KeyedList(Rc<dyn Fn() -> Vec<(String, Node)>>),
+    /// A keyed list backed by a [`SignalVec`], patched from its diffs instead of re-rendered.
+    DiffList(DiffListData),
     Head(Head),
     Suspense(SuspenseData),
     ErrorBoundary(ErrorBoundaryData),
+    /// Renders the child into the element matching the CSS selector instead of in place.
+    Portal(String, Box<Node>),
+    /// Rebuilds and swaps its entire subtree whenever a signal read while building it
+    /// changes, disposing the previous subtree's effects.
+    Dynamic(Rc<dyn Fn() -> Node>),
+    /// Applies enter/leave classes when the wrapped conditional or dynamic node changes.
+    Transition(Transition, Box<Node>),
+    /// A static subtree shared by every use of a [`Template`].
+    Template(Template),
 }
 
 pub trait IntoNode {
@@ -51,6 +67,83 @@ impl IntoNode for Node {
     }
 }
 
+/// `None` renders nothing.
+impl<T: IntoNode> IntoNode for Option<T> {
+    fn into_node(self) -> Node {
+        match self {
+            Some(node) => node.into_node(),
+            None => Node::Fragment(Vec::new()),
+        }
+    }
+}
+
+/// An error renders as its message.
+impl<T: IntoNode, E: std::fmt::Display> IntoNode for Result<T, E> {
+    fn into_node(self) -> Node {
+        match self {
+            Ok(node) => node.into_node(),
+            Err(error) => Node::Text(error.to_string()),
+        }
+    }
+}
+
+/// Lets `items.iter().map(|item| li().text(item))` be passed as a child directly.
+impl<I, F, N> IntoNode for std::iter::Map<I, F>
+where
+    I: Iterator,
+    F: FnMut(I::Item) -> N,
+    N: IntoNode,
+{
+    fn into_node(self) -> Node {
+        self.collect()
+    }
+}
+
+impl<T: IntoNode> IntoNode for Children<T> {
+    fn into_node(self) -> Node {
+        self.into_iter().collect()
+    }
+}
+
+impl<N: IntoNode> FromIterator<N> for Node {
+    fn from_iter<I: IntoIterator<Item = N>>(iter: I) -> Self {
+        Node::Fragment(iter.into_iter().map(IntoNode::into_node).collect())
+    }
+}
+
+/// Sibling nodes without a wrapping element, built with [`fragment`].
+#[derive(Default)]
+pub struct Fragment {
+    children: Vec<Node>,
+}
+
+impl Fragment {
+    pub fn child(mut self, child: impl IntoNode) -> Self {
+        self.children.push(child.into_node());
+        self
+    }
+
+    pub fn children<I, C>(mut self, children: I) -> Self
+    where
+        I: IntoIterator<Item = C>,
+        C: IntoNode,
+    {
+        self.children
+            .extend(children.into_iter().map(IntoNode::into_node));
+        self
+    }
+}
+
+impl IntoNode for Fragment {
+    fn into_node(self) -> Node {
+        Node::Fragment(self.children)
+    }
+}
+
+pub fn fragment() -> Fragment {
+    Fragment::default()
+}
+
 impl IntoNode for Head {
     fn into_node(self) -> Node {
         Node::Head(self)
@@ -72,6 +165,9 @@ where
     }))
 }
 
+/// Like [`each`], but every row carries the key returned by `key_fn`, so the browser
+/// renderer matches rows by identity: reordered rows are moved rather than re-rendered and
+/// keep their DOM and reactive state.
 pub fn each_keyed<T, K, F>(
     items: react_rs_core::signal::ReadSignal<Vec<T>>,
     key_fn: impl Fn(&T) -> K + 'static,
@@ -92,6 +188,172 @@ where
     }))
 }
 
+/// One change to a [`Node::DiffList`], with inserted and updated items already rendered.
+pub enum ListChange {
+    Insert {
+        index: usize,
+        key: String,
+        node: Node,
+    },
+    Update {
+        index: usize,
+        key: String,
+        node: Node,
+    },
+    Remove {
+        index: usize,
+    },
+    Move {
+        from: usize,
+        to: usize,
+    },
+    Reset(Vec<(String, Node)>),
+}
+
+type ChangeReader = Box<dyn Fn() -> Vec<ListChange>>;
+
+pub struct DiffListData {
+    /// Renders the current items without subscribing to the list.
+    pub items: Rc<dyn Fn() -> Vec<(String, Node)>>,
+    /// Starts recording changes. The returned reader drains them and subscribes the calling
+    /// effect to further changes, so create it before rendering `items`.
+    pub changes: Rc<dyn Fn() -> ChangeReader>,
+}
+
+/// Like [`each_keyed`], but driven by a [`SignalVec`]: renderers apply its insertions,
+/// removals and moves directly rather than diffing the whole list on every change.
+pub fn each_keyed_vec<T, F>(items: SignalVec<T>, render: F) -> Node
+where
+    T: Clone + 'static,
+    F: Fn(&T, usize) -> Node + 'static,
+{
+    let render = Rc::new(render);
+    let snapshot = {
+        let items = items.clone();
+        let render = render.clone();
+        Rc::new(move || render_items(&items, &*render))
+    };
+    let changes = Rc::new(move || -> ChangeReader {
+        let reader = items.diffs();
+        let items = items.clone();
+        let render = render.clone();
+        Box::new(move || {
+            let diffs = reader.take();
+            untrack(|| {
+                diffs
+                    .into_iter()
+                    .map(|diff| match diff {
+                        VecDiff::Insert { index, value } => ListChange::Insert {
+                            index,
+                            key: items.key_of(&value),
+                            node: render(&value, index),
+                        },
+                        VecDiff::Update { index, value } => ListChange::Update {
+                            index,
+                            key: items.key_of(&value),
+                            node: render(&value, index),
+                        },
+                        VecDiff::Remove { index } => ListChange::Remove { index },
+                        VecDiff::Move { from, to } => ListChange::Move { from, to },
+                        VecDiff::Replace(values) => ListChange::Reset(
+                            values
+                                .iter()
+                                .enumerate()
+                                .map(|(i, value)| (items.key_of(value), render(value, i)))
+                                .collect(),
+                        ),
+                    })
+                    .collect()
+            })
+        })
+    });
+    Node::DiffList(DiffListData {
+        items: snapshot,
+        changes,
+    })
+}
+
+fn render_items<T: Clone + 'static>(
+    items: &SignalVec<T>,
+    render: &dyn Fn(&T, usize) -> Node,
+) -> Vec<(String, Node)> {
+    untrack(|| {
+        items.with_untracked(|list| {
+            list.iter()
+                .enumerate()
+                .map(|(i, item)| (items.key_of(item), render(item, i)))
+                .collect()
+        })
+    })
+}
+
+/// Mounts `children` into the element matching `target` (e.g. `"body"`), for modals, toasts
+/// and tooltips that must escape their parent's stacking context or overflow. Pass a `Vec` to
+/// portal several nodes at once.
+pub fn portal(target: &str, children: impl IntoNode) -> Node {
+    Node::Portal(target.to_string(), Box::new(children.into_node()))
+}
+
+/// Renders whatever `view` returns and replaces it wholesale when a signal `view` reads
+/// changes, e.g. `dyn_view(move || match tab.get() { Tab::Posts => posts(), .. })` for tab
+/// panels and route outlets. Unlike [`Element::show_when`](crate::Element::show_when), only
+/// the current branch exists at a time.
+pub fn dyn_view<N: IntoNode>(view: impl Fn() -> N + 'static) -> Node {
+    Node::Dynamic(Rc::new(move || view().into_node()))
+}
+
+/// Starts an if/else view: `when(logged_in).then(|| dashboard()).otherwise(|| login())`.
+/// Both branches are built up front and toggled as `condition` changes.
+pub fn when(condition: impl IntoReactiveBool) -> When {
+    When {
+        condition: condition.into_reactive_bool(),
+    }
+}
+
+/// Renders `node` only while `condition` holds.
+pub fn show(condition: impl IntoReactiveBool, node: impl IntoNode) -> Node {
+    Node::Conditional(
+        condition.into_reactive_bool(),
+        Box::new(node.into_node()),
+        None,
+    )
+}
+
+pub struct When {
+    condition: ReactiveValue<bool>,
+}
+
+impl When {
+    pub fn then<N: IntoNode>(self, then: impl FnOnce() -> N) -> WhenThen {
+        WhenThen {
+            condition: self.condition,
+            then: then().into_node(),
+        }
+    }
+}
+
+/// A [`when`] with its `then` branch; used as a node directly when there is no else branch.
+pub struct WhenThen {
+    condition: ReactiveValue<bool>,
+    then: Node,
+}
+
+impl WhenThen {
+    pub fn otherwise<N: IntoNode>(self, otherwise: impl FnOnce() -> N) -> Node {
+        Node::Conditional(
+            self.condition,
+            Box::new(self.then),
+            Some(Box::new(otherwise().into_node())),
+        )
+    }
+}
+
+impl IntoNode for WhenThen {
+    fn into_node(self) -> Node {
+        Node::Conditional(self.condition, Box::new(self.then), None)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -112,6 +374,27 @@ mod tests {
         assert!(matches!(node, Node::Conditional(_, _, Some(_))));
     }
 
+    #[test]
+    fn test_when_then_otherwise() {
+        let (logged_in, set_logged_in) = create_signal(false);
+        let node = when(logged_in)
+            .then(|| html::p().text("Welcome back"))
+            .otherwise(|| html::button().text("Sign in"));
+        let Node::Conditional(condition, _, Some(_)) = &node else {
+            panic!("expected a conditional with an else branch");
+        };
+        assert!(!condition.get());
+        set_logged_in.set(true);
+        assert!(condition.get());
+
+        let node = when(true).then(|| "only").into_node();
+        assert!(matches!(node, Node::Conditional(_, _, None)));
+        assert!(matches!(
+            show(false, "hidden"),
+            Node::Conditional(_, _, None)
+        ));
+    }
+
     #[test]
     fn test_each_creates_reactive_list() {
         let (items, _) = create_signal(vec![1, 2, 3]);
@@ -120,4 +403,98 @@ mod tests {
         });
         assert!(matches!(node, Node::ReactiveList(_)));
     }
+
+    #[test]
+    fn test_each_keyed_carries_keys() {
+        let (items, set_items) = create_signal(vec![(7, "a"), (9, "b")]);
+        let node = each_keyed(
+            items,
+            |(id, _)| *id,
+            |(_, label), _| html::li().text(*label).into_node(),
+        );
+        let Node::KeyedList(list_fn) = node else {
+            panic!("expected a keyed list");
+        };
+        set_items.update(|items| items.reverse());
+        let keys: Vec<String> = list_fn().into_iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec!["9", "7"]);
+    }
+
+    #[test]
+    fn test_each_keyed_vec_renders_changes() {
+        let items = react_rs_core::create_keyed_signal(vec![1, 2], |n| *n);
+        let node = each_keyed_vec(items.clone(), |item, _| {
+            html::li().text(item.to_string()).into_node()
+        });
+        let Node::DiffList(data) = node else {
+            panic!("expected a diff list");
+        };
+        let changes = (data.changes)();
+        let keys: Vec<String> = (data.items)().into_iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec!["1", "2"]);
+
+        items.push(3);
+        items.move_item(2, 0);
+        items.remove(1);
+        let changes = changes();
+        assert_eq!(changes.len(), 3);
+        assert!(matches!(
+            &changes[0],
+            ListChange::Insert { index: 2, key, .. } if key == "3"
+        ));
+        assert!(matches!(changes[1], ListChange::Move { from: 2, to: 0 }));
+        assert!(matches!(changes[2], ListChange::Remove { index: 1 }));
+    }
+
+    #[test]
+    fn test_option_result_and_iterators_into_node() {
+        assert!(matches!(None::<Element>.into_node(), Node::Fragment(ref c) if c.is_empty()));
+        assert!(matches!(Some("hi").into_node(), Node::Text(ref t) if t == "hi"));
+
+        let failed: Result<Element, String> = Err("not found".to_string());
+        assert!(matches!(failed.into_node(), Node::Text(ref t) if t == "not found"));
+
+        let list = html::ul().child(["a", "b", "c"].iter().map(|s| html::li().text(*s)));
+        assert!(matches!(&list.get_children()[0], Node::Fragment(c) if c.len() == 3));
+    }
+
+    #[test]
+    fn test_fragment_builder() {
+        let node = fragment()
+            .child(html::h1().text("Title"))
+            .children(vec!["a", "b"])
+            .into_node();
+        assert!(matches!(node, Node::Fragment(ref c) if c.len() == 3));
+    }
+
+    #[test]
+    fn test_dyn_view_follows_signal() {
+        let (tab, set_tab) = create_signal(0);
+        let node = dyn_view(move || {
+            if tab.get() == 0 {
+                html::div().class("posts")
+            } else {
+                html::div().class("settings")
+            }
+        });
+        let Node::Dynamic(view) = node else {
+            panic!("expected a dynamic node");
+        };
+        assert!(matches!(view(), Node::Element(el) if el.has_class("posts")));
+
+        set_tab.set(1);
+        assert!(matches!(view(), Node::Element(el) if el.has_class("settings")));
+    }
+
+    #[test]
+    fn test_portal_creates_node() {
+        let node = portal("body", html::div().class("modal"));
+        assert!(matches!(node, Node::Portal(ref target, _) if target == "body"));
+
+        let node = portal("#overlays", vec![html::div(), html::div()]);
+        assert!(matches!(
+            node,
+            Node::Portal(_, ref children) if matches!(**children, Node::Fragment(ref nodes) if nodes.len() == 2)
+        ));
+    }
 }
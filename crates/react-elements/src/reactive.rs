@@ -1,6 +1,6 @@
 use std::rc::Rc;
 
-use react_rs_core::signal::ReadSignal;
+use react_rs_core::signal::{ReadSignal, RwSignal};
 
 pub trait SignalExt<T> {
     fn map<U, F>(&self, f: F) -> MappedSignal<T, U, F>
@@ -20,6 +20,15 @@ impl<T: Clone + 'static> SignalExt<T> for ReadSignal<T> {
     }
 }
 
+impl<T: Clone + 'static> SignalExt<T> for RwSignal<T> {
+    fn map<U, F>(&self, f: F) -> MappedSignal<T, U, F>
+    where
+        F: Fn(&T) -> U + 'static,
+    {
+        self.read_only().map(f)
+    }
+}
+
 pub struct MappedSignal<T, U, F>
 where
     F: Fn(&T) -> U,
@@ -96,6 +105,24 @@ impl IntoReactiveBool for bool {
     }
 }
 
+impl IntoReactiveBool for ReadSignal<bool> {
+    fn into_reactive_bool(self) -> ReactiveValue<bool> {
+        ReactiveValue::Dynamic(Rc::new(move || self.get()))
+    }
+}
+
+impl IntoReactiveBool for RwSignal<bool> {
+    fn into_reactive_bool(self) -> ReactiveValue<bool> {
+        self.read_only().into_reactive_bool()
+    }
+}
+
+impl IntoReactiveString for ReactiveValue<String> {
+    fn into_reactive_string(self) -> ReactiveValue<String> {
+        self
+    }
+}
+
 impl<T, F> IntoReactiveBool for MappedSignal<T, bool, F>
 where
     T: Clone + 'static,
@@ -1,8 +1,152 @@
 use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::rc::Rc;
+use std::time::Duration;
 
-#[derive(Debug, Clone, Default)]
+use react_rs_core::signal::ReadSignal;
+
+use crate::reactive::{IntoReactiveString, ReactiveValue};
+
+#[derive(Clone, Default)]
 pub struct Style {
-    properties: BTreeMap<String, String>,
+    properties: BTreeMap<String, ReactiveValue<String>>,
+    nested: Vec<(Block, Style)>,
+}
+
+/// Where a nested block's declarations apply, relative to the element's own rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Block {
+    /// Appended to the selector, e.g. `:hover` or `::placeholder`.
+    Selector(String),
+    Media(String),
+}
+
+/// A value accepted by the typed [`Style`] setters: plain strings, [`Length`]s and
+/// [`Color`]s.
+pub trait IntoCssValue {
+    fn into_css_value(self) -> String;
+}
+
+impl IntoCssValue for &str {
+    fn into_css_value(self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoCssValue for String {
+    fn into_css_value(self) -> String {
+        self
+    }
+}
+
+impl IntoCssValue for &String {
+    fn into_css_value(self) -> String {
+        self.clone()
+    }
+}
+
+/// A CSS length, e.g. `Length::rem(1.5)` or `Length::percent(50)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Length {
+    Px(f64),
+    Rem(f64),
+    Em(f64),
+    Percent(f64),
+    Vw(f64),
+    Vh(f64),
+    Auto,
+}
+
+impl Length {
+    pub fn px(value: impl Into<f64>) -> Self {
+        Self::Px(value.into())
+    }
+    pub fn rem(value: impl Into<f64>) -> Self {
+        Self::Rem(value.into())
+    }
+    pub fn em(value: impl Into<f64>) -> Self {
+        Self::Em(value.into())
+    }
+    pub fn percent(value: impl Into<f64>) -> Self {
+        Self::Percent(value.into())
+    }
+    pub fn vw(value: impl Into<f64>) -> Self {
+        Self::Vw(value.into())
+    }
+    pub fn vh(value: impl Into<f64>) -> Self {
+        Self::Vh(value.into())
+    }
+}
+
+impl Display for Length {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Px(v) if *v == 0.0 => write!(f, "0"),
+            Self::Px(v) => write!(f, "{}px", v),
+            Self::Rem(v) => write!(f, "{}rem", v),
+            Self::Em(v) => write!(f, "{}em", v),
+            Self::Percent(v) => write!(f, "{}%", v),
+            Self::Vw(v) => write!(f, "{}vw", v),
+            Self::Vh(v) => write!(f, "{}vh", v),
+            Self::Auto => write!(f, "auto"),
+        }
+    }
+}
+
+impl IntoCssValue for Length {
+    fn into_css_value(self) -> String {
+        self.to_string()
+    }
+}
+
+/// A CSS color, e.g. `Color::hex("#0070f3")` or `Color::rgba(0, 0, 0, 0.5)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Color(String);
+
+impl Color {
+    pub fn hex(hex: &str) -> Self {
+        Self(format!("#{}", hex.trim_start_matches('#')))
+    }
+    pub fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self(format!("rgb({}, {}, {})", r, g, b))
+    }
+    pub fn rgba(r: u8, g: u8, b: u8, alpha: f32) -> Self {
+        Self(format!("rgba({}, {}, {}, {})", r, g, b, alpha))
+    }
+    pub fn hsl(hue: u16, saturation: u8, lightness: u8) -> Self {
+        Self(format!("hsl({}, {}%, {}%)", hue, saturation, lightness))
+    }
+    /// A custom property, e.g. `Color::var("--accent")`.
+    pub fn var(name: &str) -> Self {
+        Self(format!("var({})", name))
+    }
+    pub fn transparent() -> Self {
+        Self("transparent".to_string())
+    }
+    pub fn current() -> Self {
+        Self("currentColor".to_string())
+    }
+}
+
+impl Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl IntoCssValue for Color {
+    fn into_css_value(self) -> String {
+        self.0
+    }
+}
+
+impl std::fmt::Debug for Style {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Style")
+            .field("css", &self.to_css())
+            .field("reactive", &self.is_reactive())
+            .finish()
+    }
 }
 
 impl Style {
@@ -10,122 +154,423 @@ impl Style {
         Self::default()
     }
 
-    pub fn set(mut self, property: &str, value: &str) -> Self {
+    pub fn set(mut self, property: &str, value: impl IntoCssValue) -> Self {
+        self.properties.insert(
+            property.to_string(),
+            ReactiveValue::Static(value.into_css_value()),
+        );
+        self
+    }
+
+    /// Sets `property` to a value that is re-read whenever the style is rendered, so an
+    /// element using [`Element::style_reactive`](crate::Element::style_reactive) updates only
+    /// when the signals behind it change.
+    pub fn set_reactive(mut self, property: &str, value: impl IntoReactiveString) -> Self {
         self.properties
-            .insert(property.to_string(), value.to_string());
+            .insert(property.to_string(), value.into_reactive_string());
         self
     }
 
-    pub fn display(self, value: &str) -> Self {
+    pub fn width_signal(self, value: impl IntoReactiveString) -> Self {
+        self.set_reactive("width", value)
+    }
+    pub fn height_signal(self, value: impl IntoReactiveString) -> Self {
+        self.set_reactive("height", value)
+    }
+    pub fn transform_signal(self, value: impl IntoReactiveString) -> Self {
+        self.set_reactive("transform", value)
+    }
+    pub fn opacity_signal(self, value: impl IntoReactiveString) -> Self {
+        self.set_reactive("opacity", value)
+    }
+    pub fn color_signal(self, value: impl IntoReactiveString) -> Self {
+        self.set_reactive("color", value)
+    }
+    pub fn background_color_signal(self, value: impl IntoReactiveString) -> Self {
+        self.set_reactive("background-color", value)
+    }
+    pub fn display_signal(self, value: impl IntoReactiveString) -> Self {
+        self.set_reactive("display", value)
+    }
+
+    /// Whether any property was set from a signal.
+    pub fn is_reactive(&self) -> bool {
+        self.properties
+            .values()
+            .any(|value| matches!(value, ReactiveValue::Dynamic(_)))
+    }
+
+    pub fn display(self, value: impl IntoCssValue) -> Self {
         self.set("display", value)
     }
-    pub fn position(self, value: &str) -> Self {
+    pub fn position(self, value: impl IntoCssValue) -> Self {
         self.set("position", value)
     }
-    pub fn width(self, value: &str) -> Self {
+    pub fn width(self, value: impl IntoCssValue) -> Self {
         self.set("width", value)
     }
-    pub fn height(self, value: &str) -> Self {
+    pub fn height(self, value: impl IntoCssValue) -> Self {
         self.set("height", value)
     }
-    pub fn max_width(self, value: &str) -> Self {
+    pub fn max_width(self, value: impl IntoCssValue) -> Self {
         self.set("max-width", value)
     }
-    pub fn min_height(self, value: &str) -> Self {
+    pub fn min_height(self, value: impl IntoCssValue) -> Self {
         self.set("min-height", value)
     }
-    pub fn margin(self, value: &str) -> Self {
+    pub fn margin(self, value: impl IntoCssValue) -> Self {
         self.set("margin", value)
     }
-    pub fn margin_top(self, value: &str) -> Self {
+    pub fn margin_top(self, value: impl IntoCssValue) -> Self {
         self.set("margin-top", value)
     }
-    pub fn margin_bottom(self, value: &str) -> Self {
+    pub fn margin_bottom(self, value: impl IntoCssValue) -> Self {
         self.set("margin-bottom", value)
     }
-    pub fn padding(self, value: &str) -> Self {
+    pub fn padding(self, value: impl IntoCssValue) -> Self {
         self.set("padding", value)
     }
-    pub fn background(self, value: &str) -> Self {
+    pub fn background(self, value: impl IntoCssValue) -> Self {
         self.set("background", value)
     }
-    pub fn background_color(self, value: &str) -> Self {
+    pub fn background_color(self, value: impl IntoCssValue) -> Self {
         self.set("background-color", value)
     }
-    pub fn color(self, value: &str) -> Self {
+    pub fn color(self, value: impl IntoCssValue) -> Self {
         self.set("color", value)
     }
-    pub fn font_size(self, value: &str) -> Self {
+    pub fn font_size(self, value: impl IntoCssValue) -> Self {
         self.set("font-size", value)
     }
-    pub fn font_weight(self, value: &str) -> Self {
+    pub fn font_weight(self, value: impl IntoCssValue) -> Self {
         self.set("font-weight", value)
     }
-    pub fn font_family(self, value: &str) -> Self {
+    pub fn font_family(self, value: impl IntoCssValue) -> Self {
         self.set("font-family", value)
     }
-    pub fn line_height(self, value: &str) -> Self {
+    pub fn line_height(self, value: impl IntoCssValue) -> Self {
         self.set("line-height", value)
     }
-    pub fn text_align(self, value: &str) -> Self {
+    pub fn text_align(self, value: impl IntoCssValue) -> Self {
         self.set("text-align", value)
     }
-    pub fn border(self, value: &str) -> Self {
+    pub fn border(self, value: impl IntoCssValue) -> Self {
         self.set("border", value)
     }
-    pub fn border_radius(self, value: &str) -> Self {
+    pub fn border_radius(self, value: impl IntoCssValue) -> Self {
         self.set("border-radius", value)
     }
-    pub fn box_shadow(self, value: &str) -> Self {
+    pub fn box_shadow(self, value: impl IntoCssValue) -> Self {
         self.set("box-shadow", value)
     }
-    pub fn cursor(self, value: &str) -> Self {
+    pub fn cursor(self, value: impl IntoCssValue) -> Self {
         self.set("cursor", value)
     }
-    pub fn overflow(self, value: &str) -> Self {
+    pub fn overflow(self, value: impl IntoCssValue) -> Self {
         self.set("overflow", value)
     }
-    pub fn opacity(self, value: &str) -> Self {
+    pub fn opacity(self, value: impl IntoCssValue) -> Self {
         self.set("opacity", value)
     }
-    pub fn transition(self, value: &str) -> Self {
+    pub fn transition(self, value: impl IntoCssValue) -> Self {
         self.set("transition", value)
     }
-    pub fn transform(self, value: &str) -> Self {
+    pub fn transform(self, value: impl IntoCssValue) -> Self {
         self.set("transform", value)
     }
-    pub fn gap(self, value: &str) -> Self {
+    pub fn gap(self, value: impl IntoCssValue) -> Self {
         self.set("gap", value)
     }
-    pub fn flex(self, value: &str) -> Self {
+    pub fn flex(self, value: impl IntoCssValue) -> Self {
         self.set("flex", value)
     }
-    pub fn flex_direction(self, value: &str) -> Self {
+    pub fn flex_direction(self, value: impl IntoCssValue) -> Self {
         self.set("flex-direction", value)
     }
-    pub fn align_items(self, value: &str) -> Self {
+    pub fn align_items(self, value: impl IntoCssValue) -> Self {
         self.set("align-items", value)
     }
-    pub fn justify_content(self, value: &str) -> Self {
+    pub fn justify_content(self, value: impl IntoCssValue) -> Self {
         self.set("justify-content", value)
     }
-    pub fn grid_template_columns(self, value: &str) -> Self {
+    pub fn grid_template_columns(self, value: impl IntoCssValue) -> Self {
         self.set("grid-template-columns", value)
     }
+    pub fn view_transition_name(self, value: impl IntoCssValue) -> Self {
+        self.set("view-transition-name", value)
+    }
+    pub fn min_width(self, value: impl IntoCssValue) -> Self {
+        self.set("min-width", value)
+    }
+    pub fn max_height(self, value: impl IntoCssValue) -> Self {
+        self.set("max-height", value)
+    }
+    pub fn margin_left(self, value: impl IntoCssValue) -> Self {
+        self.set("margin-left", value)
+    }
+    pub fn margin_right(self, value: impl IntoCssValue) -> Self {
+        self.set("margin-right", value)
+    }
+    pub fn padding_top(self, value: impl IntoCssValue) -> Self {
+        self.set("padding-top", value)
+    }
+    pub fn padding_bottom(self, value: impl IntoCssValue) -> Self {
+        self.set("padding-bottom", value)
+    }
+    pub fn padding_left(self, value: impl IntoCssValue) -> Self {
+        self.set("padding-left", value)
+    }
+    pub fn padding_right(self, value: impl IntoCssValue) -> Self {
+        self.set("padding-right", value)
+    }
+    pub fn margin_x(self, value: impl IntoCssValue) -> Self {
+        let value = value.into_css_value();
+        self.margin_left(&value).margin_right(value)
+    }
+    pub fn padding_x(self, value: impl IntoCssValue) -> Self {
+        let value = value.into_css_value();
+        self.padding_left(&value).padding_right(value)
+    }
+    pub fn padding_y(self, value: impl IntoCssValue) -> Self {
+        let value = value.into_css_value();
+        self.padding_top(&value).padding_bottom(value)
+    }
+    pub fn top(self, value: impl IntoCssValue) -> Self {
+        self.set("top", value)
+    }
+    pub fn right(self, value: impl IntoCssValue) -> Self {
+        self.set("right", value)
+    }
+    pub fn bottom(self, value: impl IntoCssValue) -> Self {
+        self.set("bottom", value)
+    }
+    pub fn left(self, value: impl IntoCssValue) -> Self {
+        self.set("left", value)
+    }
+    pub fn z_index(self, value: i32) -> Self {
+        self.set("z-index", value.to_string())
+    }
+    pub fn border_color(self, value: impl IntoCssValue) -> Self {
+        self.set("border-color", value)
+    }
+    pub fn outline(self, value: impl IntoCssValue) -> Self {
+        self.set("outline", value)
+    }
+    pub fn text_decoration(self, value: impl IntoCssValue) -> Self {
+        self.set("text-decoration", value)
+    }
+
+    /// `display: flex` laid out in a row.
+    pub fn flex_row(self) -> Self {
+        self.display("flex").flex_direction("row")
+    }
+    /// `display: flex` laid out in a column.
+    pub fn flex_column(self) -> Self {
+        self.display("flex").flex_direction("column")
+    }
+    /// Centers children on both axes of a flex or grid container.
+    pub fn center(self) -> Self {
+        self.align_items("center").justify_content("center")
+    }
+    pub fn flex_wrap(self, value: impl IntoCssValue) -> Self {
+        self.set("flex-wrap", value)
+    }
+    pub fn flex_grow(self, value: u32) -> Self {
+        self.set("flex-grow", value.to_string())
+    }
+    pub fn flex_shrink(self, value: u32) -> Self {
+        self.set("flex-shrink", value.to_string())
+    }
+    pub fn flex_basis(self, value: impl IntoCssValue) -> Self {
+        self.set("flex-basis", value)
+    }
+    pub fn align_self(self, value: impl IntoCssValue) -> Self {
+        self.set("align-self", value)
+    }
+    pub fn row_gap(self, value: impl IntoCssValue) -> Self {
+        self.set("row-gap", value)
+    }
+    pub fn column_gap(self, value: impl IntoCssValue) -> Self {
+        self.set("column-gap", value)
+    }
+
+    pub fn grid(self) -> Self {
+        self.display("grid")
+    }
+    /// `count` equal-width columns that may shrink below their content's width.
+    pub fn grid_columns(self, count: usize) -> Self {
+        self.grid_template_columns(format!("repeat({}, minmax(0, 1fr))", count))
+    }
+    pub fn grid_template_rows(self, value: impl IntoCssValue) -> Self {
+        self.set("grid-template-rows", value)
+    }
+    pub fn grid_template_areas(self, value: impl IntoCssValue) -> Self {
+        self.set("grid-template-areas", value)
+    }
+    pub fn grid_column(self, value: impl IntoCssValue) -> Self {
+        self.set("grid-column", value)
+    }
+    pub fn grid_row(self, value: impl IntoCssValue) -> Self {
+        self.set("grid-row", value)
+    }
+    pub fn grid_area(self, value: impl IntoCssValue) -> Self {
+        self.set("grid-area", value)
+    }
+    pub fn place_items(self, value: impl IntoCssValue) -> Self {
+        self.set("place-items", value)
+    }
+
+    /// Adds a transition of `property` to any already set, e.g.
+    /// `.transition_on("opacity", Duration::from_millis(150), "ease-out")`.
+    pub fn transition_on(self, property: &str, duration: Duration, easing: &str) -> Self {
+        let entry = format!("{} {}ms {}", property, duration.as_millis(), easing);
+        let value = match self.properties.get("transition") {
+            Some(ReactiveValue::Static(existing)) if !existing.is_empty() => {
+                format!("{}, {}", existing, entry)
+            }
+            _ => entry,
+        };
+        self.set("transition", value)
+    }
+
+    /// Declarations applied while the element is hovered.
+    pub fn hover(self, block: impl FnOnce(Style) -> Style) -> Self {
+        self.selector(":hover", block)
+    }
+    pub fn focus(self, block: impl FnOnce(Style) -> Style) -> Self {
+        self.selector(":focus", block)
+    }
+    pub fn focus_visible(self, block: impl FnOnce(Style) -> Style) -> Self {
+        self.selector(":focus-visible", block)
+    }
+    pub fn active(self, block: impl FnOnce(Style) -> Style) -> Self {
+        self.selector(":active", block)
+    }
+    pub fn disabled(self, block: impl FnOnce(Style) -> Style) -> Self {
+        self.selector(":disabled", block)
+    }
+
+    /// Declarations for the element's selector with `suffix` appended, such as a
+    /// pseudo-class (`":first-child"`) or pseudo-element (`"::placeholder"`).
+    pub fn selector(mut self, suffix: &str, block: impl FnOnce(Style) -> Style) -> Self {
+        self.nested
+            .push((Block::Selector(suffix.to_string()), block(Style::new())));
+        self
+    }
+
+    /// Declarations applied under a media query, e.g. `.media("(max-width: 640px)", |s|
+    /// s.flex_column())`. Blocks can nest, so a media block may contain `hover`.
+    pub fn media(mut self, query: &str, block: impl FnOnce(Style) -> Style) -> Self {
+        self.nested
+            .push((Block::Media(query.to_string()), block(Style::new())));
+        self
+    }
+
+    /// Whether the style has pseudo-class or media blocks, which cannot be written inline
+    /// and have to be emitted as stylesheet rules.
+    pub fn has_rules(&self) -> bool {
+        !self.nested.is_empty()
+    }
+
+    /// A class name derived from the style's static declarations and blocks, identical on
+    /// server and client so extracted rules are shared by every element with the same style.
+    pub fn class_name(&self) -> String {
+        // FNV-1a: stable across builds, unlike `DefaultHasher`.
+        let hash = self
+            .static_rules("&")
+            .bytes()
+            .fold(0xcbf29ce484222325u64, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+            });
+        format!("s-{:x}", hash)
+    }
+
+    /// The stylesheet rules for `selector`: its static declarations, then each nested block.
+    /// Reactive properties are left out; they stay inline so they can update.
+    pub fn static_rules(&self, selector: &str) -> String {
+        let mut rules = Vec::new();
+        self.write_rules(selector, &mut rules);
+        rules.join("\n")
+    }
+
+    fn write_rules(&self, selector: &str, rules: &mut Vec<String>) {
+        let declarations = self.static_declarations();
+        if !declarations.is_empty() {
+            rules.push(format!("{} {{ {} }}", selector, declarations));
+        }
+        for (block, style) in &self.nested {
+            match block {
+                Block::Selector(suffix) => {
+                    style.write_rules(&format!("{}{}", selector, suffix), rules)
+                }
+                Block::Media(query) => {
+                    let mut inner = Vec::new();
+                    style.write_rules(selector, &mut inner);
+                    if !inner.is_empty() {
+                        rules.push(format!("@media {} {{ {} }}", query, inner.join(" ")));
+                    }
+                }
+            }
+        }
+    }
+
+    fn static_declarations(&self) -> String {
+        self.properties
+            .iter()
+            .filter_map(|(k, v)| match v {
+                ReactiveValue::Static(value) => Some(format!("{}: {}", k, value)),
+                ReactiveValue::Dynamic(_) => None,
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    /// Splits the style for an element that uses it through a class: the reactive
+    /// properties that must stay inline, leaving static ones to [`Style::static_rules`].
+    pub(crate) fn into_reactive_part(self) -> Style {
+        Style {
+            properties: self
+                .properties
+                .into_iter()
+                .filter(|(_, v)| matches!(v, ReactiveValue::Dynamic(_)))
+                .collect(),
+            nested: Vec::new(),
+        }
+    }
 
     pub fn to_css(&self) -> String {
         self.properties
             .iter()
-            .map(|(k, v)| format!("{}: {}", k, v))
+            .map(|(k, v)| format!("{}: {}", k, v.get()))
             .collect::<Vec<_>>()
             .join("; ")
     }
 }
 
+impl IntoReactiveString for Style {
+    fn into_reactive_string(self) -> ReactiveValue<String> {
+        if self.is_reactive() {
+            ReactiveValue::Dynamic(Rc::new(move || self.to_css()))
+        } else {
+            ReactiveValue::Static(self.to_css())
+        }
+    }
+}
+
 pub fn style() -> Style {
     Style::new()
 }
 
+/// Formats a numeric signal as a CSS pixel length, e.g. `.width_signal(px(width))`.
+pub fn px<T: Display + Clone + 'static>(signal: ReadSignal<T>) -> ReactiveValue<String> {
+    ReactiveValue::Dynamic(Rc::new(move || format!("{}px", signal.get())))
+}
+
+/// Formats a numeric signal as a CSS percentage.
+pub fn percent<T: Display + Clone + 'static>(signal: ReadSignal<T>) -> ReactiveValue<String> {
+    ReactiveValue::Dynamic(Rc::new(move || format!("{}%", signal.get())))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -148,6 +593,20 @@ mod tests {
         assert_eq!(s.to_css(), "color: #333");
     }
 
+    #[test]
+    fn test_reactive_property() {
+        use react_rs_core::signal::create_signal;
+
+        let (width, set_width) = create_signal(100);
+        let s = style().display("block").width_signal(px(width));
+        assert!(s.is_reactive());
+        assert_eq!(s.to_css(), "display: block; width: 100px");
+
+        set_width.set(240);
+        assert_eq!(s.to_css(), "display: block; width: 240px");
+        assert!(!style().color("red").is_reactive());
+    }
+
     #[test]
     fn test_style_complex() {
         let s = style()
@@ -162,4 +621,100 @@ mod tests {
         assert!(css.contains("grid-template-columns: 1fr 1fr"));
         assert!(css.contains("border-radius: 8px"));
     }
+
+    #[test]
+    fn test_typed_values_and_layout_helpers() {
+        let s = style()
+            .flex_column()
+            .center()
+            .padding_x(Length::rem(1.5))
+            .width(Length::percent(50))
+            .margin(Length::px(0))
+            .color(Color::hex("0070f3"))
+            .background_color(Color::rgba(0, 0, 0, 0.5))
+            .transition_on("opacity", Duration::from_millis(150), "ease-out")
+            .transition_on("transform", Duration::from_millis(200), "ease");
+        let css = s.to_css();
+        assert!(css.contains("display: flex; flex-direction: column"));
+        assert!(css.contains("align-items: center"));
+        assert!(css.contains("padding-left: 1.5rem; padding-right: 1.5rem"));
+        assert!(css.contains("width: 50%"));
+        assert!(css.contains("margin: 0;"));
+        assert!(css.contains("color: #0070f3"));
+        assert!(css.contains("background-color: rgba(0, 0, 0, 0.5)"));
+        assert!(css.contains("transition: opacity 150ms ease-out, transform 200ms ease"));
+
+        let grid = style().grid().grid_columns(3).gap(Length::px(8));
+        assert_eq!(
+            grid.to_css(),
+            "display: grid; gap: 8px; grid-template-columns: repeat(3, minmax(0, 1fr))"
+        );
+    }
+
+    #[test]
+    fn test_nested_blocks_emit_rules() {
+        let button = style()
+            .color("white")
+            .hover(|s| s.color("black"))
+            .media("(max-width: 640px)", |s| {
+                s.padding("4px").hover(|s| s.opacity("0.8"))
+            });
+        assert!(button.has_rules());
+        assert!(!style().color("red").has_rules());
+
+        assert_eq!(
+            button.static_rules(".btn"),
+            ".btn { color: white }\n.btn:hover { color: black }\n@media (max-width: 640px) { .btn { padding: 4px } .btn:hover { opacity: 0.8 } }"
+        );
+
+        let same = style().color("white").hover(|s| s.color("black"));
+        let other = style().color("white").hover(|s| s.color("red"));
+        assert_eq!(same.class_name(), same.clone().class_name());
+        assert_ne!(same.class_name(), other.class_name());
+        assert!(same.class_name().starts_with("s-"));
+    }
+
+    #[test]
+    fn test_styled_element_extracts_rules() {
+        use crate::attributes::AttributeValue;
+        use react_rs_core::signal::create_signal;
+
+        let (width, _) = create_signal(10);
+        let style = style()
+            .color("white")
+            .width_signal(px(width))
+            .hover(|s| s.color("black"));
+        let class = style.class_name();
+        let el = crate::html::button().class("btn").styled(style);
+
+        assert_eq!(el.scoped_styles().len(), 1);
+        assert_eq!(el.scoped_styles()[0].0, class);
+        assert!(el.scoped_styles()[0]
+            .1
+            .contains(&format!(".{}:hover {{ color: black }}", class)));
+
+        let class_attr = el.attributes().iter().find(|a| a.name == "class").unwrap();
+        assert_eq!(class_attr.to_static_value(), format!("btn {}", class));
+        let inline = el.attributes().iter().find(|a| a.name == "style").unwrap();
+        assert!(matches!(inline.value, AttributeValue::ReactiveString(_)));
+        assert_eq!(inline.to_static_value(), "width: 10px");
+    }
+
+    #[test]
+    fn test_styled_element_extracts_plain_declarations() {
+        let card = || style().padding("8px").color("red");
+        let a = crate::html::div().styled(card());
+        let b = crate::html::div().styled(card());
+
+        assert_eq!(a.scoped_styles(), b.scoped_styles());
+        assert_eq!(
+            a.scoped_styles()[0].1,
+            format!(".{} {{ color: red; padding: 8px }}", card().class_name())
+        );
+        assert!(a.attributes().iter().all(|attr| attr.name != "style"));
+
+        let empty = crate::html::div().styled(style());
+        assert!(empty.scoped_styles().is_empty());
+        assert!(empty.attributes().is_empty());
+    }
 }
@@ -1,10 +1,12 @@
 use crate::node::{IntoNode, Node};
 use react_rs_core::resource::{Resource, ResourceState};
+use react_rs_core::suspense::create_suspense_scope;
 use std::rc::Rc;
 
 pub struct SuspenseData {
     pub fallback: Box<Node>,
-    pub children: Box<Node>,
+    /// Shared so a streaming renderer can finish the boundary after the rest of the page.
+    pub children: Rc<Node>,
     pub loading_signal: Rc<dyn Fn() -> bool>,
 }
 
@@ -22,11 +24,25 @@ pub fn suspense<T: Clone + 'static>(
     let state = resource.state();
     Node::Suspense(SuspenseData {
         fallback: Box::new(fallback.into_node()),
-        children: Box::new(children.into_node()),
+        children: Rc::new(children.into_node()),
         loading_signal: Rc::new(move || state.get().is_loading()),
     })
 }
 
+/// Shows `fallback` until every resource created while building `children` has settled.
+/// Unlike [`suspense`], the resources do not need to be passed in.
+pub fn suspense_boundary<C: IntoNode>(
+    fallback: impl IntoNode,
+    children: impl FnOnce() -> C,
+) -> Node {
+    let (context, children) = create_suspense_scope(|| children().into_node());
+    Node::Suspense(SuspenseData {
+        fallback: Box::new(fallback.into_node()),
+        children: Rc::new(children),
+        loading_signal: Rc::new(move || context.is_pending()),
+    })
+}
+
 pub fn error_boundary<T: Clone + 'static>(
     resource: &Resource<T>,
     error_fallback: impl Fn(String) -> Node + 'static,
@@ -47,11 +63,11 @@ pub fn error_boundary<T: Clone + 'static>(
 mod tests {
     use super::*;
     use crate::html;
-    use react_rs_core::resource::create_resource;
+    use react_rs_core::resource::create_manual_resource;
 
     #[test]
     fn test_suspense_creates_node() {
-        let resource = create_resource::<String>();
+        let resource = create_manual_resource::<String>();
         let node = suspense(
             &resource,
             html::p().text("Loading..."),
@@ -60,9 +76,29 @@ mod tests {
         assert!(matches!(node, Node::Suspense(_)));
     }
 
+    #[test]
+    fn test_suspense_boundary_tracks_nested_resources() {
+        let mut resources = Vec::new();
+        let node = suspense_boundary(html::p().text("Loading..."), || {
+            resources.push(create_manual_resource::<String>());
+            resources.push(create_manual_resource::<String>());
+            html::div().text("Content")
+        });
+        let loading = match &node {
+            Node::Suspense(data) => data.loading_signal.clone(),
+            _ => panic!("expected a suspense node"),
+        };
+
+        assert!(loading());
+        resources[0].set_ready("a".to_string());
+        assert!(loading());
+        resources[1].set_ready("b".to_string());
+        assert!(!loading());
+    }
+
     #[test]
     fn test_error_boundary_creates_node() {
-        let resource = create_resource::<String>();
+        let resource = create_manual_resource::<String>();
         let node = error_boundary(
             &resource,
             |err| html::p().text(format!("Error: {}", err)).into_node(),
@@ -0,0 +1,236 @@
+//! Builders for SVG elements. They are created in the SVG namespace, so they render as
+//! graphics in the browser rather than as unknown HTML tags.
+
+use crate::Element;
+use std::fmt::Display;
+
+pub const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+
+fn svg_element(tag: &'static str) -> Element {
+    Element::new_ns(tag, SVG_NAMESPACE)
+}
+
+pub fn svg() -> Element {
+    svg_element("svg").attr("xmlns", SVG_NAMESPACE)
+}
+pub fn g() -> Element {
+    svg_element("g")
+}
+pub fn defs() -> Element {
+    svg_element("defs")
+}
+pub fn symbol() -> Element {
+    svg_element("symbol")
+}
+pub fn use_() -> Element {
+    svg_element("use")
+}
+
+pub fn path() -> Element {
+    svg_element("path")
+}
+pub fn circle() -> Element {
+    svg_element("circle")
+}
+pub fn ellipse() -> Element {
+    svg_element("ellipse")
+}
+pub fn rect() -> Element {
+    svg_element("rect")
+}
+pub fn line() -> Element {
+    svg_element("line")
+}
+pub fn polyline() -> Element {
+    svg_element("polyline")
+}
+pub fn polygon() -> Element {
+    svg_element("polygon")
+}
+
+pub fn text() -> Element {
+    svg_element("text")
+}
+pub fn tspan() -> Element {
+    svg_element("tspan")
+}
+pub fn title() -> Element {
+    svg_element("title")
+}
+
+pub fn linear_gradient() -> Element {
+    svg_element("linearGradient")
+}
+pub fn radial_gradient() -> Element {
+    svg_element("radialGradient")
+}
+pub fn stop() -> Element {
+    svg_element("stop")
+}
+pub fn clip_path() -> Element {
+    svg_element("clipPath")
+}
+pub fn mask() -> Element {
+    svg_element("mask")
+}
+
+fn num(value: impl Display) -> String {
+    value.to_string()
+}
+
+/// Presentation and geometry attributes used by the builders above.
+impl Element {
+    /// `view_box(0, 0, 24, 24)` sets `viewBox="0 0 24 24"`.
+    pub fn view_box(
+        self,
+        min_x: impl Display,
+        min_y: impl Display,
+        width: impl Display,
+        height: impl Display,
+    ) -> Self {
+        let value = format!("{} {} {} {}", min_x, min_y, width, height);
+        self.attr("viewBox", &value)
+    }
+
+    /// Path data, e.g. `"M0 0 L10 10"`.
+    pub fn d(self, data: &str) -> Self {
+        self.attr("d", data)
+    }
+
+    pub fn fill(self, paint: &str) -> Self {
+        self.attr("fill", paint)
+    }
+
+    pub fn stroke(self, paint: &str) -> Self {
+        self.attr("stroke", paint)
+    }
+
+    pub fn stroke_width(self, width: impl Display) -> Self {
+        self.attr("stroke-width", &num(width))
+    }
+
+    pub fn stroke_linecap(self, cap: &str) -> Self {
+        self.attr("stroke-linecap", cap)
+    }
+
+    pub fn stroke_linejoin(self, join: &str) -> Self {
+        self.attr("stroke-linejoin", join)
+    }
+
+    pub fn opacity(self, opacity: impl Display) -> Self {
+        self.attr("opacity", &num(opacity))
+    }
+
+    pub fn transform(self, transform: &str) -> Self {
+        self.attr("transform", transform)
+    }
+
+    pub fn x(self, x: impl Display) -> Self {
+        self.attr("x", &num(x))
+    }
+
+    pub fn y(self, y: impl Display) -> Self {
+        self.attr("y", &num(y))
+    }
+
+    pub fn width(self, width: impl Display) -> Self {
+        self.attr("width", &num(width))
+    }
+
+    pub fn height(self, height: impl Display) -> Self {
+        self.attr("height", &num(height))
+    }
+
+    pub fn cx(self, cx: impl Display) -> Self {
+        self.attr("cx", &num(cx))
+    }
+
+    pub fn cy(self, cy: impl Display) -> Self {
+        self.attr("cy", &num(cy))
+    }
+
+    pub fn r(self, r: impl Display) -> Self {
+        self.attr("r", &num(r))
+    }
+
+    pub fn rx(self, rx: impl Display) -> Self {
+        self.attr("rx", &num(rx))
+    }
+
+    pub fn ry(self, ry: impl Display) -> Self {
+        self.attr("ry", &num(ry))
+    }
+
+    pub fn x1(self, x1: impl Display) -> Self {
+        self.attr("x1", &num(x1))
+    }
+
+    pub fn y1(self, y1: impl Display) -> Self {
+        self.attr("y1", &num(y1))
+    }
+
+    pub fn x2(self, x2: impl Display) -> Self {
+        self.attr("x2", &num(x2))
+    }
+
+    pub fn y2(self, y2: impl Display) -> Self {
+        self.attr("y2", &num(y2))
+    }
+
+    /// `points(&[(0.0, 0.0), (10.0, 5.0)])` sets `points="0,0 10,5"`.
+    pub fn points<N: Display>(self, points: &[(N, N)]) -> Self {
+        let value = points
+            .iter()
+            .map(|(x, y)| format!("{},{}", x, y))
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.attr("points", &value)
+    }
+
+    pub fn offset(self, offset: &str) -> Self {
+        self.attr("offset", offset)
+    }
+
+    pub fn stop_color(self, color: &str) -> Self {
+        self.attr("stop-color", color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attr<'a>(element: &'a Element, name: &str) -> Option<&'a str> {
+        element
+            .attributes()
+            .iter()
+            .find(|a| a.name == name)
+            .and_then(|a| match &a.value {
+                crate::attributes::AttributeValue::String(s) => Some(s.as_str()),
+                _ => None,
+            })
+    }
+
+    #[test]
+    fn test_svg_builders_use_svg_namespace() {
+        let icon = svg()
+            .view_box(0, 0, 24, 24)
+            .child(circle().cx(12).cy(12).r(10.5).fill("none").stroke("red"));
+        assert_eq!(icon.namespace(), Some(SVG_NAMESPACE));
+        assert_eq!(attr(&icon, "viewBox"), Some("0 0 24 24"));
+
+        let crate::node::Node::Element(circle) = &icon.get_children()[0] else {
+            panic!("expected an element");
+        };
+        assert_eq!(circle.namespace(), Some(SVG_NAMESPACE));
+        assert_eq!(attr(circle, "r"), Some("10.5"));
+    }
+
+    #[test]
+    fn test_points_and_path() {
+        let shape = polyline().points(&[(0, 0), (10, 5)]);
+        assert_eq!(attr(&shape, "points"), Some("0,0 10,5"));
+        assert_eq!(attr(&path().d("M0 0 L1 1"), "d"), Some("M0 0 L1 1"));
+        assert_eq!(linear_gradient().tag(), "linearGradient");
+    }
+}
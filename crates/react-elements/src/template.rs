@@ -0,0 +1,149 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::attributes::AttributeValue;
+use crate::node::{IntoNode, Node};
+
+/// A static subtree built once and stamped out wherever it is used, e.g. an icon repeated in
+/// every table row. Clones share the subtree; the wasm renderer builds its DOM once and
+/// `cloneNode`s it for each use.
+///
+/// ```ignore
+/// let chevron = Template::new(svg().attr("viewBox", "0 0 24 24").child(path().attr("d", "M9 6l6 6-6 6")));
+/// each(rows, move |row, _| tr().child(td().text(&row.name)).child(td().child(&chevron)))
+/// ```
+#[derive(Clone)]
+pub struct Template {
+    node: Rc<Node>,
+    cache: Rc<RefCell<Option<Box<dyn Any>>>>,
+}
+
+impl Template {
+    /// # Panics
+    ///
+    /// Panics if `view` is not static; see [`Node::is_static`].
+    pub fn new(view: impl IntoNode) -> Self {
+        let node = view.into_node();
+        assert!(
+            node.is_static(),
+            "templates cannot contain event handlers, reactive values or dynamic nodes"
+        );
+        Self {
+            node: Rc::new(node),
+            cache: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    pub fn node(&self) -> &Node {
+        &self.node
+    }
+
+    /// Returns the value a renderer cached for this template, building it on first use.
+    /// Shared by every clone of the template.
+    pub fn cached<T, E>(&self, build: impl FnOnce() -> Result<T, E>) -> Result<T, E>
+    where
+        T: Clone + 'static,
+    {
+        if let Some(value) = self
+            .cache
+            .borrow()
+            .as_ref()
+            .and_then(|cached| cached.downcast_ref::<T>())
+        {
+            return Ok(value.clone());
+        }
+        let value = build()?;
+        *self.cache.borrow_mut() = Some(Box::new(value.clone()));
+        Ok(value)
+    }
+}
+
+impl IntoNode for Template {
+    fn into_node(self) -> Node {
+        Node::Template(self)
+    }
+}
+
+impl IntoNode for &Template {
+    fn into_node(self) -> Node {
+        Node::Template(self.clone())
+    }
+}
+
+impl Node {
+    /// Whether this subtree renders the same markup every time and needs nothing wired up:
+    /// only elements with plain attributes and no event handlers, text, fragments and
+    /// templates.
+    pub fn is_static(&self) -> bool {
+        match self {
+            Node::Element(element) => {
+                element.event_handlers().is_empty()
+                    && element.attributes().iter().all(|attr| {
+                        matches!(
+                            attr.value,
+                            AttributeValue::String(_) | AttributeValue::Bool(_)
+                        )
+                    })
+                    && element.get_children().iter().all(Node::is_static)
+            }
+            Node::Text(_) | Node::Template(_) => true,
+            Node::Fragment(children) => children.iter().all(Node::is_static),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::html;
+    use crate::reactive::SignalExt;
+    use react_rs_core::create_signal;
+
+    #[test]
+    fn test_template_stamps_share_subtree() {
+        let icon = Template::new(html::span().class("icon").text("★"));
+        let first = (&icon).into_node();
+        let second = icon.clone().into_node();
+
+        let (Node::Template(a), Node::Template(b)) = (first, second) else {
+            panic!("expected template nodes");
+        };
+        assert!(Rc::ptr_eq(&a.node, &b.node));
+        assert!(matches!(a.node(), Node::Element(el) if el.tag() == "span"));
+
+        let built = Rc::new(std::cell::Cell::new(0));
+        for _ in 0..3 {
+            let built = built.clone();
+            let html = b
+                .cached(|| {
+                    built.set(built.get() + 1);
+                    Ok::<_, ()>("<span>".to_string())
+                })
+                .unwrap();
+            assert_eq!(html, "<span>");
+        }
+        assert_eq!(built.get(), 1);
+    }
+
+    #[test]
+    fn test_is_static() {
+        let (count, _) = create_signal(0);
+        assert!(html::div()
+            .child(html::strong().text("x"))
+            .into_node()
+            .is_static());
+        assert!(!html::button().on_click(|_| {}).into_node().is_static());
+        assert!(!html::p()
+            .text_reactive(count.map(|n| n.to_string()))
+            .into_node()
+            .is_static());
+    }
+
+    #[test]
+    #[should_panic(expected = "templates cannot contain")]
+    fn test_template_rejects_handlers() {
+        Template::new(html::button().on_click(|_| {}));
+    }
+}
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use crate::node::Node;
+
+/// CSS classes applied while a [`Node::Conditional`] or [`Node::Dynamic`] branch enters or
+/// leaves. The enter class is removed once the transition ends; a leaving branch keeps its
+/// class until then and is only hidden or removed afterwards. `duration` bounds the wait in
+/// case `transitionend` never fires, e.g. when the class animates nothing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transition {
+    pub enter_class: String,
+    pub leave_class: String,
+    pub duration: Duration,
+}
+
+impl Transition {
+    pub fn new(enter_class: &str, leave_class: &str, duration: Duration) -> Self {
+        Self {
+            enter_class: enter_class.to_string(),
+            leave_class: leave_class.to_string(),
+            duration,
+        }
+    }
+}
+
+impl Node {
+    /// Animates this conditional or dynamic node's branch changes with CSS classes, e.g.
+    /// `show(open, modal()).transition("fade-in", "fade-out", Duration::from_millis(200))`. Other
+    /// nodes render unchanged.
+    pub fn transition(self, enter_class: &str, leave_class: &str, duration: Duration) -> Node {
+        Node::Transition(
+            Transition::new(enter_class, leave_class, duration),
+            Box::new(self),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::html;
+    use crate::node::show;
+
+    #[test]
+    fn test_transition_wraps_node() {
+        let node = show(true, html::div()).transition("in", "out", Duration::from_millis(150));
+        let Node::Transition(transition, child) = node else {
+            panic!("expected a transition node");
+        };
+        assert_eq!(transition.enter_class, "in");
+        assert_eq!(transition.duration, Duration::from_millis(150));
+        assert!(matches!(*child, Node::Conditional(..)));
+    }
+}
@@ -86,6 +86,22 @@ impl FormMethod {
     }
 }
 
+pub enum AriaLive {
+    Off,
+    Polite,
+    Assertive,
+}
+
+impl AriaLive {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Polite => "polite",
+            Self::Assertive => "assertive",
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,1049 @@
+use crate::fetch::{post, FetchError, FetchErrorKind, FetchResponse};
+use next_rs_actions::{
+    cookie_value, too_many_requests, ActionError, ActionResponse, ActionStreamDecoder,
+    ActionStreamEvent, FieldErrors, ACTION_STREAM_CONTENT_TYPE, BOUND_ARGS_FIELD, CSRF_COOKIE,
+    CSRF_HEADER, TOO_MANY_REQUESTS,
+};
+use next_rs_rsc::{ActionReference, FORM_ACTION_ATTR};
+use react_rs_core::context::{use_context, with_context};
+use react_rs_core::signal::{create_signal, ReadSignal, WriteSignal};
+use react_rs_elements::attributes::AttributeValue;
+use react_rs_elements::Element;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Duration;
+use wasm_bindgen::{JsCast, JsValue};
+
+pub const ACTION_PREFIX: &str = "/_action/";
+
+thread_local! {
+    static FORM_ACTIONS: RefCell<HashMap<String, ActionHandle<Value, Value>>> =
+        RefCell::new(HashMap::new());
+}
+
+pub fn action_url(id: &str) -> String {
+    format!("{}{}", ACTION_PREFIX, id)
+}
+
+#[derive(Debug, Clone)]
+pub enum ActionCallError {
+    /// The request never produced an action response (network failure, timeout, non-JSON error).
+    Fetch(FetchError),
+    /// The action ran and returned an error.
+    Action(ActionError),
+    /// The action succeeded but its output did not match the expected type.
+    Deserialize(String),
+    /// The action called `redirect`; the router is navigating to this URL.
+    Redirect(String),
+    /// A rate limit or concurrency cap on the action rejected the call.
+    TooManyRequests { retry_after: Option<Duration> },
+}
+
+impl ActionCallError {
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Fetch(e) => &e.message,
+            Self::Action(e) => &e.message,
+            Self::Deserialize(message) | Self::Redirect(message) => message,
+            Self::TooManyRequests { .. } => "Too many requests",
+        }
+    }
+
+    /// How long to wait before calling the action again, when the server said so.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::TooManyRequests { retry_after } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// Messages for the fields that failed validation on the server, for
+    /// `Form::set_field_errors`.
+    pub fn field_errors(&self) -> FieldErrors {
+        match self {
+            Self::Action(e) => e.field_errors().unwrap_or_default(),
+            _ => FieldErrors::new(),
+        }
+    }
+
+    /// The error code set by the server, e.g. `INVALID_INPUT`.
+    pub fn code(&self) -> Option<&str> {
+        match self {
+            Self::Action(e) => e.code.as_deref(),
+            Self::TooManyRequests { .. } => Some(TOO_MANY_REQUESTS),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ActionCallError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl From<FetchError> for ActionCallError {
+    fn from(error: FetchError) -> Self {
+        Self::Fetch(error)
+    }
+}
+
+impl From<ActionCallError> for ActionError {
+    fn from(error: ActionCallError) -> Self {
+        match error {
+            ActionCallError::Action(e) => e,
+            ActionCallError::Fetch(e) => ActionError::with_code(e.message, "FETCH_FAILED"),
+            ActionCallError::Deserialize(message) => {
+                ActionError::with_code(message, "INVALID_OUTPUT")
+            }
+            ActionCallError::Redirect(url) => next_rs_actions::redirect(url),
+            ActionCallError::TooManyRequests { retry_after } => too_many_requests(retry_after),
+        }
+    }
+}
+
+/// Calls the server action `id` with `args` serialized as its JSON payload.
+pub async fn call_action<Args, Output>(id: &str, args: Args) -> Result<Output, ActionCallError>
+where
+    Args: Serialize,
+    Output: DeserializeOwned,
+{
+    let mut request = post(action_url(id))
+        .header("Accept", "application/json")
+        .json(&args);
+    if let Some(token) = csrf_token() {
+        request = request.header(CSRF_HEADER, token);
+    }
+    let response = request.send().await?;
+    finish_call(decode_response(response))
+}
+
+/// Submits `fields` to the server action `id` as a multipart form, so file inputs reach
+/// the action as uploads. The server builds the action's payload from the form like it
+/// does for forms posted without JS.
+async fn call_form_action(id: &str, fields: web_sys::FormData) -> Result<Value, ActionCallError> {
+    let mut request = post(action_url(id))
+        .header("Accept", "application/json")
+        .form_data(fields);
+    if let Some(token) = csrf_token() {
+        request = request.header(CSRF_HEADER, token);
+    }
+    let response = request.send().await?;
+    finish_call(decode_response(response))
+}
+
+/// The token the server set in the CSRF cookie, echoed back so it accepts the call.
+fn csrf_token() -> Option<String> {
+    let document = web_sys::window()?
+        .document()?
+        .dyn_into::<web_sys::HtmlDocument>()
+        .ok()?;
+    let cookies = document.cookie().ok()?;
+    cookie_value(&cookies, CSRF_COOKIE).map(str::to_string)
+}
+
+/// Calls the streaming server action `id`, running `on_progress` with each progress event
+/// as it arrives. Resolves with the action's output once the server sends its response.
+pub async fn call_action_stream<Args, Progress, Output>(
+    id: &str,
+    args: Args,
+    mut on_progress: impl FnMut(Progress),
+) -> Result<Output, ActionCallError>
+where
+    Args: Serialize,
+    Progress: DeserializeOwned,
+    Output: DeserializeOwned,
+{
+    let network = |e: JsValue| {
+        ActionCallError::Fetch(FetchError::new(
+            FetchErrorKind::Network,
+            e.as_string()
+                .unwrap_or_else(|| "action stream failed".to_string()),
+        ))
+    };
+    let body = serde_json::to_string(&args).map_err(|e| {
+        ActionCallError::Fetch(FetchError::new(FetchErrorKind::Serialize, e.to_string()))
+    })?;
+    let window = web_sys::window().ok_or_else(|| network("no window".into()))?;
+
+    let opts = web_sys::RequestInit::new();
+    opts.set_method("POST");
+    opts.set_body(&JsValue::from_str(&body));
+    let request =
+        web_sys::Request::new_with_str_and_init(&action_url(id), &opts).map_err(network)?;
+    let headers = request.headers();
+    headers
+        .set("Content-Type", "application/json")
+        .map_err(network)?;
+    headers
+        .set("Accept", ACTION_STREAM_CONTENT_TYPE)
+        .map_err(network)?;
+    if let Some(token) = csrf_token() {
+        headers.set(CSRF_HEADER, &token).map_err(network)?;
+    }
+
+    let response: web_sys::Response =
+        wasm_bindgen_futures::JsFuture::from(window.fetch_with_request(&request))
+            .await
+            .map_err(network)?
+            .dyn_into()
+            .map_err(network)?;
+    let reader: web_sys::ReadableStreamDefaultReader = response
+        .body()
+        .ok_or_else(|| network("action response has no body".into()))?
+        .get_reader()
+        .dyn_into()
+        .map_err(|e| network(e.into()))?;
+    let text = web_sys::TextDecoder::new().map_err(network)?;
+    let text_opts = web_sys::TextDecodeOptions::new();
+    text_opts.set_stream(true);
+
+    // A call rejected before it ran (e.g. by the CSRF check) answers with plain JSON.
+    let streamed = response
+        .headers()
+        .get("Content-Type")
+        .ok()
+        .flatten()
+        .is_some_and(|ct| ct.starts_with(ACTION_STREAM_CONTENT_TYPE));
+    let mut decoder = ActionStreamDecoder::new();
+    let mut buffered = String::new();
+    loop {
+        let chunk = wasm_bindgen_futures::JsFuture::from(reader.read())
+            .await
+            .map_err(network)?;
+        let done = js_sys::Reflect::get(&chunk, &JsValue::from_str("done")).map_err(network)?;
+        if done.as_bool().unwrap_or(true) {
+            break;
+        }
+        let value = js_sys::Reflect::get(&chunk, &JsValue::from_str("value")).map_err(network)?;
+        let bytes: js_sys::Uint8Array = value.dyn_into().map_err(network)?;
+        let chunk = text
+            .decode_with_js_u8_array_and_options(&bytes, &text_opts)
+            .map_err(network)?;
+        if !streamed {
+            buffered.push_str(&chunk);
+            continue;
+        }
+        let events = decoder.push(&chunk).map_err(|e| {
+            ActionCallError::Fetch(FetchError::new(
+                FetchErrorKind::Deserialize,
+                format!("invalid action stream: {}", e),
+            ))
+        })?;
+        for event in events {
+            match event {
+                ActionStreamEvent::Progress(progress) => match serde_json::from_value(progress) {
+                    Ok(progress) => on_progress(progress),
+                    Err(e) => return Err(ActionCallError::Deserialize(e.to_string())),
+                },
+                ActionStreamEvent::Done(action_response) => {
+                    return finish_call(decode_action_response(action_response));
+                }
+            }
+        }
+    }
+
+    match serde_json::from_str::<ActionResponse>(&buffered) {
+        Ok(action_response) if !streamed => finish_call(decode_action_response(action_response)),
+        _ => Err(ActionCallError::Fetch(FetchError::new(
+            FetchErrorKind::Status(response.status()),
+            format!(
+                "action stream ended without a response (status {})",
+                response.status()
+            ),
+        ))),
+    }
+}
+
+fn finish_call<Output>(result: Result<Output, ActionCallError>) -> Result<Output, ActionCallError> {
+    if let Err(ActionCallError::Redirect(url)) = &result {
+        crate::router::navigate(url);
+    }
+    result
+}
+
+fn decode_response<Output: DeserializeOwned>(
+    response: FetchResponse,
+) -> Result<Output, ActionCallError> {
+    let action_response: ActionResponse = match response.json() {
+        Ok(parsed) => parsed,
+        Err(e) if response.ok => {
+            return Err(ActionCallError::Fetch(FetchError::new(
+                FetchErrorKind::Deserialize,
+                format!("invalid action response: {}", e),
+            )))
+        }
+        Err(_) => {
+            return Err(ActionCallError::Fetch(FetchError::new(
+                FetchErrorKind::Status(response.status),
+                format!("action request failed with status {}", response.status),
+            )))
+        }
+    };
+
+    decode_action_response(action_response)
+}
+
+fn decode_action_response<Output: DeserializeOwned>(
+    action_response: ActionResponse,
+) -> Result<Output, ActionCallError> {
+    if !action_response.success {
+        let error = action_response
+            .error
+            .unwrap_or_else(|| ActionError::new("action failed"));
+        if error.is_too_many_requests() {
+            return Err(ActionCallError::TooManyRequests {
+                retry_after: error.retry_after(),
+            });
+        }
+        return Err(ActionCallError::Action(error));
+    }
+
+    if let Some(url) = action_response.redirect {
+        return Err(ActionCallError::Redirect(url));
+    }
+
+    let data = action_response.data.unwrap_or(serde_json::Value::Null);
+    serde_json::from_value(data).map_err(|e| ActionCallError::Deserialize(e.to_string()))
+}
+
+/// A server action bound to pending/result signals, created with [`use_action`].
+pub struct ActionHandle<Args, Output: 'static> {
+    id: Rc<str>,
+    pending: ReadSignal<bool>,
+    set_pending: WriteSignal<bool>,
+    result: ReadSignal<Option<Result<Output, ActionCallError>>>,
+    set_result: WriteSignal<Option<Result<Output, ActionCallError>>>,
+    submit_count: ReadSignal<u32>,
+    set_submit_count: WriteSignal<u32>,
+    in_flight: Rc<Cell<usize>>,
+    latest: Rc<Cell<u64>>,
+    _args: std::marker::PhantomData<fn(Args)>,
+}
+
+impl<Args, Output: 'static> Clone for ActionHandle<Args, Output> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            pending: self.pending.clone(),
+            set_pending: self.set_pending.clone(),
+            result: self.result.clone(),
+            set_result: self.set_result.clone(),
+            submit_count: self.submit_count.clone(),
+            set_submit_count: self.set_submit_count.clone(),
+            in_flight: self.in_flight.clone(),
+            latest: self.latest.clone(),
+            _args: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<Args, Output> ActionHandle<Args, Output>
+where
+    Args: Serialize + 'static,
+    Output: DeserializeOwned + 'static,
+{
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// `true` while any dispatched call is still in flight.
+    pub fn pending(&self) -> ReadSignal<bool> {
+        self.pending.clone()
+    }
+
+    /// The result of the most recently dispatched call, once it completes.
+    pub fn result(&self) -> ReadSignal<Option<Result<Output, ActionCallError>>> {
+        self.result.clone()
+    }
+
+    /// How many times the action was dispatched.
+    pub fn submit_count(&self) -> ReadSignal<u32> {
+        self.submit_count.clone()
+    }
+
+    /// Calls the action in the background. Results of calls superseded by a newer dispatch
+    /// are discarded.
+    pub fn dispatch(&self, args: Args) {
+        self.dispatch_then(args, |_, _| {});
+    }
+
+    /// Like [`ActionHandle::dispatch`], running `settled` with the result once the call
+    /// completes, and whether it is still the latest call, before the result is published.
+    fn dispatch_then(
+        &self,
+        args: Args,
+        settled: impl FnOnce(&Result<Output, ActionCallError>, bool) + 'static,
+    ) {
+        self.dispatch_with(
+            move |id| async move { call_action::<Args, Output>(&id, args).await },
+            settled,
+        );
+    }
+
+    /// Tracks `call` as the latest dispatch of the action.
+    fn dispatch_with<Fut>(
+        &self,
+        call: impl FnOnce(Rc<str>) -> Fut,
+        settled: impl FnOnce(&Result<Output, ActionCallError>, bool) + 'static,
+    ) where
+        Fut: std::future::Future<Output = Result<Output, ActionCallError>> + 'static,
+    {
+        let call_id = self.latest.get() + 1;
+        self.latest.set(call_id);
+        self.in_flight.set(self.in_flight.get() + 1);
+        self.set_pending.set_if_changed(true);
+        self.set_submit_count.update(|count| *count += 1);
+
+        let handle = self.clone();
+        let call = call(self.id.clone());
+        wasm_bindgen_futures::spawn_local(async move {
+            let result = call.await;
+            handle.in_flight.set(handle.in_flight.get() - 1);
+            let latest = handle.latest.get() == call_id;
+            settled(&result, latest);
+            if latest {
+                handle.set_result.set(Some(result));
+            }
+            if handle.in_flight.get() == 0 {
+                handle.set_pending.set_if_changed(false);
+            }
+        });
+    }
+}
+
+pub fn use_action<Args, Output>(id: impl Into<String>) -> ActionHandle<Args, Output>
+where
+    Args: Serialize + 'static,
+    Output: DeserializeOwned + 'static,
+{
+    let (pending, set_pending) = create_signal(false);
+    let (result, set_result) = create_signal(None);
+    let (submit_count, set_submit_count) = create_signal(0);
+    ActionHandle {
+        id: Rc::from(id.into()),
+        pending,
+        set_pending,
+        result,
+        set_result,
+        submit_count,
+        set_submit_count,
+        in_flight: Rc::new(Cell::new(0)),
+        latest: Rc::new(Cell::new(0)),
+        _args: std::marker::PhantomData,
+    }
+}
+
+/// An action with the state a mutating form displays, created with [`use_action_state`].
+pub struct ActionState<Args, Output: 'static> {
+    handle: ActionHandle<Args, Output>,
+    data: ReadSignal<Option<Output>>,
+    set_data: WriteSignal<Option<Output>>,
+    error: ReadSignal<Option<ActionCallError>>,
+    set_error: WriteSignal<Option<ActionCallError>>,
+}
+
+impl<Args, Output: 'static> Clone for ActionState<Args, Output> {
+    fn clone(&self) -> Self {
+        Self {
+            handle: self.handle.clone(),
+            data: self.data.clone(),
+            set_data: self.set_data.clone(),
+            error: self.error.clone(),
+            set_error: self.set_error.clone(),
+        }
+    }
+}
+
+impl<Args, Output> ActionState<Args, Output>
+where
+    Args: Serialize + 'static,
+    Output: DeserializeOwned + Clone + 'static,
+{
+    pub fn handle(&self) -> &ActionHandle<Args, Output> {
+        &self.handle
+    }
+
+    pub fn pending(&self) -> ReadSignal<bool> {
+        self.handle.pending()
+    }
+
+    pub fn submit_count(&self) -> ReadSignal<u32> {
+        self.handle.submit_count()
+    }
+
+    /// The output of the last call that succeeded, kept while later calls run or fail.
+    pub fn data(&self) -> ReadSignal<Option<Output>> {
+        self.data.clone()
+    }
+
+    /// The error of the latest call; cleared once a call succeeds.
+    pub fn error(&self) -> ReadSignal<Option<ActionCallError>> {
+        self.error.clone()
+    }
+
+    pub fn dispatch(&self, args: Args) {
+        let set_data = self.set_data.clone();
+        let set_error = self.set_error.clone();
+        self.handle
+            .dispatch_then(args, move |result, latest| match result {
+                _ if !latest => {}
+                Ok(output) => {
+                    set_data.set(Some(output.clone()));
+                    set_error.set(None);
+                }
+                Err(error) => set_error.set(Some(error.clone())),
+            });
+    }
+}
+
+/// Confirmed state plus the updates of calls still in flight, applied on top in order.
+struct OptimisticQueue<T, Args> {
+    confirmed: T,
+    pending: Vec<(u64, Args)>,
+    next_id: u64,
+}
+
+impl<T: Clone, Args> OptimisticQueue<T, Args> {
+    fn new(confirmed: T) -> Self {
+        Self {
+            confirmed,
+            pending: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    fn push(&mut self, args: Args) -> u64 {
+        self.next_id += 1;
+        self.pending.push((self.next_id, args));
+        self.next_id
+    }
+
+    /// Drops update `id`; on success `confirm` folds the server's result into the state.
+    fn settle(&mut self, id: u64, confirm: impl FnOnce(&mut T)) {
+        self.pending.retain(|(pending, _)| *pending != id);
+        confirm(&mut self.confirmed);
+    }
+
+    fn view(&self, apply: &dyn Fn(&mut T, &Args)) -> T {
+        let mut value = self.confirmed.clone();
+        for (_, args) in &self.pending {
+            apply(&mut value, args);
+        }
+        value
+    }
+}
+
+type Apply<T, Args> = Rc<dyn Fn(&mut T, &Args)>;
+type Reconcile<T, Output> = Rc<dyn Fn(&mut T, &Output)>;
+
+/// An action whose effect shows before the server confirms it, created with
+/// [`use_optimistic`].
+pub struct OptimisticAction<T: 'static, Args: 'static, Output: 'static> {
+    state: ActionState<Args, Output>,
+    value: ReadSignal<T>,
+    set_value: WriteSignal<T>,
+    queue: Rc<RefCell<OptimisticQueue<T, Args>>>,
+    apply: Apply<T, Args>,
+    reconcile: Reconcile<T, Output>,
+}
+
+impl<T, Args, Output> OptimisticAction<T, Args, Output>
+where
+    T: Clone + 'static,
+    Args: Serialize + Clone + 'static,
+    Output: DeserializeOwned + Clone + 'static,
+{
+    /// The state with every pending call's update applied.
+    pub fn value(&self) -> ReadSignal<T> {
+        self.value.clone()
+    }
+
+    pub fn pending(&self) -> ReadSignal<bool> {
+        self.state.pending()
+    }
+
+    /// The error of the latest call. Its update has been rolled back.
+    pub fn error(&self) -> ReadSignal<Option<ActionCallError>> {
+        self.state.error()
+    }
+
+    /// Replaces the confirmed state, e.g. after refetching it; pending updates stay applied.
+    pub fn set(&self, value: T) {
+        self.queue.borrow_mut().confirmed = value;
+        self.refresh();
+    }
+
+    /// Applies the update for `args` now and calls the action. On success the server's
+    /// output is reconciled into the state; on failure the update is rolled back.
+    pub fn dispatch(&self, args: Args) {
+        let id = self.queue.borrow_mut().push(args.clone());
+        self.refresh();
+
+        let this = self.clone();
+        let set_error = self.state.set_error.clone();
+        let set_data = self.state.set_data.clone();
+        self.state
+            .handle
+            .dispatch_then(args, move |result, latest| {
+                match result {
+                    Ok(output) => {
+                        let reconcile = this.reconcile.clone();
+                        this.queue
+                            .borrow_mut()
+                            .settle(id, |state| reconcile(state, output));
+                        if latest {
+                            set_data.set(Some(output.clone()));
+                            set_error.set(None);
+                        }
+                    }
+                    Err(error) => {
+                        this.queue.borrow_mut().settle(id, |_| {});
+                        if latest {
+                            set_error.set(Some(error.clone()));
+                        }
+                    }
+                }
+                this.refresh();
+            });
+    }
+
+    fn refresh(&self) {
+        let value = self.queue.borrow().view(self.apply.as_ref());
+        self.set_value.set(value);
+    }
+}
+
+impl<T: 'static, Args: 'static, Output: 'static> Clone for OptimisticAction<T, Args, Output> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            value: self.value.clone(),
+            set_value: self.set_value.clone(),
+            queue: self.queue.clone(),
+            apply: self.apply.clone(),
+            reconcile: self.reconcile.clone(),
+        }
+    }
+}
+
+/// Wraps action `id` so the UI updates the moment it is dispatched. `apply` projects a
+/// call's arguments onto the state; `reconcile` folds the server's output into the
+/// confirmed state once the call succeeds.
+///
+/// ```rust,ignore
+/// let todos = use_optimistic(
+///     "add_todo",
+///     initial_todos,
+///     |todos: &mut Vec<Todo>, title: &String| todos.push(Todo::pending(title)),
+///     |todos: &mut Vec<Todo>, saved: &Todo| todos.push(saved.clone()),
+/// );
+/// todos.dispatch("Buy milk".to_string());
+/// ```
+pub fn use_optimistic<T, Args, Output>(
+    id: impl Into<String>,
+    initial: T,
+    apply: impl Fn(&mut T, &Args) + 'static,
+    reconcile: impl Fn(&mut T, &Output) + 'static,
+) -> OptimisticAction<T, Args, Output>
+where
+    T: Clone + 'static,
+    Args: Serialize + Clone + 'static,
+    Output: DeserializeOwned + Clone + 'static,
+{
+    let (value, set_value) = create_signal(initial.clone());
+    OptimisticAction {
+        state: use_action_state(id),
+        value,
+        set_value,
+        queue: Rc::new(RefCell::new(OptimisticQueue::new(initial))),
+        apply: Rc::new(apply),
+        reconcile: Rc::new(reconcile),
+    }
+}
+
+pub fn use_action_state<Args, Output>(id: impl Into<String>) -> ActionState<Args, Output>
+where
+    Args: Serialize + 'static,
+    Output: DeserializeOwned + Clone + 'static,
+{
+    let (data, set_data) = create_signal(None);
+    let (error, set_error) = create_signal(None);
+    ActionState {
+        handle: use_action(id),
+        data,
+        set_data,
+        error,
+        set_error,
+    }
+}
+
+/// A long-running action reporting progress while it runs, created with
+/// [`use_action_stream`].
+pub struct ActionStreamHandle<Args, Progress: 'static, Output: 'static> {
+    handle: ActionHandle<Args, Output>,
+    progress: ReadSignal<Vec<Progress>>,
+    set_progress: WriteSignal<Vec<Progress>>,
+    generation: Rc<Cell<u64>>,
+}
+
+impl<Args, Progress: 'static, Output: 'static> Clone
+    for ActionStreamHandle<Args, Progress, Output>
+{
+    fn clone(&self) -> Self {
+        Self {
+            handle: self.handle.clone(),
+            progress: self.progress.clone(),
+            set_progress: self.set_progress.clone(),
+            generation: self.generation.clone(),
+        }
+    }
+}
+
+impl<Args, Progress, Output> ActionStreamHandle<Args, Progress, Output>
+where
+    Args: Serialize + 'static,
+    Progress: DeserializeOwned + 'static,
+    Output: DeserializeOwned + 'static,
+{
+    pub fn pending(&self) -> ReadSignal<bool> {
+        self.handle.pending()
+    }
+
+    /// The progress events of the latest call so far, oldest first.
+    pub fn progress(&self) -> ReadSignal<Vec<Progress>> {
+        self.progress.clone()
+    }
+
+    pub fn result(&self) -> ReadSignal<Option<Result<Output, ActionCallError>>> {
+        self.handle.result()
+    }
+
+    /// Starts a call, clearing the progress of the previous one. Progress and results of
+    /// superseded calls are discarded.
+    pub fn dispatch(&self, args: Args) {
+        let generation = self.generation.get() + 1;
+        self.generation.set(generation);
+        self.set_progress.set(Vec::new());
+
+        let current = self.generation.clone();
+        let set_progress = self.set_progress.clone();
+        self.handle.dispatch_with(
+            move |id| async move {
+                call_action_stream::<Args, Progress, Output>(&id, args, |event| {
+                    if current.get() == generation {
+                        set_progress.update(|progress| progress.push(event));
+                    }
+                })
+                .await
+            },
+            |_, _| {},
+        );
+    }
+}
+
+/// Binds the streaming action `id` (registered with `ActionRegistry::register_streaming`)
+/// to a signal of its progress events.
+///
+/// ```rust,ignore
+/// let import = use_action_stream::<String, ImportProgress, ImportSummary>("import_csv");
+/// import.dispatch(url);
+/// let percent = import.progress().with(|events| events.last().map(|p| p.percent));
+/// ```
+pub fn use_action_stream<Args, Progress, Output>(
+    id: impl Into<String>,
+) -> ActionStreamHandle<Args, Progress, Output>
+where
+    Args: Serialize + 'static,
+    Progress: DeserializeOwned + 'static,
+    Output: DeserializeOwned + 'static,
+{
+    let (progress, set_progress) = create_signal(Vec::new());
+    ActionStreamHandle {
+        handle: use_action(id),
+        progress,
+        set_progress,
+        generation: Rc::new(Cell::new(0)),
+    }
+}
+
+/// The submission state of the enclosing action form, read with [`use_form_status`].
+#[derive(Clone)]
+pub struct FormStatus {
+    /// The action the form posts to; `None` outside [`with_form_status`].
+    pub action: Option<String>,
+    pub pending: ReadSignal<bool>,
+    pub submit_count: ReadSignal<u32>,
+}
+
+/// Builds the children of a form bound to `action`, letting nested components such as
+/// submit buttons read its status with [`use_form_status`].
+///
+/// ```rust,ignore
+/// form().action(&save).children(with_form_status(&save, || {
+///     vec![input().name("title"), submit_button()]
+/// }))
+/// ```
+pub fn with_form_status<R>(action: &ActionReference, children: impl FnOnce() -> R) -> R {
+    let handle = form_action_handle(&action.id);
+    let status = FormStatus {
+        action: Some(action.id.clone()),
+        pending: handle.pending(),
+        submit_count: handle.submit_count(),
+    };
+    with_context(status, children)
+}
+
+pub fn use_form_status() -> FormStatus {
+    use_context::<FormStatus>().unwrap_or_else(|| FormStatus {
+        action: None,
+        pending: create_signal(false).0,
+        submit_count: create_signal(0).0,
+    })
+}
+
+/// The pending/result state of forms bound to `action` with `FormActionExt::action`. Every
+/// form bound to the same action shares one handle.
+pub fn use_form_action(action: &ActionReference) -> ActionHandle<Value, Value> {
+    form_action_handle(&action.id)
+}
+
+fn form_action_handle(id: &str) -> ActionHandle<Value, Value> {
+    FORM_ACTIONS.with(|actions| {
+        actions
+            .borrow_mut()
+            .entry(id.to_string())
+            .or_insert_with(|| use_action(id))
+            .clone()
+    })
+}
+
+/// Takes over submission of a rendered or hydrated action form or button: the submit is
+/// intercepted and the form, files included, is posted to the action with fetch.
+pub(crate) fn attach_form_action(element: &Element, dom: &web_sys::Element) {
+    let Some(id) = element
+        .attributes()
+        .iter()
+        .find_map(|attr| match &attr.value {
+            AttributeValue::String(v) if attr.name == FORM_ACTION_ATTR => Some(v.clone()),
+            _ => None,
+        })
+    else {
+        return;
+    };
+
+    let handle = form_action_handle(&id);
+    if let Some(form) = dom.dyn_ref::<web_sys::HtmlFormElement>() {
+        let form = form.clone();
+        crate::listener::listen(dom.as_ref(), "submit", move |event| {
+            let fields = match web_sys::FormData::new_with_form(&form) {
+                Ok(fields) => fields,
+                Err(_) => return,
+            };
+            event.prevent_default();
+            submit_form(&handle, fields);
+        });
+    } else if let Some(button) = dom.dyn_ref::<web_sys::HtmlButtonElement>() {
+        let button = button.clone();
+        crate::listener::listen(dom.as_ref(), "click", move |event| {
+            let fields = match button.form() {
+                Some(form) => web_sys::FormData::new_with_form(&form),
+                None => web_sys::FormData::new(),
+            };
+            let Ok(fields) = fields else {
+                return;
+            };
+            event.prevent_default();
+            // Like a native submit, the clicked button contributes its own bound arguments.
+            if button.name() == BOUND_ARGS_FIELD {
+                let _ = fields.set_with_str(BOUND_ARGS_FIELD, &button.value());
+            }
+            submit_form(&handle, fields);
+        });
+    }
+}
+
+fn submit_form(handle: &ActionHandle<Value, Value>, fields: web_sys::FormData) {
+    handle.dispatch_with(
+        move |id| async move { call_form_action(&id, fields).await },
+        |_, _| {},
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status: u16, body: serde_json::Value) -> FetchResponse {
+        FetchResponse {
+            status,
+            ok: (200..300).contains(&status),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_decode_success() {
+        let resp = response(
+            200,
+            serde_json::to_value(ActionResponse::success(vec![1, 2, 3])).unwrap(),
+        );
+        let data: Vec<u32> = decode_response(resp).unwrap();
+        assert_eq!(data, vec![1, 2, 3]);
+        assert_eq!(action_url("create-post"), "/_action/create-post");
+    }
+
+    #[test]
+    fn test_decode_action_error() {
+        let resp = response(
+            400,
+            serde_json::to_value(ActionResponse::error(ActionError::with_code(
+                "Invalid input",
+                "INVALID_INPUT",
+            )))
+            .unwrap(),
+        );
+        let err = decode_response::<String>(resp).unwrap_err();
+        assert!(matches!(err, ActionCallError::Action(_)));
+        assert_eq!(err.code(), Some("INVALID_INPUT"));
+        assert_eq!(err.message(), "Invalid input");
+        assert!(err.field_errors().is_empty());
+
+        let errors = FieldErrors::from([("email".to_string(), "Already taken".to_string())]);
+        let resp = response(
+            400,
+            serde_json::to_value(ActionResponse::error(ActionError::validation(errors))).unwrap(),
+        );
+        let err = decode_response::<String>(resp).unwrap_err();
+        assert_eq!(err.field_errors()["email"], "Already taken");
+    }
+
+    #[test]
+    fn test_decode_transport_and_type_errors() {
+        let resp = FetchResponse {
+            status: 502,
+            ok: false,
+            body: "Bad Gateway".to_string(),
+        };
+        match decode_response::<String>(resp).unwrap_err() {
+            ActionCallError::Fetch(e) => assert_eq!(e.kind, FetchErrorKind::Status(502)),
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        let resp = response(
+            200,
+            serde_json::to_value(ActionResponse::success("x")).unwrap(),
+        );
+        assert!(matches!(
+            decode_response::<u32>(resp),
+            Err(ActionCallError::Deserialize(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_redirect() {
+        let resp = response(
+            200,
+            serde_json::to_value(ActionResponse::redirect("/posts/9")).unwrap(),
+        );
+        match decode_response::<u32>(resp).unwrap_err() {
+            ActionCallError::Redirect(url) => assert_eq!(url, "/posts/9"),
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decode_too_many_requests() {
+        let resp = response(
+            429,
+            serde_json::to_value(ActionResponse::error(too_many_requests(Some(
+                Duration::from_secs(30),
+            ))))
+            .unwrap(),
+        );
+        let err = decode_response::<u32>(resp).unwrap_err();
+        assert!(matches!(err, ActionCallError::TooManyRequests { .. }));
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(30)));
+        assert_eq!(err.code(), Some(TOO_MANY_REQUESTS));
+    }
+
+    #[test]
+    fn test_action_state_starts_idle() {
+        let state = use_action_state::<String, u32>("rename");
+        assert!(!state.pending().get_untracked());
+        assert_eq!(state.submit_count().get_untracked(), 0);
+        assert!(state.data().get_untracked().is_none());
+        assert!(state.error().get_untracked().is_none());
+        assert_eq!(state.handle().id(), "rename");
+    }
+
+    #[test]
+    fn test_optimistic_queue_reconciles_and_rolls_back() {
+        let apply = |likes: &mut Vec<String>, user: &String| likes.push(format!("{}?", user));
+        let mut queue = OptimisticQueue::new(vec!["ann".to_string()]);
+
+        let bob = queue.push("bob".to_string());
+        let cat = queue.push("cat".to_string());
+        assert_eq!(queue.view(&apply), vec!["ann", "bob?", "cat?"]);
+
+        // Bob's call fails and is rolled back; cat's stays pending.
+        queue.settle(bob, |_| {});
+        assert_eq!(queue.view(&apply), vec!["ann", "cat?"]);
+
+        queue.settle(cat, |likes| likes.push("cat".to_string()));
+        assert_eq!(queue.view(&apply), vec!["ann", "cat"]);
+        assert!(queue.pending.is_empty());
+    }
+
+    #[test]
+    fn test_optimistic_set_keeps_pending_updates() {
+        let likes = use_optimistic(
+            "like",
+            0u32,
+            |count: &mut u32, _: &()| *count += 1,
+            |count: &mut u32, confirmed: &u32| *count = *confirmed,
+        );
+        likes.queue.borrow_mut().push(());
+        likes.set(10);
+        assert_eq!(likes.value().get_untracked(), 11);
+        assert!(likes.error().get_untracked().is_none());
+    }
+
+    #[test]
+    fn test_form_status_follows_enclosing_form() {
+        let reference = ActionReference {
+            id: "comment".to_string(),
+            bound_args: None,
+        };
+        let outside = use_form_status();
+        assert!(outside.action.is_none());
+
+        let status = with_form_status(&reference, use_form_status);
+        assert_eq!(status.action.as_deref(), Some("comment"));
+        assert!(!status.pending.get_untracked());
+
+        let handle = use_form_action(&reference);
+        handle.set_pending.set(true);
+        handle.set_submit_count.set(1);
+        assert!(status.pending.get_untracked());
+        assert_eq!(status.submit_count.get_untracked(), 1);
+    }
+
+    #[test]
+    fn test_form_actions_share_handle() {
+        let reference = ActionReference {
+            id: "subscribe".to_string(),
+            bound_args: None,
+        };
+        let first = use_form_action(&reference);
+        let second = use_form_action(&reference);
+
+        first.set_pending.set(true);
+        assert!(second.pending().get_untracked());
+        assert_eq!(second.id(), "subscribe");
+    }
+}
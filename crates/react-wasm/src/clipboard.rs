@@ -0,0 +1,197 @@
+use crate::hooks::browser_window;
+use react_rs_core::signal::{create_signal, ReadSignal, WriteSignal};
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::PermissionState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardAccess {
+    Read,
+    Write,
+}
+
+impl ClipboardAccess {
+    fn permission_name(&self) -> &'static str {
+        match self {
+            Self::Read => "clipboard-read",
+            Self::Write => "clipboard-write",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardPermission {
+    Granted,
+    /// The browser will ask the user on first access.
+    Prompt,
+    Denied,
+    /// The Permissions API does not know about clipboard access (e.g. Firefox, Safari);
+    /// the request itself decides.
+    Unknown,
+}
+
+impl From<PermissionState> for ClipboardPermission {
+    fn from(state: PermissionState) -> Self {
+        match state {
+            PermissionState::Granted => Self::Granted,
+            PermissionState::Prompt => Self::Prompt,
+            PermissionState::Denied => Self::Denied,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClipboardError {
+    /// No async clipboard, e.g. outside a secure context or during SSR.
+    Unsupported,
+    PermissionDenied,
+    Failed(String),
+}
+
+impl std::fmt::Display for ClipboardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unsupported => write!(f, "clipboard is not available"),
+            Self::PermissionDenied => write!(f, "clipboard permission denied"),
+            Self::Failed(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<JsValue> for ClipboardError {
+    fn from(value: JsValue) -> Self {
+        let name = js_sys::Reflect::get(&value, &"name".into())
+            .ok()
+            .and_then(|n| n.as_string());
+        match name.as_deref() {
+            Some("NotAllowedError") | Some("SecurityError") => Self::PermissionDenied,
+            _ => Self::Failed(format!("{:?}", value)),
+        }
+    }
+}
+
+fn clipboard() -> Result<web_sys::Clipboard, ClipboardError> {
+    let navigator = browser_window()
+        .ok_or(ClipboardError::Unsupported)?
+        .navigator();
+    // `navigator.clipboard` is undefined outside secure contexts.
+    let clipboard = js_sys::Reflect::get(&navigator, &"clipboard".into())
+        .map_err(|_| ClipboardError::Unsupported)?;
+    if clipboard.is_undefined() || clipboard.is_null() {
+        return Err(ClipboardError::Unsupported);
+    }
+    Ok(navigator.clipboard())
+}
+
+pub async fn clipboard_permission(access: ClipboardAccess) -> ClipboardPermission {
+    let permissions = match browser_window().and_then(|w| w.navigator().permissions().ok()) {
+        Some(permissions) => permissions,
+        None => return ClipboardPermission::Unknown,
+    };
+    let descriptor = js_sys::Object::new();
+    let _ = js_sys::Reflect::set(
+        &descriptor,
+        &"name".into(),
+        &access.permission_name().into(),
+    );
+    let promise = match permissions.query(&descriptor) {
+        Ok(promise) => promise,
+        Err(_) => return ClipboardPermission::Unknown,
+    };
+    match JsFuture::from(promise).await {
+        Ok(status) => status
+            .dyn_into::<web_sys::PermissionStatus>()
+            .map(|s| s.state().into())
+            .unwrap_or(ClipboardPermission::Unknown),
+        // Unknown permission names reject with a TypeError.
+        Err(_) => ClipboardPermission::Unknown,
+    }
+}
+
+pub async fn write_text(text: &str) -> Result<(), ClipboardError> {
+    let clipboard = clipboard()?;
+    JsFuture::from(clipboard.write_text(text)).await?;
+    Ok(())
+}
+
+/// Reads text from the clipboard. Browsers may prompt the user; a denied prompt yields
+/// [`ClipboardError::PermissionDenied`].
+pub async fn read_text() -> Result<String, ClipboardError> {
+    let clipboard = clipboard()?;
+    if clipboard_permission(ClipboardAccess::Read).await == ClipboardPermission::Denied {
+        return Err(ClipboardError::PermissionDenied);
+    }
+    let text = JsFuture::from(clipboard.read_text()).await?;
+    Ok(text.as_string().unwrap_or_default())
+}
+
+/// A copy-to-clipboard helper with signals for "Copied!" indicators and error messages.
+#[derive(Clone)]
+pub struct ClipboardHandle {
+    copied: ReadSignal<bool>,
+    set_copied: WriteSignal<bool>,
+    error: ReadSignal<Option<ClipboardError>>,
+    set_error: WriteSignal<Option<ClipboardError>>,
+    supported: Rc<bool>,
+}
+
+impl ClipboardHandle {
+    /// `true` once the most recent copy succeeded.
+    pub fn copied(&self) -> ReadSignal<bool> {
+        self.copied.clone()
+    }
+
+    pub fn error(&self) -> ReadSignal<Option<ClipboardError>> {
+        self.error.clone()
+    }
+
+    pub fn is_supported(&self) -> bool {
+        *self.supported
+    }
+
+    pub fn copy(&self, text: impl Into<String>) {
+        let text = text.into();
+        let handle = self.clone();
+        handle.set_copied.set_if_changed(false);
+        wasm_bindgen_futures::spawn_local(async move {
+            match write_text(&text).await {
+                Ok(()) => {
+                    handle.set_error.set(None);
+                    handle.set_copied.set(true);
+                }
+                Err(e) => handle.set_error.set(Some(e)),
+            }
+        });
+    }
+}
+
+pub fn use_clipboard() -> ClipboardHandle {
+    let (copied, set_copied) = create_signal(false);
+    let (error, set_error) = create_signal(None);
+    ClipboardHandle {
+        copied,
+        set_copied,
+        error,
+        set_error,
+        supported: Rc::new(clipboard().is_ok()),
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clipboard_unsupported_off_browser() {
+        let handle = use_clipboard();
+        assert!(!handle.is_supported());
+        assert!(!handle.copied().get_untracked());
+        assert_eq!(clipboard().err(), Some(ClipboardError::Unsupported));
+        assert_eq!(
+            ClipboardPermission::from(PermissionState::Denied),
+            ClipboardPermission::Denied
+        );
+    }
+}
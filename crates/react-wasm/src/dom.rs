@@ -1,18 +1,33 @@
-use react_rs_elements::attributes::AttributeValue;
-use react_rs_elements::node::Node;
+use react_rs_elements::attributes::{Attribute, AttributeValue};
+use react_rs_elements::node::{DiffListData, ListChange, Node};
+use react_rs_elements::reactive::ReactiveValue;
+use react_rs_elements::transition::Transition;
 use react_rs_elements::Element;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use web_sys::Document;
 
+use crate::patch::MountHandle;
+use react_rs_core::ScopeId;
+
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
+const NON_BUBBLING_EVENTS: &[&str] = &[
+    "mouseenter",
+    "mouseleave",
+    "pointerenter",
+    "pointerleave",
+    "focus",
+    "blur",
+];
+
 static EVENT_ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
 type EventCallback = Rc<dyn Fn(WasmEvent)>;
+type KeyedListFn = Rc<dyn Fn() -> Vec<(String, Node)>>;
 
 thread_local! {
     static EVENT_REGISTRY: RefCell<HashMap<usize, EventCallback>> = RefCell::new(HashMap::new());
@@ -62,6 +77,71 @@ impl WasmEvent {
                 .map(|e| e.checked())
         })
     }
+
+    /// Converts to the platform-independent event passed to element handlers.
+    pub fn to_event(&self) -> react_rs_elements::events::Event {
+        use react_rs_elements::events::{Event, KeyModifiers, PointerInfo};
+
+        let mut event = Event::new(self.inner.type_());
+        if let Some(val) = self.target_value() {
+            event = event.with_target_value(val);
+        }
+        if let Some(checked) = self.target_checked() {
+            event = event.with_checked(checked);
+        }
+        if let Some(mouse) = self.inner.dyn_ref::<web_sys::MouseEvent>() {
+            // Also covers pointer and wheel events, which extend MouseEvent.
+            event = event
+                .with_pointer(PointerInfo {
+                    client_x: mouse.client_x() as f64,
+                    client_y: mouse.client_y() as f64,
+                    page_x: mouse.page_x() as f64,
+                    page_y: mouse.page_y() as f64,
+                    offset_x: mouse.offset_x() as f64,
+                    offset_y: mouse.offset_y() as f64,
+                    button: mouse.button(),
+                    buttons: mouse.buttons(),
+                })
+                .with_key_modifiers(KeyModifiers {
+                    alt: mouse.alt_key(),
+                    ctrl: mouse.ctrl_key(),
+                    shift: mouse.shift_key(),
+                    meta: mouse.meta_key(),
+                });
+        }
+        if let Some(wheel) = self.inner.dyn_ref::<web_sys::WheelEvent>() {
+            event = event.with_wheel_delta(wheel.delta_x(), wheel.delta_y());
+        }
+        if let Some(touch_event) = self.inner.dyn_ref::<web_sys::TouchEvent>() {
+            if let Some(touch) = touch_event.changed_touches().get(0) {
+                event = event.with_pointer(PointerInfo {
+                    client_x: touch.client_x() as f64,
+                    client_y: touch.client_y() as f64,
+                    page_x: touch.page_x() as f64,
+                    page_y: touch.page_y() as f64,
+                    ..PointerInfo::default()
+                });
+            }
+            event = event.with_key_modifiers(KeyModifiers {
+                alt: touch_event.alt_key(),
+                ctrl: touch_event.ctrl_key(),
+                shift: touch_event.shift_key(),
+                meta: touch_event.meta_key(),
+            });
+        }
+        if let Some(keyboard) = self.inner.dyn_ref::<web_sys::KeyboardEvent>() {
+            event = event
+                .with_key(keyboard.key(), keyboard.code())
+                .with_repeat(keyboard.repeat())
+                .with_key_modifiers(KeyModifiers {
+                    alt: keyboard.alt_key(),
+                    ctrl: keyboard.ctrl_key(),
+                    shift: keyboard.shift_key(),
+                    meta: keyboard.meta_key(),
+                });
+        }
+        event
+    }
 }
 
 pub struct DomNode {
@@ -126,47 +206,7 @@ fn render_node(document: &Document, node: &Node) -> Result<web_sys::Node, JsValu
             Ok(fragment.into())
         }
         Node::Conditional(condition, then_node, else_node) => {
-            use react_rs_core::effect::create_effect;
-
-            let then_dom = render_node(document, then_node)?;
-            let then_el = then_dom.dyn_ref::<web_sys::Element>().cloned();
-
-            let else_dom = else_node
-                .as_ref()
-                .map(|en| render_node(document, en))
-                .transpose()?;
-            let else_el = else_dom
-                .as_ref()
-                .and_then(|n| n.dyn_ref::<web_sys::Element>().cloned());
-
-            let container = document.create_element("span")?;
-            container.set_attribute("data-cond", "")?;
-            container.set_attribute("style", "display:contents")?;
-            container.append_child(&then_dom)?;
-            if let Some(ref ed) = else_dom {
-                container.append_child(ed)?;
-            }
-
-            let show = condition.get();
-            if let Some(ref el) = then_el {
-                let _ = el.set_attribute("style", if show { "" } else { "display:none" });
-            }
-            if let Some(ref el) = else_el {
-                let _ = el.set_attribute("style", if show { "display:none" } else { "" });
-            }
-
-            let condition = condition.clone();
-            create_effect(move || {
-                let visible = condition.get();
-                if let Some(ref el) = then_el {
-                    let _ = el.set_attribute("style", if visible { "" } else { "display:none" });
-                }
-                if let Some(ref el) = else_el {
-                    let _ = el.set_attribute("style", if visible { "display:none" } else { "" });
-                }
-            });
-
-            Ok(container.into())
+            render_conditional(document, condition, then_node, else_node.as_deref(), None)
         }
         Node::ReactiveList(list_fn) => {
             use react_rs_core::effect::create_effect;
@@ -191,83 +231,28 @@ fn render_node(document: &Document, node: &Node) -> Result<web_sys::Node, JsValu
                         let _ = container_rc.append_child(&dom_child);
                     }
                 }
+                run_mounts_if_connected(&container_rc);
             });
 
             Ok(container.into())
         }
         Node::KeyedList(list_fn) => {
-            use react_rs_core::effect::create_effect;
-            use std::cell::RefCell;
-            use std::collections::HashMap;
-
             let container = document.create_element("span")?;
             container.set_attribute("data-list", "")?;
             container.set_attribute("style", "display:contents")?;
-
-            let initial_items = list_fn();
-            let mut initial_cache: HashMap<String, web_sys::Node> = HashMap::new();
-            let mut initial_keys: Vec<String> = Vec::new();
-
-            for (key, child_node) in &initial_items {
-                let dom_child = render_node(document, child_node)?;
-                container.append_child(&dom_child)?;
-                initial_cache.insert(key.clone(), dom_child);
-                initial_keys.push(key.clone());
-            }
-
-            let container_rc = Rc::new(container.clone());
-            let list_fn = list_fn.clone();
-            let cache = Rc::new(RefCell::new(initial_cache));
-            let prev_keys = Rc::new(RefCell::new(initial_keys));
-
-            create_effect(move || {
-                let new_items = list_fn();
-                let doc = get_document();
-                let mut cache_ref = cache.borrow_mut();
-                let mut old_keys = prev_keys.borrow_mut();
-
-                let new_keys: Vec<String> = new_items.iter().map(|(k, _)| k.clone()).collect();
-
-                let new_key_set: std::collections::HashSet<&String> = new_keys.iter().collect();
-                let old_key_set: std::collections::HashSet<&String> = old_keys.iter().collect();
-
-                for old_key in &*old_keys {
-                    if !new_key_set.contains(old_key) {
-                        if let Some(dom_node) = cache_ref.remove(old_key) {
-                            let _ = container_rc.remove_child(&dom_node);
-                        }
-                    }
-                }
-
-                for (key, child_node) in &new_items {
-                    if !old_key_set.contains(key) {
-                        if let Ok(dom_child) = render_node(&doc, child_node) {
-                            cache_ref.insert(key.clone(), dom_child);
-                        }
-                    }
-                }
-
-                let children = container_rc.child_nodes();
-                for (i, key) in new_keys.iter().enumerate() {
-                    if let Some(dom_node) = cache_ref.get(key) {
-                        let current = children.get(i as u32);
-                        let needs_move = current
-                            .as_ref()
-                            .map(|c| !c.is_same_node(Some(dom_node)))
-                            .unwrap_or(true);
-                        if needs_move {
-                            let reference = children.get(i as u32);
-                            let _ = container_rc.insert_before(dom_node, reference.as_ref());
-                        }
-                    }
-                }
-
-                *old_keys = new_keys;
-            });
-
+            attach_keyed_list(document, &container, list_fn)?;
+            Ok(container.into())
+        }
+        Node::DiffList(list) => {
+            let container = document.create_element("span")?;
+            container.set_attribute("data-list", "")?;
+            container.set_attribute("style", "display:contents")?;
+            attach_diff_list(document, &container, list)?;
             Ok(container.into())
         }
-        Node::Head(_) => {
+        Node::Head(head) => {
+            let head_id = crate::head::apply_head(head);
+            react_rs_core::effect::on_cleanup(move || crate::head::remove_head(head_id));
             let placeholder = document.create_text_node("");
             Ok(placeholder.into())
         }
@@ -337,6 +322,7 @@ fn render_node(document: &Document, node: &Node) -> Result<web_sys::Node, JsValu
                     if let Ok(error_dom) = render_node_pub(&doc, &error_node) {
                         let _ = container_rc.append_child(&error_dom);
                     }
+                    run_mounts_if_connected(&container_rc);
                 } else if let Some(ref el) = children_el {
                     let _ = el.set_attribute("style", "");
                 }
@@ -344,55 +330,455 @@ fn render_node(document: &Document, node: &Node) -> Result<web_sys::Node, JsValu
 
             Ok(container.into())
         }
+        Node::Dynamic(view) => render_dynamic(document, view, None),
+        Node::Transition(transition, child) => match &**child {
+            Node::Conditional(condition, then_node, else_node) => render_conditional(
+                document,
+                condition,
+                then_node,
+                else_node.as_deref(),
+                Some(transition.clone()),
+            ),
+            Node::Dynamic(view) => render_dynamic(document, view, Some(transition.clone())),
+            other => render_node(document, other),
+        },
+        Node::Template(template) => {
+            let prototype = template.cached(|| render_node(document, template.node()))?;
+            prototype.clone_node_with_deep(true)
+        }
+        Node::Portal(target, child) => {
+            let placeholder = document.create_element("span")?;
+            placeholder.set_attribute("data-portal", "")?;
+            placeholder.set_attribute("style", "display:contents")?;
+            mount_portal(document, target, child)?;
+            Ok(placeholder.into())
+        }
     }
 }
 
-fn render_element(document: &Document, element: &Element) -> Result<web_sys::Node, JsValue> {
-    use react_rs_core::effect::create_effect;
-
-    let el = document.create_element(element.tag())?;
-
-    for attr in element.attributes() {
-        match &attr.value {
-            AttributeValue::String(s) => {
-                el.set_attribute(&attr.name, s)?;
+/// Renders `view` into `container` and re-renders it from scratch whenever a signal it reads
+/// changes. Each rendering gets its own scope under the current owner, disposed when the next
+/// one replaces it.
+pub(crate) fn attach_dynamic(
+    container: web_sys::Element,
+    view: Rc<dyn Fn() -> Node>,
+    transition: Option<Transition>,
+) {
+    use react_rs_core::effect::{
+        create_effect, current_scope, dispose_scope, run_in_new_scope, run_in_scope, untrack,
+    };
+
+    let owner = current_scope();
+    let mounted: Rc<std::cell::Cell<Option<ScopeId>>> = Rc::new(std::cell::Cell::new(None));
+
+    create_effect(move || {
+        // Only the signals `view` reads decide when to swap; the subtree tracks its own.
+        let build = || {
+            run_in_new_scope(|| {
+                let node = view();
+                untrack(|| render_node(&get_document(), &node))
+            })
+        };
+        let (scope, rendered) = match owner {
+            Some(owner) => run_in_scope(owner, build),
+            None => build(),
+        };
+        let previous = mounted.replace(Some(scope));
+        let old_children = child_nodes_of(&container);
+
+        match (&transition, previous) {
+            // The old subtree stays alive until its leave transition finishes.
+            (Some(transition), Some(previous)) => {
+                let mut pending = Some(previous);
+                for child in &old_children {
+                    match child.dyn_ref::<web_sys::Element>() {
+                        Some(el) => {
+                            let (node, scope) = (child.clone(), pending.take());
+                            crate::transition::animate_leave(el, transition, move || {
+                                if let Some(parent) = node.parent_node() {
+                                    let _ = parent.remove_child(&node);
+                                }
+                                if let Some(scope) = scope {
+                                    untrack(|| dispose_scope(scope));
+                                }
+                            });
+                        }
+                        None => {
+                            let _ = container.remove_child(child);
+                        }
+                    }
+                }
+                if let Some(scope) = pending {
+                    untrack(|| dispose_scope(scope));
+                }
             }
-            AttributeValue::Bool(b) => {
-                if *b {
-                    el.set_attribute(&attr.name, "")?;
+            (_, previous) => {
+                if let Some(previous) = previous {
+                    untrack(|| dispose_scope(previous));
                 }
+                container.set_inner_html("");
             }
-            AttributeValue::ReactiveString(reactive) => {
-                let initial_value = reactive.get();
-                el.set_attribute(&attr.name, &initial_value)?;
+        }
 
-                let el_rc = Rc::new(el.clone());
-                let name_rc = Rc::new(attr.name.clone());
-                let reactive = reactive.clone();
+        if let Ok(dom_child) = rendered {
+            if let (Some(transition), Some(el)) =
+                (&transition, dom_child.dyn_ref::<web_sys::Element>())
+            {
+                if !old_children.is_empty() {
+                    crate::transition::animate_enter(el, transition);
+                }
+            }
+            let _ = container.append_child(&dom_child);
+        }
+        run_mounts_if_connected(&container);
+    });
+}
 
-                create_effect(move || {
-                    let value = reactive.get();
-                    let _ = el_rc.set_attribute(&name_rc, &value);
-                });
+fn child_nodes_of(container: &web_sys::Element) -> Vec<web_sys::Node> {
+    let children = container.child_nodes();
+    (0..children.length())
+        .filter_map(|i| children.get(i))
+        .collect()
+}
+
+/// Renders a keyed list into `container` and reconciles it by key on every change, moving
+/// existing rows instead of re-rendering them so their state and scopes survive.
+pub(crate) fn attach_keyed_list(
+    document: &Document,
+    container: &web_sys::Element,
+    list_fn: &KeyedListFn,
+) -> Result<(), JsValue> {
+    use react_rs_core::effect::{create_effect, current_scope, dispose_scope};
+
+    // Items live in their own scopes under the list's owner rather than in the list
+    // effect's run, so re-running the effect keeps existing rows reactive.
+    let owner = current_scope();
+    let initial_items = list_fn();
+    let mut initial_cache: HashMap<String, (web_sys::Node, ScopeId)> = HashMap::new();
+    let mut initial_keys: Vec<String> = Vec::new();
+
+    for (key, child_node) in &initial_items {
+        let (scope, dom_child) = render_keyed_item(document, owner, child_node)?;
+        container.append_child(&dom_child)?;
+        initial_cache.insert(key.clone(), (dom_child, scope));
+        initial_keys.push(key.clone());
+    }
+
+    let container_rc = Rc::new(container.clone());
+    let list_fn = list_fn.clone();
+    let cache = Rc::new(RefCell::new(initial_cache));
+    let prev_keys = Rc::new(RefCell::new(initial_keys));
+
+    create_effect(move || {
+        let new_items = list_fn();
+        let doc = get_document();
+        let mut cache_ref = cache.borrow_mut();
+        let mut old_keys = prev_keys.borrow_mut();
+        let mut removed = Vec::new();
+
+        let new_keys: Vec<String> = new_items.iter().map(|(k, _)| k.clone()).collect();
+
+        let new_key_set: std::collections::HashSet<&String> = new_keys.iter().collect();
+        let old_key_set: std::collections::HashSet<&String> = old_keys.iter().collect();
+
+        for old_key in &*old_keys {
+            if !new_key_set.contains(old_key) {
+                if let Some((dom_node, scope)) = cache_ref.remove(old_key) {
+                    let _ = container_rc.remove_child(&dom_node);
+                    removed.push(scope);
+                }
             }
-            AttributeValue::ReactiveBool(reactive) => {
-                if reactive.get() {
-                    el.set_attribute(&attr.name, "")?;
+        }
+
+        for (key, child_node) in &new_items {
+            if !old_key_set.contains(key) {
+                if let Ok((scope, dom_child)) = render_keyed_item(&doc, owner, child_node) {
+                    cache_ref.insert(key.clone(), (dom_child, scope));
                 }
+            }
+        }
 
-                let el_rc = Rc::new(el.clone());
-                let name_rc = Rc::new(attr.name.clone());
-                let reactive = reactive.clone();
+        let children = container_rc.child_nodes();
+        for (i, key) in new_keys.iter().enumerate() {
+            if let Some((dom_node, _)) = cache_ref.get(key) {
+                let current = children.get(i as u32);
+                let needs_move = current
+                    .as_ref()
+                    .map(|c| !c.is_same_node(Some(dom_node)))
+                    .unwrap_or(true);
+                if needs_move {
+                    let reference = children.get(i as u32);
+                    let _ = container_rc.insert_before(dom_node, reference.as_ref());
+                }
+            }
+        }
 
-                create_effect(move || {
-                    if reactive.get() {
-                        let _ = el_rc.set_attribute(&name_rc, "");
-                    } else {
-                        let _ = el_rc.remove_attribute(&name_rc);
+        *old_keys = new_keys;
+        drop(cache_ref);
+        drop(old_keys);
+        for scope in removed {
+            dispose_scope(scope);
+        }
+        run_mounts_if_connected(&container_rc);
+    });
+
+    Ok(())
+}
+
+/// Renders `list` into `container` and keeps it in sync by applying each change in place.
+/// Rows get their own scopes under the current owner, disposed when they are removed.
+pub(crate) fn attach_diff_list(
+    document: &Document,
+    container: &web_sys::Element,
+    list: &DiffListData,
+) -> Result<(), JsValue> {
+    use react_rs_core::effect::{create_effect, current_scope, dispose_scope};
+
+    let owner = current_scope();
+    let changes = (list.changes)();
+    let mut initial_rows = Vec::new();
+    for (_, child_node) in (list.items)() {
+        let (scope, dom_child) = render_keyed_item(document, owner, &child_node)?;
+        container.append_child(&dom_child)?;
+        initial_rows.push((dom_child, scope));
+    }
+
+    let container_rc = Rc::new(container.clone());
+    let rows: Rc<RefCell<Vec<(web_sys::Node, ScopeId)>>> = Rc::new(RefCell::new(initial_rows));
+
+    create_effect(move || {
+        let doc = get_document();
+        let mut removed = Vec::new();
+        let mut rows_ref = rows.borrow_mut();
+
+        for change in changes() {
+            match change {
+                ListChange::Insert { index, node, .. } => {
+                    if let Ok((scope, dom_child)) = render_keyed_item(&doc, owner, &node) {
+                        let reference = rows_ref.get(index).map(|(n, _)| n.clone());
+                        let _ = container_rc.insert_before(&dom_child, reference.as_ref());
+                        rows_ref.insert(index, (dom_child, scope));
                     }
-                });
+                }
+                ListChange::Update { index, node, .. } => {
+                    if let Ok((scope, dom_child)) = render_keyed_item(&doc, owner, &node) {
+                        let (old, old_scope) =
+                            std::mem::replace(&mut rows_ref[index], (dom_child.clone(), scope));
+                        let _ = container_rc.replace_child(&dom_child, &old);
+                        removed.push(old_scope);
+                    }
+                }
+                ListChange::Remove { index } => {
+                    let (old, old_scope) = rows_ref.remove(index);
+                    let _ = container_rc.remove_child(&old);
+                    removed.push(old_scope);
+                }
+                ListChange::Move { from, to } => {
+                    let row = rows_ref.remove(from);
+                    let reference = rows_ref.get(to).map(|(n, _)| n.clone());
+                    let _ = container_rc.insert_before(&row.0, reference.as_ref());
+                    rows_ref.insert(to, row);
+                }
+                ListChange::Reset(items) => {
+                    for (old, old_scope) in rows_ref.drain(..) {
+                        let _ = container_rc.remove_child(&old);
+                        removed.push(old_scope);
+                    }
+                    for (_, child_node) in items {
+                        if let Ok((scope, dom_child)) = render_keyed_item(&doc, owner, &child_node)
+                        {
+                            let _ = container_rc.append_child(&dom_child);
+                            rows_ref.push((dom_child, scope));
+                        }
+                    }
+                }
             }
         }
+
+        drop(rows_ref);
+        for scope in removed {
+            dispose_scope(scope);
+        }
+        run_mounts_if_connected(&container_rc);
+    });
+
+    Ok(())
+}
+
+fn render_keyed_item(
+    document: &Document,
+    owner: Option<ScopeId>,
+    node: &Node,
+) -> Result<(ScopeId, web_sys::Node), JsValue> {
+    use react_rs_core::effect::{run_in_new_scope, run_in_scope};
+
+    let render = || run_in_new_scope(|| render_node(document, node));
+    let (scope, rendered) = match owner {
+        Some(owner) => run_in_scope(owner, render),
+        None => render(),
+    };
+    Ok((scope, rendered?))
+}
+
+/// Runs pending `on_mount` callbacks once freshly rendered DOM is part of the document.
+/// Renders that happen while building a detached tree leave them queued for the caller
+/// that attaches it.
+pub(crate) fn run_mounts_if_connected(node: &web_sys::Node) {
+    if node.is_connected() {
+        react_rs_core::effect::run_mount_callbacks();
+    }
+}
+
+/// Renders `child` into a container appended to the element matching `target`, falling back
+/// to `document.body`. Events still reach their handlers since delegation listens on the
+/// document. The container is removed when the owning effect is cleaned up.
+pub(crate) fn mount_portal(document: &Document, target: &str, child: &Node) -> Result<(), JsValue> {
+    let host: web_sys::Element = match document.query_selector(target)? {
+        Some(el) => el,
+        None => document
+            .body()
+            .ok_or_else(|| JsValue::from_str("no body"))?
+            .into(),
+    };
+
+    let container = document.create_element("div")?;
+    container.set_attribute("data-portal-root", "")?;
+    container.append_child(&render_node(document, child)?)?;
+    host.append_child(&container)?;
+
+    react_rs_core::effect::on_cleanup(move || container.remove());
+    Ok(())
+}
+
+fn render_conditional(
+    document: &Document,
+    condition: &ReactiveValue<bool>,
+    then_node: &Node,
+    else_node: Option<&Node>,
+    transition: Option<Transition>,
+) -> Result<web_sys::Node, JsValue> {
+    use react_rs_core::effect::create_effect;
+
+    let then_dom = render_node(document, then_node)?;
+    let then_el = then_dom.dyn_ref::<web_sys::Element>().cloned();
+
+    let else_dom = else_node.map(|en| render_node(document, en)).transpose()?;
+    let else_el = else_dom
+        .as_ref()
+        .and_then(|n| n.dyn_ref::<web_sys::Element>().cloned());
+
+    let container = document.create_element("span")?;
+    container.set_attribute("data-cond", "")?;
+    container.set_attribute("style", "display:contents")?;
+    container.append_child(&then_dom)?;
+    if let Some(ref ed) = else_dom {
+        container.append_child(ed)?;
+    }
+
+    let show = condition.get();
+    toggle_conditional(then_el.as_ref(), else_el.as_ref(), show);
+
+    let condition = condition.clone();
+    let last_shown = std::cell::Cell::new(show);
+    create_effect(move || {
+        let visible = condition.get();
+        if last_shown.replace(visible) == visible {
+            return;
+        }
+        swap_conditional(
+            then_el.clone(),
+            else_el.clone(),
+            visible,
+            transition.as_ref(),
+        );
+    });
+
+    Ok(container.into())
+}
+
+fn render_dynamic(
+    document: &Document,
+    view: &Rc<dyn Fn() -> Node>,
+    transition: Option<Transition>,
+) -> Result<web_sys::Node, JsValue> {
+    let container = document.create_element("span")?;
+    container.set_attribute("data-dyn", "")?;
+    container.set_attribute("style", "display:contents")?;
+    attach_dynamic(container.clone(), view.clone(), transition);
+    Ok(container.into())
+}
+
+/// Switches a conditional's branches after its condition changed: through CSS classes when
+/// it has a [`Transition`], otherwise inside a view transition.
+pub(crate) fn swap_conditional(
+    then_el: Option<web_sys::Element>,
+    else_el: Option<web_sys::Element>,
+    show_then: bool,
+    transition: Option<&Transition>,
+) {
+    let Some(transition) = transition else {
+        crate::transition::with_view_transition(move || {
+            toggle_conditional(then_el.as_ref(), else_el.as_ref(), show_then);
+        });
+        return;
+    };
+    let (entering, leaving) = if show_then {
+        (then_el, else_el)
+    } else {
+        (else_el, then_el)
+    };
+    if let Some(el) = entering {
+        set_shown(&el, true);
+        crate::transition::animate_enter(&el, transition);
+    }
+    if let Some(el) = leaving {
+        let hidden = el.clone();
+        crate::transition::animate_leave(&el, transition, move || set_shown(&hidden, false));
+    }
+}
+
+pub(crate) fn set_shown(el: &web_sys::Element, shown: bool) {
+    if let Some(html_el) = el.dyn_ref::<web_sys::HtmlElement>() {
+        let style = html_el.style();
+        if shown {
+            let _ = style.remove_property("display");
+        } else {
+            let _ = style.set_property("display", "none");
+        }
+    }
+}
+
+pub(crate) fn toggle_conditional(
+    then_el: Option<&web_sys::Element>,
+    else_el: Option<&web_sys::Element>,
+    show_then: bool,
+) {
+    if let Some(el) = then_el {
+        set_shown(el, show_then);
+    }
+    if let Some(el) = else_el {
+        set_shown(el, !show_then);
+    }
+}
+
+fn render_element(document: &Document, element: &Element) -> Result<web_sys::Node, JsValue> {
+    let el = match element.namespace() {
+        Some(namespace) => document.create_element_ns(Some(namespace), element.tag())?,
+        None => document.create_element(element.tag())?,
+    };
+
+    for attr in element.attributes() {
+        apply_attribute(&el, attr)?;
+    }
+
+    crate::viewport::attach_pending(element, &el);
+    crate::actions::attach_form_action(element, &el);
+    for (class, rules) in element.scoped_styles() {
+        crate::head::ensure_style(class, rules);
+    }
+
+    if let Some(html) = element.get_inner_html() {
+        el.set_inner_html(html);
     }
 
     for child in element.get_children() {
@@ -400,32 +786,179 @@ fn render_element(document: &Document, element: &Element) -> Result<web_sys::Nod
         el.append_child(&child_node)?;
     }
 
+    // A select's value can only be applied once its options exist.
+    if element.tag() == "select" {
+        if let Some(value) = element
+            .attributes()
+            .iter()
+            .find(|attr| attr.name == "value")
+        {
+            sync_form_property(&el, "value", Some(&value.to_static_value()), None);
+        }
+    }
+
+    attach_event_handlers(document, element, &el)?;
+
+    Ok(el.into())
+}
+
+/// Sets an attribute on `el`, keeping it in sync with its signal when it is reactive.
+pub(crate) fn apply_attribute(el: &web_sys::Element, attr: &Attribute) -> Result<(), JsValue> {
+    use react_rs_core::effect::create_effect;
+
+    if !Attribute::is_valid_name(&attr.name) {
+        return Ok(());
+    }
+
+    match &attr.value {
+        AttributeValue::String(s) => {
+            el.set_attribute(&attr.name, s)?;
+        }
+        AttributeValue::Bool(b) => {
+            if *b {
+                el.set_attribute(&attr.name, "")?;
+            } else {
+                el.remove_attribute(&attr.name)?;
+            }
+        }
+        AttributeValue::ReactiveString(reactive) => {
+            let initial_value = reactive.get();
+            el.set_attribute(&attr.name, &initial_value)?;
+
+            let el_rc = Rc::new(el.clone());
+            let name_rc = Rc::new(attr.name.clone());
+            let reactive = reactive.clone();
+
+            create_effect(move || {
+                let value = reactive.get();
+                let _ = el_rc.set_attribute(&name_rc, &value);
+                sync_form_property(&el_rc, &name_rc, Some(&value), None);
+            });
+        }
+        AttributeValue::ReactiveBool(reactive) => {
+            if reactive.get() {
+                el.set_attribute(&attr.name, "")?;
+            } else {
+                el.remove_attribute(&attr.name)?;
+            }
+
+            let el_rc = Rc::new(el.clone());
+            let name_rc = Rc::new(attr.name.clone());
+            let reactive = reactive.clone();
+
+            create_effect(move || {
+                let on = reactive.get();
+                if on {
+                    let _ = el_rc.set_attribute(&name_rc, "");
+                } else {
+                    let _ = el_rc.remove_attribute(&name_rc);
+                }
+                sync_form_property(&el_rc, &name_rc, None, Some(on));
+            });
+        }
+    }
+    Ok(())
+}
+
+/// The `value` and `checked` attributes only set a control's initial state, so once the user
+/// has edited it the matching property must be written too for a signal change to show.
+pub(crate) fn sync_form_property(
+    el: &web_sys::Element,
+    name: &str,
+    value: Option<&str>,
+    checked: Option<bool>,
+) {
+    match (name, value, checked) {
+        ("value", Some(value), _) => {
+            if let Some(input) = el.dyn_ref::<web_sys::HtmlInputElement>() {
+                if input.value() != value {
+                    input.set_value(value);
+                }
+            } else if let Some(textarea) = el.dyn_ref::<web_sys::HtmlTextAreaElement>() {
+                if textarea.value() != value {
+                    textarea.set_value(value);
+                }
+            } else if let Some(select) = el.dyn_ref::<web_sys::HtmlSelectElement>() {
+                select.set_value(value);
+            }
+        }
+        ("checked", _, Some(checked)) => {
+            if let Some(input) = el.dyn_ref::<web_sys::HtmlInputElement>() {
+                input.set_checked(checked);
+            }
+        }
+        _ => {}
+    }
+}
+
+pub(crate) fn attach_event_handlers(
+    document: &Document,
+    element: &Element,
+    el: &web_sys::Element,
+) -> Result<(), JsValue> {
     for handler in element.event_handlers() {
         let event_type = handler.event_type().to_string();
         let event_id = next_event_id();
 
+        let modifiers = handler.modifiers();
         let callback = handler.take_handler_rc();
+        let wrapped: EventCallback = Rc::new(move |wasm_event: WasmEvent| {
+            if modifiers.prevent_default {
+                wasm_event.inner().prevent_default();
+            }
+            if modifiers.stop_propagation {
+                wasm_event.inner().stop_propagation();
+            }
+            if modifiers.once {
+                unregister_event_handler(event_id);
+            }
+            callback(wasm_event.to_event());
+        });
+
+        // Passive listeners must be registered with the browser as such, which delegation
+        // cannot express, so they are attached to the element itself.
+        if modifiers.passive {
+            attach_passive_listener(el, &event_type, event_id, wrapped)?;
+            continue;
+        }
 
-        register_event_callback(
-            event_id,
-            Rc::new(move |wasm_event: WasmEvent| {
-                let mut react_event =
-                    react_rs_elements::events::Event::new(wasm_event.inner().type_());
-                if let Some(val) = wasm_event.target_value() {
-                    react_event = react_event.with_target_value(val);
-                }
-                if let Some(checked) = wasm_event.target_checked() {
-                    react_event = react_event.with_checked(checked);
-                }
-                callback(react_event);
-            }),
-        );
-
+        register_event_callback(event_id, wrapped);
         el.set_attribute("data-eid", &event_id.to_string())?;
         ensure_delegated_listener(document, &event_type)?;
     }
+    Ok(())
+}
 
-    Ok(el.into())
+fn attach_passive_listener(
+    el: &web_sys::Element,
+    event_type: &str,
+    event_id: usize,
+    callback: EventCallback,
+) -> Result<(), JsValue> {
+    register_event_callback(event_id, callback);
+    let closure = Closure::wrap(Box::new(move |e: web_sys::Event| {
+        let callback = EVENT_REGISTRY.with(|registry| registry.borrow().get(&event_id).cloned());
+        if let Some(cb) = callback {
+            cb(WasmEvent::new(e));
+        }
+    }) as Box<dyn FnMut(web_sys::Event)>);
+
+    let options = web_sys::AddEventListenerOptions::new();
+    options.set_passive(true);
+    el.add_event_listener_with_callback_and_add_event_listener_options(
+        event_type,
+        closure.as_ref().unchecked_ref(),
+        &options,
+    )?;
+
+    let el = el.clone();
+    let event_type = event_type.to_string();
+    react_rs_core::effect::on_cleanup(move || {
+        let _ =
+            el.remove_event_listener_with_callback(&event_type, closure.as_ref().unchecked_ref());
+        unregister_event_handler(event_id);
+    });
+    Ok(())
 }
 
 pub fn next_event_id() -> usize {
@@ -453,6 +986,9 @@ pub fn ensure_delegated_listener(document: &Document, event_type: &str) -> Resul
         return Ok(());
     }
 
+    // Events that do not bubble are caught in the capture phase and only delivered to their
+    // own target, so entering a child does not fire the parent's `mouseenter`.
+    let bubbles = !NON_BUBBLING_EVENTS.contains(&event_type);
     let closure = Closure::wrap(Box::new(move |e: web_sys::Event| {
         let mut target = e
             .target()
@@ -469,28 +1005,32 @@ pub fn ensure_delegated_listener(document: &Document, event_type: &str) -> Resul
                     }
                 }
             }
+            if !bubbles {
+                return;
+            }
             target = el.parent_element();
         }
     }) as Box<dyn FnMut(web_sys::Event)>);
 
-    document.add_event_listener_with_callback(event_type, closure.as_ref().unchecked_ref())?;
+    document.add_event_listener_with_callback_and_bool(
+        event_type,
+        closure.as_ref().unchecked_ref(),
+        !bubbles,
+    )?;
     closure.forget();
 
     Ok(())
 }
 
-pub fn mount(node: &Node, container_id: &str) -> Result<(), JsValue> {
+/// Renders `node` into the element with id `container_id`, replacing its contents. The
+/// returned handle re-renders in place with [`MountHandle::update`].
+pub fn mount(node: &Node, container_id: &str) -> Result<MountHandle, JsValue> {
     let document = get_document();
     let container = document
         .get_element_by_id(container_id)
         .ok_or_else(|| JsValue::from_str(&format!("Container '{}' not found", container_id)))?;
 
-    container.set_inner_html("");
-
-    let dom_node = render_to_dom(node)?;
-    container.append_child(&dom_node)?;
-
-    Ok(())
+    MountHandle::mount(container, node)
 }
 
 pub fn register_event_handler<F: Fn(WasmEvent) + 'static>(event_id: usize, handler: F) {
@@ -1,7 +1,37 @@
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::rc::Rc;
+use std::time::Duration;
+
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::JsFuture;
-use web_sys::{Request, RequestInit, Response};
+use web_sys::{AbortController, FormData, Request, RequestInit, Response};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+    Head,
+    Options,
+}
+
+impl Method {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Get => "GET",
+            Self::Post => "POST",
+            Self::Put => "PUT",
+            Self::Patch => "PATCH",
+            Self::Delete => "DELETE",
+            Self::Head => "HEAD",
+            Self::Options => "OPTIONS",
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct FetchResponse {
@@ -14,13 +44,56 @@ impl FetchResponse {
     pub fn json<T: serde::de::DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
         serde_json::from_str(&self.body)
     }
+
+    pub fn text(&self) -> &str {
+        &self.body
+    }
+
+    pub fn error_for_status(self) -> Result<Self, FetchError> {
+        if self.ok {
+            Ok(self)
+        } else {
+            Err(FetchError::new(
+                FetchErrorKind::Status(self.status),
+                format!("request failed with status {}", self.status),
+            ))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchErrorKind {
+    Network,
+    Timeout,
+    Aborted,
+    Status(u16),
+    Serialize,
+    Deserialize,
 }
 
 #[derive(Debug, Clone)]
 pub struct FetchError {
+    pub kind: FetchErrorKind,
     pub message: String,
 }
 
+impl FetchError {
+    pub fn new(kind: FetchErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    fn is_retryable(&self) -> bool {
+        match self.kind {
+            FetchErrorKind::Network | FetchErrorKind::Timeout => true,
+            FetchErrorKind::Status(status) => status >= 500,
+            _ => false,
+        }
+    }
+}
+
 impl std::fmt::Display for FetchError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.message)
@@ -29,56 +102,200 @@ impl std::fmt::Display for FetchError {
 
 impl From<JsValue> for FetchError {
     fn from(value: JsValue) -> Self {
-        FetchError {
-            message: format!("{:?}", value),
-        }
+        FetchError::new(FetchErrorKind::Network, format!("{:?}", value))
     }
 }
 
-pub async fn fetch(url: &str) -> Result<FetchResponse, FetchError> {
-    fetch_with_options(url, "GET", None).await
+pub struct RequestBuilder {
+    url: String,
+    method: Method,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+    form: Option<FormData>,
+    body_error: Option<FetchError>,
+    timeout: Option<Duration>,
+    retries: u32,
+    retry_backoff: Duration,
 }
 
-pub async fn fetch_json<T: serde::de::DeserializeOwned>(url: &str) -> Result<T, FetchError> {
-    let response = fetch(url).await?;
-    response.json().map_err(|e| FetchError {
-        message: e.to_string(),
-    })
-}
+impl RequestBuilder {
+    pub fn new(method: Method, url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            method,
+            headers: Vec::new(),
+            body: None,
+            form: None,
+            body_error: None,
+            timeout: None,
+            retries: 0,
+            retry_backoff: Duration::from_millis(200),
+        }
+    }
 
-pub async fn post_json<T: serde::Serialize>(
-    url: &str,
-    body: &T,
-) -> Result<FetchResponse, FetchError> {
-    let json = serde_json::to_string(body).map_err(|e| FetchError {
-        message: e.to_string(),
-    })?;
-    fetch_with_options(url, "POST", Some(&json)).await
-}
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
 
-async fn fetch_with_options(
-    url: &str,
-    method: &str,
-    body: Option<&str>,
-) -> Result<FetchResponse, FetchError> {
-    let window = web_sys::window().ok_or_else(|| FetchError {
-        message: "no window".to_string(),
-    })?;
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Sends `form` as a `multipart/form-data` body, files included. The browser sets the
+    /// content type with its boundary.
+    pub fn form_data(mut self, form: FormData) -> Self {
+        self.form = Some(form);
+        self
+    }
+
+    pub fn json<T: serde::Serialize>(mut self, body: &T) -> Self {
+        match serde_json::to_string(body) {
+            Ok(json) => {
+                self.body = Some(json);
+                if !self.has_header("content-type") {
+                    self = self.header("Content-Type", "application/json");
+                }
+            }
+            Err(e) => {
+                self.body_error = Some(FetchError::new(FetchErrorKind::Serialize, e.to_string()))
+            }
+        }
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
 
-    let opts = RequestInit::new();
-    opts.set_method(method);
+    /// Retries network failures, timeouts and 5xx responses up to `retries` times,
+    /// doubling the delay after every attempt starting from `backoff`.
+    pub fn retry(mut self, retries: u32, backoff: Duration) -> Self {
+        self.retries = retries;
+        self.retry_backoff = backoff;
+        self
+    }
+
+    fn has_header(&self, name: &str) -> bool {
+        self.headers
+            .iter()
+            .any(|(k, _)| k.eq_ignore_ascii_case(name))
+    }
 
-    if let Some(body_str) = body {
-        opts.set_body(&JsValue::from_str(body_str));
+    /// Starts the request. When called inside an effect, the request is aborted once that
+    /// effect re-runs or its scope is disposed.
+    pub fn send(self) -> impl Future<Output = Result<FetchResponse, FetchError>> {
+        let cancelled = Rc::new(Cell::new(false));
+        let controller: Rc<RefCell<Option<AbortController>>> = Rc::new(RefCell::new(None));
+
+        {
+            let cancelled = cancelled.clone();
+            let controller = controller.clone();
+            react_rs_core::effect::on_cleanup(move || {
+                cancelled.set(true);
+                if let Some(c) = controller.borrow().as_ref() {
+                    c.abort();
+                }
+            });
+        }
+
+        async move {
+            if let Some(err) = self.body_error.clone() {
+                return Err(err);
+            }
+
+            let mut attempt = 0;
+            loop {
+                if cancelled.get() {
+                    return Err(FetchError::new(FetchErrorKind::Aborted, "request aborted"));
+                }
+
+                let result = self.send_once(&controller, &cancelled).await;
+                match result {
+                    Err(err) if attempt < self.retries && err.is_retryable() => {
+                        sleep(backoff_delay(self.retry_backoff, attempt)).await;
+                        attempt += 1;
+                    }
+                    Ok(resp) if attempt < self.retries && resp.status >= 500 => {
+                        sleep(backoff_delay(self.retry_backoff, attempt)).await;
+                        attempt += 1;
+                    }
+                    other => return other,
+                }
+            }
+        }
     }
 
-    let request = Request::new_with_str_and_init(url, &opts)?;
+    async fn send_once(
+        &self,
+        controller_slot: &Rc<RefCell<Option<AbortController>>>,
+        cancelled: &Rc<Cell<bool>>,
+    ) -> Result<FetchResponse, FetchError> {
+        let window = web_sys::window()
+            .ok_or_else(|| FetchError::new(FetchErrorKind::Network, "no window"))?;
 
-    if body.is_some() {
-        request.headers().set("Content-Type", "application/json")?;
+        let controller = AbortController::new()?;
+        *controller_slot.borrow_mut() = Some(controller.clone());
+
+        let opts = RequestInit::new();
+        opts.set_method(self.method.as_str());
+        opts.set_signal(Some(&controller.signal()));
+        if let Some(form) = &self.form {
+            opts.set_body(form);
+        } else if let Some(body) = &self.body {
+            opts.set_body(&JsValue::from_str(body));
+        }
+
+        let request = Request::new_with_str_and_init(&self.url, &opts)?;
+        for (name, value) in &self.headers {
+            request.headers().set(name, value)?;
+        }
+
+        let timed_out = Rc::new(Cell::new(false));
+        let timeout_handle = match self.timeout {
+            Some(timeout) => {
+                let timed_out = timed_out.clone();
+                let controller = controller.clone();
+                let callback = Closure::once_into_js(move || {
+                    timed_out.set(true);
+                    controller.abort();
+                });
+                window
+                    .set_timeout_with_callback_and_timeout_and_arguments_0(
+                        callback.unchecked_ref(),
+                        timeout.as_millis() as i32,
+                    )
+                    .ok()
+            }
+            None => None,
+        };
+
+        let result = read_response(&window, &request).await;
+
+        if let Some(handle) = timeout_handle {
+            window.clear_timeout_with_handle(handle);
+        }
+        *controller_slot.borrow_mut() = None;
+
+        result.map_err(|err| {
+            if timed_out.get() {
+                FetchError::new(FetchErrorKind::Timeout, "request timed out")
+            } else if cancelled.get() {
+                FetchError::new(FetchErrorKind::Aborted, "request aborted")
+            } else {
+                err
+            }
+        })
     }
+}
 
-    let resp_value = JsFuture::from(window.fetch_with_request(&request)).await?;
+async fn read_response(
+    window: &web_sys::Window,
+    request: &Request,
+) -> Result<FetchResponse, FetchError> {
+    let resp_value = JsFuture::from(window.fetch_with_request(request)).await?;
     let resp: Response = resp_value.dyn_into()?;
 
     let status = resp.status();
@@ -91,14 +308,115 @@ async fn fetch_with_options(
     Ok(FetchResponse { status, ok, body })
 }
 
+fn backoff_delay(base: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(2u32.saturating_pow(attempt))
+}
+
+async fn sleep(duration: Duration) {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        if let Some(window) = web_sys::window() {
+            let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+                &resolve,
+                duration.as_millis() as i32,
+            );
+        }
+    });
+    let _ = JsFuture::from(promise).await;
+}
+
+pub fn request(method: Method, url: impl Into<String>) -> RequestBuilder {
+    RequestBuilder::new(method, url)
+}
+
+pub fn get(url: impl Into<String>) -> RequestBuilder {
+    RequestBuilder::new(Method::Get, url)
+}
+
+pub fn post(url: impl Into<String>) -> RequestBuilder {
+    RequestBuilder::new(Method::Post, url)
+}
+
+pub fn put(url: impl Into<String>) -> RequestBuilder {
+    RequestBuilder::new(Method::Put, url)
+}
+
+pub fn patch(url: impl Into<String>) -> RequestBuilder {
+    RequestBuilder::new(Method::Patch, url)
+}
+
+pub fn delete(url: impl Into<String>) -> RequestBuilder {
+    RequestBuilder::new(Method::Delete, url)
+}
+
+pub async fn fetch(url: &str) -> Result<FetchResponse, FetchError> {
+    get(url).send().await
+}
+
+pub async fn fetch_json<T: serde::de::DeserializeOwned>(url: &str) -> Result<T, FetchError> {
+    let response = fetch(url).await?;
+    response
+        .json()
+        .map_err(|e| FetchError::new(FetchErrorKind::Deserialize, e.to_string()))
+}
+
+pub async fn post_json<T: serde::Serialize>(
+    url: &str,
+    body: &T,
+) -> Result<FetchResponse, FetchError> {
+    post(url).json(body).send().await
+}
+
 pub fn use_fetch<T, F>(url: &str, on_result: F)
 where
     T: serde::de::DeserializeOwned + 'static,
     F: Fn(Result<T, FetchError>) + 'static,
 {
-    let url = url.to_string();
+    let request = get(url).send();
     wasm_bindgen_futures::spawn_local(async move {
-        let result = fetch_json::<T>(&url).await;
+        let result = request.await.and_then(|resp| {
+            resp.json::<T>()
+                .map_err(|e| FetchError::new(FetchErrorKind::Deserialize, e.to_string()))
+        });
         on_result(result);
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_method_as_str() {
+        assert_eq!(Method::Get.as_str(), "GET");
+        assert_eq!(Method::Patch.as_str(), "PATCH");
+        assert_eq!(Method::Delete.as_str(), "DELETE");
+    }
+
+    #[test]
+    fn test_request_builder_json_sets_content_type() {
+        let builder = post("/api/items").json(&serde_json::json!({"name": "a"}));
+        assert_eq!(builder.body.as_deref(), Some(r#"{"name":"a"}"#));
+        assert!(builder.has_header("Content-Type"));
+
+        let builder = post("/api/items")
+            .header("content-type", "application/vnd.api+json")
+            .json(&1);
+        assert_eq!(builder.headers.len(), 1);
+    }
+
+    #[test]
+    fn test_backoff_delay_doubles() {
+        let base = Duration::from_millis(100);
+        assert_eq!(backoff_delay(base, 0), Duration::from_millis(100));
+        assert_eq!(backoff_delay(base, 1), Duration::from_millis(200));
+        assert_eq!(backoff_delay(base, 3), Duration::from_millis(800));
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        assert!(FetchError::new(FetchErrorKind::Timeout, "").is_retryable());
+        assert!(FetchError::new(FetchErrorKind::Status(503), "").is_retryable());
+        assert!(!FetchError::new(FetchErrorKind::Status(404), "").is_retryable());
+        assert!(!FetchError::new(FetchErrorKind::Aborted, "").is_retryable());
+    }
+}
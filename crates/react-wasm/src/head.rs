@@ -0,0 +1,207 @@
+use react_rs_elements::head::{Head, ScriptStrategy};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use wasm_bindgen::JsCast;
+use web_sys::Document;
+
+const MANAGED_ATTR: &str = "data-next-head";
+
+static HEAD_ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static HEAD_MANAGER: RefCell<HeadManager> = RefCell::new(HeadManager::default());
+}
+
+#[derive(Default)]
+struct HeadManager {
+    entries: Vec<(usize, Head)>,
+    original_title: Option<String>,
+    overridden_meta: Vec<(web_sys::Element, Option<String>)>,
+}
+
+/// Applies `head` to `document.head`, returning a key that reverts it via [`remove_head`].
+pub fn apply_head(head: &Head) -> usize {
+    let id = HEAD_ID_COUNTER.fetch_add(1, Ordering::SeqCst);
+    HEAD_MANAGER.with(|manager| {
+        let mut manager = manager.borrow_mut();
+        if manager.entries.is_empty() && manager.original_title.is_none() {
+            manager.original_title = document().map(|d| d.title());
+        }
+        manager.entries.push((id, head.clone()));
+        manager.sync();
+    });
+    id
+}
+
+pub fn remove_head(id: usize) {
+    HEAD_MANAGER.with(|manager| {
+        let mut manager = manager.borrow_mut();
+        manager.entries.retain(|(entry_id, _)| *entry_id != id);
+        manager.sync();
+    });
+}
+
+fn append_script(document: &Document, src: &str) {
+    let (Some(head_el), Ok(el)) = (document.head(), document.create_element("script")) else {
+        return;
+    };
+    let _ = el.set_attribute("src", src);
+    let _ = el.set_attribute(MANAGED_ATTR, "");
+    let _ = head_el.append_child(&el);
+}
+
+/// Adds a `<style>` with `css` under `key` unless one is already present. Extracted styles are
+/// keyed by their content, so they are kept for the page's lifetime rather than removed.
+pub fn ensure_style(key: &str, css: &str) {
+    let Some(document) = document() else {
+        return;
+    };
+    let selector = format!("style[data-style=\"{}\"]", key.replace('"', "\\\""));
+    if let Ok(Some(_)) = document.query_selector(&selector) {
+        return;
+    }
+    let (Some(head_el), Ok(el)) = (document.head(), document.create_element("style")) else {
+        return;
+    };
+    let _ = el.set_attribute("data-style", key);
+    el.set_text_content(Some(css));
+    let _ = head_el.append_child(&el);
+}
+
+fn document() -> Option<Document> {
+    web_sys::window()?.document()
+}
+
+impl HeadManager {
+    fn sync(&mut self) {
+        let document = match document() {
+            Some(d) => d,
+            None => return,
+        };
+        let head_el = match document.head() {
+            Some(h) => h,
+            None => return,
+        };
+
+        // Scripts stay once added: removing the element cannot undo running it, and adding it
+        // back would run it again.
+        if let Ok(managed) = head_el.query_selector_all(&format!("[{}]:not(script)", MANAGED_ATTR))
+        {
+            for i in 0..managed.length() {
+                if let Some(node) = managed.get(i) {
+                    let _ = head_el.remove_child(&node);
+                }
+            }
+        }
+        for (el, content) in self.overridden_meta.drain(..) {
+            match content {
+                Some(c) => {
+                    let _ = el.set_attribute("content", &c);
+                }
+                None => {
+                    let _ = el.remove_attribute("content");
+                }
+            }
+        }
+
+        let merged = Head::merge_all(self.entries.iter().map(|(_, head)| head));
+
+        match (&merged.title, &self.original_title) {
+            (Some(title), _) => document.set_title(title),
+            (None, Some(original)) => document.set_title(original),
+            (None, None) => {}
+        }
+
+        for meta in &merged.meta_tags {
+            let attr = if meta.property { "property" } else { "name" };
+            let selector = format!("meta[{}=\"{}\"]", attr, meta.name.replace('"', "\\\""));
+            if let Ok(Some(existing)) = head_el.query_selector(&selector) {
+                self.overridden_meta
+                    .push((existing.clone(), existing.get_attribute("content")));
+                let _ = existing.set_attribute("content", &meta.content);
+            } else if let Ok(el) = document.create_element("meta") {
+                let _ = el.set_attribute(attr, &meta.name);
+                let _ = el.set_attribute("content", &meta.content);
+                let _ = el.set_attribute(MANAGED_ATTR, "");
+                let _ = head_el.append_child(&el);
+            }
+        }
+
+        for link in &merged.links {
+            let (rel, href) = (&link.rel, &link.href);
+            let selector = format!(
+                "link[rel=\"{}\"][href=\"{}\"]",
+                rel.replace('"', "\\\""),
+                href.replace('"', "\\\"")
+            );
+            if let Ok(Some(_)) = head_el.query_selector(&selector) {
+                continue;
+            }
+            if let Ok(link) = document.create_element("link") {
+                let _ = link.set_attribute("rel", rel);
+                let _ = link.set_attribute("href", href);
+                let _ = link.set_attribute(MANAGED_ATTR, "");
+                let _ = head_el.append_child(&link);
+            }
+        }
+
+        for style in &merged.styles {
+            ensure_style(&style.key, &style.css);
+        }
+
+        for script in &merged.scripts {
+            let selector = format!("script[src=\"{}\"]", script.src.replace('"', "\\\""));
+            if let Ok(Some(_)) = document.query_selector(&selector) {
+                continue;
+            }
+            // The page is already interactive on the client, so only lazy scripts wait.
+            if script.strategy == ScriptStrategy::LazyOnLoad && document.ready_state() != "complete"
+            {
+                let src = script.src.clone();
+                let doc = document.clone();
+                let load = wasm_bindgen::closure::Closure::once_into_js(move || {
+                    append_script(&doc, &src);
+                });
+                if let Some(window) = web_sys::window() {
+                    let _ = window.add_event_listener_with_callback("load", load.unchecked_ref());
+                }
+                continue;
+            }
+            append_script(&document, &script.src);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_heads_later_wins() {
+        let layout = Head::new()
+            .title("Site")
+            .description("Site description")
+            .link_stylesheet("/app.css");
+        let page = Head::new()
+            .title("Post")
+            .description("Post description")
+            .link_stylesheet("/app.css")
+            .link_stylesheet("/post.css");
+
+        let merged = Head::merge_all([layout, page].iter());
+        assert_eq!(merged.title.as_deref(), Some("Post"));
+        assert_eq!(merged.meta_tags.len(), 1);
+        assert_eq!(merged.meta_tags[0].content, "Post description");
+        assert_eq!(merged.links.len(), 2);
+    }
+
+    #[test]
+    fn test_merge_heads_keeps_earlier_title() {
+        let layout = Head::new().title("Site");
+        let page = Head::new().keywords("rust");
+
+        let merged = Head::merge_all([layout, page].iter());
+        assert_eq!(merged.title.as_deref(), Some("Site"));
+        assert_eq!(merged.meta_tags.len(), 1);
+    }
+}
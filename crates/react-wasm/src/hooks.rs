@@ -0,0 +1,282 @@
+use crate::listener::{drop_later, listen};
+use react_rs_core::effect::on_cleanup;
+use react_rs_core::signal::{create_signal, ReadSignal};
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+use std::time::Duration;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+type TimerClosure = Closure<dyn FnMut(JsValue)>;
+
+/// Returns the browser window, or `None` when running outside the browser (e.g. during SSR).
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn browser_window() -> Option<web_sys::Window> {
+    web_sys::window()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn browser_window() -> Option<web_sys::Window> {
+    None
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct WindowSize {
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Tracks whether `query` currently matches, e.g. `use_media_query("(prefers-color-scheme: dark)")`.
+pub fn use_media_query(query: &str) -> ReadSignal<bool> {
+    let list = browser_window().and_then(|w| w.match_media(query).ok().flatten());
+    let list = match list {
+        Some(list) => list,
+        None => return create_signal(false).0,
+    };
+
+    let (matches, set_matches) = create_signal(list.matches());
+    let target = list.clone();
+    listen(target.as_ref(), "change", move |_| {
+        set_matches.set_if_changed(list.matches());
+    });
+    matches
+}
+
+pub fn use_window_size() -> ReadSignal<WindowSize> {
+    let window = match browser_window() {
+        Some(window) => window,
+        None => return create_signal(WindowSize::default()).0,
+    };
+
+    let (size, set_size) = create_signal(window_size(&window));
+    let target = window.clone();
+    listen(target.as_ref(), "resize", move |_| {
+        set_size.set_if_changed(window_size(&window));
+    });
+    size
+}
+
+fn window_size(window: &web_sys::Window) -> WindowSize {
+    WindowSize {
+        width: window
+            .inner_width()
+            .ok()
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0),
+        height: window
+            .inner_height()
+            .ok()
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0),
+    }
+}
+
+pub fn use_online() -> ReadSignal<bool> {
+    let window = match browser_window() {
+        Some(window) => window,
+        None => return create_signal(true).0,
+    };
+
+    let (online, set_online) = create_signal(window.navigator().on_line());
+    let set_offline = set_online.clone();
+    listen(window.as_ref(), "online", move |_| {
+        set_online.set_if_changed(true)
+    });
+    listen(window.as_ref(), "offline", move |_| {
+        set_offline.set_if_changed(false)
+    });
+    online
+}
+
+/// `true` while the document is visible; `false` when the tab is hidden or minimized, e.g. to
+/// pause polling or animations.
+pub fn use_document_visibility() -> ReadSignal<bool> {
+    let document = match browser_window().and_then(|w| w.document()) {
+        Some(document) => document,
+        None => return create_signal(true).0,
+    };
+
+    let (visible, set_visible) = create_signal(!document.hidden());
+    let target = document.clone();
+    listen(target.as_ref(), "visibilitychange", move |_| {
+        set_visible.set_if_changed(!document.hidden());
+    });
+    visible
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TimerKind {
+    Interval,
+    Timeout,
+    AnimationFrame,
+}
+
+struct TimerInner {
+    kind: TimerKind,
+    id: Cell<Option<i32>>,
+    cancelled: Cell<bool>,
+    closure: RefCell<Option<TimerClosure>>,
+}
+
+/// Handle to a running timer. Timers are cancelled automatically when the effect that created
+/// them is cleaned up, or explicitly with [`TimerHandle::cancel`].
+#[derive(Clone)]
+pub struct TimerHandle {
+    inner: Rc<TimerInner>,
+}
+
+impl TimerHandle {
+    fn new(kind: TimerKind) -> Self {
+        Self {
+            inner: Rc::new(TimerInner {
+                kind,
+                id: Cell::new(None),
+                cancelled: Cell::new(false),
+                closure: RefCell::new(None),
+            }),
+        }
+    }
+
+    pub fn cancel(&self) {
+        if self.inner.cancelled.replace(true) {
+            return;
+        }
+        if let (Some(window), Some(id)) = (browser_window(), self.inner.id.take()) {
+            match self.inner.kind {
+                TimerKind::Interval => window.clear_interval_with_handle(id),
+                TimerKind::Timeout => window.clear_timeout_with_handle(id),
+                TimerKind::AnimationFrame => {
+                    let _ = window.cancel_animation_frame(id);
+                }
+            }
+        }
+        if let Some(closure) = self.inner.closure.borrow_mut().take() {
+            drop_later(closure);
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.inner.cancelled.get() && self.inner.id.get().is_some()
+    }
+
+    fn cancel_on_cleanup(&self) {
+        let handle = self.clone();
+        on_cleanup(move || handle.cancel());
+    }
+}
+
+/// Calls `f` every `period` until cancelled.
+pub fn use_interval(period: Duration, mut f: impl FnMut() + 'static) -> TimerHandle {
+    let handle = TimerHandle::new(TimerKind::Interval);
+    if let Some(window) = browser_window() {
+        let closure = TimerClosure::new(move |_| f());
+        let id = window.set_interval_with_callback_and_timeout_and_arguments_0(
+            closure.as_ref().unchecked_ref(),
+            period.as_millis() as i32,
+        );
+        handle.inner.id.set(id.ok());
+        *handle.inner.closure.borrow_mut() = Some(closure);
+    }
+    handle.cancel_on_cleanup();
+    handle
+}
+
+/// Calls `f` once after `delay` unless cancelled first.
+pub fn use_timeout(delay: Duration, f: impl FnOnce() + 'static) -> TimerHandle {
+    let handle = TimerHandle::new(TimerKind::Timeout);
+    if let Some(window) = browser_window() {
+        let mut f = Some(f);
+        let weak = Rc::downgrade(&handle.inner);
+        let closure = TimerClosure::new(move |_| {
+            if let Some(inner) = weak.upgrade() {
+                inner.id.set(None);
+            }
+            if let Some(f) = f.take() {
+                f();
+            }
+        });
+        let id = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+            closure.as_ref().unchecked_ref(),
+            delay.as_millis() as i32,
+        );
+        handle.inner.id.set(id.ok());
+        *handle.inner.closure.borrow_mut() = Some(closure);
+    }
+    handle.cancel_on_cleanup();
+    handle
+}
+
+/// Calls `f` with the frame timestamp (ms) on every animation frame until cancelled.
+pub fn use_raf(mut f: impl FnMut(f64) + 'static) -> TimerHandle {
+    let handle = TimerHandle::new(TimerKind::AnimationFrame);
+    if let Some(window) = browser_window() {
+        let weak: Weak<TimerInner> = Rc::downgrade(&handle.inner);
+        let frame_window = window.clone();
+        let closure = TimerClosure::new(move |timestamp: JsValue| {
+            f(timestamp.as_f64().unwrap_or(0.0));
+            if let Some(inner) = weak.upgrade() {
+                if inner.cancelled.get() {
+                    return;
+                }
+                if let Some(next) = inner.closure.borrow().as_ref() {
+                    let id = frame_window.request_animation_frame(next.as_ref().unchecked_ref());
+                    inner.id.set(id.ok());
+                }
+            }
+        });
+        let id = window.request_animation_frame(closure.as_ref().unchecked_ref());
+        handle.inner.id.set(id.ok());
+        *handle.inner.closure.borrow_mut() = Some(closure);
+    }
+    handle.cancel_on_cleanup();
+    handle
+}
+
+/// A signal counting elapsed intervals, handy for clocks and polling.
+pub fn use_interval_signal(period: Duration) -> ReadSignal<u64> {
+    let (ticks, set_ticks) = create_signal(0u64);
+    use_interval(period, move || set_ticks.update(|n| *n += 1));
+    ticks
+}
+
+/// A signal that flips to `true` once `delay` has elapsed.
+pub fn use_timeout_signal(delay: Duration) -> ReadSignal<bool> {
+    let (fired, set_fired) = create_signal(false);
+    use_timeout(delay, move || set_fired.set(true));
+    fired
+}
+
+/// A signal holding the latest animation frame timestamp, for driving animations.
+pub fn use_raf_signal() -> ReadSignal<f64> {
+    let (timestamp, set_timestamp) = create_signal(0.0);
+    use_raf(move |ts| set_timestamp.set(ts));
+    timestamp
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hooks_have_server_defaults() {
+        assert!(!use_media_query("(min-width: 600px)").get_untracked());
+        assert_eq!(use_window_size().get_untracked(), WindowSize::default());
+        assert!(use_online().get_untracked());
+        assert!(use_document_visibility().get_untracked());
+    }
+
+    #[test]
+    fn test_timers_are_inert_off_browser() {
+        let handle = use_interval(Duration::from_millis(10), || {});
+        assert!(!handle.is_active());
+        handle.cancel();
+        handle.cancel();
+
+        assert!(!use_timeout_signal(Duration::from_millis(1)).get_untracked());
+        assert_eq!(
+            use_interval_signal(Duration::from_secs(1)).get_untracked(),
+            0
+        );
+        assert_eq!(use_raf_signal().get_untracked(), 0.0);
+    }
+}
@@ -1,5 +1,7 @@
 use react_rs_elements::attributes::AttributeValue;
 use react_rs_elements::node::Node;
+use react_rs_elements::reactive::ReactiveValue;
+use react_rs_elements::transition::Transition;
 use react_rs_elements::Element;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
@@ -31,107 +33,174 @@ fn get_document() -> Document {
 }
 
 pub fn hydrate(node: &Node, container_id: &str) -> HydrationResult<()> {
+    crate::scheduler::install_task_spawner();
+    crate::scheduler::install_timer();
     let document = get_document();
     let container = document
         .get_element_by_id(container_id)
         .ok_or_else(|| HydrationError::ContainerNotFound(container_id.to_string()))?;
 
-    let children = container.child_nodes();
-
-    if children.length() == 0 {
+    if !container.has_child_nodes() {
         return Err(HydrationError::ChildCountMismatch {
             expected: 1,
             found: 0,
         });
     }
 
-    if let Some(first_child) = children.get(0) {
-        hydrate_node(node, &first_child)?;
-    }
+    remove_server_portals(&document);
+    let mut cursor = Cursor::new(&container);
+    hydrate_node(node, &mut cursor)?;
+    crate::dom::run_mounts_if_connected(&container);
 
     Ok(())
 }
 
-fn hydrate_node(virtual_node: &Node, dom_node: &web_sys::Node) -> HydrationResult<()> {
-    match virtual_node {
-        Node::Element(element) => hydrate_element(element, dom_node),
-        Node::Text(_) => Ok(()),
-        Node::ReactiveText(reactive) => {
-            use react_rs_core::effect::create_effect;
+/// Removes the portal content the server placed at the end of `<body>`; hydrating the
+/// portals mounts the client's own.
+fn remove_server_portals(document: &Document) {
+    let Ok(portals) = document.query_selector_all("[data-portal-root][data-ssr]") else {
+        return;
+    };
+    for i in 0..portals.length() {
+        if let Some(portal) = portals
+            .get(i)
+            .and_then(|node| node.dyn_into::<WebElement>().ok())
+        {
+            portal.remove();
+        }
+    }
+}
 
-            if let Some(text_node) = dom_node.dyn_ref::<web_sys::Text>() {
-                let text_node_rc = Rc::new(text_node.clone());
-                let reactive = reactive.clone();
+/// Walks the server-rendered siblings under one parent as their virtual nodes are
+/// hydrated. Nodes that render to no DOM node (heads, empty text) or to several (fragments,
+/// reactive text) are delimited by the markers `react-rs-dom` emits, so hydration never
+/// relies on child positions.
+struct Cursor {
+    next: Option<web_sys::Node>,
+}
 
-                create_effect(move || {
-                    let value = reactive.get();
-                    text_node_rc.set_text_content(Some(&value));
-                });
-            }
-            Ok(())
+impl Cursor {
+    fn new(parent: &web_sys::Node) -> Self {
+        Self {
+            next: parent.first_child(),
         }
-        Node::Fragment(children) => {
-            for (i, child) in children.iter().enumerate() {
-                if let Some(dom_child) = dom_node.child_nodes().get(i as u32) {
-                    hydrate_node(child, &dom_child)?;
-                }
-            }
-            Ok(())
+    }
+
+    fn take(&mut self) -> Option<web_sys::Node> {
+        let node = self.next.take()?;
+        self.next = node.next_sibling();
+        Some(node)
+    }
+
+    fn element(&mut self, expected: &str) -> HydrationResult<WebElement> {
+        let node = self.take().ok_or_else(|| mismatch(expected, "nothing"))?;
+        node.dyn_into::<WebElement>()
+            .map_err(|node| mismatch(expected, &describe(&node)))
+    }
+
+    /// Consumes the comment `<!--{data}-->`.
+    fn marker(&mut self, data: &str) -> HydrationResult<()> {
+        let expected = format!("<!--{}-->", data);
+        match self.take() {
+            Some(node) if is_marker(&node, data) => Ok(()),
+            Some(node) => Err(mismatch(&expected, &describe(&node))),
+            None => Err(mismatch(&expected, "nothing")),
         }
-        Node::Conditional(condition, then_node, else_node) => {
+    }
+
+    /// Consumes a text node holding exactly `text`, splitting it off the front of a longer
+    /// one: the parser merges adjacent text nodes the server rendered separately.
+    fn text(&mut self, text: &str) -> HydrationResult<()> {
+        let node = self.take().ok_or_else(|| mismatch("text", "nothing"))?;
+        let text_node = node
+            .dyn_ref::<web_sys::Text>()
+            .ok_or_else(|| mismatch("text", &describe(&node)))?;
+        let len = text.encode_utf16().count() as u32;
+        if text_node.length() > len {
+            self.next = Some(text_node.split_text(len)?.into());
+        }
+        Ok(())
+    }
+}
+
+fn is_marker(node: &web_sys::Node, data: &str) -> bool {
+    node.node_type() == web_sys::Node::COMMENT_NODE && node.node_value().as_deref() == Some(data)
+}
+
+fn describe(node: &web_sys::Node) -> String {
+    match node.dyn_ref::<WebElement>() {
+        Some(el) => el.tag_name().to_lowercase(),
+        None => node.node_name(),
+    }
+}
+
+fn mismatch(expected: &str, found: &str) -> HydrationError {
+    HydrationError::NodeMismatch {
+        expected: expected.to_string(),
+        found: found.to_string(),
+    }
+}
+
+/// Replaces a server-rendered container with a client-rendered one, for regions whose
+/// client structure differs from the server's.
+fn rerender(container: &WebElement, node: &Node) -> HydrationResult<()> {
+    let rendered = crate::dom::render_node_pub(&get_document(), node)?;
+    container.replace_with_with_node_1(&rendered)?;
+    Ok(())
+}
+
+fn hydrate_node(virtual_node: &Node, cursor: &mut Cursor) -> HydrationResult<()> {
+    match virtual_node {
+        Node::Element(element) => {
+            let dom_element = cursor.element(element.tag())?;
+            hydrate_element(element, &dom_element)
+        }
+        Node::Text(text) if text.is_empty() => Ok(()),
+        Node::Text(text) => cursor.text(text),
+        Node::ReactiveText(reactive) => {
             use react_rs_core::effect::create_effect;
 
-            let dom_element = dom_node.dyn_ref::<web_sys::Element>().ok_or_else(|| {
-                HydrationError::NodeMismatch {
-                    expected: "conditional-container".to_string(),
-                    found: "non-element".to_string(),
+            cursor.marker("t")?;
+            // An empty value renders no text node between the markers.
+            let text_node = match cursor.next.clone() {
+                Some(node) if !is_marker(&node, "/t") => {
+                    cursor.take();
+                    node.dyn_into::<web_sys::Text>()
+                        .map_err(|node| mismatch("text", &describe(&node)))?
                 }
-            })?;
-
-            let children = dom_element.child_nodes();
-            if let Some(first) = children.get(0) {
-                hydrate_node(then_node, &first)?;
-            }
-            if let Some(else_n) = else_node {
-                if let Some(second) = children.get(1) {
-                    hydrate_node(else_n, &second)?;
+                Some(end) => {
+                    let text_node = get_document().create_text_node("");
+                    end.parent_node()
+                        .ok_or_else(|| mismatch("text", "detached marker"))?
+                        .insert_before(&text_node, Some(&end))?;
+                    text_node
                 }
-            }
+                None => return Err(mismatch("<!--/t-->", "nothing")),
+            };
+            cursor.marker("/t")?;
 
-            let cond_children = dom_element.child_nodes();
-            let then_el: Option<Rc<web_sys::Element>> = cond_children
-                .get(0)
-                .and_then(|n| n.dyn_into::<web_sys::Element>().ok())
-                .map(Rc::new);
-            let else_el: Option<Rc<web_sys::Element>> = cond_children
-                .get(1)
-                .and_then(|n| n.dyn_into::<web_sys::Element>().ok())
-                .map(Rc::new);
-
-            let condition = condition.clone();
+            let reactive = reactive.clone();
             create_effect(move || {
-                let visible = condition.get();
-                if let Some(ref el) = then_el {
-                    let _ = el.set_attribute("style", if visible { "" } else { "display:none" });
-                }
-                if let Some(ref el) = else_el {
-                    let _ = el.set_attribute("style", if visible { "display:none" } else { "" });
-                }
+                let value = reactive.get();
+                text_node.set_text_content(Some(&value));
             });
-
             Ok(())
         }
+        Node::Fragment(children) => {
+            cursor.marker("[")?;
+            for child in children {
+                hydrate_node(child, cursor)?;
+            }
+            cursor.marker("]")
+        }
+        Node::Conditional(condition, then_node, else_node) => {
+            let container = cursor.element("conditional-container")?;
+            hydrate_conditional(condition, then_node, else_node.as_deref(), &container, None)
+        }
         Node::ReactiveList(list_fn) => {
             use react_rs_core::effect::create_effect;
 
-            let dom_element = dom_node.dyn_ref::<web_sys::Element>().ok_or_else(|| {
-                HydrationError::NodeMismatch {
-                    expected: "list-container".to_string(),
-                    found: "non-element".to_string(),
-                }
-            })?;
-
-            let container_rc = Rc::new(dom_element.clone());
+            let container_rc = Rc::new(cursor.element("list-container")?);
             let list_fn = list_fn.clone();
 
             create_effect(move || {
@@ -147,44 +216,111 @@ fn hydrate_node(virtual_node: &Node, dom_node: &web_sys::Node) -> HydrationResul
             Ok(())
         }
         Node::KeyedList(list_fn) => {
-            use react_rs_core::effect::create_effect;
-
-            let dom_element = dom_node.dyn_ref::<web_sys::Element>().ok_or_else(|| {
-                HydrationError::NodeMismatch {
-                    expected: "keyed-list-container".to_string(),
-                    found: "non-element".to_string(),
-                }
-            })?;
+            let dom_element = cursor.element("keyed-list-container")?;
 
-            let container_rc = Rc::new(dom_element.clone());
-            let list_fn = list_fn.clone();
+            // Rows are re-rendered so each owns its scope; later changes move them by key.
+            dom_element.set_inner_html("");
+            crate::dom::attach_keyed_list(&get_document(), &dom_element, list_fn)
+                .map_err(HydrationError::from)
+        }
+        Node::DiffList(list) => {
+            let dom_element = cursor.element("keyed-list-container")?;
 
-            create_effect(move || {
-                container_rc.set_inner_html("");
-                let doc = get_document();
-                for (_, child_node) in list_fn() {
-                    if let Ok(dom_child) = crate::dom::render_node_pub(&doc, &child_node) {
-                        let _ = container_rc.append_child(&dom_child);
-                    }
-                }
-            });
+            // Rows are re-rendered so each owns its scope; later changes patch them in place.
+            dom_element.set_inner_html("");
+            crate::dom::attach_diff_list(&get_document(), &dom_element, list)
+                .map_err(HydrationError::from)
+        }
+        Node::Dynamic(view) => {
+            let dom_element = cursor.element("dynamic-container")?;
 
+            // The subtree is re-rendered so it owns a scope that can be swapped out later.
+            crate::dom::attach_dynamic(dom_element, view.clone(), None);
+            Ok(())
+        }
+        Node::Transition(transition, child) => match &**child {
+            Node::Conditional(condition, then_node, else_node) => {
+                let container = cursor.element("conditional-container")?;
+                hydrate_conditional(
+                    condition,
+                    then_node,
+                    else_node.as_deref(),
+                    &container,
+                    Some(transition.clone()),
+                )
+            }
+            Node::Dynamic(view) => {
+                let dom_element = cursor.element("dynamic-container")?;
+                dom_element.set_inner_html("");
+                crate::dom::attach_dynamic(dom_element, view.clone(), Some(transition.clone()));
+                Ok(())
+            }
+            other => hydrate_node(other, cursor),
+        },
+        Node::Template(template) => hydrate_node(template.node(), cursor),
+        Node::Head(head) => {
+            let head_id = crate::head::apply_head(head);
+            react_rs_core::effect::on_cleanup(move || crate::head::remove_head(head_id));
             Ok(())
         }
-        Node::Head(_) | Node::Suspense(_) | Node::ErrorBoundary(_) => Ok(()),
+        // The server renders only the branch that was current; the client needs both.
+        Node::Suspense(_) | Node::ErrorBoundary(_) => {
+            let container = cursor.element("suspense-container")?;
+            rerender(&container, virtual_node)
+        }
+        // The server renders the content out of place, removed above, so it is mounted fresh.
+        Node::Portal(target, child) => {
+            cursor.element("portal-placeholder")?;
+            crate::dom::mount_portal(&get_document(), target, child).map_err(HydrationError::from)
+        }
     }
 }
 
-fn hydrate_element(element: &Element, dom_node: &web_sys::Node) -> HydrationResult<()> {
+fn hydrate_conditional(
+    condition: &ReactiveValue<bool>,
+    then_node: &Node,
+    else_node: Option<&Node>,
+    dom_element: &WebElement,
+    transition: Option<Transition>,
+) -> HydrationResult<()> {
     use react_rs_core::effect::create_effect;
 
-    let dom_element: &WebElement =
-        dom_node
-            .dyn_ref()
-            .ok_or_else(|| HydrationError::NodeMismatch {
-                expected: element.tag().to_string(),
-                found: "non-element".to_string(),
-            })?;
+    // Each branch is rendered inside its own wrapper span.
+    let mut branches = Cursor::new(dom_element);
+    let then_el = branches.element("conditional-branch")?;
+    hydrate_node(then_node, &mut Cursor::new(&then_el))?;
+    let else_el = match else_node {
+        Some(else_n) => {
+            let else_el = branches.element("conditional-branch")?;
+            hydrate_node(else_n, &mut Cursor::new(&else_el))?;
+            Some(else_el)
+        }
+        None => None,
+    };
+    let then_el = Some(then_el);
+
+    let condition = condition.clone();
+    let last_shown: std::cell::Cell<Option<bool>> = std::cell::Cell::new(None);
+    create_effect(move || {
+        let visible = condition.get();
+        let previous = last_shown.replace(Some(visible));
+        match previous {
+            None => crate::dom::toggle_conditional(then_el.as_ref(), else_el.as_ref(), visible),
+            Some(prev) if prev != visible => crate::dom::swap_conditional(
+                then_el.clone(),
+                else_el.clone(),
+                visible,
+                transition.as_ref(),
+            ),
+            Some(_) => {}
+        }
+    });
+
+    Ok(())
+}
+
+fn hydrate_element(element: &Element, dom_element: &WebElement) -> HydrationResult<()> {
+    use react_rs_core::effect::create_effect;
 
     let dom_tag = dom_element.tag_name().to_lowercase();
     if dom_tag != element.tag() {
@@ -204,6 +340,7 @@ fn hydrate_element(element: &Element, dom_node: &web_sys::Node) -> HydrationResu
                 create_effect(move || {
                     let value = reactive.get();
                     let _ = el_rc.set_attribute(&name_rc, &value);
+                    crate::dom::sync_form_property(&el_rc, &name_rc, Some(&value), None);
                 });
             }
             AttributeValue::ReactiveBool(reactive) => {
@@ -212,54 +349,35 @@ fn hydrate_element(element: &Element, dom_node: &web_sys::Node) -> HydrationResu
                 let reactive = reactive.clone();
 
                 create_effect(move || {
-                    if reactive.get() {
+                    let on = reactive.get();
+                    if on {
                         let _ = el_rc.set_attribute(&name_rc, "");
                     } else {
                         let _ = el_rc.remove_attribute(&name_rc);
                     }
+                    crate::dom::sync_form_property(&el_rc, &name_rc, None, Some(on));
                 });
             }
             _ => {}
         }
     }
 
-    for handler in element.event_handlers() {
-        let event_type = handler.event_type().to_string();
-        let event_id = crate::dom::next_event_id();
+    crate::viewport::attach_pending(element, dom_element);
+    crate::actions::attach_form_action(element, dom_element);
 
-        let callback = handler.take_handler_rc();
-
-        crate::dom::register_event_callback(
-            event_id,
-            Rc::new(move |wasm_event: crate::dom::WasmEvent| {
-                let mut react_event =
-                    react_rs_elements::events::Event::new(wasm_event.inner().type_());
-                if let Some(val) = wasm_event.target_value() {
-                    react_event = react_event.with_target_value(val);
-                }
-                if let Some(checked) = wasm_event.target_checked() {
-                    react_event = react_event.with_checked(checked);
-                }
-                callback(react_event);
-            }),
-        );
-
-        dom_element
-            .set_attribute("data-eid", &event_id.to_string())
-            .map_err(HydrationError::from)?;
+    crate::dom::attach_event_handlers(&get_document(), element, dom_element)
+        .map_err(HydrationError::from)?;
 
-        let document = get_document();
-        crate::dom::ensure_delegated_listener(&document, &event_type)
-            .map_err(HydrationError::from)?;
+    // Markup set with `inner_html`, a textarea's rendered value and raw script or style text
+    // have no virtual children to attach to.
+    if element.get_inner_html().is_some()
+        || matches!(element.tag(), "textarea" | "script" | "style")
+    {
+        return Ok(());
     }
-
-    let virtual_children = element.get_children();
-    let dom_children = dom_node.child_nodes();
-
-    for (i, virtual_child) in virtual_children.iter().enumerate() {
-        if let Some(dom_child) = dom_children.get(i as u32) {
-            hydrate_node(virtual_child, &dom_child)?;
-        }
+    let mut cursor = Cursor::new(dom_element);
+    for virtual_child in element.get_children() {
+        hydrate_node(virtual_child, &mut cursor)?;
     }
 
     Ok(())
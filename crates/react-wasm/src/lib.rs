@@ -3,24 +3,68 @@
 //! Client-side WASM runtime for react.rs applications.
 //!
 //! Provides DOM mounting, hydration, event delegation, client-side routing,
-//! and React Server Components runtime.
+//! the React Server Components runtime and the server action client.
 //!
 //! For server-side rendering, use `react-rs-dom` instead.
 
+pub mod actions;
+pub mod clipboard;
 mod dom;
 pub mod fetch;
+mod head;
+pub mod hooks;
 mod hydration;
+mod listener;
+mod patch;
 mod router;
 mod runtime;
+mod scheduler;
+pub mod sse;
+pub mod storage;
+mod transition;
+pub mod viewport;
 pub mod websocket;
 
+pub use actions::{
+    call_action, call_action_stream, use_action, use_action_state, use_action_stream,
+    use_form_action, use_form_status, use_optimistic, with_form_status, ActionCallError,
+    ActionHandle, ActionState, ActionStreamHandle, FormStatus, OptimisticAction,
+};
+pub use clipboard::{
+    clipboard_permission, read_text, use_clipboard, write_text, ClipboardAccess, ClipboardError,
+    ClipboardHandle, ClipboardPermission,
+};
 pub use dom::{
     mount, register_event_handler, render_to_dom, unregister_event_handler, DomNode, WasmEvent,
 };
+pub use head::{apply_head, remove_head};
+pub use hooks::{
+    use_document_visibility, use_interval, use_interval_signal, use_media_query, use_online,
+    use_raf, use_raf_signal, use_timeout, use_timeout_signal, use_window_size, TimerHandle,
+    WindowSize,
+};
 pub use hydration::{hydrate, hydrate_client_components, HydrationError, HydrationResult};
-pub use router::{back, forward, navigate, replace, setup_link_interception, use_location, Router};
-pub use runtime::{ClientComponentRegistry, RscRuntime};
-pub use websocket::{use_websocket, use_websocket_simple, WsHandle};
+pub use patch::MountHandle;
+pub use router::{
+    back, forward, navigate, replace, set_focus_target, setup_link_interception, use_location,
+    FocusTarget, Router,
+};
+pub use runtime::{
+    parse_row, stream_rsc_payload, ClientComponentRegistry, RscHint, RscRow, RscRuntime,
+    RscStreamParser, RscStreamRenderer,
+};
+pub use scheduler::{flush_sync, set_update_strategy, UpdateStrategy};
+pub use sse::{use_sse, use_sse_json};
+pub use storage::{use_local_storage, use_session_storage, use_storage, StorageKind};
+pub use transition::{
+    set_view_transitions_enabled, supports_view_transitions, view_transitions_enabled,
+    with_view_transition,
+};
+pub use viewport::{use_in_viewport, ViewportExt, ViewportOptions};
+pub use websocket::{
+    use_websocket, use_websocket_messages, use_websocket_simple, use_websocket_with,
+    ConnectionState, ReconnectConfig, WsHandle, WsMessage, WsOptions,
+};
 
 use wasm_bindgen::prelude::*;
 
@@ -0,0 +1,64 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::EventTarget;
+
+type ListenerClosure = Closure<dyn FnMut(web_sys::Event)>;
+
+/// A DOM event listener that is removed from its target when dropped.
+pub(crate) struct EventListener {
+    target: EventTarget,
+    event_type: String,
+    closure: Option<ListenerClosure>,
+}
+
+impl EventListener {
+    pub(crate) fn new(
+        target: &EventTarget,
+        event_type: &str,
+        handler: impl FnMut(web_sys::Event) + 'static,
+    ) -> Self {
+        let closure = ListenerClosure::new(handler);
+        let _ =
+            target.add_event_listener_with_callback(event_type, closure.as_ref().unchecked_ref());
+        Self {
+            target: target.clone(),
+            event_type: event_type.to_string(),
+            closure: Some(closure),
+        }
+    }
+}
+
+impl Drop for EventListener {
+    fn drop(&mut self) {
+        let closure = match self.closure.take() {
+            Some(closure) => closure,
+            None => return,
+        };
+        let _ = self.target.remove_event_listener_with_callback(
+            &self.event_type,
+            closure.as_ref().unchecked_ref(),
+        );
+
+        drop_later(closure);
+    }
+}
+
+/// Drops `value` on a later tick. Closures can be released from inside their own callback
+/// (a signal write that re-runs the owning effect), which must not free them mid-call.
+pub(crate) fn drop_later<T: 'static>(value: T) {
+    let release = Closure::once_into_js(move || drop(value));
+    if let Some(window) = web_sys::window() {
+        let _ = window
+            .set_timeout_with_callback_and_timeout_and_arguments_0(release.unchecked_ref(), 0);
+    }
+}
+
+/// Adds an event listener that is removed when the current effect or scope is cleaned up.
+pub(crate) fn listen(
+    target: &EventTarget,
+    event_type: &str,
+    handler: impl FnMut(web_sys::Event) + 'static,
+) {
+    let listener = EventListener::new(target, event_type, handler);
+    react_rs_core::effect::on_cleanup(move || drop(listener));
+}
@@ -0,0 +1,291 @@
+use crate::dom::{
+    apply_attribute, attach_event_handlers, render_node_pub, run_mounts_if_connected,
+    unregister_event_handler,
+};
+use react_rs_core::effect::{dispose_scope, run_in_new_scope};
+use react_rs_core::ScopeId;
+use react_rs_elements::attributes::AttributeValue;
+use react_rs_elements::node::Node;
+use react_rs_elements::Element;
+use wasm_bindgen::prelude::*;
+use web_sys::Document;
+
+/// Attributes whose elements are wired up once at render time and so are never reused.
+const ATTACHED_ATTRS: [&str; 2] = ["data-viewport-id", next_rs_rsc::FORM_ACTION_ATTR];
+
+/// What the previous render produced for one DOM node. Only static structure is recorded;
+/// reactive nodes cannot be compared and are always re-rendered.
+#[derive(Debug, Clone, PartialEq)]
+enum Snapshot {
+    Element {
+        tag: String,
+        attrs: Vec<(String, Option<String>)>,
+        children: Vec<Snapshot>,
+    },
+    Text(String),
+    Dynamic,
+}
+
+impl Snapshot {
+    fn of(node: &Node) -> Self {
+        match node {
+            Node::Element(element) if is_reusable(element) => Self::Element {
+                tag: element.tag().to_string(),
+                attrs: element
+                    .attributes()
+                    .iter()
+                    .map(|attr| (attr.name.clone(), static_value(&attr.value)))
+                    .collect(),
+                children: flatten(element.get_children())
+                    .into_iter()
+                    .map(Snapshot::of)
+                    .collect(),
+            },
+            Node::Text(text) => Self::Text(text.clone()),
+            _ => Self::Dynamic,
+        }
+    }
+
+    fn list(node: &Node) -> Vec<Self> {
+        flatten(std::slice::from_ref(node))
+            .into_iter()
+            .map(Snapshot::of)
+            .collect()
+    }
+
+    /// Whether the DOM rendered for `self` can be patched into `node` instead of replaced.
+    fn can_patch(&self, node: &Node) -> bool {
+        match (self, node) {
+            (Self::Element { tag, .. }, Node::Element(element)) => {
+                *tag == element.tag() && is_reusable(element)
+            }
+            (Self::Text(_), Node::Text(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+fn is_reusable(element: &Element) -> bool {
+    element.get_inner_html().is_none()
+        && !element
+            .attributes()
+            .iter()
+            .any(|attr| ATTACHED_ATTRS.contains(&attr.name.as_str()))
+}
+
+fn static_value(value: &AttributeValue) -> Option<String> {
+    match value {
+        AttributeValue::String(s) => Some(s.clone()),
+        AttributeValue::Bool(true) => Some(String::new()),
+        AttributeValue::Bool(false) => None,
+        AttributeValue::ReactiveString(_) | AttributeValue::ReactiveBool(_) => None,
+    }
+}
+
+/// Fragments render their children directly into the parent, so diffing works on the
+/// flattened child list.
+fn flatten(nodes: &[Node]) -> Vec<&Node> {
+    let mut out = Vec::new();
+    for node in nodes {
+        match node {
+            Node::Fragment(children) => out.extend(flatten(children)),
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// A mounted tree that can be re-rendered in place.
+pub struct MountHandle {
+    container: web_sys::Element,
+    snapshot: Vec<Snapshot>,
+    scope: ScopeId,
+}
+
+impl MountHandle {
+    pub(crate) fn mount(container: web_sys::Element, node: &Node) -> Result<Self, JsValue> {
+        crate::scheduler::install_task_spawner();
+        crate::scheduler::install_timer();
+        container.set_inner_html("");
+        let (scope, rendered) = run_in_new_scope(|| render_node_pub(&document(), node));
+        container.append_child(&rendered?)?;
+        run_mounts_if_connected(&container);
+        Ok(Self {
+            container,
+            snapshot: Snapshot::list(node),
+            scope,
+        })
+    }
+
+    pub fn container(&self) -> &web_sys::Element {
+        &self.container
+    }
+
+    /// Re-renders `node` against the DOM produced by the previous render. Elements and text
+    /// whose structure matches are patched in place, preserving focus, selection and scroll
+    /// position; everything else is replaced. Effects from the previous render are disposed.
+    pub fn update(&mut self, node: &Node) -> Result<(), JsValue> {
+        let document = document();
+        let new_nodes = flatten(std::slice::from_ref(node));
+        let container: web_sys::Node = self.container.clone().into();
+        let old = std::mem::take(&mut self.snapshot);
+
+        let (scope, result) =
+            run_in_new_scope(|| patch_children(&document, &container, &old, &new_nodes));
+        dispose_scope(std::mem::replace(&mut self.scope, scope));
+        self.snapshot = Snapshot::list(node);
+        run_mounts_if_connected(&container);
+        result
+    }
+
+    /// Disposes the tree's effects and empties the container.
+    pub fn unmount(self) {
+        dispose_scope(self.scope);
+        self.container.set_inner_html("");
+    }
+}
+
+fn document() -> Document {
+    web_sys::window()
+        .expect("no window")
+        .document()
+        .expect("no document")
+}
+
+fn child_nodes(parent: &web_sys::Node) -> Vec<web_sys::Node> {
+    let list = parent.child_nodes();
+    (0..list.length()).filter_map(|i| list.get(i)).collect()
+}
+
+fn patch_children(
+    document: &Document,
+    parent: &web_sys::Node,
+    old: &[Snapshot],
+    new: &[&Node],
+) -> Result<(), JsValue> {
+    let existing = child_nodes(parent);
+    // Something else touched this subtree; rebuild it rather than guess.
+    if existing.len() != old.len() {
+        for child in &existing {
+            parent.remove_child(child)?;
+        }
+        for node in new {
+            parent.append_child(&render_node_pub(document, node)?)?;
+        }
+        return Ok(());
+    }
+
+    for (i, node) in new.iter().enumerate() {
+        match (old.get(i), existing.get(i)) {
+            (Some(snapshot), Some(dom)) if snapshot.can_patch(node) => {
+                patch_node(document, snapshot, node, dom)?;
+            }
+            (Some(_), Some(dom)) => {
+                parent.replace_child(&render_node_pub(document, node)?, dom)?;
+            }
+            _ => {
+                parent.append_child(&render_node_pub(document, node)?)?;
+            }
+        }
+    }
+    for dom in existing.iter().skip(new.len()) {
+        parent.remove_child(dom)?;
+    }
+    Ok(())
+}
+
+fn patch_node(
+    document: &Document,
+    snapshot: &Snapshot,
+    node: &Node,
+    dom: &web_sys::Node,
+) -> Result<(), JsValue> {
+    match (snapshot, node) {
+        (Snapshot::Text(old), Node::Text(new)) => {
+            if old != new {
+                dom.set_text_content(Some(new));
+            }
+            Ok(())
+        }
+        (
+            Snapshot::Element {
+                attrs, children, ..
+            },
+            Node::Element(element),
+        ) => {
+            let el: &web_sys::Element = dom.unchecked_ref();
+            patch_attributes(el, attrs, element)?;
+
+            if let Some(eid) = el.get_attribute("data-eid") {
+                if let Ok(eid) = eid.parse() {
+                    unregister_event_handler(eid);
+                }
+                el.remove_attribute("data-eid")?;
+            }
+            attach_event_handlers(document, element, el)?;
+
+            patch_children(document, dom, children, &flatten(element.get_children()))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn patch_attributes(
+    el: &web_sys::Element,
+    old: &[(String, Option<String>)],
+    element: &Element,
+) -> Result<(), JsValue> {
+    for (name, _) in old {
+        if !element.attributes().iter().any(|attr| &attr.name == name) {
+            el.remove_attribute(name)?;
+        }
+    }
+    for attr in element.attributes() {
+        let previous = old.iter().find(|(name, _)| *name == attr.name);
+        let unchanged = matches!(
+            (previous, static_value(&attr.value)),
+            (Some((_, Some(prev))), Some(new)) if *prev == new
+        );
+        if !unchanged {
+            apply_attribute(el, attr)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use react_rs_elements::html::*;
+
+    #[test]
+    fn test_snapshot_flattens_fragments() {
+        let node = Node::Fragment(vec![
+            Node::Text("a".to_string()),
+            Node::Fragment(vec![Node::Element(div().class("x"))]),
+        ]);
+        let snapshot = Snapshot::list(&node);
+
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot[0], Snapshot::Text("a".to_string()));
+        assert!(matches!(
+            &snapshot[1],
+            Snapshot::Element { tag, attrs, .. } if tag == "div" && attrs[0] == ("class".to_string(), Some("x".to_string()))
+        ));
+    }
+
+    #[test]
+    fn test_can_patch_same_tag_only() {
+        let snapshot = Snapshot::of(&Node::Element(div().text("old")));
+
+        assert!(snapshot.can_patch(&Node::Element(div().class("new"))));
+        assert!(!snapshot.can_patch(&Node::Element(span())));
+        assert!(!snapshot.can_patch(&Node::Text("text".to_string())));
+        assert_eq!(
+            Snapshot::of(&Node::Element(
+                form().attr(next_rs_rsc::FORM_ACTION_ATTR, "save")
+            )),
+            Snapshot::Dynamic
+        );
+    }
+}
@@ -1,10 +1,48 @@
+use crate::transition::with_view_transition;
 use react_rs_core::signal::{create_signal, ReadSignal, WriteSignal};
 use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 
+static ENTRY_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
 thread_local! {
     static ROUTER: RefCell<Option<RouterInner>> = const { RefCell::new(None) };
+    static SCROLL_POSITIONS: RefCell<HashMap<String, (f64, f64)>> = RefCell::new(HashMap::new());
+    static CURRENT_ENTRY: RefCell<String> = const { RefCell::new(String::new()) };
+    static FOCUS_TARGET: RefCell<FocusTarget> = const { RefCell::new(FocusTarget::Heading) };
+    static FOCUSED_ELEMENTS: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+}
+
+/// Where focus moves after a client-side navigation, so keyboard and screen reader users
+/// are not left on a link that no longer exists.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FocusTarget {
+    /// The page's first `<h1>`, falling back to the main landmark (the default).
+    Heading,
+    /// The `<main>` landmark.
+    Main,
+    /// The first element matching a CSS selector.
+    Selector(String),
+    /// Leave focus alone.
+    None,
+}
+
+impl FocusTarget {
+    fn selectors(&self) -> Vec<&str> {
+        match self {
+            Self::Heading => vec!["h1", "main", "[role=main]"],
+            Self::Main => vec!["main", "[role=main]"],
+            Self::Selector(selector) => vec![selector.as_str()],
+            Self::None => Vec::new(),
+        }
+    }
+}
+
+pub fn set_focus_target(target: FocusTarget) {
+    FOCUS_TARGET.with(|t| *t.borrow_mut() = target);
 }
 
 struct RouterInner {
@@ -26,10 +64,33 @@ impl Router {
             let initial_path = get_current_path();
             let (current_path, set_path) = create_signal(initial_path);
 
+            init_scroll_restoration();
+
             let set_path_clone = set_path.clone();
             let popstate_closure = Closure::wrap(Box::new(move |_: web_sys::Event| {
+                remember_scroll(&current_entry_key(), current_scroll());
+                remember_focus(&current_entry_key());
+
+                let (key, stored) = history_state()
+                    .as_ref()
+                    .and_then(entry_from_state)
+                    .unwrap_or_else(|| (next_entry_key(), (0.0, 0.0)));
+                let scroll = SCROLL_POSITIONS
+                    .with(|positions| positions.borrow().get(&key).copied())
+                    .unwrap_or(stored);
+                let focused = FOCUSED_ELEMENTS.with(|f| f.borrow().get(&key).cloned());
+                set_current_entry_key(key);
+
                 let path = get_current_path();
-                set_path_clone.set(path);
+                let set_path = set_path_clone.clone();
+                with_view_transition(move || {
+                    set_path.set(path);
+                    crate::scheduler::flush_sync();
+                    scroll_to(scroll);
+                    if !focused.is_some_and(|selector| focus_selector(&selector)) {
+                        focus_new_page();
+                    }
+                });
             }) as Box<dyn FnMut(web_sys::Event)>);
 
             web_sys::window()
@@ -49,6 +110,202 @@ impl Router {
     }
 }
 
+fn next_entry_key() -> String {
+    format!(
+        "{}-{}",
+        js_sys::Date::now() as u64,
+        ENTRY_COUNTER.fetch_add(1, Ordering::SeqCst)
+    )
+}
+
+fn current_entry_key() -> String {
+    CURRENT_ENTRY.with(|k| k.borrow().clone())
+}
+
+fn set_current_entry_key(key: String) {
+    CURRENT_ENTRY.with(|k| *k.borrow_mut() = key);
+}
+
+fn current_scroll() -> (f64, f64) {
+    web_sys::window()
+        .map(|w| (w.scroll_x().unwrap_or(0.0), w.scroll_y().unwrap_or(0.0)))
+        .unwrap_or((0.0, 0.0))
+}
+
+fn scroll_to((x, y): (f64, f64)) {
+    if let Some(window) = web_sys::window() {
+        window.scroll_to_with_x_and_y(x, y);
+    }
+}
+
+fn history_state() -> Option<JsValue> {
+    web_sys::window()?.history().ok()?.state().ok()
+}
+
+fn entry_state(key: &str, (x, y): (f64, f64)) -> JsValue {
+    let state = js_sys::Object::new();
+    let _ = js_sys::Reflect::set(&state, &"key".into(), &key.into());
+    let _ = js_sys::Reflect::set(&state, &"scrollX".into(), &x.into());
+    let _ = js_sys::Reflect::set(&state, &"scrollY".into(), &y.into());
+    state.into()
+}
+
+fn entry_from_state(state: &JsValue) -> Option<(String, (f64, f64))> {
+    let key = js_sys::Reflect::get(state, &"key".into())
+        .ok()?
+        .as_string()?;
+    let x = js_sys::Reflect::get(state, &"scrollX".into())
+        .ok()
+        .and_then(|v| v.as_f64())
+        .unwrap_or(0.0);
+    let y = js_sys::Reflect::get(state, &"scrollY".into())
+        .ok()
+        .and_then(|v| v.as_f64())
+        .unwrap_or(0.0);
+    Some((key, (x, y)))
+}
+
+fn remember_scroll(key: &str, scroll: (f64, f64)) {
+    if key.is_empty() {
+        return;
+    }
+    SCROLL_POSITIONS.with(|positions| {
+        positions.borrow_mut().insert(key.to_string(), scroll);
+    });
+}
+
+/// Records the current scroll offset for the active history entry, both in memory and in
+/// `history.state` so it survives reloads.
+fn save_current_scroll() {
+    let key = current_entry_key();
+    if key.is_empty() {
+        return;
+    }
+    let scroll = current_scroll();
+    remember_scroll(&key, scroll);
+    if let Some(history) = web_sys::window().and_then(|w| w.history().ok()) {
+        let _ = history.replace_state(&entry_state(&key, scroll), "");
+    }
+}
+
+fn init_scroll_restoration() {
+    let history = match web_sys::window().and_then(|w| w.history().ok()) {
+        Some(h) => h,
+        None => return,
+    };
+    let _ = history.set_scroll_restoration(web_sys::ScrollRestoration::Manual);
+
+    let key = match history.state().ok().as_ref().and_then(entry_from_state) {
+        Some((key, scroll)) => {
+            remember_scroll(&key, scroll);
+            key
+        }
+        None => {
+            let key = next_entry_key();
+            let _ = history.replace_state(&entry_state(&key, current_scroll()), "");
+            key
+        }
+    };
+    set_current_entry_key(key);
+}
+
+fn document() -> Option<web_sys::Document> {
+    web_sys::window()?.document()
+}
+
+/// Moves focus to the configured target on the freshly rendered page.
+fn focus_new_page() {
+    let target = FOCUS_TARGET.with(|t| t.borrow().clone());
+    for selector in target.selectors() {
+        if focus_selector(selector) {
+            return;
+        }
+    }
+}
+
+fn focus_selector(selector: &str) -> bool {
+    let element = match document().and_then(|d| d.query_selector(selector).ok().flatten()) {
+        Some(el) => el,
+        None => return false,
+    };
+    let element = match element.dyn_into::<web_sys::HtmlElement>() {
+        Ok(el) => el,
+        Err(_) => return false,
+    };
+    // Headings and landmarks are not focusable by default.
+    if !element.has_attribute("tabindex") {
+        let _ = element.set_attribute("tabindex", "-1");
+    }
+    let options = web_sys::FocusOptions::new();
+    options.set_prevent_scroll(true);
+    element.focus_with_options(&options).is_ok()
+}
+
+/// Records which element had focus on the entry being left, so going back can restore it.
+fn remember_focus(key: &str) {
+    if key.is_empty() {
+        return;
+    }
+    let selector = document()
+        .and_then(|d| d.active_element())
+        .and_then(|el| selector_for(&el));
+    FOCUSED_ELEMENTS.with(|focused| match selector {
+        Some(selector) => {
+            focused.borrow_mut().insert(key.to_string(), selector);
+        }
+        None => {
+            focused.borrow_mut().remove(key);
+        }
+    });
+}
+
+/// Builds a selector that finds an equivalent element once the page is rendered again:
+/// the element's id, a link's href, or its position in the tree.
+fn selector_for(element: &web_sys::Element) -> Option<String> {
+    let tag = element.tag_name().to_lowercase();
+    if tag == "body" || tag == "html" {
+        return None;
+    }
+    if !element.id().is_empty() {
+        return Some(format!("#{}", css_escape(&element.id())));
+    }
+    if let Some(href) = element.get_attribute("href").filter(|_| tag == "a") {
+        return Some(format!("a[href=\"{}\"]", href.replace('"', "\\\"")));
+    }
+
+    let mut parts = Vec::new();
+    let mut current = Some(element.clone());
+    while let Some(el) = current {
+        let parent = match el.parent_element() {
+            Some(parent) => parent,
+            None => break,
+        };
+        let children = parent.children();
+        let index = (0..children.length()).find(|&i| children.item(i).as_ref() == Some(&el))?;
+        parts.push(format!(":nth-child({})", index + 1));
+        if parent.tag_name().eq_ignore_ascii_case("body") {
+            parts.push("body".to_string());
+            break;
+        }
+        current = Some(parent);
+    }
+    parts.reverse();
+    Some(parts.join(" > "))
+}
+
+fn css_escape(ident: &str) -> String {
+    ident
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c.to_string()
+            } else {
+                format!("\\{:x} ", c as u32)
+            }
+        })
+        .collect()
+}
+
 fn get_current_path() -> String {
     web_sys::window()
         .expect("no window")
@@ -74,11 +331,22 @@ pub fn navigate(path: &str) {
             let window = web_sys::window().expect("no window");
             let history = window.history().expect("no history");
 
+            save_current_scroll();
+            remember_focus(&current_entry_key());
+            let key = next_entry_key();
             history
-                .push_state_with_url(&JsValue::NULL, "", Some(path))
+                .push_state_with_url(&entry_state(&key, (0.0, 0.0)), "", Some(path))
                 .expect("failed to push state");
+            set_current_entry_key(key);
 
-            inner.set_path.set(path.to_string());
+            let set_path = inner.set_path.clone();
+            let path = path.to_string();
+            with_view_transition(move || {
+                set_path.set(path);
+                crate::scheduler::flush_sync();
+                scroll_to((0.0, 0.0));
+                focus_new_page();
+            });
         }
     });
 }
@@ -90,11 +358,20 @@ pub fn replace(path: &str) {
             let window = web_sys::window().expect("no window");
             let history = window.history().expect("no history");
 
+            let key = next_entry_key();
             history
-                .replace_state_with_url(&JsValue::NULL, "", Some(path))
+                .replace_state_with_url(&entry_state(&key, (0.0, 0.0)), "", Some(path))
                 .expect("failed to replace state");
+            set_current_entry_key(key);
 
-            inner.set_path.set(path.to_string());
+            let set_path = inner.set_path.clone();
+            let path = path.to_string();
+            with_view_transition(move || {
+                set_path.set(path);
+                crate::scheduler::flush_sync();
+                scroll_to((0.0, 0.0));
+                focus_new_page();
+            });
         }
     });
 }
@@ -154,8 +431,25 @@ pub fn setup_link_interception() {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     #[test]
     fn test_router_module_compiles() {
         let _ = 1 + 1;
     }
+
+    #[test]
+    fn test_focus_target_selectors() {
+        assert_eq!(
+            FocusTarget::Heading.selectors(),
+            vec!["h1", "main", "[role=main]"]
+        );
+        assert_eq!(
+            FocusTarget::Selector("#content".to_string()).selectors(),
+            vec!["#content"]
+        );
+        assert!(FocusTarget::None.selectors().is_empty());
+        assert_eq!(css_escape("nav-1"), "nav-1");
+        assert_eq!(css_escape("a.b"), "a\\2e b");
+    }
 }
@@ -1,9 +1,12 @@
-use next_rs_rsc::{RscNode, RscPayload};
+use next_rs_rsc::{FormActionExt, Props, RowRef, RscError, RscNode, RscPayload, RscRef};
+use react_rs_core::signal::{create_signal, ReadSignal, WriteSignal};
 use react_rs_elements::html::*;
 use react_rs_elements::node::Node;
 use react_rs_elements::Element;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
 use wasm_bindgen::prelude::*;
 
 pub struct ClientComponentRegistry {
@@ -24,6 +27,24 @@ impl ClientComponentRegistry {
         self.components.insert(id.into(), Box::new(factory));
     }
 
+    /// Registers a component taking a typed props struct, deserialized from the props the
+    /// server put in the payload. Props that do not match render an error in its place.
+    pub fn register_typed<P, F>(&mut self, id: impl Into<String>, factory: F)
+    where
+        P: Props,
+        F: Fn(P) -> Element + 'static,
+    {
+        let id = id.into();
+        let error_id = id.clone();
+        self.register(id, move |props| match serde_json::from_value(props) {
+            Ok(props) => factory(props),
+            Err(error) => render_error(
+                &error_id,
+                &format!("Invalid props for {}: {}", error_id, error),
+            ),
+        });
+    }
+
     pub fn get(&self, id: &str) -> Option<&dyn Fn(Value) -> Element> {
         self.components.get(id).map(|b| b.as_ref())
     }
@@ -43,19 +64,45 @@ impl Default for ClientComponentRegistry {
     }
 }
 
+type ErrorFallback = Box<dyn Fn(&RscError) -> Element>;
+
 pub struct RscRuntime {
     registry: ClientComponentRegistry,
+    error_fallback: Option<ErrorFallback>,
 }
 
 impl RscRuntime {
     pub fn new() -> Self {
+        Self::with_registry(ClientComponentRegistry::new())
+    }
+
+    pub fn with_registry(registry: ClientComponentRegistry) -> Self {
         Self {
-            registry: ClientComponentRegistry::new(),
+            registry,
+            error_fallback: None,
         }
     }
 
-    pub fn with_registry(registry: ClientComponentRegistry) -> Self {
-        Self { registry }
+    /// What to show in place of a suspense boundary or root row whose content failed on the
+    /// server. Without one, the error's message or digest is shown.
+    pub fn set_error_fallback<F>(&mut self, fallback: F)
+    where
+        F: Fn(&RscError) -> Element + 'static,
+    {
+        self.error_fallback = Some(Box::new(fallback));
+    }
+
+    fn render_row_error(&self, id: &str, error: &RscError) -> Node {
+        if let Some(fallback) = &self.error_fallback {
+            return Node::Element(fallback(error));
+        }
+        let message = error.message.clone().unwrap_or_else(|| {
+            format!(
+                "An error occurred in the Server Components render (digest: {})",
+                error.digest
+            )
+        });
+        Node::Element(render_error(id, &message).attr("data-digest", &error.digest))
     }
 
     pub fn register_component<F>(&mut self, id: impl Into<String>, factory: F)
@@ -65,23 +112,42 @@ impl RscRuntime {
         self.registry.register(id, factory);
     }
 
-    pub fn parse_payload(&self, wire_format: &str) -> Result<RscPayload, String> {
-        let mut payload = RscPayload::new();
-
-        for line in wire_format.lines() {
-            if line.starts_with("M:") {
-                continue;
+    /// Parses a complete payload, in the text wire format or as MessagePack from
+    /// [`RscPayload::to_msgpack`]. Late `$id:` rows are folded into their suspense
+    /// boundaries; use [`RscRuntime::stream`] to render chunks as they arrive instead.
+    /// Malformed text rows are skipped; only an undecodable MessagePack payload is an error.
+    pub fn parse_payload(&self, payload: impl AsRef<[u8]>) -> Result<RscPayload, String> {
+        let bytes = payload.as_ref();
+        let mut state = RscStreamState::default();
+        // MessagePack payloads start with an array header, which is never valid UTF-8.
+        let Ok(wire_format) = std::str::from_utf8(bytes) else {
+            let payload = RscPayload::from_msgpack(bytes)
+                .map_err(|e| format!("Invalid MessagePack payload: {}", e))?;
+            for (index, node) in payload.nodes.into_iter().enumerate() {
+                state.apply(RscRow::Node { index, node });
+            }
+            for reference in payload.client_references {
+                state.apply(RscRow::ClientReference(reference));
             }
+            return Ok(state.to_payload());
+        };
 
-            if let Some(colon_pos) = line.find(':') {
-                let json_str = &line[colon_pos + 1..];
-                if let Ok(node) = serde_json::from_str::<RscNode>(json_str) {
-                    payload.add_node(node);
-                }
+        // Rows this runtime does not understand, e.g. from a newer server, are skipped rather
+        // than failing the page.
+        let mut parser = RscStreamParser::new();
+        let rows = parser.push_chunk(wire_format);
+        for row in rows.into_iter().chain(parser.finish().transpose()) {
+            match row {
+                Ok(row) => state.apply(row),
+                Err(e) => warn_skipped_row(&e),
             }
         }
+        Ok(state.to_payload())
+    }
 
-        Ok(payload)
+    /// Consumes the runtime into a renderer that updates its view as payload chunks arrive.
+    pub fn stream(self) -> RscStreamRenderer {
+        RscStreamRenderer::new(self)
     }
 
     pub fn render_payload(&self, payload: &RscPayload) -> Node {
@@ -99,30 +165,43 @@ impl RscRuntime {
     }
 
     fn render_rsc_node(&self, rsc_node: &RscNode) -> Node {
+        self.render_streamed_node(rsc_node, &RscStreamState::default())
+    }
+
+    fn render_streamed_node(&self, rsc_node: &RscNode, state: &RscStreamState) -> Node {
         match rsc_node {
+            RscNode::Element {
+                tag,
+                props,
+                children,
+            } if tag == "portal" => {
+                let target = props
+                    .get("target")
+                    .and_then(|t| t.as_str())
+                    .unwrap_or("body");
+                let content: Vec<Node> = children
+                    .iter()
+                    .map(|c| self.render_streamed_node(c, state))
+                    .collect();
+                react_rs_elements::node::portal(target, content)
+            }
             RscNode::Element {
                 tag,
                 props,
                 children,
             } => {
-                let mut element = create_element_by_tag(tag);
-
-                if let Some(obj) = props.as_object() {
-                    for (key, value) in obj {
-                        if let Some(v) = value.as_str() {
-                            element = element.attr(key, v);
-                        }
-                    }
-                }
+                let mut element = apply_props(create_element_by_tag(tag), props);
 
                 for child in children {
-                    let child_node = self.render_rsc_node(child);
+                    let child_node = self.render_streamed_node(child, state);
                     element = element.child(child_node);
                 }
 
                 Node::Element(element)
             }
             RscNode::Text { value } => Node::Text(value.clone()),
+            // Actions are props; one in child position has nothing to show.
+            RscNode::Action(_) => Node::Fragment(Vec::new()),
             RscNode::ClientReference { id, props } => {
                 if let Some(factory) = self.registry.get(id) {
                     Node::Element(factory(props.clone()))
@@ -136,19 +215,46 @@ impl RscRuntime {
             }
             RscNode::Suspense {
                 id,
+                key,
                 fallback,
                 children,
             } => {
-                let fallback_node = self.render_rsc_node(fallback);
-                let children_nodes: Vec<Node> =
-                    children.iter().map(|c| self.render_rsc_node(c)).collect();
-
-                Node::Element(
-                    div()
-                        .attr("data-suspense-id", id)
-                        .child(fallback_node)
-                        .children(children_nodes),
-                )
+                let boundary = div().attr("data-suspense-id", id);
+                if let Some(error) = state.errors.get(id) {
+                    return Node::Element(boundary.child(self.render_row_error(id, error)));
+                }
+                let content = state.boundary_content(id, key.as_deref(), children);
+                // The nearest boundary shows the failure of any row inside it.
+                if let Some((row, error)) = content.iter().find_map(|c| state.failed_row(c)) {
+                    return Node::Element(boundary.child(self.render_row_error(&row, error)));
+                }
+                if content.is_empty() {
+                    return Node::Element(
+                        boundary.child(self.render_streamed_node(fallback, state)),
+                    );
+                }
+                let content_nodes: Vec<Node> = content
+                    .iter()
+                    .map(|c| self.render_streamed_node(c, state))
+                    .collect();
+                Node::Element(boundary.children(content_nodes))
+            }
+            RscNode::Reference(RowRef(row)) => {
+                let id = row.to_string();
+                if let Some(error) = state.errors.get(&id) {
+                    return self.render_row_error(&id, error);
+                }
+                // Not arrived yet; the view re-renders when it does.
+                let Some(node) = state.rows.get(row) else {
+                    return Node::Fragment(Vec::new());
+                };
+                if state.resolving.borrow().contains(row) {
+                    return Node::Element(render_error(&id, "Circular row reference"));
+                }
+                state.resolving.borrow_mut().push(*row);
+                let rendered = self.render_streamed_node(node, state);
+                state.resolving.borrow_mut().pop();
+                rendered
             }
         }
     }
@@ -164,6 +270,581 @@ impl Default for RscRuntime {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RscHint {
+    pub kind: String,
+    pub data: String,
+}
+
+/// A single row of the RSC wire format.
+#[derive(Debug, Clone)]
+pub enum RscRow {
+    /// `<index>:<node json>`
+    Node { index: usize, node: RscNode },
+    /// `M:<id>:<module>:<export>`
+    ClientReference(RscRef),
+    /// `H:<kind>:<data>`
+    Hint(RscHint),
+    /// `E:<id>:{"digest": ..., "message": ...}`, where `id` is a row index or suspense id.
+    Error { id: String, error: RscError },
+    /// `$<suspense id>:<node json or array of nodes>`, streamed once the boundary resolves.
+    Suspense { id: String, children: Vec<RscNode> },
+}
+
+pub fn parse_row(line: &str) -> Result<Option<RscRow>, String> {
+    let line = line.trim_end_matches('\r');
+    if line.trim().is_empty() {
+        return Ok(None);
+    }
+
+    if let Some(rest) = line.strip_prefix("M:") {
+        // Module paths can contain colons (`app::counter`); ids and export names cannot.
+        let parts = rest.split_once(':').and_then(|(id, rest)| {
+            rest.rsplit_once(':')
+                .map(|(module, export)| (id, module, export))
+        });
+        return match parts {
+            Some((id, module, export)) => Ok(Some(RscRow::ClientReference(RscRef {
+                id: id.to_string(),
+                module: module.to_string(),
+                export: export.to_string(),
+            }))),
+            _ => Err(format!("Malformed client reference row: {}", line)),
+        };
+    }
+
+    if let Some(rest) = line.strip_prefix("H:") {
+        let (kind, data) = rest.split_once(':').unwrap_or((rest, ""));
+        return Ok(Some(RscRow::Hint(RscHint {
+            kind: kind.to_string(),
+            data: data.to_string(),
+        })));
+    }
+
+    if let Some(rest) = line.strip_prefix("E:") {
+        let (id, body) = rest
+            .split_once(':')
+            .ok_or_else(|| format!("Malformed error row: {}", line))?;
+        let error = serde_json::from_str::<RscError>(body).unwrap_or_else(|_| RscError {
+            digest: String::new(),
+            message: Some(body.to_string()),
+        });
+        return Ok(Some(RscRow::Error {
+            id: id.to_string(),
+            error,
+        }));
+    }
+
+    if let Some(rest) = line.strip_prefix('$') {
+        let (id, body) = rest
+            .split_once(':')
+            .ok_or_else(|| format!("Malformed suspense row: {}", line))?;
+        let children = match serde_json::from_str::<Value>(body).map_err(|e| e.to_string())? {
+            Value::Array(items) => items
+                .into_iter()
+                .map(serde_json::from_value)
+                .collect::<Result<Vec<RscNode>, _>>(),
+            value => serde_json::from_value(value).map(|node| vec![node]),
+        }
+        .map_err(|e| format!("Invalid suspense row {}: {}", id, e))?;
+        return Ok(Some(RscRow::Suspense {
+            id: id.to_string(),
+            children,
+        }));
+    }
+
+    let (index, body) = line
+        .split_once(':')
+        .ok_or_else(|| format!("Malformed RSC row: {}", line))?;
+    let index = index
+        .parse::<usize>()
+        .map_err(|_| format!("Unknown RSC row type: {}", line))?;
+    let node = serde_json::from_str::<RscNode>(body)
+        .map_err(|e| format!("Invalid node in row {}: {}", index, e))?;
+    Ok(Some(RscRow::Node { index, node }))
+}
+
+/// Splits an RSC stream into rows, buffering partial lines across chunk boundaries.
+#[derive(Debug, Default)]
+pub struct RscStreamParser {
+    buffer: String,
+}
+
+impl RscStreamParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns every row completed by `chunk`. A malformed row yields an error without
+    /// affecting the rows around it.
+    pub fn push_chunk(&mut self, chunk: &str) -> Vec<Result<RscRow, String>> {
+        self.buffer.push_str(chunk);
+        let mut rows = Vec::new();
+        while let Some(newline) = self.buffer.find('\n') {
+            let line: String = self.buffer.drain(..=newline).collect();
+            if let Some(row) = parse_row(&line[..newline]).transpose() {
+                rows.push(row);
+            }
+        }
+        rows
+    }
+
+    /// Parses whatever is left once the stream ends without a trailing newline.
+    pub fn finish(&mut self) -> Result<Option<RscRow>, String> {
+        let rest = std::mem::take(&mut self.buffer);
+        parse_row(&rest)
+    }
+}
+
+#[derive(Default)]
+struct RscStreamState {
+    rows: BTreeMap<usize, RscNode>,
+    row_revisions: HashMap<usize, u64>,
+    client_references: Vec<RscRef>,
+    hints: Vec<RscHint>,
+    errors: HashMap<String, RscError>,
+    resolved: HashMap<String, Vec<RscNode>>,
+    /// Content of keyed suspense boundaries resolved before the last navigation.
+    kept: HashMap<String, Vec<RscNode>>,
+    revision: u64,
+    /// Rows being rendered through references, to stop at cycles.
+    resolving: RefCell<Vec<usize>>,
+}
+
+impl RscStreamState {
+    fn apply(&mut self, row: RscRow) {
+        self.revision += 1;
+        match row {
+            RscRow::Node { index, node } => {
+                self.rows.insert(index, node);
+                self.row_revisions.insert(index, self.revision);
+                self.touch_referrers(index);
+            }
+            RscRow::ClientReference(reference) => {
+                self.client_references.retain(|r| r.id != reference.id);
+                self.client_references.push(reference);
+            }
+            RscRow::Hint(hint) => self.hints.push(hint),
+            RscRow::Error { id, error } => {
+                self.touch_rows_containing(&id);
+                if let Ok(index) = id.parse() {
+                    self.touch_referrers(index);
+                }
+                self.errors.insert(id, error);
+            }
+            RscRow::Suspense { id, children } => {
+                self.touch_rows_containing(&id);
+                self.resolved.insert(id, children);
+            }
+        }
+    }
+
+    /// What a boundary shows once loaded: its streamed content, the content sent inline, or
+    /// the content kept from an earlier page for its key. Empty while it is still loading.
+    fn boundary_content<'a>(
+        &'a self,
+        id: &str,
+        key: Option<&str>,
+        children: &'a [RscNode],
+    ) -> &'a [RscNode] {
+        if let Some(content) = self.resolved.get(id) {
+            return content;
+        }
+        if !children.is_empty() {
+            return children;
+        }
+        key.and_then(|key| self.kept.get(key))
+            .map_or(children, Vec::as_slice)
+    }
+
+    /// The loaded content of every keyed boundary, with references inlined so it outlives
+    /// the rows it came from.
+    fn keyed_content(&self) -> HashMap<String, Vec<RscNode>> {
+        let mut content = self.kept.clone();
+        let mut pending: Vec<&RscNode> = self.rows.values().collect();
+        while let Some(node) = pending.pop() {
+            match node {
+                RscNode::Element { children, .. } => pending.extend(children),
+                RscNode::Suspense {
+                    id, key, children, ..
+                } => {
+                    let loaded = self.boundary_content(id, key.as_deref(), children);
+                    pending.extend(loaded);
+                    if let (Some(key), false) = (key, loaded.is_empty()) {
+                        let mut loaded = loaded.to_vec();
+                        for node in &mut loaded {
+                            self.inline_references(node, &mut Vec::new());
+                            resolve_suspense(node, self);
+                        }
+                        content.insert(key.clone(), loaded);
+                    }
+                }
+                _ => {}
+            }
+        }
+        content
+    }
+
+    /// The first failed row referenced from `node` outside any suspense boundary, which
+    /// handles the failures inside it.
+    fn failed_row(&self, node: &RscNode) -> Option<(String, &RscError)> {
+        match node {
+            RscNode::Reference(RowRef(row)) => {
+                let id = row.to_string();
+                if let Some(error) = self.errors.get(&id) {
+                    return Some((id, error));
+                }
+                if self.resolving.borrow().contains(row) {
+                    return None;
+                }
+                let target = self.rows.get(row)?;
+                self.resolving.borrow_mut().push(*row);
+                let failed = self.failed_row(target);
+                self.resolving.borrow_mut().pop();
+                failed
+            }
+            RscNode::Element { children, .. } => children.iter().find_map(|c| self.failed_row(c)),
+            RscNode::Text { .. }
+            | RscNode::ClientReference { .. }
+            | RscNode::Action(_)
+            | RscNode::Suspense { .. } => None,
+        }
+    }
+
+    /// Marks rows that are affected by `id` so the view re-renders only those rows.
+    fn touch_rows_containing(&mut self, id: &str) {
+        let revision = self.revision;
+        for (index, node) in &self.rows {
+            if index.to_string() == id || contains_suspense(node, id) {
+                self.row_revisions.insert(*index, revision);
+            }
+        }
+    }
+
+    /// Marks the rows that reference `index`, directly or through other rows, so the roots
+    /// showing the newly arrived row re-render.
+    fn touch_referrers(&mut self, index: usize) {
+        let revision = self.revision;
+        let mut pending = vec![index];
+        let mut touched = vec![index];
+        while let Some(target) = pending.pop() {
+            for (row, node) in &self.rows {
+                if node.references(target) && !touched.contains(row) {
+                    touched.push(*row);
+                    pending.push(*row);
+                    self.row_revisions.insert(*row, revision);
+                }
+            }
+        }
+    }
+
+    /// Rows that no other row references; the rest are rendered where they are referenced.
+    fn roots(&self) -> impl Iterator<Item = (&usize, &RscNode)> {
+        self.rows.iter().filter(|(index, _)| {
+            !self
+                .rows
+                .iter()
+                .any(|(other, node)| other != *index && node.references(**index))
+        })
+    }
+
+    fn to_payload(&self) -> RscPayload {
+        let mut payload = RscPayload::new();
+        for (_, node) in self.roots() {
+            let mut node = node.clone();
+            self.inline_references(&mut node, &mut Vec::new());
+            resolve_suspense(&mut node, self);
+            payload.add_node(node);
+        }
+        payload.client_references = self.client_references.clone();
+        payload
+    }
+
+    fn inline_references(&self, node: &mut RscNode, visiting: &mut Vec<usize>) {
+        match node {
+            RscNode::Reference(RowRef(row)) => {
+                let row = *row;
+                if visiting.contains(&row) {
+                    return;
+                }
+                if let Some(target) = self.rows.get(&row) {
+                    *node = target.clone();
+                    visiting.push(row);
+                    self.inline_references(node, visiting);
+                    visiting.pop();
+                }
+            }
+            RscNode::Element { children, .. } => {
+                for child in children {
+                    self.inline_references(child, visiting);
+                }
+            }
+            RscNode::Suspense {
+                fallback, children, ..
+            } => {
+                self.inline_references(fallback, visiting);
+                for child in children {
+                    self.inline_references(child, visiting);
+                }
+            }
+            RscNode::Text { .. } | RscNode::ClientReference { .. } | RscNode::Action(_) => {}
+        }
+    }
+}
+
+fn contains_suspense(node: &RscNode, id: &str) -> bool {
+    match node {
+        RscNode::Element { children, .. } => children.iter().any(|c| contains_suspense(c, id)),
+        RscNode::Suspense {
+            id: boundary_id,
+            fallback,
+            children,
+            ..
+        } => {
+            boundary_id == id
+                || contains_suspense(fallback, id)
+                || children.iter().any(|c| contains_suspense(c, id))
+        }
+        RscNode::Text { .. }
+        | RscNode::ClientReference { .. }
+        | RscNode::Action(_)
+        | RscNode::Reference(_) => false,
+    }
+}
+
+fn resolve_suspense(node: &mut RscNode, state: &RscStreamState) {
+    match node {
+        RscNode::Element { children, .. } => {
+            for child in children {
+                resolve_suspense(child, state);
+            }
+        }
+        RscNode::Suspense {
+            id, key, children, ..
+        } => {
+            *children = state
+                .boundary_content(id, key.as_deref(), children)
+                .to_vec();
+            for child in children {
+                resolve_suspense(child, state);
+            }
+        }
+        RscNode::Text { .. }
+        | RscNode::ClientReference { .. }
+        | RscNode::Action(_)
+        | RscNode::Reference(_) => {}
+    }
+}
+
+fn render_error(id: &str, message: &str) -> Element {
+    div()
+        .attr("data-rsc-error", id)
+        .attr("role", "alert")
+        .text(message)
+}
+
+struct RscStreamInner {
+    runtime: RscRuntime,
+    parser: RefCell<RscStreamParser>,
+    state: RefCell<RscStreamState>,
+    version: ReadSignal<u64>,
+    set_version: WriteSignal<u64>,
+}
+
+/// Renders an RSC payload progressively: rows are applied as chunks arrive and the view
+/// returned by [`RscStreamRenderer::view`] re-renders only the rows that changed.
+#[derive(Clone)]
+pub struct RscStreamRenderer {
+    inner: Rc<RscStreamInner>,
+}
+
+impl RscStreamRenderer {
+    pub fn new(runtime: RscRuntime) -> Self {
+        let (version, set_version) = create_signal(0u64);
+        Self {
+            inner: Rc::new(RscStreamInner {
+                runtime,
+                parser: RefCell::new(RscStreamParser::new()),
+                state: RefCell::new(RscStreamState::default()),
+                version,
+                set_version,
+            }),
+        }
+    }
+
+    /// Applies the rows completed by `chunk`. Malformed rows are skipped and logged, like
+    /// [`RscRuntime::parse_payload`] does, so one bad row does not abort the navigation.
+    pub fn push_chunk(&self, chunk: &str) {
+        let rows = self.inner.parser.borrow_mut().push_chunk(chunk);
+        self.apply(rows);
+    }
+
+    /// Flushes a trailing row that was not newline-terminated.
+    pub fn finish(&self) {
+        let row = self.inner.parser.borrow_mut().finish().transpose();
+        self.apply(row.into_iter().collect());
+    }
+
+    fn apply(&self, rows: Vec<Result<RscRow, String>>) {
+        let mut changed = false;
+        {
+            let mut state = self.inner.state.borrow_mut();
+            for row in rows {
+                match row {
+                    Ok(row) => {
+                        state.apply(row);
+                        changed = true;
+                    }
+                    Err(e) => warn_skipped_row(&e),
+                }
+            }
+        }
+        if changed {
+            self.inner.set_version.update(|v| *v += 1);
+        }
+    }
+
+    pub fn view(&self) -> Node {
+        let inner = self.inner.clone();
+        Node::KeyedList(Rc::new(move || {
+            inner.version.get();
+            let state = inner.state.borrow();
+            state
+                .roots()
+                .map(|(index, node)| {
+                    let revision = state.row_revisions.get(index).copied().unwrap_or(0);
+                    let id = index.to_string();
+                    let failed = match state.errors.get(&id) {
+                        Some(error) => Some((id, error)),
+                        None => state.failed_row(node),
+                    };
+                    let rendered = match failed {
+                        Some((id, error)) => inner.runtime.render_row_error(&id, error),
+                        None => inner.runtime.render_streamed_node(node, &state),
+                    };
+                    (format!("{}@{}", index, revision), rendered)
+                })
+                .collect()
+        }))
+    }
+
+    /// The payload received so far, with resolved suspense boundaries folded in.
+    pub fn payload(&self) -> RscPayload {
+        self.inner.state.borrow().to_payload()
+    }
+
+    pub fn hints(&self) -> Vec<RscHint> {
+        self.inner.state.borrow().hints.clone()
+    }
+
+    pub fn error(&self, id: &str) -> Option<RscError> {
+        self.inner.state.borrow().errors.get(id).cloned()
+    }
+
+    /// Clears the view for the next route's payload, keeping the content of loaded keyed
+    /// suspense boundaries: a boundary the new payload sends with the same key shows that
+    /// content instead of its fallback. Send [`RscStreamRenderer::boundary_keys`] with the
+    /// request so the server can leave the content out.
+    pub fn navigate(&self) {
+        {
+            let mut state = self.inner.state.borrow_mut();
+            let kept = state.keyed_content();
+            *state = RscStreamState {
+                kept,
+                ..RscStreamState::default()
+            };
+        }
+        *self.inner.parser.borrow_mut() = RscStreamParser::new();
+        self.inner.set_version.update(|v| *v += 1);
+    }
+
+    /// The keys of the suspense boundaries whose content this renderer has, for the
+    /// [`next_rs_rsc::RSC_KEYS_HEADER`] request header.
+    pub fn boundary_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .inner
+            .state
+            .borrow()
+            .keyed_content()
+            .into_keys()
+            .collect();
+        keys.sort();
+        keys
+    }
+}
+
+/// Applies props decoded from the payload. Strings and numbers become attribute values,
+/// booleans boolean attributes, arrays space-separated lists (e.g. classes), and a `style`
+/// object its declarations; other objects are kept as JSON, e.g. for `data-*` attributes.
+fn apply_props(mut element: Element, props: &Value) -> Element {
+    let Some(props) = props.as_object() else {
+        return element;
+    };
+    for (key, value) in props {
+        element = match (key.as_str(), value) {
+            (_, Value::Null) => element,
+            (_, Value::String(s)) => element.attr(key, s),
+            (_, Value::Bool(b)) => element.attr_bool(key, *b),
+            (_, Value::Number(n)) => element.attr(key, &n.to_string()),
+            (_, Value::Array(items)) => {
+                let items: Vec<String> = items
+                    .iter()
+                    .filter_map(|item| match item {
+                        Value::Null | Value::Bool(false) => None,
+                        Value::String(s) => Some(s.clone()),
+                        other => Some(other.to_string()),
+                    })
+                    .collect();
+                element.attr(key, &items.join(" "))
+            }
+            ("style", Value::Object(declarations)) => {
+                let css: Vec<String> = declarations
+                    .iter()
+                    .filter(|(_, value)| !value.is_null())
+                    .map(|(property, value)| {
+                        let value = value
+                            .as_str()
+                            .map_or_else(|| value.to_string(), str::to_string);
+                        format!("{}: {}", css_property(property), value)
+                    })
+                    .collect();
+                element.attr(key, &css.join("; "))
+            }
+            (_, Value::Object(object)) if object.get("type") == Some(&Value::from("action")) => {
+                match serde_json::from_value(value.clone()) {
+                    Ok(RscNode::Action(action)) => element.action(&action),
+                    _ => element,
+                }
+            }
+            ("dangerouslySetInnerHTML", Value::Object(html)) => {
+                match html.get("__html").and_then(Value::as_str) {
+                    Some(html) if !element.is_void() => element.inner_html(html),
+                    _ => element,
+                }
+            }
+            (_, Value::Object(_)) => element.attr(key, &value.to_string()),
+        };
+    }
+    element
+}
+
+/// `backgroundColor` -> `background-color`; custom properties and kebab-case pass through.
+fn css_property(name: &str) -> String {
+    if name.starts_with("--") {
+        return name.to_string();
+    }
+    let mut out = String::with_capacity(name.len() + 2);
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            out.push('-');
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
 fn create_element_by_tag(tag: &str) -> Element {
     match tag {
         "div" => div(),
@@ -208,6 +889,7 @@ fn create_element_by_tag(tag: &str) -> Element {
         "option" => option(),
         "textarea" => textarea(),
         "fragment" => div().attr("data-fragment", "true"),
+        _ if Element::is_valid_tag(tag) => Element::custom(tag),
         _ => div().attr("data-unknown-tag", tag),
     }
 }
@@ -231,6 +913,61 @@ pub async fn fetch_rsc_payload(url: &str) -> Result<JsValue, JsValue> {
     Ok(text)
 }
 
+/// Fetches an RSC payload and feeds the response body into `renderer` chunk by chunk. Call
+/// [`RscStreamRenderer::navigate`] first when it already shows another route.
+pub async fn stream_rsc_payload(url: &str, renderer: &RscStreamRenderer) -> Result<(), JsValue> {
+    let window = web_sys::window().ok_or("no window")?;
+
+    let opts = web_sys::RequestInit::new();
+    opts.set_method("GET");
+    opts.set_mode(web_sys::RequestMode::Cors);
+
+    let request = web_sys::Request::new_with_str_and_init(url, &opts)?;
+    request.headers().set("Accept", "text/x-component")?;
+    let keys = renderer.boundary_keys();
+    if !keys.is_empty() {
+        request
+            .headers()
+            .set(next_rs_rsc::RSC_KEYS_HEADER, &keys.join(","))?;
+    }
+
+    let resp_value =
+        wasm_bindgen_futures::JsFuture::from(window.fetch_with_request(&request)).await?;
+    let resp: web_sys::Response = resp_value.dyn_into()?;
+    let body = resp.body().ok_or("response has no body")?;
+    let reader: web_sys::ReadableStreamDefaultReader = body.get_reader().dyn_into()?;
+
+    let decoder = web_sys::TextDecoder::new()?;
+    let decode_opts = web_sys::TextDecodeOptions::new();
+    decode_opts.set_stream(true);
+
+    loop {
+        let result = wasm_bindgen_futures::JsFuture::from(reader.read()).await?;
+        let done = js_sys::Reflect::get(&result, &JsValue::from_str("done"))?;
+        if done.as_bool().unwrap_or(true) {
+            break;
+        }
+        let value = js_sys::Reflect::get(&result, &JsValue::from_str("value"))?;
+        let bytes: js_sys::Uint8Array = value.dyn_into()?;
+        let text = decoder.decode_with_js_u8_array_and_options(&bytes, &decode_opts)?;
+        renderer.push_chunk(&text);
+    }
+
+    renderer.finish();
+    Ok(())
+}
+
+/// Reports a payload row this runtime skipped.
+#[cfg(target_arch = "wasm32")]
+fn warn_skipped_row(error: &str) {
+    web_sys::console::warn_1(&JsValue::from_str(&format!("skipped RSC row: {}", error)));
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn warn_skipped_row(error: &str) {
+    eprintln!("skipped RSC row: {}", error);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -274,6 +1011,122 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_register_typed_client_component() {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct LabelProps {
+            label: String,
+        }
+
+        let mut registry = ClientComponentRegistry::new();
+        registry.register_typed("label", |props: LabelProps| span().text(props.label));
+        let runtime = RscRuntime::with_registry(registry);
+
+        let marker = next_rs_rsc::markers::client_component("label", "./Label.js", span);
+        let node = marker
+            .reference(&LabelProps {
+                label: "Hi".to_string(),
+            })
+            .unwrap();
+        let Node::Element(el) = runtime.render_rsc_node(&node) else {
+            panic!("Expected Element");
+        };
+        assert_eq!(el.tag(), "span");
+
+        let invalid = RscNode::client_ref("label", serde_json::json!({"label": 3}));
+        let Node::Element(el) = runtime.render_rsc_node(&invalid) else {
+            panic!("Expected Element");
+        };
+        assert!(el
+            .attributes()
+            .iter()
+            .any(|attr| attr.name == "data-rsc-error"));
+    }
+
+    #[test]
+    fn test_render_rich_props() {
+        let runtime = RscRuntime::new();
+        let node = RscNode::element(
+            "div",
+            serde_json::json!({
+                "hidden": true,
+                "inert": false,
+                "tabindex": 0,
+                "class": ["card", "active"],
+                "style": {"backgroundColor": "red", "--gap": "4px", "zIndex": 2},
+                "data-config": {"page": 1},
+                "title": null,
+            }),
+            vec![],
+        );
+
+        let Node::Element(el) = runtime.render_rsc_node(&node) else {
+            panic!("Expected Element");
+        };
+        let attr = |name: &str| {
+            el.attributes()
+                .iter()
+                .find(|attr| attr.name == name)
+                .map(|attr| attr.to_static_value())
+        };
+        assert_eq!(attr("hidden").as_deref(), Some("true"));
+        assert_eq!(attr("inert").as_deref(), Some("false"));
+        assert_eq!(attr("tabindex").as_deref(), Some("0"));
+        assert_eq!(attr("class").as_deref(), Some("card active"));
+        assert_eq!(
+            attr("style").as_deref(),
+            Some("--gap: 4px; background-color: red; z-index: 2")
+        );
+        assert_eq!(attr("data-config").as_deref(), Some("{\"page\":1}"));
+        assert_eq!(attr("title"), None);
+    }
+
+    #[test]
+    fn test_render_action_props() {
+        let runtime = RscRuntime::new();
+        let action = serde_json::json!({
+            "type": "action",
+            "id": "deletePost",
+            "bound_args": {"post_id": 7},
+        });
+        let node = RscNode::element(
+            "div",
+            serde_json::json!({}),
+            vec![
+                RscNode::element("form", serde_json::json!({ "action": action }), vec![]),
+                RscNode::element(
+                    "button",
+                    serde_json::json!({ "formAction": action }),
+                    vec![],
+                ),
+            ],
+        );
+
+        let Node::Element(el) = runtime.render_rsc_node(&node) else {
+            panic!("Expected Element");
+        };
+        let Node::Element(form) = &el.get_children()[0] else {
+            panic!("Expected form");
+        };
+        let Node::Element(button) = &el.get_children()[1] else {
+            panic!("Expected button");
+        };
+        let attr = |el: &Element, name: &str| {
+            el.attributes()
+                .iter()
+                .find(|attr| attr.name == name)
+                .map(|attr| attr.to_static_value())
+        };
+        assert_eq!(attr(form, "action").as_deref(), Some("/_action/deletePost"));
+        assert_eq!(attr(form, "data-action").as_deref(), Some("deletePost"));
+        assert_eq!(form.get_children().len(), 1);
+        assert_eq!(
+            attr(button, "formaction").as_deref(),
+            Some("/_action/deletePost")
+        );
+        assert_eq!(attr(button, "value").as_deref(), Some("{\"post_id\":7}"));
+    }
+
     #[test]
     fn test_render_element_node() {
         let runtime = RscRuntime::new();
@@ -304,11 +1157,282 @@ mod tests {
         assert_eq!(payload.nodes.len(), 1);
     }
 
+    #[test]
+    fn test_parse_payload_skips_unknown_rows() {
+        let runtime = RscRuntime::new();
+        let wire = "0:{\"type\":\"text\",\"value\":\"Hello\"}\nX:from a newer server\n1:{\"type\":\"text\",\"value\":\"World\"}";
+        let payload = runtime.parse_payload(wire).unwrap();
+
+        assert_eq!(payload.nodes.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_msgpack_payload() {
+        let runtime = RscRuntime::new();
+        let mut sent = RscPayload::new();
+        sent.add_node(RscNode::element(
+            "main",
+            serde_json::json!({}),
+            vec![RscNode::reference(1)],
+        ));
+        sent.add_node(RscNode::text("Out of order"));
+        sent.add_client_reference(
+            "counter".to_string(),
+            "./Counter.js".to_string(),
+            "Counter".to_string(),
+        );
+
+        let payload = runtime.parse_payload(sent.to_msgpack()).unwrap();
+        assert_eq!(payload.nodes.len(), 1);
+        let RscNode::Element { children, .. } = &payload.nodes[0] else {
+            panic!("Expected Element node");
+        };
+        assert!(matches!(&children[0], RscNode::Text { value } if value == "Out of order"));
+        assert_eq!(payload.client_references.len(), 1);
+
+        assert!(runtime.parse_payload([0x92u8, 0xff]).is_err());
+    }
+
     #[test]
     fn test_create_element_by_tag() {
         assert_eq!(create_element_by_tag("div").tag(), "div");
         assert_eq!(create_element_by_tag("span").tag(), "span");
         assert_eq!(create_element_by_tag("button").tag(), "button");
-        assert_eq!(create_element_by_tag("unknown").tag(), "div");
+        assert_eq!(create_element_by_tag("my-widget").tag(), "my-widget");
+        assert_eq!(create_element_by_tag("no tag").tag(), "div");
+    }
+
+    #[test]
+    fn test_parse_payload_collects_client_references() {
+        let runtime = RscRuntime::new();
+        let wire = "0:{\"type\":\"client\",\"id\":\"counter\",\"props\":{}}\nM:counter:./Counter.js:Counter";
+        let payload = runtime.parse_payload(wire).unwrap();
+
+        assert_eq!(payload.nodes.len(), 1);
+        assert_eq!(payload.client_references.len(), 1);
+        assert_eq!(payload.client_references[0].module, "./Counter.js");
+        assert_eq!(payload.client_references[0].export, "Counter");
+
+        let Ok(Some(RscRow::ClientReference(reference))) =
+            parse_row("M:counter:app::widgets:Counter")
+        else {
+            panic!("Expected client reference row");
+        };
+        assert_eq!(reference.module, "app::widgets");
+        assert_eq!(reference.export, "Counter");
+    }
+
+    #[test]
+    fn test_stream_parser_buffers_partial_rows() {
+        let mut parser = RscStreamParser::new();
+
+        assert!(parser.push_chunk("0:{\"type\":\"te").is_empty());
+        let rows = parser.push_chunk("xt\",\"value\":\"Hi\"}\nH:preload:/app.css\nE:1:");
+        assert_eq!(rows.len(), 2);
+        assert!(matches!(rows[0], Ok(RscRow::Node { index: 0, .. })));
+        match &rows[1] {
+            Ok(RscRow::Hint(hint)) => {
+                assert_eq!(hint.kind, "preload");
+                assert_eq!(hint.data, "/app.css");
+            }
+            _ => panic!("Expected hint row"),
+        }
+
+        parser.push_chunk("{\"message\":\"boom\"}");
+        match parser.finish() {
+            Ok(Some(RscRow::Error { id, error })) => {
+                assert_eq!(id, "1");
+                assert_eq!(error.message.as_deref(), Some("boom"));
+            }
+            _ => panic!("Expected error row"),
+        }
+    }
+
+    #[test]
+    fn test_parse_row_rejects_unknown_rows() {
+        assert!(parse_row("X:nope").is_err());
+        assert!(parse_row("0:not json").is_err());
+        assert!(matches!(parse_row(""), Ok(None)));
+    }
+
+    #[test]
+    fn test_stream_resolves_late_suspense_rows() {
+        let renderer = RscRuntime::new().stream();
+        let boundary = RscNode::suspense("s1", RscNode::text("Loading..."), vec![]);
+        let row = format!("0:{}\n", serde_json::to_string(&boundary).unwrap());
+
+        renderer.push_chunk(&row);
+        let pending = renderer.payload();
+        assert!(matches!(
+            &pending.nodes[0],
+            RscNode::Suspense { children, .. } if children.is_empty()
+        ));
+
+        // The malformed row is skipped; the valid one before it still applies.
+        renderer.push_chunk("$s1:[{\"type\":\"text\",\"value\":\"Done\"}]\nbad row\n");
+        let resolved = renderer.payload();
+        match &resolved.nodes[0] {
+            RscNode::Suspense { children, .. } => {
+                assert!(matches!(&children[0], RscNode::Text { value } if value == "Done"));
+            }
+            _ => panic!("Expected Suspense"),
+        }
+    }
+
+    #[test]
+    fn test_stream_fills_referenced_rows_out_of_order() {
+        let renderer = RscRuntime::new().stream();
+        let keys = |renderer: &RscStreamRenderer| match renderer.view() {
+            Node::KeyedList(list) => list().into_iter().map(|(k, _)| k).collect::<Vec<_>>(),
+            _ => panic!("Expected KeyedList"),
+        };
+
+        renderer.push_chunk(
+            "0:{\"type\":\"element\",\"tag\":\"main\",\"props\":{},\"children\":[\"$2\",\"$1\"]}\n\
+                 1:{\"type\":\"text\",\"value\":\"Footer\"}\n",
+        );
+        let before = keys(&renderer);
+        assert_eq!(before.len(), 1);
+        assert!(before[0].starts_with("0@"));
+
+        renderer.push_chunk("2:{\"type\":\"text\",\"value\":\"Slow\"}\n");
+        let after = keys(&renderer);
+        assert_eq!(after.len(), 1);
+        assert_ne!(before, after);
+
+        let payload = renderer.payload();
+        let RscNode::Element { children, .. } = &payload.nodes[0] else {
+            panic!("Expected Element");
+        };
+        assert!(matches!(&children[0], RscNode::Text { value } if value == "Slow"));
+        assert!(matches!(&children[1], RscNode::Text { value } if value == "Footer"));
+    }
+
+    #[test]
+    fn test_stream_stops_at_circular_references() {
+        let renderer = RscRuntime::new().stream();
+        renderer
+            .push_chunk("0:{\"type\":\"element\",\"tag\":\"div\",\"props\":{},\"children\":[\"$1\"]}\n1:\"$0\"\n");
+        // Both rows are referenced, so neither is a root; rendering must still terminate.
+        assert!(renderer.payload().nodes.is_empty());
+        let state = renderer.inner.state.borrow();
+        let node = renderer
+            .inner
+            .runtime
+            .render_streamed_node(&RscNode::reference(0), &state);
+        assert!(matches!(node, Node::Element(_)));
+    }
+
+    #[test]
+    fn test_stream_view_rekeys_changed_rows() {
+        let renderer = RscRuntime::new().stream();
+        let boundary = RscNode::suspense("s1", RscNode::text("Loading..."), vec![]);
+        renderer.push_chunk(&format!(
+            "0:{{\"type\":\"text\",\"value\":\"Header\"}}\n1:{}\n",
+            serde_json::to_string(&boundary).unwrap()
+        ));
+
+        let keys = |renderer: &RscStreamRenderer| match renderer.view() {
+            Node::KeyedList(list) => list().into_iter().map(|(k, _)| k).collect::<Vec<_>>(),
+            _ => panic!("Expected KeyedList"),
+        };
+        let before = keys(&renderer);
+        renderer.push_chunk("E:s1:{\"message\":\"failed\"}\n");
+        let after = keys(&renderer);
+
+        assert_eq!(before[0], after[0]);
+        assert_ne!(before[1], after[1]);
+        assert_eq!(
+            renderer.error("s1").and_then(|e| e.message).as_deref(),
+            Some("failed")
+        );
+    }
+
+    #[test]
+    fn test_stream_error_renders_nearest_boundary_fallback() {
+        let mut runtime = RscRuntime::new();
+        runtime.set_error_fallback(|error| p().class("oops").text(&error.digest));
+        let renderer = runtime.stream();
+        let boundary = RscNode::suspense(
+            "feed",
+            RscNode::text("Loading..."),
+            vec![RscNode::element(
+                "ul",
+                serde_json::json!({}),
+                vec![RscNode::reference(2)],
+            )],
+        );
+        renderer.push_chunk(&format!(
+            "0:{{\"type\":\"text\",\"value\":\"Header\"}}\n1:{}\n",
+            serde_json::to_string(&boundary).unwrap()
+        ));
+        renderer.push_chunk("E:2:{\"digest\":\"1a2b3c4d\"}\n");
+
+        let error = renderer.error("2").unwrap();
+        assert_eq!(error.digest, "1a2b3c4d");
+        assert_eq!(error.message, None);
+
+        let Node::KeyedList(list) = renderer.view() else {
+            panic!("Expected KeyedList");
+        };
+        let rows = list();
+        let Node::Element(boundary) = &rows[1].1 else {
+            panic!("Expected boundary element");
+        };
+        let Node::Element(fallback) = &boundary.get_children()[0] else {
+            panic!("Expected error fallback");
+        };
+        assert_eq!(fallback.tag(), "p");
+        assert!(matches!(&fallback.get_children()[0], Node::Text(t) if t == "1a2b3c4d"));
+    }
+
+    #[test]
+    fn test_navigation_keeps_keyed_boundaries() {
+        let renderer = RscRuntime::new().stream();
+        let sidebar =
+            RscNode::keyed_suspense("sidebar-0", "sidebar:v1", RscNode::text("..."), vec![]);
+        let feed = RscNode::keyed_suspense("feed-1", "feed:page1", RscNode::text("..."), vec![]);
+        renderer.push_chunk(&format!(
+            "0:{}\n1:{}\n$sidebar-0:\"$2\"\n2:{{\"type\":\"text\",\"value\":\"Links\"}}\n",
+            serde_json::to_string(&sidebar).unwrap(),
+            serde_json::to_string(&feed).unwrap(),
+        ));
+        assert_eq!(renderer.boundary_keys(), vec!["sidebar:v1".to_string()]);
+
+        renderer.navigate();
+        let sidebar =
+            RscNode::keyed_suspense("sidebar-0", "sidebar:v1", RscNode::text("..."), vec![]);
+        let feed = RscNode::keyed_suspense("feed-1", "feed:page2", RscNode::text("..."), vec![]);
+        renderer.push_chunk(&format!(
+            "0:{}\n1:{}\n",
+            serde_json::to_string(&sidebar).unwrap(),
+            serde_json::to_string(&feed).unwrap(),
+        ));
+
+        let payload = renderer.payload();
+        let loaded = |node: &RscNode| match node {
+            RscNode::Suspense { children, .. } => children.clone(),
+            _ => panic!("Expected Suspense"),
+        };
+        assert!(matches!(
+            &loaded(&payload.nodes[0])[..],
+            [RscNode::Text { value }] if value == "Links"
+        ));
+        assert!(loaded(&payload.nodes[1]).is_empty());
+    }
+
+    #[test]
+    fn test_render_portal_node() {
+        let runtime = RscRuntime::new();
+        let node = RscNode::element(
+            "portal",
+            serde_json::json!({"target": "#modals"}),
+            vec![RscNode::text("Dialog")],
+        );
+
+        match runtime.render_rsc_node(&node) {
+            Node::Portal(target, _) => assert_eq!(target, "#modals"),
+            _ => panic!("Expected Portal"),
+        }
     }
 }
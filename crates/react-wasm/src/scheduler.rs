@@ -0,0 +1,77 @@
+use react_rs_core::effect::{clear_effect_scheduler, flush_effects, set_effect_scheduler};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateStrategy {
+    /// Effects run synchronously on every signal write (the default).
+    Sync,
+    /// Effects triggered by writes in the same task are coalesced and run in a microtask.
+    Microtask,
+    /// Effects are coalesced and run right before the next paint.
+    AnimationFrame,
+}
+
+/// Chooses when DOM effects run after signal writes. Batching avoids repeated attribute and
+/// text churn when many signals are written in a row.
+pub fn set_update_strategy(strategy: UpdateStrategy) {
+    match strategy {
+        UpdateStrategy::Sync => clear_effect_scheduler(),
+        UpdateStrategy::Microtask => set_effect_scheduler(|| {
+            wasm_bindgen_futures::spawn_local(async { flush_effects() });
+        }),
+        UpdateStrategy::AnimationFrame => set_effect_scheduler(|| {
+            let callback = Closure::once_into_js(flush_effects);
+            match web_sys::window() {
+                Some(window) => {
+                    let _ = window.request_animation_frame(callback.unchecked_ref());
+                }
+                None => flush_effects(),
+            }
+        }),
+    }
+}
+
+/// Routes `react_rs_core::spawn_local` (used by resources) to the browser's microtask queue,
+/// starting any tasks queued while components were built.
+pub(crate) fn install_task_spawner() {
+    react_rs_core::task::set_task_spawner(wasm_bindgen_futures::spawn_local);
+}
+
+/// Backs time-based signal combinators such as `ReadSignal::debounce` with `setTimeout`.
+pub(crate) fn install_timer() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    react_rs_core::timer::set_timer(|delay, f| {
+        let Some(window) = crate::hooks::browser_window() else {
+            f();
+            return Box::new(|| {});
+        };
+        // The callback is held on the Rust side so cancelling frees it even though the JS
+        // shim created by `once_into_js` is never called.
+        let slot = Rc::new(RefCell::new(Some(f)));
+        let fire_slot = slot.clone();
+        let callback = Closure::once_into_js(move || {
+            let f = fire_slot.borrow_mut().take();
+            if let Some(f) = f {
+                f();
+            }
+        });
+        let id = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+            callback.unchecked_ref(),
+            delay.as_millis() as i32,
+        );
+        Box::new(move || {
+            slot.borrow_mut().take();
+            if let Ok(id) = id {
+                window.clear_timeout_with_handle(id);
+            }
+        })
+    });
+}
+
+/// Runs any scheduled DOM updates immediately, e.g. before asserting on the DOM in tests.
+pub fn flush_sync() {
+    flush_effects();
+}
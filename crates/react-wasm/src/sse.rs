@@ -0,0 +1,149 @@
+use react_rs_core::signal::{create_signal, ReadSignal};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{EventSource, MessageEvent};
+
+const INITIAL_RETRY_MS: u32 = 500;
+const MAX_RETRY_MS: u32 = 30_000;
+
+type EventClosure = Closure<dyn FnMut(web_sys::Event)>;
+
+struct SseConnection {
+    url: String,
+    source: RefCell<Option<EventSource>>,
+    closed: Cell<bool>,
+    attempts: Cell<u32>,
+    on_message: Rc<dyn Fn(String)>,
+    closures: RefCell<Vec<EventClosure>>,
+}
+
+impl SseConnection {
+    fn connect(self: &Rc<Self>) {
+        if self.closed.get() {
+            return;
+        }
+
+        let source = match EventSource::new(&self.url) {
+            Ok(source) => source,
+            Err(_) => {
+                self.schedule_reconnect();
+                return;
+            }
+        };
+
+        let on_message = self.on_message.clone();
+        let message_cb = EventClosure::new(move |e: web_sys::Event| {
+            if let Some(text) = e
+                .dyn_ref::<MessageEvent>()
+                .and_then(|m| m.data().as_string())
+            {
+                on_message(text);
+            }
+        });
+        source.set_onmessage(Some(message_cb.as_ref().unchecked_ref()));
+
+        let conn = self.clone();
+        let open_cb = EventClosure::new(move |_: web_sys::Event| {
+            conn.attempts.set(0);
+        });
+        source.set_onopen(Some(open_cb.as_ref().unchecked_ref()));
+
+        // The browser retries transient failures itself; only a CLOSED source needs a new one.
+        let conn = self.clone();
+        let error_cb = EventClosure::new(move |_: web_sys::Event| {
+            let is_closed = conn
+                .source
+                .borrow()
+                .as_ref()
+                .map(|s| s.ready_state() == EventSource::CLOSED)
+                .unwrap_or(true);
+            if is_closed {
+                conn.schedule_reconnect();
+            }
+        });
+        source.set_onerror(Some(error_cb.as_ref().unchecked_ref()));
+
+        let mut closures = self.closures.borrow_mut();
+        closures.clear();
+        closures.extend([message_cb, open_cb, error_cb]);
+        *self.source.borrow_mut() = Some(source);
+    }
+
+    fn schedule_reconnect(self: &Rc<Self>) {
+        if self.closed.get() {
+            return;
+        }
+        if let Some(source) = self.source.borrow_mut().take() {
+            source.close();
+        }
+
+        let attempt = self.attempts.get();
+        self.attempts.set(attempt.saturating_add(1));
+        let delay = INITIAL_RETRY_MS
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_RETRY_MS);
+
+        let conn = self.clone();
+        let callback = Closure::once_into_js(move || conn.connect());
+        if let Some(window) = web_sys::window() {
+            let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+                callback.unchecked_ref(),
+                delay as i32,
+            );
+        }
+    }
+
+    fn close(&self) {
+        self.closed.set(true);
+        if let Some(source) = self.source.borrow_mut().take() {
+            source.close();
+        }
+
+        // close() may run from inside one of these callbacks, so drop them on a later tick.
+        let closures = std::mem::take(&mut *self.closures.borrow_mut());
+        let release = Closure::once_into_js(move || drop(closures));
+        if let Some(window) = web_sys::window() {
+            let _ = window
+                .set_timeout_with_callback_and_timeout_and_arguments_0(release.unchecked_ref(), 0);
+        }
+    }
+}
+
+fn open_sse(url: &str, on_message: impl Fn(String) + 'static) {
+    let conn = Rc::new(SseConnection {
+        url: url.to_string(),
+        source: RefCell::new(None),
+        closed: Cell::new(false),
+        attempts: Cell::new(0),
+        on_message: Rc::new(on_message),
+        closures: RefCell::new(Vec::new()),
+    });
+    conn.connect();
+
+    react_rs_core::effect::on_cleanup(move || conn.close());
+}
+
+/// Subscribes to a server-sent event stream. The returned signal holds the latest message.
+/// The stream reconnects with exponential backoff and closes when the owning effect is cleaned up.
+pub fn use_sse(url: &str) -> ReadSignal<Option<String>> {
+    let (latest, set_latest) = create_signal(None);
+    open_sse(url, move |text| set_latest.set(Some(text)));
+    latest
+}
+
+/// Like [`use_sse`], but deserializes every message as JSON. Messages that fail to parse are
+/// ignored.
+pub fn use_sse_json<T>(url: &str) -> ReadSignal<Option<T>>
+where
+    T: serde::de::DeserializeOwned + 'static,
+{
+    let (latest, set_latest) = create_signal(None);
+    open_sse(url, move |text| {
+        if let Ok(value) = serde_json::from_str::<T>(&text) {
+            set_latest.set(Some(value));
+        }
+    });
+    latest
+}
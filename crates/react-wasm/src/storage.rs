@@ -0,0 +1,113 @@
+use crate::hooks::browser_window;
+use react_rs_core::effect::create_effect;
+use react_rs_core::signal::{create_signal, ReadSignal, WriteSignal};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::cell::Cell;
+use wasm_bindgen::JsCast;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageKind {
+    Local,
+    Session,
+}
+
+/// Web Storage only exists in the browser; on the server the hooks fall back to their defaults.
+fn web_storage(kind: StorageKind) -> Option<web_sys::Storage> {
+    let window = browser_window()?;
+    match kind {
+        StorageKind::Local => window.local_storage().ok().flatten(),
+        StorageKind::Session => window.session_storage().ok().flatten(),
+    }
+}
+
+/// Creates a signal persisted under `key` in the given storage area. Values are stored as JSON,
+/// and changes made in other tabs are picked up through the `storage` event.
+pub fn use_storage<T>(kind: StorageKind, key: &str, default: T) -> (ReadSignal<T>, WriteSignal<T>)
+where
+    T: Serialize + DeserializeOwned + Clone + 'static,
+{
+    let storage = match web_storage(kind) {
+        Some(storage) => storage,
+        None => return create_signal(default),
+    };
+
+    let initial = storage
+        .get_item(key)
+        .ok()
+        .flatten()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_else(|| default.clone());
+    let (value, set_value) = create_signal(initial);
+
+    let persist_key = key.to_string();
+    let persist_value = value.clone();
+    let persist_storage = storage.clone();
+    let first_run = Cell::new(true);
+    create_effect(move || {
+        let json = persist_value.with(serde_json::to_string);
+        if first_run.replace(false) {
+            return;
+        }
+        if let Ok(json) = json {
+            let _ = persist_storage.set_item(&persist_key, &json);
+        }
+    });
+
+    if let Some(window) = browser_window() {
+        let key = key.to_string();
+        let sync_value = set_value.clone();
+        crate::listener::listen(window.as_ref(), "storage", move |e| {
+            let event = match e.dyn_ref::<web_sys::StorageEvent>() {
+                Some(event) => event,
+                None => return,
+            };
+            if event.key().as_deref() != Some(key.as_str())
+                || event.storage_area().as_ref() != Some(&storage)
+            {
+                return;
+            }
+            match event.new_value() {
+                Some(json) => {
+                    if let Ok(parsed) = serde_json::from_str(&json) {
+                        sync_value.set(parsed);
+                    }
+                }
+                None => sync_value.set(default.clone()),
+            }
+        });
+    }
+
+    (value, set_value)
+}
+
+pub fn use_local_storage<T>(key: &str, default: T) -> (ReadSignal<T>, WriteSignal<T>)
+where
+    T: Serialize + DeserializeOwned + Clone + 'static,
+{
+    use_storage(StorageKind::Local, key, default)
+}
+
+pub fn use_session_storage<T>(key: &str, default: T) -> (ReadSignal<T>, WriteSignal<T>)
+where
+    T: Serialize + DeserializeOwned + Clone + 'static,
+{
+    use_storage(StorageKind::Session, key, default)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_falls_back_to_default_off_browser() {
+        let (theme, set_theme) = use_local_storage("theme", "light".to_string());
+        assert_eq!(theme.get_untracked(), "light");
+
+        set_theme.set("dark".to_string());
+        assert_eq!(theme.get_untracked(), "dark");
+
+        let (count, _) = use_session_storage("count", 3u32);
+        assert_eq!(count.get_untracked(), 3);
+    }
+}
@@ -0,0 +1,118 @@
+use react_rs_elements::transition::Transition;
+use std::cell::Cell;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+thread_local! {
+    static VIEW_TRANSITIONS_ENABLED: Cell<bool> = const { Cell::new(true) };
+}
+
+/// Globally enables or disables wrapping DOM swaps in view transitions.
+pub fn set_view_transitions_enabled(enabled: bool) {
+    VIEW_TRANSITIONS_ENABLED.with(|e| e.set(enabled));
+}
+
+pub fn view_transitions_enabled() -> bool {
+    VIEW_TRANSITIONS_ENABLED.with(|e| e.get())
+}
+
+fn start_view_transition_fn() -> Option<(web_sys::Document, js_sys::Function)> {
+    let document = web_sys::window()?.document()?;
+    let start = js_sys::Reflect::get(&document, &JsValue::from_str("startViewTransition")).ok()?;
+    let start = start.dyn_into::<js_sys::Function>().ok()?;
+    Some((document, start))
+}
+
+pub fn supports_view_transitions() -> bool {
+    start_view_transition_fn().is_some()
+}
+
+/// Runs `update` inside `document.startViewTransition` when the browser supports it,
+/// otherwise runs it immediately.
+pub fn with_view_transition(update: impl FnOnce() + 'static) {
+    if !view_transitions_enabled() {
+        update();
+        return;
+    }
+
+    match start_view_transition_fn() {
+        Some((document, start)) => {
+            let callback = Closure::once_into_js(update);
+            let _ = start.call1(&document, &callback);
+        }
+        None => update(),
+    }
+}
+
+type PendingCallback = std::cell::RefCell<Option<Box<dyn FnOnce()>>>;
+
+/// Runs `done` once `el` fires `transitionend` or `animationend`, or after `timeout` if
+/// neither does.
+fn after_transition(
+    el: &web_sys::Element,
+    timeout: std::time::Duration,
+    done: impl FnOnce() + 'static,
+) {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let pending: Rc<PendingCallback> = Rc::new(RefCell::new(Some(Box::new(done))));
+    let finish = move || {
+        let done = pending.borrow_mut().take();
+        if let Some(done) = done {
+            done();
+        }
+    };
+
+    let options = web_sys::AddEventListenerOptions::new();
+    options.set_once(true);
+    for event in ["transitionend", "animationend"] {
+        let finish = finish.clone();
+        let listener = Closure::once_into_js(finish);
+        let _ = el.add_event_listener_with_callback_and_add_event_listener_options(
+            event,
+            listener.unchecked_ref(),
+            &options,
+        );
+    }
+    if let Some(window) = web_sys::window() {
+        let timer = Closure::once_into_js(finish);
+        let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+            timer.unchecked_ref(),
+            timeout.as_millis() as i32,
+        );
+    }
+}
+
+/// Applies the enter class to a freshly shown or inserted element until its transition ends.
+pub(crate) fn animate_enter(el: &web_sys::Element, transition: &Transition) {
+    let classes = el.class_list();
+    let _ = classes.remove_1(&transition.leave_class);
+    let _ = classes.add_1(&transition.enter_class);
+    let el_clone = el.clone();
+    let enter = transition.enter_class.clone();
+    after_transition(el, transition.duration, move || {
+        let _ = el_clone.class_list().remove_1(&enter);
+    });
+}
+
+/// Applies the leave class and runs `done` once the transition ends. `done` is skipped if
+/// the element re-entered in the meantime, which removes the leave class.
+pub(crate) fn animate_leave(
+    el: &web_sys::Element,
+    transition: &Transition,
+    done: impl FnOnce() + 'static,
+) {
+    let classes = el.class_list();
+    let _ = classes.remove_1(&transition.enter_class);
+    let _ = classes.add_1(&transition.leave_class);
+    let el_clone = el.clone();
+    let leave = transition.leave_class.clone();
+    after_transition(el, transition.duration, move || {
+        let classes = el_clone.class_list();
+        if classes.contains(&leave) {
+            let _ = classes.remove_1(&leave);
+            done();
+        }
+    });
+}
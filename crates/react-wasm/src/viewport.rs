@@ -0,0 +1,176 @@
+use react_rs_core::signal::{create_signal, ReadSignal, WriteSignal};
+use react_rs_elements::attributes::AttributeValue;
+use react_rs_elements::Element;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{IntersectionObserver, IntersectionObserverEntry, IntersectionObserverInit};
+
+const VIEWPORT_ATTR: &str = "data-viewport-id";
+
+static VIEWPORT_ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static PENDING_OBSERVERS: RefCell<HashMap<usize, (ViewportOptions, WriteSignal<bool>)>> =
+        RefCell::new(HashMap::new());
+}
+
+#[derive(Debug, Clone)]
+pub struct ViewportOptions {
+    pub threshold: f64,
+    pub root_margin: String,
+    /// Stop observing after the element first becomes visible (useful for lazy loading).
+    pub once: bool,
+}
+
+impl Default for ViewportOptions {
+    fn default() -> Self {
+        Self {
+            threshold: 0.0,
+            root_margin: "0px".to_string(),
+            once: false,
+        }
+    }
+}
+
+impl ViewportOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn root_margin(mut self, margin: impl Into<String>) -> Self {
+        self.root_margin = margin.into();
+        self
+    }
+
+    pub fn once(mut self) -> Self {
+        self.once = true;
+        self
+    }
+}
+
+/// Tracks whether an already-mounted DOM element intersects the viewport.
+pub fn use_in_viewport(element: &web_sys::Element, options: ViewportOptions) -> ReadSignal<bool> {
+    let (visible, set_visible) = create_signal(false);
+    observe(element, options, set_visible);
+    visible
+}
+
+pub trait ViewportExt {
+    /// Writes the element's viewport visibility into `visible` once it is rendered or hydrated.
+    fn in_viewport(self, visible: WriteSignal<bool>, options: ViewportOptions) -> Self;
+}
+
+impl ViewportExt for Element {
+    fn in_viewport(self, visible: WriteSignal<bool>, options: ViewportOptions) -> Self {
+        // Server renders have nothing to observe, and the marker would leak into their HTML.
+        if crate::hooks::browser_window().is_none() {
+            return self;
+        }
+        mark_pending(self, visible, options)
+    }
+}
+
+/// Marks `element` for [`attach_pending`]. The entry is dropped with the current scope, so
+/// elements that are built but never mounted do not keep it.
+fn mark_pending(element: Element, visible: WriteSignal<bool>, options: ViewportOptions) -> Element {
+    let id = VIEWPORT_ID_COUNTER.fetch_add(1, Ordering::SeqCst);
+    PENDING_OBSERVERS.with(|pending| {
+        pending.borrow_mut().insert(id, (options, visible));
+    });
+    react_rs_core::effect::on_cleanup(move || {
+        PENDING_OBSERVERS.with(|pending| pending.borrow_mut().remove(&id));
+    });
+    element.attr(VIEWPORT_ATTR, &id.to_string())
+}
+
+/// Starts observing `dom` if the virtual element was marked with [`ViewportExt::in_viewport`].
+pub(crate) fn attach_pending(element: &Element, dom: &web_sys::Element) {
+    let id = element
+        .attributes()
+        .iter()
+        .find_map(|attr| match &attr.value {
+            AttributeValue::String(v) if attr.name == VIEWPORT_ATTR => v.parse::<usize>().ok(),
+            _ => None,
+        });
+    let pending = id.and_then(|id| PENDING_OBSERVERS.with(|p| p.borrow_mut().remove(&id)));
+    if let Some((options, visible)) = pending {
+        observe(dom, options, visible);
+    }
+}
+
+fn observe(element: &web_sys::Element, options: ViewportOptions, visible: WriteSignal<bool>) {
+    let once = options.once;
+    let callback = Closure::<dyn FnMut(js_sys::Array, IntersectionObserver)>::new(
+        move |entries: js_sys::Array, observer: IntersectionObserver| {
+            let intersecting = entries.iter().any(|entry| {
+                entry
+                    .dyn_into::<IntersectionObserverEntry>()
+                    .map(|e| e.is_intersecting())
+                    .unwrap_or(false)
+            });
+            visible.set_if_changed(intersecting);
+            if once && intersecting {
+                observer.disconnect();
+            }
+        },
+    );
+
+    let init = IntersectionObserverInit::new();
+    init.set_threshold(&JsValue::from_f64(options.threshold));
+    init.set_root_margin(&options.root_margin);
+
+    let observer =
+        match IntersectionObserver::new_with_options(callback.as_ref().unchecked_ref(), &init) {
+            Ok(observer) => observer,
+            Err(_) => return,
+        };
+    observer.observe(element);
+
+    react_rs_core::effect::on_cleanup(move || {
+        observer.disconnect();
+        crate::listener::drop_later(callback);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use react_rs_elements::html::div;
+
+    use react_rs_core::effect::{create_scope, dispose_scope};
+
+    fn pending_options(element: &Element) -> Option<ViewportOptions> {
+        let id: usize = element
+            .attributes()
+            .iter()
+            .find(|a| a.name == VIEWPORT_ATTR)
+            .map(|a| a.to_static_value().parse().unwrap())?;
+        PENDING_OBSERVERS.with(|p| p.borrow().get(&id).map(|(o, _)| o.clone()))
+    }
+
+    #[test]
+    fn test_mark_pending_registers_observer_until_scope_cleanup() {
+        let (_, set_visible) = create_signal(false);
+        let scope = create_scope();
+        let element = mark_pending(div(), set_visible, ViewportOptions::new().threshold(0.5));
+        assert_eq!(pending_options(&element).map(|o| o.threshold), Some(0.5));
+
+        dispose_scope(scope);
+        assert!(pending_options(&element).is_none());
+    }
+
+    #[test]
+    fn test_in_viewport_leaves_server_markup_alone() {
+        let (_, set_visible) = create_signal(false);
+        let element = div().in_viewport(set_visible, ViewportOptions::new());
+        assert!(element.attributes().iter().all(|a| a.name != VIEWPORT_ATTR));
+    }
+}
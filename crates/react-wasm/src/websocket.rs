@@ -1,30 +1,301 @@
+use react_rs_core::signal::{create_signal, ReadSignal, WriteSignal};
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::time::Duration;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use web_sys::{CloseEvent, MessageEvent, WebSocket};
 
+type EventClosure = Closure<dyn FnMut(web_sys::Event)>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connecting,
+    Open,
+    Reconnecting,
+    Closed,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReconnectConfig {
+    pub max_retries: Option<u32>,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    /// Fraction of the delay (0.0..=1.0) that is randomized to avoid reconnect stampedes.
+    pub jitter: f64,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: None,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: 0.3,
+        }
+    }
+}
+
+impl ReconnectConfig {
+    pub fn delay_for(&self, attempt: u32, random: f64) -> Duration {
+        let base = self
+            .initial_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        let jitter = self.jitter.clamp(0.0, 1.0) * random.clamp(0.0, 1.0);
+        base.mul_f64(1.0 - jitter)
+    }
+
+    fn allows(&self, attempt: u32) -> bool {
+        self.max_retries.map(|max| attempt < max).unwrap_or(true)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WsOptions {
+    pub reconnect: Option<ReconnectConfig>,
+}
+
+impl WsOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reconnect(mut self, config: ReconnectConfig) -> Self {
+        self.reconnect = Some(config);
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WsMessage {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+impl WsMessage {
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Self::Text(text) => Some(text),
+            Self::Binary(_) => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Text(text) => text.as_bytes(),
+            Self::Binary(data) => data,
+        }
+    }
+}
+
+struct WsCallbacks {
+    on_message: Rc<dyn Fn(WsMessage)>,
+    on_open: Option<Rc<dyn Fn()>>,
+    on_close: Option<Rc<dyn Fn(u16, String)>>,
+    on_error: Option<Rc<dyn Fn(String)>>,
+}
+
+struct WsInner {
+    url: String,
+    ws: RefCell<WebSocket>,
+    options: WsOptions,
+    callbacks: WsCallbacks,
+    state: ReadSignal<ConnectionState>,
+    set_state: WriteSignal<ConnectionState>,
+    queue: RefCell<VecDeque<WsMessage>>,
+    attempts: Cell<u32>,
+    manually_closed: Cell<bool>,
+    closures: RefCell<Vec<EventClosure>>,
+}
+
+impl WsInner {
+    fn attach(self: &Rc<Self>, ws: &WebSocket) {
+        ws.set_binary_type(web_sys::BinaryType::Arraybuffer);
+
+        let on_message = self.callbacks.on_message.clone();
+        let message_cb = EventClosure::new(move |e: web_sys::Event| {
+            let data = match e.dyn_ref::<MessageEvent>() {
+                Some(m) => m.data(),
+                None => return,
+            };
+            if let Some(text) = data.as_string() {
+                on_message(WsMessage::Text(text));
+            } else if let Some(buffer) = data.dyn_ref::<js_sys::ArrayBuffer>() {
+                on_message(WsMessage::Binary(js_sys::Uint8Array::new(buffer).to_vec()));
+            } else if let Some(blob) = data.dyn_ref::<web_sys::Blob>() {
+                let on_message = on_message.clone();
+                let promise = blob.array_buffer();
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Ok(buffer) = wasm_bindgen_futures::JsFuture::from(promise).await {
+                        on_message(WsMessage::Binary(js_sys::Uint8Array::new(&buffer).to_vec()));
+                    }
+                });
+            }
+        });
+        ws.set_onmessage(Some(message_cb.as_ref().unchecked_ref()));
+
+        let inner = self.clone();
+        let open_cb = EventClosure::new(move |_: web_sys::Event| {
+            inner.attempts.set(0);
+            inner.set_state.set(ConnectionState::Open);
+            inner.flush_queue();
+            if let Some(on_open) = &inner.callbacks.on_open {
+                on_open();
+            }
+        });
+        ws.set_onopen(Some(open_cb.as_ref().unchecked_ref()));
+
+        let inner = self.clone();
+        let close_cb = EventClosure::new(move |e: web_sys::Event| {
+            if let Some(on_close) = &inner.callbacks.on_close {
+                let (code, reason) = e
+                    .dyn_ref::<CloseEvent>()
+                    .map(|c| (c.code(), c.reason()))
+                    .unwrap_or((1006, String::new()));
+                on_close(code, reason);
+            }
+            inner.schedule_reconnect();
+        });
+        ws.set_onclose(Some(close_cb.as_ref().unchecked_ref()));
+
+        let on_error = self.callbacks.on_error.clone();
+        let error_cb = EventClosure::new(move |e: web_sys::Event| {
+            if let Some(on_error) = &on_error {
+                let message = e
+                    .dyn_ref::<web_sys::ErrorEvent>()
+                    .map(|err| err.message())
+                    .unwrap_or_else(|| "WebSocket error".to_string());
+                on_error(message);
+            }
+        });
+        ws.set_onerror(Some(error_cb.as_ref().unchecked_ref()));
+
+        let mut closures = self.closures.borrow_mut();
+        closures.clear();
+        closures.extend([message_cb, open_cb, close_cb, error_cb]);
+    }
+
+    fn schedule_reconnect(self: &Rc<Self>) {
+        let config = match &self.options.reconnect {
+            Some(config) if !self.manually_closed.get() => config,
+            _ => {
+                self.set_state.set(ConnectionState::Closed);
+                return;
+            }
+        };
+
+        let attempt = self.attempts.get();
+        if !config.allows(attempt) {
+            self.set_state.set(ConnectionState::Closed);
+            return;
+        }
+        self.attempts.set(attempt + 1);
+        self.set_state.set(ConnectionState::Reconnecting);
+
+        let delay = config.delay_for(attempt, js_sys::Math::random());
+        let inner = self.clone();
+        let callback = Closure::once_into_js(move || inner.reconnect());
+        if let Some(window) = web_sys::window() {
+            let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+                callback.unchecked_ref(),
+                delay.as_millis() as i32,
+            );
+        }
+    }
+
+    fn reconnect(self: &Rc<Self>) {
+        if self.manually_closed.get() {
+            return;
+        }
+        match WebSocket::new(&self.url) {
+            Ok(ws) => {
+                self.attach(&ws);
+                *self.ws.borrow_mut() = ws;
+            }
+            Err(_) => self.schedule_reconnect(),
+        }
+    }
+
+    /// Closes the socket for good and detaches its handlers, dropping the closures that keep
+    /// `self` alive. A pending reconnect still fires, sees `manually_closed` and does nothing.
+    fn shutdown(&self) {
+        self.manually_closed.set(true);
+        self.queue.borrow_mut().clear();
+        let ws = self.ws.borrow();
+        ws.set_onmessage(None);
+        ws.set_onopen(None);
+        ws.set_onclose(None);
+        ws.set_onerror(None);
+        let _ = ws.close();
+        crate::listener::drop_later(std::mem::take(&mut *self.closures.borrow_mut()));
+    }
+
+    fn is_open(&self) -> bool {
+        self.ws.borrow().ready_state() == WebSocket::OPEN
+    }
+
+    fn send(&self, message: WsMessage) {
+        if self.is_open() {
+            let ws = self.ws.borrow();
+            let _ = match &message {
+                WsMessage::Text(text) => ws.send_with_str(text),
+                WsMessage::Binary(data) => ws.send_with_u8_array(data),
+            };
+        } else if self.options.reconnect.is_some() && !self.manually_closed.get() {
+            self.queue.borrow_mut().push_back(message);
+        }
+    }
+
+    fn flush_queue(&self) {
+        let queued: Vec<WsMessage> = self.queue.borrow_mut().drain(..).collect();
+        for message in queued {
+            self.send(message);
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct WsHandle {
-    ws: WebSocket,
+    inner: Rc<WsInner>,
 }
 
 impl WsHandle {
+    /// Sends a text frame. While reconnecting, messages are queued and replayed once open.
     pub fn send_text(&self, text: &str) {
-        let _ = self.ws.send_with_str(text);
+        self.inner.send(WsMessage::Text(text.to_string()));
     }
 
     pub fn send_binary(&self, data: &[u8]) {
-        let _ = self.ws.send_with_u8_array(data);
+        self.inner.send(WsMessage::Binary(data.to_vec()));
     }
 
+    pub fn send_bytes(&self, data: Vec<u8>) {
+        self.inner.send(WsMessage::Binary(data));
+    }
+
+    pub fn send(&self, message: WsMessage) {
+        self.inner.send(message);
+    }
+
+    /// Closes the socket and stops any further reconnection attempts.
     pub fn close(&self) {
-        let _ = self.ws.close();
+        self.inner.manually_closed.set(true);
+        self.inner.queue.borrow_mut().clear();
+        let _ = self.inner.ws.borrow().close();
     }
 
     pub fn ready_state(&self) -> u16 {
-        self.ws.ready_state()
+        self.inner.ws.borrow().ready_state()
     }
 
     pub fn is_open(&self) -> bool {
-        self.ws.ready_state() == WebSocket::OPEN
+        self.inner.is_open()
+    }
+
+    pub fn state(&self) -> ReadSignal<ConnectionState> {
+        self.inner.state.clone()
     }
 }
 
@@ -40,46 +311,136 @@ where
     FOpen: Fn() + 'static,
     FClose: Fn(u16, String) + 'static,
     FErr: Fn(String) + 'static,
+{
+    use_websocket_with(
+        url,
+        WsOptions::default(),
+        on_message,
+        on_open,
+        on_close,
+        on_error,
+    )
+}
+
+pub fn use_websocket_with<FMsg, FOpen, FClose, FErr>(
+    url: &str,
+    options: WsOptions,
+    on_message: FMsg,
+    on_open: Option<FOpen>,
+    on_close: Option<FClose>,
+    on_error: Option<FErr>,
+) -> WsHandle
+where
+    FMsg: Fn(String) + 'static,
+    FOpen: Fn() + 'static,
+    FClose: Fn(u16, String) + 'static,
+    FErr: Fn(String) + 'static,
+{
+    use_websocket_messages(
+        url,
+        options,
+        move |message| {
+            if let WsMessage::Text(text) = message {
+                on_message(text);
+            }
+        },
+        on_open,
+        on_close,
+        on_error,
+    )
+}
+
+/// Like [`use_websocket_with`], but delivers both text and binary frames as [`WsMessage`].
+pub fn use_websocket_messages<FMsg, FOpen, FClose, FErr>(
+    url: &str,
+    options: WsOptions,
+    on_message: FMsg,
+    on_open: Option<FOpen>,
+    on_close: Option<FClose>,
+    on_error: Option<FErr>,
+) -> WsHandle
+where
+    FMsg: Fn(WsMessage) + 'static,
+    FOpen: Fn() + 'static,
+    FClose: Fn(u16, String) + 'static,
+    FErr: Fn(String) + 'static,
 {
     let ws = WebSocket::new(url).expect("Failed to create WebSocket");
-    ws.set_binary_type(web_sys::BinaryType::Arraybuffer);
+    let (state, set_state) = create_signal(ConnectionState::Connecting);
 
-    let on_message_cb = Closure::<dyn FnMut(MessageEvent)>::new(move |e: MessageEvent| {
-        if let Some(text) = e.data().as_string() {
-            on_message(text);
-        }
+    let inner = Rc::new(WsInner {
+        url: url.to_string(),
+        ws: RefCell::new(ws.clone()),
+        options,
+        callbacks: WsCallbacks {
+            on_message: Rc::new(on_message),
+            on_open: on_open.map(|f| Rc::new(f) as Rc<dyn Fn()>),
+            on_close: on_close.map(|f| Rc::new(f) as Rc<dyn Fn(u16, String)>),
+            on_error: on_error.map(|f| Rc::new(f) as Rc<dyn Fn(String)>),
+        },
+        state,
+        set_state,
+        queue: RefCell::new(VecDeque::new()),
+        attempts: Cell::new(0),
+        manually_closed: Cell::new(false),
+        closures: RefCell::new(Vec::new()),
     });
-    ws.set_onmessage(Some(on_message_cb.as_ref().unchecked_ref()));
-    on_message_cb.forget();
+    inner.attach(&ws);
 
-    if let Some(on_open) = on_open {
-        let on_open_cb = Closure::<dyn FnMut()>::new(move || {
-            on_open();
-        });
-        ws.set_onopen(Some(on_open_cb.as_ref().unchecked_ref()));
-        on_open_cb.forget();
-    }
+    let owned = inner.clone();
+    react_rs_core::effect::on_cleanup(move || owned.shutdown());
 
-    if let Some(on_close) = on_close {
-        let on_close_cb = Closure::<dyn FnMut(CloseEvent)>::new(move |e: CloseEvent| {
-            on_close(e.code(), e.reason());
-        });
-        ws.set_onclose(Some(on_close_cb.as_ref().unchecked_ref()));
-        on_close_cb.forget();
+    WsHandle { inner }
+}
+
+pub fn use_websocket_simple(url: &str, on_message: impl Fn(String) + 'static) -> WsHandle {
+    use_websocket::<_, fn(), fn(u16, String), fn(String)>(url, on_message, None, None, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconnect_delay_backoff() {
+        let config = ReconnectConfig {
+            jitter: 0.0,
+            ..ReconnectConfig::default()
+        };
+        assert_eq!(config.delay_for(0, 0.5), Duration::from_millis(500));
+        assert_eq!(config.delay_for(2, 0.5), Duration::from_millis(2000));
+        assert_eq!(config.delay_for(20, 0.5), Duration::from_secs(30));
     }
 
-    if let Some(on_error) = on_error {
-        let on_error_cb =
-            Closure::<dyn FnMut(web_sys::ErrorEvent)>::new(move |e: web_sys::ErrorEvent| {
-                on_error(e.message());
-            });
-        ws.set_onerror(Some(on_error_cb.as_ref().unchecked_ref()));
-        on_error_cb.forget();
+    #[test]
+    fn test_reconnect_delay_jitter() {
+        let config = ReconnectConfig {
+            jitter: 0.5,
+            ..ReconnectConfig::default()
+        };
+        assert_eq!(config.delay_for(0, 0.0), Duration::from_millis(500));
+        assert_eq!(config.delay_for(0, 1.0), Duration::from_millis(250));
     }
 
-    WsHandle { ws }
-}
+    #[test]
+    fn test_ws_message_accessors() {
+        let text = WsMessage::Text("hi".to_string());
+        assert_eq!(text.as_text(), Some("hi"));
+        assert_eq!(text.as_bytes(), b"hi");
 
-pub fn use_websocket_simple(url: &str, on_message: impl Fn(String) + 'static) -> WsHandle {
-    use_websocket::<_, fn(), fn(u16, String), fn(String)>(url, on_message, None, None, None)
+        let binary = WsMessage::Binary(vec![1, 2, 3]);
+        assert_eq!(binary.as_text(), None);
+        assert_eq!(binary.as_bytes(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_reconnect_max_retries() {
+        let config = ReconnectConfig {
+            max_retries: Some(2),
+            ..ReconnectConfig::default()
+        };
+        assert!(config.allows(1));
+        assert!(!config.allows(2));
+        assert!(ReconnectConfig::default().allows(1000));
+    }
 }
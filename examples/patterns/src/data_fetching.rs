@@ -1,9 +1,9 @@
-use react_rs_core::{create_resource, ResourceState};
+use react_rs_core::{create_manual_resource, ResourceState};
 use react_rs_elements::html::*;
 use react_rs_elements::node::IntoNode;
 
 pub fn user_profile() -> impl IntoNode {
-    let user = create_resource::<String>();
+    let user = create_manual_resource::<String>();
 
     user.set_ready("Alice".to_string());
 
@@ -1,4 +1,4 @@
-use react_rs_core::{create_signal, ReadSignal, WriteSignal};
+use react_rs_core::{create_rw_signal, RwSignal};
 use react_rs_elements::html::*;
 use react_rs_elements::node::IntoNode;
 
@@ -12,7 +12,7 @@ pub struct Todo {
 }
 
 pub fn todo_app() -> Element {
-    let (todos, set_todos) = create_signal(vec![
+    let todos = create_rw_signal(vec![
         Todo {
             id: 1,
             text: "Learn react.rs".to_string(),
@@ -30,28 +30,24 @@ pub fn todo_app() -> Element {
         },
     ]);
 
-    let (next_id, set_next_id) = create_signal(4u32);
+    let next_id = create_rw_signal(4u32);
 
     div()
         .class("todo-app")
         .child(h1().text("Todo App"))
-        .child(todo_input(set_todos.clone(), next_id.clone(), set_next_id))
-        .child(todo_list(todos.clone(), set_todos.clone()))
+        .child(todo_input(todos.clone(), next_id))
+        .child(todo_list(todos.clone()))
         .child(todo_stats(todos))
 }
 
-fn todo_input(
-    set_todos: WriteSignal<Vec<Todo>>,
-    next_id: ReadSignal<u32>,
-    set_next_id: WriteSignal<u32>,
-) -> Element {
+fn todo_input(todos: RwSignal<Vec<Todo>>, next_id: RwSignal<u32>) -> Element {
     div()
         .class("todo-input")
         .child(input().type_("text").placeholder("What needs to be done?"))
         .child(button().text("Add").on_click(move |_| {
             let id = next_id.get();
-            set_next_id.update(|n| *n += 1);
-            set_todos.update(|todos| {
+            next_id.update(|n| *n += 1);
+            todos.update(|todos| {
                 todos.push(Todo {
                     id,
                     text: format!("New todo #{}", id),
@@ -61,19 +57,19 @@ fn todo_input(
         }))
 }
 
-fn todo_list(todos: ReadSignal<Vec<Todo>>, set_todos: WriteSignal<Vec<Todo>>) -> Element {
+fn todo_list(todos: RwSignal<Vec<Todo>>) -> Element {
     let items: Vec<Element> = todos
         .get()
         .iter()
-        .map(|todo| todo_item(todo.clone(), set_todos.clone()))
+        .map(|todo| todo_item(todo.clone(), todos.clone()))
         .collect();
 
     ul().class("todo-list").children(items)
 }
 
-fn todo_item(todo: Todo, set_todos: WriteSignal<Vec<Todo>>) -> Element {
+fn todo_item(todo: Todo, todos: RwSignal<Vec<Todo>>) -> Element {
     let id = todo.id;
-    let toggle_todos = set_todos.clone();
+    let toggle_todos = todos.clone();
 
     li().class(if todo.completed {
         "completed"
@@ -94,13 +90,13 @@ fn todo_item(todo: Todo, set_todos: WriteSignal<Vec<Todo>>) -> Element {
     )
     .child(span().text(&todo.text))
     .child(button().text("×").on_click(move |_| {
-        set_todos.update(|todos| {
+        todos.update(|todos| {
             todos.retain(|t| t.id != id);
         });
     }))
 }
 
-fn todo_stats(todos: ReadSignal<Vec<Todo>>) -> Element {
+fn todo_stats(todos: RwSignal<Vec<Todo>>) -> Element {
     let total = todos.get().len();
     let completed = todos.get().iter().filter(|t| t.completed).count();
     let remaining = total - completed;
@@ -1,4 +1,7 @@
-use react_rs_core::{create_resource, create_signal, provide_context, Resource};
+use react_rs_core::{
+    create_manual_resource, create_rw_signal, create_signal, use_context, with_context, Resource,
+    RwSignal,
+};
 use react_rs_elements::html;
 use react_rs_elements::node::{each, IntoNode, Node};
 use react_rs_elements::reactive::SignalExt;
@@ -161,7 +164,7 @@ pub fn greeting_form() -> Element {
 }
 
 pub fn data_loading_demo() -> Node {
-    let resource: Resource<Vec<String>> = create_resource();
+    let resource: Resource<Vec<String>> = create_manual_resource();
     let (users, set_users) = create_signal(Vec::<String>::new());
 
     let load_res = resource.clone();
@@ -226,58 +229,50 @@ pub fn data_loading_demo() -> Node {
         .into_node()
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct AppTheme {
-    pub name: String,
+    pub name: RwSignal<String>,
 }
 
 pub fn context_demo() -> Node {
-    provide_context(AppTheme {
-        name: "Default".to_string(),
-    });
+    let theme = AppTheme {
+        name: create_rw_signal("Default".to_string()),
+    };
 
-    let (theme_name, set_theme) = create_signal("Default".to_string());
+    with_context(theme.clone(), || {
+        let set_dark = theme.name.clone();
+        let set_light = theme.name.clone();
 
-    let set_dark = set_theme.clone();
-    let set_light = set_theme.clone();
+        html::div()
+            .class("context-demo")
+            .child(html::h3().text("Context API"))
+            .child(html::p().text("Current theme: ").child(theme_label()))
+            .child(
+                html::div()
+                    .class("counter-buttons")
+                    .child(
+                        html::button()
+                            .class("btn btn-increment")
+                            .text("Dark Theme")
+                            .on_click(move |_| set_dark.set("Dark".to_string())),
+                    )
+                    .child(
+                        html::button()
+                            .class("btn btn-reset")
+                            .text("Light Theme")
+                            .on_click(move |_| set_light.set("Light".to_string())),
+                    ),
+            )
+            .into_node()
+    })
+}
 
-    html::div()
-        .class("context-demo")
-        .child(html::h3().text("Context API"))
-        .child(
-            html::p().text("Current theme: ").child(
-                html::span()
-                    .class("greeting-name")
-                    .text_reactive(theme_name.map(|n| n.clone())),
-            ),
-        )
-        .child(
-            html::div()
-                .class("counter-buttons")
-                .child(
-                    html::button()
-                        .class("btn btn-increment")
-                        .text("Dark Theme")
-                        .on_click(move |_| {
-                            provide_context(AppTheme {
-                                name: "Dark".to_string(),
-                            });
-                            set_dark.set("Dark".to_string());
-                        }),
-                )
-                .child(
-                    html::button()
-                        .class("btn btn-reset")
-                        .text("Light Theme")
-                        .on_click(move |_| {
-                            provide_context(AppTheme {
-                                name: "Light".to_string(),
-                            });
-                            set_light.set("Light".to_string());
-                        }),
-                ),
-        )
-        .into_node()
+fn theme_label() -> Element {
+    let theme =
+        use_context::<AppTheme>().expect("theme_label must be rendered inside context_demo");
+    html::span()
+        .class("greeting-name")
+        .text_reactive(theme.name.map(|n| n.clone()))
 }
 
 pub fn feature_card(title: &str, description: &str) -> Element {